@@ -0,0 +1,86 @@
+//! Decoding throughput for transaction scans, where most events belong to
+//! other contracts
+//!
+//! Run with `cargo bench --bench event_decoding`.
+
+use aptos_client_sdk::types::EventSchemaVersion;
+use aptos_client_sdk::BridgeEventDecoder;
+use aptos_sdk::move_types::language_storage::TypeTag;
+use aptos_sdk::types::contract_event::ContractEvent;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use serde::Serialize;
+
+const BRIDGE_ADDRESS: &str = "0xb";
+const EVENTS: usize = 10_000;
+/// One bridge event in this many
+const BRIDGE_EVENT_EVERY: usize = 10;
+
+/// Layout of the on-chain `Burn` event (schema V1)
+#[derive(Serialize)]
+struct BurnEvent {
+    from_address: [u8; 32],
+    btc_address: String,
+    fee_rate: u64,
+    amount: u64,
+    operator_id: u64,
+}
+
+fn event(type_tag: &str, data: Vec<u8>) -> ContractEvent {
+    ContractEvent::new_v2(type_tag.parse::<TypeTag>().unwrap(), data).unwrap()
+}
+
+fn events() -> Vec<ContractEvent> {
+    let burn = bcs::to_bytes(&BurnEvent {
+        from_address: [1u8; 32],
+        btc_address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+        fee_rate: 5,
+        amount: 250000,
+        operator_id: 1,
+    })
+    .unwrap();
+    let deposit = bcs::to_bytes(&100u64).unwrap();
+    (0..EVENTS)
+        .map(|i| {
+            if i % BRIDGE_EVENT_EVERY == 0 {
+                event("0xb::bridge::Burn", burn.clone())
+            } else {
+                event("0x1::coin::CoinDeposit", deposit.clone())
+            }
+        })
+        .collect()
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let events = events();
+    let decoder =
+        BridgeEventDecoder::new(BRIDGE_ADDRESS, "bridge", EventSchemaVersion::V1).unwrap();
+
+    let mut group = c.benchmark_group("event_decoding");
+    group.throughput(Throughput::Elements(EVENTS as u64));
+    group.bench_function("reused_decoder", |b| {
+        b.iter(|| {
+            events
+                .iter()
+                .filter_map(|event| decoder.decode(event).unwrap())
+                .count()
+        })
+    });
+    // What every event paid before decoders were reused across a scan
+    group.bench_function("decoder_per_event", |b| {
+        b.iter(|| {
+            events
+                .iter()
+                .filter_map(|event| {
+                    BridgeEventDecoder::new(BRIDGE_ADDRESS, "bridge", EventSchemaVersion::V1)
+                        .unwrap()
+                        .decode(event)
+                        .unwrap()
+                })
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_decode);
+criterion_main!(benches);
@@ -3,7 +3,9 @@
 //! This example shows how to use the Aptos Bridge SDK to listen to bridge events.
 
 use anyhow::Result;
-use aptos_client_sdk::{BurnEvent, EventHandler, EventMonitor, MintEvent, WithdrawByLPEvent};
+use aptos_client_sdk::{
+    networks, BurnEvent, EventHandler, EventMonitor, MintEvent, TxidByteOrder, WithdrawByLPEvent,
+};
 use async_trait::async_trait;
 use std::env;
 
@@ -17,7 +19,9 @@ impl EventHandler for CustomEventHandler {
             event.to_address,
             event.amount,
             event.btc_block_num,
-            event.btc_tx_id,
+            event
+                .format_btc_tx_id(TxidByteOrder::Display)
+                .unwrap_or_else(|_| event.btc_tx_id.clone()),
             event.version.unwrap_or(0),
             event.timestamp.unwrap_or(0),
             event.transaction_hash.unwrap_or("N/A".to_string()),
@@ -84,7 +88,7 @@ async fn main() -> Result<()> {
     let aptos_api_key = env::var("APTOS_API_KEY").ok();
 
     // Aptos node URL
-    let node_url = "https://fullnode.testnet.aptoslabs.com/v1";
+    let node_url = networks::TESTNET.fullnode_url;
 
     // Create event monitor with API key
     let monitor = EventMonitor::new(
@@ -4,7 +4,8 @@
 
 use anyhow::Result;
 use aptos_client_sdk::{
-    BridgeClient, ClaimLPWithdrawParams, RegisterLPParams, TxProof, WithdrawByLPParams,
+    networks, script_hash_for_address, BridgeClient, BtcAddress, ClaimLPWithdrawParams, Network,
+    RegisterLPParams, Sats, ScriptType, TxProof, WithdrawByLPParams,
 };
 use aptos_sdk::rest_client::aptos_api_types::TransactionData;
 use std::{env, time::Duration};
@@ -15,30 +16,27 @@ async fn main() -> Result<()> {
     // Load environment variables
     dotenv::dotenv().ok();
 
-    let node_url = env::var("APTOS_NODE_URL")
-        .unwrap_or_else(|_| "https://fullnode.testnet.aptoslabs.com/v1".to_string());
+    let network = networks::TESTNET;
+    let node_url = env::var("APTOS_NODE_URL").unwrap_or_else(|_| network.fullnode_url.to_string());
 
     let private_key =
         env::var("PRIVATE_KEY").expect("PRIVATE_KEY environment variable must be set");
     let aptos_api_key = env::var("APTOS_API_KEY").ok();
-    let bridge_contract_address =
-        "0xeed4b8e27b6bd68e902e0e20633814d0d6d1a1c096763507fcaf058854a5b9b4";
-    let btc_light_client = "0x749e2800973809a39eb72ed6e38f154151cef1213b2e72e031ad86875bbc051a";
 
     // Create bridge client
     let client = BridgeClient::new(
         &node_url,
         aptos_api_key.as_deref(),
-        &private_key,
-        &bridge_contract_address,
-        Some(&btc_light_client),
-    )?;
+        private_key,
+        Network::Testnet.bridge_contract_address()?,
+        network.btc_light_client,
+    )?
+    .with_btc_network(network.btc_network);
 
     // Example 1: Register a new LP
     let register_params = RegisterLPParams {
         lp_id: 1,
-        bitcoin_addr: "bcrt1phcnl4zcl2fu047pv4wx6y058v8u0n02at6lthvm7pcf2wrvjm5tqatn90k"
-            .to_string(),
+        bitcoin_addr: "bcrt1phcnl4zcl2fu047pv4wx6y058v8u0n02at6lthvm7pcf2wrvjm5tqatn90k".parse()?,
         lp_addr: "0x2823126c1fd6124b0496b89dcb1de2ae0a71011baadf058c6a12ee22d0024cbe".to_string(),
         lp_fee: 1000, // 0.1% fee (basis points)
     };
@@ -67,15 +65,23 @@ async fn main() -> Result<()> {
         Err(e) => println!("Failed to get LP status: {}", e),
     }
 
+    // Show the LP's fee and BTC address before withdrawing through it
+    match client.get_lp_info(1).await {
+        Ok(lp) => println!("LP fee: {}, BTC address: {}", lp.lp_fee, lp.bitcoin_addr),
+        Err(e) => println!("Failed to get LP info: {}", e),
+    }
+
     // Example 3: Withdraw through LP
+    let btc_address: BtcAddress =
+        "bcrt1phcnl4zcl2fu047pv4wx6y058v8u0n02at6lthvm7pcf2wrvjm5tqatn90k".parse()?;
     let withdraw_params = WithdrawByLPParams {
         withdraw_id: 12351,
-        btc_address: "bcrt1phcnl4zcl2fu047pv4wx6y058v8u0n02at6lthvm7pcf2wrvjm5tqatn90k".to_string(),
-        receiver_script_hash: hex::decode("a914b7fcce0647b5e26b4a14b6b3b6f8b5e8e8e8e8e8e887")?,
-        receive_min_amount: 450000, // 0.0045 BTC minimum
+        receiver_script_hash: script_hash_for_address(&btc_address, ScriptType::P2TR)?,
+        btc_address,
+        receive_min_amount: Sats(450000), // 0.0045 BTC minimum
         lp_id: 1,
-        amount: 500000, // 0.005 BTC
-        fee_rate: 10,   // 10 sat/vB
+        amount: Sats(500000), // 0.005 BTC
+        fee_rate: 10,         // 10 sat/vB
     };
 
     let tx_hash = client.withdraw_by_lp(withdraw_params).await?;
@@ -121,7 +127,7 @@ async fn main() -> Result<()> {
         withdraw_id: 12351,
         block_num: 800000,
         tx_out_ix: 0,
-        amount_sats: 460000, // Amount actually received (after fees)
+        amount_sats: Sats(460000), // Amount actually received (after fees)
         inclusion_proof: TxProof {
             block_header: vec![0; 80], // Placeholder block header
             tx_id: vec![0; 32],        // Placeholder transaction ID
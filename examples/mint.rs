@@ -4,8 +4,8 @@
 
 use anyhow::Result;
 use aptos_client_sdk::{
-    types::{Peg, ScriptType, TxProof},
-    BridgeClient,
+    types::{Peg, Sats, ScriptType, TxProof},
+    BridgeClient, Network,
 };
 use aptos_sdk::rest_client::aptos_api_types::TransactionData;
 use std::{env, time::Duration};
@@ -15,27 +15,18 @@ use tokio::time;
 async fn main() -> Result<()> {
     dotenv::dotenv().ok();
     // Get configuration from environment variables
-    let node_url = "https://fullnode.testnet.aptoslabs.com/v1";
     let private_key =
         env::var("PRIVATE_KEY").expect("PRIVATE_KEY environment variable is required");
     let aptos_api_key = env::var("APTOS_API_KEY").ok();
-    let bridge_contract_address =
-        "0xeed4b8e27b6bd68e902e0e20633814d0d6d1a1c096763507fcaf058854a5b9b4";
-    let btc_light_client = "0x749e2800973809a39eb72ed6e38f154151cef1213b2e72e031ad86875bbc051a";
 
-    let bridge_client = BridgeClient::new(
-        &node_url,
-        aptos_api_key.as_deref(),
-        &private_key,
-        &bridge_contract_address,
-        Some(&btc_light_client),
-    )?;
+    let bridge_client =
+        BridgeClient::for_network(Network::Testnet, private_key, aptos_api_key.as_deref())?;
 
     // Create example peg
     let peg = create_example_peg()?;
 
     // Display peg information
-    println!("Peg: {} satoshi to {}", peg.value, peg.to);
+    println!("Peg: {} to {}", peg.value, peg.to);
 
     // Save the address before moving peg
     let peg_address = peg.to.clone();
@@ -61,7 +52,7 @@ async fn main() -> Result<()> {
 
     // Query the balance of the peg
     let balance = bridge_client.get_btc_peg_balance(&peg_address).await?;
-    println!("BTC peg balance: {} satoshi", balance);
+    println!("BTC peg balance: {}", balance);
 
     Ok(())
 }
@@ -69,7 +60,7 @@ async fn main() -> Result<()> {
 fn create_example_peg() -> Result<Peg> {
     let peg = Peg {
         to: "0x2823126c1fd6124b0496b89dcb1de2ae0a71011baadf058c6a12ee22d0024cbe".to_string(),
-        value: 500000,
+        value: Sats(500000),
         // For Local testing, we don't need to provide the block number and inclusion proof
         block_num: 0,
         inclusion_proof: TxProof {
@@ -3,19 +3,17 @@
 //! This example shows how to query bridge events from a user transaction hash.
 
 use anyhow::Result;
-use aptos_client_sdk::{BridgeClient, QueryClient};
+use aptos_client_sdk::{networks, BridgeClient, Network, QueryClient};
 use std::env;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv::dotenv().ok();
     // Initialize query client
-    let node_url = "https://fullnode.mainnet.aptoslabs.com/v1";
     let aptos_api_key = env::var("APTOS_API_KEY").ok();
-    let query_client = QueryClient::new(node_url, aptos_api_key.as_deref())?;
+    let query_client = QueryClient::new(networks::MAINNET.fullnode_url, aptos_api_key.as_deref())?;
 
-    let bridge_contract_address =
-        "0x094ff62f3aca82c9f6cb5f80d987f18ea834692425f97aaa4bdf8f73c3c0a7ee";
+    let bridge_contract_address = Network::Mainnet.bridge_contract_address()?;
 
     let tx_hash = "0xa67e4ae484a47ac36acb3009ba8f2982ffde7ff6e4ff0f8121dc8ff6f88b9a3e";
 
@@ -38,6 +36,19 @@ async fn main() -> Result<()> {
         }
     }
 
+    // Every event in the transaction, including those of other contracts
+    match query_client.get_all_events_by_tx_hash(tx_hash).await {
+        Ok(events) => {
+            println!("Transaction emitted {} events:", events.len());
+            for event in &events {
+                println!("  [{}] {}: {}", event.index, event.event_type, event.data);
+            }
+        }
+        Err(e) => {
+            println!("❌ Error querying transaction events: {}", e);
+        }
+    }
+
     // Test BridgeClient methods
     println!("\n--- Testing BridgeClient methods ---");
     test_bridge_client_methods().await?;
@@ -57,7 +68,6 @@ async fn main() -> Result<()> {
 
 /// Test BridgeClient methods: get_latest_block_height and get_min_confirmations
 async fn test_bridge_client_methods() -> Result<()> {
-    let node_url = "https://fullnode.testnet.aptoslabs.com/v1";
     let aptos_api_key = env::var("APTOS_API_KEY").ok();
 
     // We need a private key to create BridgeClient, but we won't use it for these read-only operations
@@ -66,17 +76,8 @@ async fn test_bridge_client_methods() -> Result<()> {
         "0x1".to_string()
     });
 
-    let bridge_contract_address =
-        "0xeed4b8e27b6bd68e902e0e20633814d0d6d1a1c096763507fcaf058854a5b9b4";
-    let btc_light_client = "0x749e2800973809a39eb72ed6e38f154151cef1213b2e72e031ad86875bbc051a";
-
-    let bridge_client = BridgeClient::new(
-        node_url,
-        aptos_api_key.as_deref(),
-        &private_key,
-        bridge_contract_address,
-        Some(&btc_light_client),
-    )?;
+    let bridge_client =
+        BridgeClient::for_network(Network::Testnet, private_key, aptos_api_key.as_deref())?;
 
     // Test get_min_confirmations
     match bridge_client.get_min_confirmations().await {
@@ -0,0 +1,157 @@
+//! Contract ABI compatibility
+//!
+//! Argument layouts of the entry functions this SDK serializes, checked against
+//! the ABI of the deployed modules so that a contract upgrade shows up as a clear
+//! version mismatch instead of an opaque BCS abort.
+
+use crate::contract::ContractModule;
+
+/// Entry functions the SDK calls, as `(module, function, parameter types)`
+///
+/// Function names are the SDK's names, before `ContractSpec` overrides. Parameter
+/// types exclude the leading signer. Light client functions are checked against
+/// the light client's address, and only when one is configured.
+pub(crate) const ENTRY_FUNCTIONS: &[(ContractModule, &str, &[&str])] = &[
+    (
+        ContractModule::Bridge,
+        "mint",
+        &[
+            "address",
+            "u64",
+            "u64",
+            "u64",
+            "u64",
+            "u8",
+            "vector<u8>",
+            "vector<u8>",
+            "vector<vector<u8>>",
+            "vector<u8>",
+            "vector<u8>",
+        ],
+    ),
+    (
+        ContractModule::Bridge,
+        "burn",
+        &["vector<u8>", "u64", "u64", "u64"],
+    ),
+    (
+        ContractModule::Bridge,
+        "withdraw_by_lp",
+        &[
+            "u64",
+            "vector<u8>",
+            "vector<u8>",
+            "u64",
+            "u64",
+            "u64",
+            "u64",
+        ],
+    ),
+    (
+        ContractModule::Bridge,
+        "claim_lp_withdraw",
+        &[
+            "u64",
+            "u64",
+            "u64",
+            "u64",
+            "vector<u8>",
+            "vector<u8>",
+            "u64",
+            "vector<vector<u8>>",
+            "vector<u8>",
+        ],
+    ),
+    (
+        ContractModule::LpManager,
+        "register_lp",
+        &["u64", "vector<u8>", "address", "u64"],
+    ),
+    (
+        ContractModule::LightClient,
+        "submit_block_headers",
+        &["u64", "vector<vector<u8>>"],
+    ),
+];
+
+/// Describe how a deployed entry function differs from what the SDK serializes
+///
+/// `found` is the function's `is_entry` flag and parameter types as shown by the
+/// node, or none if the module does not expose it.
+pub(crate) fn entry_function_mismatch(
+    module: &str,
+    function: &str,
+    expected: &[&str],
+    found: Option<(bool, &[String])>,
+) -> Option<String> {
+    let Some((is_entry, params)) = found else {
+        return Some(format!("{}::{} is missing", module, function));
+    };
+    if !is_entry {
+        return Some(format!("{}::{} is not an entry function", module, function));
+    }
+
+    let params: Vec<&str> = params
+        .iter()
+        .map(|param| bcs_layout(param))
+        .skip_while(|param| *param == "signer" || *param == "&signer")
+        .collect();
+    if params != expected {
+        return Some(format!(
+            "{}::{} takes ({}), SDK sends ({})",
+            module,
+            function,
+            params.join(", "),
+            expected.join(", ")
+        ));
+    }
+    None
+}
+
+/// Type with the same BCS encoding as `move_type`, as the SDK names it
+fn bcs_layout(move_type: &str) -> &str {
+    match move_type {
+        // Strings are BCS-encoded as their UTF-8 bytes
+        "0x1::string::String" => "vector<u8>",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(types: &[&str]) -> Vec<String> {
+        types.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_entry_function_mismatch() {
+        let expected = ["vector<u8>", "u64", "u64", "u64"];
+
+        let deployed = params(&["&signer", "0x1::string::String", "u64", "u64", "u64"]);
+        assert_eq!(
+            entry_function_mismatch("bridge", "burn", &expected, Some((true, &deployed))),
+            None
+        );
+
+        let reordered = params(&["&signer", "u64", "0x1::string::String", "u64", "u64"]);
+        assert_eq!(
+            entry_function_mismatch("bridge", "burn", &expected, Some((true, &reordered))),
+            Some(
+                "bridge::burn takes (u64, vector<u8>, u64, u64), \
+                 SDK sends (vector<u8>, u64, u64, u64)"
+                    .to_string()
+            )
+        );
+
+        assert!(
+            entry_function_mismatch("bridge", "burn", &expected, Some((false, &deployed)))
+                .unwrap()
+                .contains("not an entry function")
+        );
+        assert!(entry_function_mismatch("bridge", "burn", &expected, None)
+            .unwrap()
+            .contains("missing"));
+    }
+}
@@ -0,0 +1,138 @@
+//! Bridge contract abort codes
+//!
+//! Decodes Move aborts raised by the bridge and light-client modules into named
+//! errors. The reason name and description are the ones Aptos reports from the
+//! module's error metadata; aborts from modules published without it carry
+//! only their code.
+
+use anyhow::anyhow;
+use aptos_sdk::move_types::vm_status::AbortLocation;
+use aptos_sdk::types::transaction::ExecutionStatus;
+
+/// Move abort raised by a bridge contract module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeAbort {
+    /// Aborting module (e.g. `bridge`)
+    pub module: String,
+    /// Abort code as reported, including any error category
+    pub code: u64,
+    /// Reason name (e.g. `EPEG_ALREADY_MINTED`), if the node reported one
+    pub name: Option<String>,
+    /// Reason description, if the node reported one
+    pub description: Option<String>,
+}
+
+impl BridgeAbort {
+    /// Build from module, code and the reason reported by the node, if any
+    pub fn new(module: &str, code: u64, name: Option<&str>, description: Option<&str>) -> Self {
+        Self {
+            module: module.to_string(),
+            code,
+            name: name.filter(|n| !n.is_empty()).map(str::to_string),
+            description: description.filter(|d| !d.is_empty()).map(str::to_string),
+        }
+    }
+
+    /// Parse a REST API `vm_status` such as
+    /// `Move abort in 0x1::bridge: EPEG_ALREADY_MINTED(0x10003): ...` or
+    /// `Move abort in 0x1::bridge: 0x3`
+    pub fn from_vm_status(vm_status: &str) -> Option<Self> {
+        let (_, rest) = vm_status.split_once("Move abort in ")?;
+        let (location, detail) = rest.split_once(": ")?;
+        let (_, module) = location.rsplit_once("::")?;
+
+        let (name, code, description) = match detail.split_once('(') {
+            Some((name, rest)) => {
+                let (code, description) = rest.split_once(')')?;
+                let description = description.trim_start_matches(':').trim();
+                (Some(name.trim()), code, Some(description))
+            }
+            None => (None, detail.trim(), None),
+        };
+        let code = match code.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+            None => code.parse().ok()?,
+        };
+
+        Some(Self::new(module, code, name, description))
+    }
+
+    /// Decode a Move abort from a committed transaction's status
+    pub fn from_execution_status(status: &ExecutionStatus) -> Option<Self> {
+        match status {
+            ExecutionStatus::MoveAbort {
+                location: AbortLocation::Module(module_id),
+                code,
+                info,
+            } => Some(Self::new(
+                module_id.name().as_str(),
+                *code,
+                info.as_ref().map(|info| info.reason_name.as_str()),
+                info.as_ref().map(|info| info.description.as_str()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for BridgeAbort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} aborted with ", self.module)?;
+        match &self.name {
+            Some(name) => write!(f, "{} ({:#x})", name, self.code)?,
+            None => write!(f, "code {:#x}", self.code)?,
+        }
+        match &self.description {
+            Some(description) => write!(f, ": {}", description),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for BridgeAbort {}
+
+/// Error for a committed transaction that did not succeed
+///
+/// Move aborts become a [`BridgeAbort`]; other failures keep the raw status.
+pub(crate) fn execution_error(
+    what: &str,
+    tx_hash: &str,
+    status: &ExecutionStatus,
+) -> anyhow::Error {
+    match BridgeAbort::from_execution_status(status) {
+        Some(abort) => abort.into(),
+        None => anyhow!("{} {} failed: {:?}", what, tx_hash, status),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_vm_status_with_reason() {
+        let abort = BridgeAbort::from_vm_status(
+            "Move abort in 0xabc::bridge: EPEG_ALREADY_MINTED(0x10003): Peg was already minted",
+        )
+        .unwrap();
+        assert_eq!(abort.module, "bridge");
+        assert_eq!(abort.code, 0x10003);
+        assert_eq!(abort.name.as_deref(), Some("EPEG_ALREADY_MINTED"));
+        assert_eq!(abort.description.as_deref(), Some("Peg was already minted"));
+    }
+
+    #[test]
+    fn test_from_vm_status_code_only() {
+        let abort = BridgeAbort::from_vm_status("Move abort in 0xabc::bridge: 0x10005").unwrap();
+        assert_eq!(abort.code, 0x10005);
+        assert_eq!(abort.name, None);
+        assert_eq!(abort.to_string(), "bridge aborted with code 0x10005");
+
+        let unknown = BridgeAbort::from_vm_status("Move abort in 0xabc::other: 42").unwrap();
+        assert_eq!(unknown.name, None);
+        assert_eq!(unknown.to_string(), "other aborted with code 0x2a");
+
+        assert!(BridgeAbort::from_vm_status("Executed successfully").is_none());
+        assert!(BridgeAbort::from_vm_status("Move abort in 0xabc::bridge: oops").is_none());
+    }
+}
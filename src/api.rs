@@ -0,0 +1,250 @@
+//! Client traits
+//!
+//! [`BridgeQueryApi`] and [`BridgeApi`] cover the public surface of
+//! [`QueryClient`] and [`BridgeClient`], so applications can depend on the traits
+//! and swap in [`MockBridgeClient`](crate::mock::MockBridgeClient) (feature
+//! `test-utils`) in unit tests.
+
+use crate::btc::BtcAddress;
+#[cfg(feature = "events-rest")]
+use crate::types::BridgeEvent;
+use crate::types::{
+    ClaimLPWithdrawParams, LPInfo, LPStatus, LPWithdraw, Peg, RegisterLPParams, Sats,
+    WithdrawByLPParams, WithdrawStatus,
+};
+use crate::{BridgeClient, QueryClient};
+use anyhow::Result;
+use aptos_sdk::rest_client::aptos_api_types::TransactionData;
+use aptos_sdk::types::account_address::AccountAddress;
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Read-only queries against the Aptos node
+#[async_trait]
+pub trait BridgeQueryApi: Send + Sync {
+    /// Get the current ledger version of the node
+    async fn get_ledger_version(&self) -> Result<u64>;
+
+    /// Query transaction status
+    async fn get_transaction_by_hash(&self, tx_hash: &str) -> Result<TransactionData>;
+
+    /// Wait until a transaction is committed on chain
+    async fn wait_for_transaction(
+        &self,
+        tx_hash: &str,
+        timeout: Duration,
+    ) -> Result<TransactionData>;
+
+    /// Get the hash of the transaction at `version`
+    async fn get_tx_hash_by_version(&self, version: u64) -> Result<String>;
+
+    /// Get bridge events from user transaction hash
+    #[cfg(feature = "events-rest")]
+    async fn get_bridge_events_by_hash(
+        &self,
+        tx_hash: &str,
+        bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEvent>>;
+}
+
+/// Bridge transactions and view calls
+#[async_trait]
+pub trait BridgeApi: BridgeQueryApi {
+    /// Bridge contract address
+    fn bridge_contract_address(&self) -> AccountAddress;
+
+    /// Mint tokens based on BTC deposits
+    async fn mint(&self, peg: Peg) -> Result<String>;
+
+    /// Burn tokens
+    async fn burn(
+        &self,
+        btc_address: BtcAddress,
+        fee_rate: u64,
+        amount: Sats,
+        operator_id: u64,
+    ) -> Result<String>;
+
+    /// Withdraw tokens through LP mode
+    async fn withdraw_by_lp(&self, params: WithdrawByLPParams) -> Result<String>;
+
+    /// Claim LP withdrawal
+    async fn claim_lp_withdraw(&self, params: ClaimLPWithdrawParams) -> Result<String>;
+
+    /// Register a new LP
+    async fn register_lp(&self, params: RegisterLPParams) -> Result<String>;
+
+    /// Submit consecutive BTC block headers to the light client
+    async fn submit_block_headers(&self, start_height: u64, headers: &[Vec<u8>]) -> Result<String>;
+
+    /// Check whether a BTC output has already been minted
+    async fn is_peg_minted(&self, tx_id: &[u8], tx_out_ix: u64) -> Result<bool>;
+
+    /// Get minimum confirmations required for BTC transactions
+    async fn get_min_confirmations(&self) -> Result<u64>;
+
+    /// Get the latest block height of the light client
+    async fn get_latest_block_height(&self) -> Result<u64>;
+
+    /// Get the light client's block hash at `height`
+    async fn get_block_hash(&self, height: u64) -> Result<Vec<u8>>;
+
+    /// Get LP withdraw information
+    async fn get_lp_withdraw(&self, withdraw_id: u64) -> Result<LPWithdraw>;
+
+    /// Get the status of an LP withdraw
+    async fn get_withdraw_status(&self, withdraw_id: u64) -> Result<WithdrawStatus>;
+
+    /// Get LP status
+    async fn get_lp_status(&self, lp_id: u64) -> Result<LPStatus>;
+
+    /// Get the registration of an LP
+    async fn get_lp_info(&self, lp_id: u64) -> Result<LPInfo>;
+
+    /// Get the LP registered with the Aptos account `aptos_addr`, if any
+    async fn find_lp_by_address(&self, aptos_addr: &str) -> Result<Option<LPInfo>>;
+
+    /// Get BTC peg balance with the given address
+    async fn get_btc_peg_balance(&self, address: &str) -> Result<Sats>;
+}
+
+#[async_trait]
+impl BridgeQueryApi for QueryClient {
+    async fn get_ledger_version(&self) -> Result<u64> {
+        QueryClient::get_ledger_version(self).await
+    }
+
+    async fn get_transaction_by_hash(&self, tx_hash: &str) -> Result<TransactionData> {
+        QueryClient::get_transaction_by_hash(self, tx_hash).await
+    }
+
+    async fn wait_for_transaction(
+        &self,
+        tx_hash: &str,
+        timeout: Duration,
+    ) -> Result<TransactionData> {
+        QueryClient::wait_for_transaction(self, tx_hash, timeout).await
+    }
+
+    async fn get_tx_hash_by_version(&self, version: u64) -> Result<String> {
+        QueryClient::get_tx_hash_by_version(self, version).await
+    }
+
+    #[cfg(feature = "events-rest")]
+    async fn get_bridge_events_by_hash(
+        &self,
+        tx_hash: &str,
+        bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEvent>> {
+        QueryClient::get_bridge_events_by_hash(self, tx_hash, bridge_contract_address).await
+    }
+}
+
+#[async_trait]
+impl BridgeQueryApi for BridgeClient {
+    async fn get_ledger_version(&self) -> Result<u64> {
+        QueryClient::get_ledger_version(self).await
+    }
+
+    async fn get_transaction_by_hash(&self, tx_hash: &str) -> Result<TransactionData> {
+        QueryClient::get_transaction_by_hash(self, tx_hash).await
+    }
+
+    async fn wait_for_transaction(
+        &self,
+        tx_hash: &str,
+        timeout: Duration,
+    ) -> Result<TransactionData> {
+        QueryClient::wait_for_transaction(self, tx_hash, timeout).await
+    }
+
+    async fn get_tx_hash_by_version(&self, version: u64) -> Result<String> {
+        QueryClient::get_tx_hash_by_version(self, version).await
+    }
+
+    #[cfg(feature = "events-rest")]
+    async fn get_bridge_events_by_hash(
+        &self,
+        tx_hash: &str,
+        bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEvent>> {
+        QueryClient::get_bridge_events_by_hash(self, tx_hash, bridge_contract_address).await
+    }
+}
+
+#[async_trait]
+impl BridgeApi for BridgeClient {
+    fn bridge_contract_address(&self) -> AccountAddress {
+        BridgeClient::bridge_contract_address(self)
+    }
+
+    async fn mint(&self, peg: Peg) -> Result<String> {
+        BridgeClient::mint(self, peg).await
+    }
+
+    async fn burn(
+        &self,
+        btc_address: BtcAddress,
+        fee_rate: u64,
+        amount: Sats,
+        operator_id: u64,
+    ) -> Result<String> {
+        BridgeClient::burn(self, btc_address, fee_rate, amount, operator_id).await
+    }
+
+    async fn withdraw_by_lp(&self, params: WithdrawByLPParams) -> Result<String> {
+        BridgeClient::withdraw_by_lp(self, params).await
+    }
+
+    async fn claim_lp_withdraw(&self, params: ClaimLPWithdrawParams) -> Result<String> {
+        BridgeClient::claim_lp_withdraw(self, params).await
+    }
+
+    async fn register_lp(&self, params: RegisterLPParams) -> Result<String> {
+        BridgeClient::register_lp(self, params).await
+    }
+
+    async fn submit_block_headers(&self, start_height: u64, headers: &[Vec<u8>]) -> Result<String> {
+        BridgeClient::submit_block_headers(self, start_height, headers).await
+    }
+
+    async fn is_peg_minted(&self, tx_id: &[u8], tx_out_ix: u64) -> Result<bool> {
+        BridgeClient::is_peg_minted(self, tx_id, tx_out_ix).await
+    }
+
+    async fn get_min_confirmations(&self) -> Result<u64> {
+        BridgeClient::get_min_confirmations(self).await
+    }
+
+    async fn get_latest_block_height(&self) -> Result<u64> {
+        BridgeClient::get_latest_block_height(self).await
+    }
+
+    async fn get_block_hash(&self, height: u64) -> Result<Vec<u8>> {
+        BridgeClient::get_block_hash(self, height).await
+    }
+
+    async fn get_lp_withdraw(&self, withdraw_id: u64) -> Result<LPWithdraw> {
+        BridgeClient::get_lp_withdraw(self, withdraw_id).await
+    }
+
+    async fn get_withdraw_status(&self, withdraw_id: u64) -> Result<WithdrawStatus> {
+        BridgeClient::get_withdraw_status(self, withdraw_id).await
+    }
+
+    async fn get_lp_status(&self, lp_id: u64) -> Result<LPStatus> {
+        BridgeClient::get_lp_status(self, lp_id).await
+    }
+
+    async fn get_lp_info(&self, lp_id: u64) -> Result<LPInfo> {
+        BridgeClient::get_lp_info(self, lp_id).await
+    }
+
+    async fn find_lp_by_address(&self, aptos_addr: &str) -> Result<Option<LPInfo>> {
+        BridgeClient::find_lp_by_address(self, aptos_addr).await
+    }
+
+    async fn get_btc_peg_balance(&self, address: &str) -> Result<Sats> {
+        BridgeClient::get_btc_peg_balance(self, address).await
+    }
+}
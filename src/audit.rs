@@ -0,0 +1,316 @@
+//! Transaction audit log
+//!
+//! With an [`AuditLog`] set via [`BridgeClient::with_audit_log`](crate::BridgeClient::with_audit_log),
+//! every signed transaction is recorded when it is submitted (or rejected by the
+//! node), and again once its final status is known. Records are append-only and
+//! self-contained, so each line of a [`JsonlAuditLog`] can be reviewed on its own.
+
+use crate::types::constants::TRANSACTION_COMMIT_TIMEOUT_SECS;
+use crate::QueryClient;
+use anyhow::{anyhow, Result};
+use aptos_sdk::rest_client::aptos_api_types::TransactionData;
+use aptos_sdk::types::transaction::{SignedTransaction, TransactionPayload};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// Status of an audited transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AuditStatus {
+    /// Accepted by the node
+    Submitted,
+    /// Rejected by the node (after retries)
+    Rejected { error: String },
+    /// Committed on chain
+    Committed {
+        version: u64,
+        success: bool,
+        vm_status: String,
+    },
+    /// Not seen on chain within the commit timeout
+    Unconfirmed { error: String },
+}
+
+/// One audit record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Seconds since the Unix epoch when the record was written
+    pub timestamp: u64,
+    /// Sender address
+    pub sender: String,
+    /// Sender sequence number
+    pub sequence_number: u64,
+    /// Entry function, `address::module::function`
+    pub function: String,
+    /// Move type arguments
+    pub type_args: Vec<String>,
+    /// BCS-encoded arguments, hex
+    pub args: Vec<String>,
+    /// BCS-encoded signed transaction, hex
+    pub signed_transaction: String,
+    /// Transaction hash
+    pub tx_hash: String,
+    /// Status at `timestamp`
+    #[serde(flatten)]
+    pub status: AuditStatus,
+}
+
+impl AuditEntry {
+    /// Record for a signed transaction with the given status
+    pub fn new(signed_transaction: &SignedTransaction, status: AuditStatus) -> Result<Self> {
+        let (function, type_args, args) = match signed_transaction.payload() {
+            TransactionPayload::EntryFunction(entry_function) => (
+                format!(
+                    "{}::{}::{}",
+                    entry_function.module().address().to_hex_literal(),
+                    entry_function.module().name(),
+                    entry_function.function()
+                ),
+                entry_function
+                    .ty_args()
+                    .iter()
+                    .map(|type_arg| type_arg.to_string())
+                    .collect(),
+                entry_function.args().iter().map(hex::encode).collect(),
+            ),
+            _ => ("unknown".to_string(), vec![], vec![]),
+        };
+        let signed_transaction_bytes = bcs::to_bytes(signed_transaction)
+            .map_err(|e| anyhow!("Failed to serialize signed transaction: {}", e))?;
+
+        Ok(Self {
+            timestamp: crate::time::unix_timestamp_secs(),
+            sender: signed_transaction.sender().to_hex_literal(),
+            sequence_number: signed_transaction.sequence_number(),
+            function,
+            type_args,
+            args,
+            signed_transaction: hex::encode(signed_transaction_bytes),
+            tx_hash: signed_transaction.committed_hash().to_hex_literal(),
+            status,
+        })
+    }
+
+    /// The same transaction with a later status
+    pub fn with_status(&self, status: AuditStatus) -> Self {
+        Self {
+            timestamp: crate::time::unix_timestamp_secs(),
+            status,
+            ..self.clone()
+        }
+    }
+}
+
+/// Append-only sink for audit records
+#[async_trait]
+pub trait AuditLog: Send + Sync {
+    /// Append a record
+    async fn record(&self, entry: &AuditEntry) -> Result<()>;
+}
+
+/// Audit log writing one JSON record per line
+pub struct JsonlAuditLog {
+    /// Log file
+    path: PathBuf,
+    /// Keeps concurrent records from interleaving
+    lock: Mutex<()>,
+}
+
+impl JsonlAuditLog {
+    /// Append to `path`, which is created on first write
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditLog for JsonlAuditLog {
+    async fn record(&self, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)
+            .map_err(|e| anyhow!("Failed to serialize audit record: {}", e))?;
+        line.push(b'\n');
+
+        let _guard = self.lock.lock().await;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| anyhow!("Failed to open {}: {}", self.path.display(), e))?;
+        file.write_all(&line)
+            .await
+            .map_err(|e| anyhow!("Failed to write {}: {}", self.path.display(), e))?;
+        file.sync_data()
+            .await
+            .map_err(|e| anyhow!("Failed to sync {}: {}", self.path.display(), e))
+    }
+}
+
+/// Audit log in a PostgreSQL `bridge_audit_log` table
+#[cfg(feature = "postgres")]
+#[derive(Clone)]
+pub struct PostgresAuditLog {
+    client: Arc<tokio_postgres::Client>,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresAuditLog {
+    /// Use an already connected client
+    pub fn from_client(client: tokio_postgres::Client) -> Self {
+        Self {
+            client: Arc::new(client),
+        }
+    }
+
+    /// Create the table and indexes if they don't exist
+    pub async fn migrate(&self) -> Result<()> {
+        self.client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS bridge_audit_log (
+                    id BIGSERIAL PRIMARY KEY,
+                    recorded_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+                    tx_hash TEXT NOT NULL,
+                    sender TEXT NOT NULL,
+                    status TEXT NOT NULL,
+                    entry JSONB NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS bridge_audit_log_tx_hash ON bridge_audit_log (tx_hash);
+                CREATE INDEX IF NOT EXISTS bridge_audit_log_sender ON bridge_audit_log (sender);",
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to migrate audit log: {}", e))
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl AuditLog for PostgresAuditLog {
+    async fn record(&self, entry: &AuditEntry) -> Result<()> {
+        let json = serde_json::to_value(entry)
+            .map_err(|e| anyhow!("Failed to serialize audit record: {}", e))?;
+        let status = json["status"].as_str().unwrap_or_default().to_string();
+        self.client
+            .execute(
+                "INSERT INTO bridge_audit_log (tx_hash, sender, status, entry) VALUES ($1, $2, $3, $4)",
+                &[&entry.tx_hash, &entry.sender, &status, &json],
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to write audit record: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Record a submission attempt and, once it's accepted, its final status
+///
+/// The final status is awaited on a spawned task so submission isn't delayed.
+/// Audit failures are logged rather than returned: the transaction may already
+/// be on its way, and failing the call would invite a duplicate resubmission.
+pub(crate) async fn record_submission(
+    audit_log: &Arc<dyn AuditLog>,
+    query_client: &QueryClient,
+    signed_transaction: &SignedTransaction,
+    submit_error: Option<&anyhow::Error>,
+) {
+    let status = match submit_error {
+        Some(e) => AuditStatus::Rejected {
+            error: e.to_string(),
+        },
+        None => AuditStatus::Submitted,
+    };
+    let entry = match AuditEntry::new(signed_transaction, status) {
+        Ok(entry) => entry,
+        Err(_e) => {
+            #[cfg(feature = "tracing")]
+            tracing::error!(error = %_e, "Failed to build audit record");
+            return;
+        }
+    };
+    record_or_log(audit_log.as_ref(), &entry).await;
+    if submit_error.is_some() {
+        return;
+    }
+
+    let audit_log = audit_log.clone();
+    let query_client = query_client.clone();
+    tokio::spawn(async move {
+        let status = match query_client
+            .wait_for_transaction(
+                &entry.tx_hash,
+                Duration::from_secs(TRANSACTION_COMMIT_TIMEOUT_SECS),
+            )
+            .await
+        {
+            Ok(TransactionData::OnChain(txn)) => AuditStatus::Committed {
+                version: txn.version,
+                success: txn.info.status().is_success(),
+                vm_status: format!("{:?}", txn.info.status()),
+            },
+            Ok(TransactionData::Pending(_)) => AuditStatus::Unconfirmed {
+                error: "Transaction is still pending".to_string(),
+            },
+            Err(e) => AuditStatus::Unconfirmed {
+                error: e.to_string(),
+            },
+        };
+        record_or_log(audit_log.as_ref(), &entry.with_status(status)).await;
+    });
+}
+
+async fn record_or_log(audit_log: &dyn AuditLog, entry: &AuditEntry) {
+    if let Err(_e) = audit_log.record(entry).await {
+        #[cfg(feature = "tracing")]
+        tracing::error!(error = %_e, tx_hash = %entry.tx_hash, "Failed to write audit record");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> AuditEntry {
+        AuditEntry {
+            timestamp: 1_700_000_000,
+            sender: "0x1".to_string(),
+            sequence_number: 3,
+            function: "0xb::bridge::burn".to_string(),
+            type_args: vec![],
+            args: vec!["05".to_string()],
+            signed_transaction: "00".to_string(),
+            tx_hash: "0xabc".to_string(),
+            status: AuditStatus::Submitted,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_audit_log_appends() {
+        let path = std::env::temp_dir().join(format!("audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let audit_log = JsonlAuditLog::new(&path);
+
+        let submitted = entry();
+        let committed = submitted.with_status(AuditStatus::Committed {
+            version: 42,
+            success: true,
+            vm_status: "Success".to_string(),
+        });
+        audit_log.record(&submitted).await.unwrap();
+        audit_log.record(&committed).await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let records: Vec<AuditEntry> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records, vec![submitted, committed]);
+        assert!(contents.contains(r#""status":"committed""#));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+//! `events tail` and `events backfill`
+//!
+//! Both subcommands write one JSON bridge event per line (NDJSON), in the
+//! `BridgeEvent` serde format.
+
+use crate::output::Output;
+use anyhow::{anyhow, Result};
+use aptos_client_sdk::{
+    BridgeEvent, BurnEvent, Config, EventHandler, EventMonitor, GraphQLAuth, IndexerSchema,
+    MintEvent, WithdrawByLPEvent,
+};
+use async_trait::async_trait;
+use clap::ValueEnum;
+use futures::TryStreamExt;
+use serde_json::json;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Bridge event type selected with `--filter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EventKind {
+    Mint,
+    Burn,
+    WithdrawByLp,
+}
+
+impl EventKind {
+    fn of(event: &BridgeEvent) -> Self {
+        match event {
+            BridgeEvent::Mint(_) => EventKind::Mint,
+            BridgeEvent::Burn(_) => EventKind::Burn,
+            BridgeEvent::WithdrawByLP(_) => EventKind::WithdrawByLp,
+        }
+    }
+}
+
+/// Whether `event` passes `filter`; an empty filter passes everything
+fn passes_filter(filter: &[EventKind], event: &BridgeEvent) -> bool {
+    filter.is_empty() || filter.contains(&EventKind::of(event))
+}
+
+/// Writes every event matching the filter as one JSON line
+struct NdjsonHandler {
+    /// Event types to write; empty writes all
+    filter: Vec<EventKind>,
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+impl NdjsonHandler {
+    fn new(filter: Vec<EventKind>, sink: Box<dyn Write + Send>) -> Self {
+        Self {
+            filter,
+            sink: Mutex::new(sink),
+        }
+    }
+
+    fn write(&self, event: BridgeEvent) -> Result<()> {
+        if !passes_filter(&self.filter, &event) {
+            return Ok(());
+        }
+        let line = serde_json::to_string(&event)
+            .map_err(|e| anyhow!("Failed to serialize event: {}", e))?;
+        let mut sink = self
+            .sink
+            .lock()
+            .map_err(|_| anyhow!("Event sink poisoned"))?;
+        writeln!(sink, "{}", line).map_err(|e| anyhow!("Failed to write event: {}", e))?;
+        sink.flush()
+            .map_err(|e| anyhow!("Failed to flush events: {}", e))
+    }
+}
+
+#[async_trait]
+impl EventHandler for NdjsonHandler {
+    async fn handle_mint(&self, event: MintEvent) -> Result<()> {
+        self.write(BridgeEvent::Mint(event))
+    }
+
+    async fn handle_burn(&self, event: BurnEvent) -> Result<()> {
+        self.write(BridgeEvent::Burn(event))
+    }
+
+    async fn handle_withdraw_by_lp(&self, event: WithdrawByLPEvent) -> Result<()> {
+        self.write(BridgeEvent::WithdrawByLP(event))
+    }
+}
+
+/// Event monitor for the configured indexer, dispatching to `handler`
+fn event_monitor(
+    config: &Config,
+    handler: NdjsonHandler,
+    from_version: u64,
+) -> Result<EventMonitor> {
+    let indexer_url = config
+        .indexer_url()
+        .ok_or_else(|| anyhow!("Config is missing indexer_url (or network)"))?;
+    let monitor = EventMonitor::new(
+        indexer_url,
+        config.graphql_api_key.as_deref().unwrap_or_default(),
+        config.node_url()?,
+        config.aptos_api_key.as_deref(),
+        Box::new(handler),
+        from_version,
+    )?;
+    let monitor = match &config.indexer_table_prefix {
+        Some(prefix) => {
+            monitor.with_indexer_schema(IndexerSchema::new().with_table_prefix(prefix)?)
+        }
+        None => monitor,
+    };
+    Ok(match &config.graphql_api_key {
+        Some(_) => monitor,
+        None => monitor.with_graphql_auth(GraphQLAuth::None),
+    })
+}
+
+/// Stream live events after `from_version` to stdout until interrupted
+///
+/// Poll errors are reported on stderr and retried on the next tick.
+pub async fn tail(
+    config: &Config,
+    output: &Output,
+    from_version: u64,
+    interval: u64,
+    filter: Vec<EventKind>,
+) -> Result<()> {
+    let handler = NdjsonHandler::new(filter, Box::new(std::io::stdout()));
+    let monitor = event_monitor(config, handler, from_version)?;
+
+    loop {
+        if let Err(e) = monitor.process().await {
+            output.error(&e);
+        }
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+    }
+}
+
+/// Replay events in `[from_version, to_version]` to `out` (or stdout)
+///
+/// The range is fetched `chunk_size` versions at a time so large ranges stay
+/// within indexer response limits. The summary goes to stderr when events are
+/// written to stdout.
+pub async fn backfill(
+    config: &Config,
+    output: &Output,
+    from_version: u64,
+    to_version: u64,
+    out: Option<&Path>,
+    chunk_size: u64,
+    filter: Vec<EventKind>,
+) -> Result<()> {
+    if from_version > to_version {
+        return Err(anyhow!(
+            "Invalid range: --from {} is greater than --to {}",
+            from_version,
+            to_version
+        ));
+    }
+    if chunk_size == 0 {
+        return Err(anyhow!("--chunk-size must be greater than 0"));
+    }
+
+    let sink: Box<dyn Write + Send> = match out {
+        Some(path) => Box::new(
+            File::create(path)
+                .map_err(|e| anyhow!("Failed to create {}: {}", path.display(), e))?,
+        ),
+        None => Box::new(std::io::stdout()),
+    };
+    let handler = NdjsonHandler::new(filter.clone(), sink);
+    let monitor = event_monitor(config, handler, from_version)?;
+
+    let mut written = 0;
+    let mut events = std::pin::pin!(monitor.replay_stream(from_version, to_version, chunk_size));
+    while let Some(event) = events.try_next().await? {
+        if passes_filter(&filter, &event) {
+            written += 1;
+        }
+    }
+
+    let summary = json!({
+        "from_version": from_version,
+        "to_version": to_version,
+        "events": written,
+        "out": out.map(|path| path.display().to_string()),
+    });
+    match out {
+        Some(_) => output.print(&summary),
+        None => output.print_stderr(&summary),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_client_sdk::Sats;
+    use std::sync::Arc;
+
+    /// Sink whose contents stay readable after it's boxed
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn burn_event() -> BurnEvent {
+        BurnEvent {
+            from_address: "0x1".to_string(),
+            btc_address: "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_string(),
+            fee_rate: 5,
+            amount: Sats(1_000),
+            operator_id: 0,
+            timestamp: None,
+            version: Some(42),
+            transaction_hash: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_handler_filters_and_writes_lines() {
+        let buffer = SharedBuffer::default();
+        let handler = NdjsonHandler::new(vec![EventKind::Burn], Box::new(buffer.clone()));
+
+        handler.handle_burn(burn_event()).await.unwrap();
+        handler.handle_burn(burn_event()).await.unwrap();
+
+        let written = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let event: BridgeEvent = serde_json::from_str(lines[0]).unwrap();
+        assert!(matches!(event, BridgeEvent::Burn(burn) if burn.version == Some(42)));
+
+        assert!(!passes_filter(
+            &[EventKind::Mint],
+            &BridgeEvent::Burn(burn_event())
+        ));
+        assert!(passes_filter(&[], &BridgeEvent::Burn(burn_event())));
+    }
+}
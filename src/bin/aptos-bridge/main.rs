@@ -0,0 +1,451 @@
+//! `aptos-bridge` command-line client
+//!
+//! Reads a [`Config`] from `--config` (TOML or YAML) and/or the environment and
+//! runs one bridge operation, printing human-readable or JSON output.
+
+mod events;
+mod output;
+mod proof;
+
+use anyhow::{anyhow, Result};
+use aptos_client_sdk::abort::BridgeAbort;
+use aptos_client_sdk::types::constants::TRANSACTION_COMMIT_TIMEOUT_SECS;
+use aptos_client_sdk::{
+    script_hash_for_address, BridgeClient, BtcAddress, ClaimLPWithdrawParams, Config, Network, Peg,
+    QueryClient, RegisterLPParams, Sats, TxProof, WithdrawByLPParams,
+};
+use aptos_sdk::rest_client::aptos_api_types::TransactionData;
+use clap::{Args, Parser, Subcommand};
+use events::EventKind;
+use output::Output;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(name = "aptos-bridge", version, about = "Aptos BTC bridge client")]
+struct Cli {
+    /// TOML or YAML config file; environment variables override its settings
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Network preset, overriding the config
+    #[arg(long, global = true)]
+    network: Option<Network>,
+
+    /// Print JSON instead of human-readable output
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Mint from a peg (JSON file, as built by `ProofBuilder::build_peg`)
+    Mint {
+        /// Path to the peg JSON
+        #[arg(long)]
+        peg: PathBuf,
+        #[command(flatten)]
+        submit: SubmitArgs,
+    },
+    /// Burn pegged BTC for an operator payout
+    Burn {
+        /// Bitcoin address receiving the payout
+        #[arg(long)]
+        btc_address: BtcAddress,
+        /// Amount, e.g. `0.001 BTC`, `100000 sats` or `100000`
+        #[arg(long)]
+        amount: Sats,
+        /// Fee rate in sat/vB
+        #[arg(long)]
+        fee_rate: u64,
+        /// Operator ID
+        #[arg(long, default_value_t = 0)]
+        operator_id: u64,
+        #[command(flatten)]
+        submit: SubmitArgs,
+    },
+    /// Liquidity provider operations
+    #[command(subcommand)]
+    Lp(LpCommand),
+    /// Read-only queries
+    #[command(subcommand)]
+    Query(QueryCommand),
+    /// Bridge event streams
+    #[command(subcommand)]
+    Events(EventsCommand),
+    /// Build the inclusion proof (or `Peg`) of a confirmed BTC transaction
+    Proof(proof::ProofArgs),
+    /// Node, light client and bridge status
+    Status,
+}
+
+#[derive(Subcommand)]
+enum LpCommand {
+    /// Register a liquidity provider
+    Register {
+        /// LP ID
+        #[arg(long)]
+        lp_id: u64,
+        /// Bitcoin address of the LP
+        #[arg(long)]
+        btc_address: BtcAddress,
+        /// Aptos address of the LP
+        #[arg(long)]
+        lp_addr: String,
+        /// LP fee in basis points
+        #[arg(long)]
+        lp_fee: u64,
+        #[command(flatten)]
+        submit: SubmitArgs,
+    },
+    /// Request a withdrawal through an LP
+    Withdraw {
+        /// Withdraw ID
+        #[arg(long)]
+        withdraw_id: u64,
+        /// Bitcoin address receiving the payout
+        #[arg(long)]
+        btc_address: BtcAddress,
+        /// Smallest payout the receiver accepts
+        #[arg(long)]
+        receive_min_amount: Sats,
+        /// LP ID
+        #[arg(long)]
+        lp_id: u64,
+        /// Amount withdrawn
+        #[arg(long)]
+        amount: Sats,
+        /// Fee rate in sat/vB
+        #[arg(long)]
+        fee_rate: u64,
+        #[command(flatten)]
+        submit: SubmitArgs,
+    },
+    /// Claim an LP withdrawal with the payout's inclusion proof
+    Claim {
+        /// Withdraw ID
+        #[arg(long)]
+        withdraw_id: u64,
+        /// BTC block height of the payout
+        #[arg(long)]
+        block_num: u64,
+        /// Output index of the payout
+        #[arg(long)]
+        tx_out_ix: u64,
+        /// Amount paid to the receiver
+        #[arg(long)]
+        amount: Sats,
+        /// Path to the payout's `TxProof` JSON
+        #[arg(long)]
+        proof: PathBuf,
+        #[command(flatten)]
+        submit: SubmitArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum QueryCommand {
+    /// Transaction status and bridge events
+    Tx {
+        /// Transaction hash
+        tx_hash: String,
+    },
+    /// LP status, or a withdrawal with `--withdraw-id`
+    Lp {
+        /// LP ID
+        #[arg(long, required_unless_present = "withdraw_id")]
+        lp_id: Option<u64>,
+        /// Withdraw ID
+        #[arg(long)]
+        withdraw_id: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+enum EventsCommand {
+    /// Stream live bridge events from the indexer to stdout as NDJSON
+    Tail {
+        /// Print events after this ledger version
+        #[arg(long, default_value_t = 0)]
+        from_version: u64,
+        /// Seconds between polls
+        #[arg(long, default_value_t = 10)]
+        interval: u64,
+        /// Only print these event types (repeatable; default all)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        filter: Vec<EventKind>,
+    },
+    /// Replay historical bridge events in a version range as NDJSON
+    Backfill {
+        /// First ledger version, inclusive
+        #[arg(long)]
+        from: u64,
+        /// Last ledger version, inclusive
+        #[arg(long)]
+        to: u64,
+        /// Output file; stdout when omitted
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Ledger versions fetched per indexer query
+        #[arg(long, default_value_t = 100_000)]
+        chunk_size: u64,
+        /// Only write these event types (repeatable; default all)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        filter: Vec<EventKind>,
+    },
+}
+
+#[derive(Args)]
+struct SubmitArgs {
+    /// Return after submission instead of waiting for the transaction to commit
+    #[arg(long)]
+    no_wait: bool,
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    let output = Output::new(cli.json);
+    if let Err(e) = run(cli, &output).await {
+        output.error(&e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli, output: &Output) -> Result<()> {
+    let mut config = match &cli.config {
+        Some(path) => Config::from_file(path)?.with_env_overrides()?,
+        None => Config::from_env()?,
+    };
+    if cli.network.is_some() {
+        config.network = cli.network;
+    }
+
+    match cli.command {
+        Command::Mint { peg, submit } => {
+            let peg: Peg = read_json(&peg)?;
+            let client = BridgeClient::from_config(&config)?;
+            let tx_hash = client.mint(peg).await?;
+            report_submission(&client, output, "mint", &tx_hash, &submit).await
+        }
+        Command::Burn {
+            btc_address,
+            amount,
+            fee_rate,
+            operator_id,
+            submit,
+        } => {
+            let client = BridgeClient::from_config(&config)?;
+            let tx_hash = client
+                .burn(btc_address, fee_rate, amount, operator_id)
+                .await?;
+            report_submission(&client, output, "burn", &tx_hash, &submit).await
+        }
+        Command::Lp(command) => run_lp(command, &config, output).await,
+        Command::Query(command) => run_query(command, &config, output).await,
+        Command::Events(EventsCommand::Tail {
+            from_version,
+            interval,
+            filter,
+        }) => events::tail(&config, output, from_version, interval, filter).await,
+        Command::Events(EventsCommand::Backfill {
+            from,
+            to,
+            out,
+            chunk_size,
+            filter,
+        }) => {
+            events::backfill(
+                &config,
+                output,
+                from,
+                to,
+                out.as_deref(),
+                chunk_size,
+                filter,
+            )
+            .await
+        }
+        Command::Proof(args) => proof::run(args, output).await,
+        Command::Status => {
+            let client = BridgeClient::from_config(&config)?;
+            let ledger_version = client.get_ledger_version().await?;
+            let latest_block_height = client.get_latest_block_height().await?;
+            let min_confirmations = client.get_min_confirmations().await?;
+            output.print(&json!({
+                "network": config.network.map(|network| network.to_string()),
+                "node_url": config.node_url()?,
+                "bridge_contract_address": client.bridge_contract_address().to_hex_literal(),
+                "btc_light_client": config.btc_light_client(),
+                "ledger_version": ledger_version,
+                "latest_btc_block_height": latest_block_height,
+                "min_confirmations": min_confirmations,
+            }));
+            Ok(())
+        }
+    }
+}
+
+async fn run_lp(command: LpCommand, config: &Config, output: &Output) -> Result<()> {
+    let client = BridgeClient::from_config(config)?;
+    match command {
+        LpCommand::Register {
+            lp_id,
+            btc_address,
+            lp_addr,
+            lp_fee,
+            submit,
+        } => {
+            let tx_hash = client
+                .register_lp(RegisterLPParams {
+                    lp_id,
+                    bitcoin_addr: btc_address,
+                    lp_addr,
+                    lp_fee,
+                })
+                .await?;
+            report_submission(&client, output, "register_lp", &tx_hash, &submit).await
+        }
+        LpCommand::Withdraw {
+            withdraw_id,
+            btc_address,
+            receive_min_amount,
+            lp_id,
+            amount,
+            fee_rate,
+            submit,
+        } => {
+            let script_type = btc_address
+                .script_type()
+                .ok_or_else(|| anyhow!("Unsupported receiver address {}", btc_address))?;
+            let receiver_script_hash = script_hash_for_address(&btc_address, script_type)?;
+            let tx_hash = client
+                .withdraw_by_lp(WithdrawByLPParams {
+                    withdraw_id,
+                    btc_address,
+                    receiver_script_hash,
+                    receive_min_amount,
+                    lp_id,
+                    amount,
+                    fee_rate,
+                })
+                .await?;
+            report_submission(&client, output, "withdraw_by_lp", &tx_hash, &submit).await
+        }
+        LpCommand::Claim {
+            withdraw_id,
+            block_num,
+            tx_out_ix,
+            amount,
+            proof,
+            submit,
+        } => {
+            let inclusion_proof: TxProof = read_json(&proof)?;
+            let tx_hash = client
+                .claim_lp_withdraw(ClaimLPWithdrawParams {
+                    withdraw_id,
+                    block_num,
+                    tx_out_ix,
+                    amount_sats: amount,
+                    inclusion_proof,
+                })
+                .await?;
+            report_submission(&client, output, "claim_lp_withdraw", &tx_hash, &submit).await
+        }
+    }
+}
+
+async fn run_query(command: QueryCommand, config: &Config, output: &Output) -> Result<()> {
+    match command {
+        QueryCommand::Tx { tx_hash } => {
+            let query_client =
+                QueryClient::new(config.node_url()?, config.aptos_api_key.as_deref())?;
+            let mut summary = transaction_summary(
+                &tx_hash,
+                query_client.get_transaction_by_hash(&tx_hash).await?,
+            );
+            if summary["committed"] == json!(true) {
+                let events = query_client
+                    .get_bridge_events_by_hash(&tx_hash, config.bridge_contract_address()?)
+                    .await?;
+                summary["bridge_events"] = serde_json::to_value(events)?;
+            }
+            output.print(&summary);
+            Ok(())
+        }
+        QueryCommand::Lp { lp_id, withdraw_id } => {
+            let client = BridgeClient::from_config(config)?;
+            if let Some(withdraw_id) = withdraw_id {
+                let withdraw = client.get_lp_withdraw(withdraw_id).await?;
+                output.print(&serde_json::to_value(withdraw)?);
+            } else if let Some(lp_id) = lp_id {
+                let status = client.get_lp_status(lp_id).await?;
+                output.print(&json!({
+                    "lp_id": lp_id,
+                    "status": format!("{:?}", status),
+                }));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Print the transaction hash and, unless `--no-wait`, its committed status
+async fn report_submission(
+    client: &BridgeClient,
+    output: &Output,
+    operation: &str,
+    tx_hash: &str,
+    submit: &SubmitArgs,
+) -> Result<()> {
+    if submit.no_wait {
+        output.print(&json!({ "operation": operation, "tx_hash": tx_hash }));
+        return Ok(());
+    }
+
+    let transaction = client
+        .wait_for_transaction(
+            tx_hash,
+            Duration::from_secs(TRANSACTION_COMMIT_TIMEOUT_SECS),
+        )
+        .await?;
+    let mut summary = transaction_summary(tx_hash, transaction);
+    summary["operation"] = json!(operation);
+    output.print(&summary);
+    if summary["success"] == json!(false) {
+        return Err(anyhow!("{} transaction {} failed", operation, tx_hash));
+    }
+    Ok(())
+}
+
+fn transaction_summary(tx_hash: &str, transaction: TransactionData) -> serde_json::Value {
+    match transaction {
+        TransactionData::OnChain(txn) => {
+            let status = txn.info.status();
+            json!({
+                "tx_hash": tx_hash,
+                "committed": true,
+                "version": txn.version,
+                "success": status.is_success(),
+                "gas_used": txn.info.gas_used(),
+                "vm_status": format!("{:?}", status),
+                "abort": BridgeAbort::from_execution_status(status).map(|abort| abort.to_string()),
+            })
+        }
+        TransactionData::Pending(_) => json!({
+            "tx_hash": tx_hash,
+            "committed": false,
+        }),
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| anyhow!("Invalid JSON in {}: {}", path.display(), e))
+}
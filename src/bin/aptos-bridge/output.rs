@@ -0,0 +1,71 @@
+//! Human-readable and JSON output
+
+use serde_json::Value;
+
+/// Output format selected with `--json`
+#[derive(Debug, Clone, Copy)]
+pub struct Output {
+    json: bool,
+}
+
+impl Output {
+    pub fn new(json: bool) -> Self {
+        Self { json }
+    }
+
+    /// Print a result object: pretty JSON, or one `key: value` line per field
+    pub fn print(&self, value: &Value) {
+        if self.json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(value).unwrap_or_default()
+            );
+            return;
+        }
+        match value {
+            Value::Object(fields) => {
+                for (key, value) in fields {
+                    if !value.is_null() {
+                        println!("{}: {}", key, human(value));
+                    }
+                }
+            }
+            value => println!("{}", human(value)),
+        }
+    }
+
+    /// Like [`Output::print`], but to stderr, keeping stdout for streamed data
+    pub fn print_stderr(&self, value: &Value) {
+        if self.json {
+            eprintln!("{}", value);
+            return;
+        }
+        match value {
+            Value::Object(fields) => {
+                for (key, value) in fields {
+                    if !value.is_null() {
+                        eprintln!("{}: {}", key, human(value));
+                    }
+                }
+            }
+            value => eprintln!("{}", human(value)),
+        }
+    }
+
+    /// Print an error to stderr
+    pub fn error(&self, error: &anyhow::Error) {
+        if self.json {
+            eprintln!("{}", serde_json::json!({ "error": format!("{:#}", error) }));
+        } else {
+            eprintln!("error: {:#}", error);
+        }
+    }
+}
+
+/// Strings without quotes, everything else as compact JSON
+fn human(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,84 @@
+//! `proof`: inclusion proofs for confirmed BTC transactions
+
+use crate::output::Output;
+use anyhow::{anyhow, Result};
+use aptos_client_sdk::ProofBuilder;
+use clap::{Args, ValueEnum};
+use serde_json::json;
+use std::path::PathBuf;
+
+/// Bitcoin data source selected with `--backend`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Backend {
+    /// Bitcoin Core JSON-RPC (needs `-txindex`)
+    Bitcoind,
+    /// Esplora/Electrs HTTP API
+    Esplora,
+}
+
+#[derive(Args)]
+pub struct ProofArgs {
+    /// BTC transaction ID, display byte order
+    #[arg(long)]
+    btc_txid: String,
+    /// Deposit output index; with `--recipient`, a `Peg` is built for it
+    #[arg(long, requires = "recipient")]
+    vout: Option<u32>,
+    /// Aptos address receiving the minted tokens
+    #[arg(long, requires = "vout")]
+    recipient: Option<String>,
+    /// Proof data source
+    #[arg(long, value_enum)]
+    backend: Backend,
+    /// Bitcoin Core RPC URL or Esplora API base URL
+    #[arg(long)]
+    btc_url: String,
+    /// Bitcoin Core RPC username
+    #[arg(long, requires = "rpc_password")]
+    rpc_user: Option<String>,
+    /// Bitcoin Core RPC password
+    #[arg(long, requires = "rpc_user")]
+    rpc_password: Option<String>,
+    /// Write the JSON here instead of stdout
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+/// Build a `Peg` (with `--vout` and `--recipient`) or a bare `TxProof`
+///
+/// The proof JSON is written as-is, ready for `mint --peg` or `lp claim --proof`;
+/// the block height of a bare proof is reported on stderr.
+pub async fn run(args: ProofArgs, output: &Output) -> Result<()> {
+    let mut builder = match args.backend {
+        Backend::Bitcoind => ProofBuilder::bitcoind(&args.btc_url),
+        Backend::Esplora => ProofBuilder::esplora(&args.btc_url),
+    };
+    if let (Some(user), Some(password)) = (&args.rpc_user, &args.rpc_password) {
+        builder = builder.with_basic_auth(user, password);
+    }
+
+    let proof = match (args.vout, &args.recipient) {
+        (Some(vout), Some(recipient)) => {
+            let peg = builder.build_peg(&args.btc_txid, vout, recipient).await?;
+            serde_json::to_string_pretty(&peg)
+        }
+        _ => {
+            let (tx_proof, block_num) = builder.build_tx_proof(&args.btc_txid).await?;
+            output.print_stderr(&json!({
+                "btc_txid": args.btc_txid,
+                "block_num": block_num,
+            }));
+            serde_json::to_string_pretty(&tx_proof)
+        }
+    }
+    .map_err(|e| anyhow!("Failed to serialize proof: {}", e))?;
+
+    match &args.out {
+        Some(path) => std::fs::write(path, proof + "\n")
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e)),
+        None => {
+            println!("{}", proof);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Bitcoin Core RPC client
+//!
+//! Minimal async JSON-RPC client covering the calls used by the proof builder
+//! and the light-client header sync.
+
+use crate::utils::decode_hex;
+use anyhow::{anyhow, Result};
+use bitcoin::block::Header;
+use bitcoin::{Block, BlockHash, Txid};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// Bitcoin Core JSON-RPC response
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+/// Bitcoin Core JSON-RPC error
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+/// `getrawtransaction` verbose result (fields used here)
+#[derive(Debug, Deserialize)]
+struct RawTransactionInfo {
+    blockhash: Option<String>,
+}
+
+/// `getblockheader` verbose result (fields used here)
+#[derive(Debug, Deserialize)]
+struct BlockHeaderInfo {
+    height: u64,
+}
+
+/// Bitcoin Core RPC client
+#[derive(Debug, Clone)]
+pub struct BitcoindRpc {
+    /// RPC URL
+    url: String,
+    /// RPC username and password
+    basic_auth: Option<(String, String)>,
+    /// HTTP client
+    http_client: reqwest::Client,
+}
+
+impl BitcoindRpc {
+    /// Create a client for a Bitcoin Core RPC endpoint
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            basic_auth: None,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// Authenticate with `rpcuser`/`rpcpassword`
+    pub fn with_basic_auth(mut self, user: &str, password: &str) -> Self {
+        self.basic_auth = Some((user.to_string(), password.to_string()));
+        self
+    }
+
+    /// Use a preconfigured HTTP client (proxies, TLS settings, timeouts)
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Height of the best chain tip
+    pub async fn get_block_count(&self) -> Result<u64> {
+        self.call("getblockcount", serde_json::json!([])).await
+    }
+
+    /// Hash of the best-chain block at `height`
+    pub async fn get_block_hash(&self, height: u64) -> Result<BlockHash> {
+        let hash: String = self
+            .call("getblockhash", serde_json::json!([height]))
+            .await?;
+        BlockHash::from_str(&hash).map_err(|e| anyhow!("Invalid block hash '{}': {}", hash, e))
+    }
+
+    /// Header of block `hash`
+    pub async fn get_block_header(&self, hash: &BlockHash) -> Result<Header> {
+        let header_hex: String = self
+            .call(
+                "getblockheader",
+                serde_json::json!([hash.to_string(), false]),
+            )
+            .await?;
+        bitcoin::consensus::deserialize(&decode_hex(&header_hex)?)
+            .map_err(|e| anyhow!("Failed to decode block header {}: {}", hash, e))
+    }
+
+    /// Height of block `hash`
+    pub async fn get_block_height(&self, hash: &BlockHash) -> Result<u64> {
+        let info: BlockHeaderInfo = self
+            .call(
+                "getblockheader",
+                serde_json::json!([hash.to_string(), true]),
+            )
+            .await?;
+        Ok(info.height)
+    }
+
+    /// Block `hash` with all transactions
+    pub async fn get_block(&self, hash: &BlockHash) -> Result<Block> {
+        let block_hex: String = self
+            .call("getblock", serde_json::json!([hash.to_string(), 0]))
+            .await?;
+        bitcoin::consensus::deserialize(&decode_hex(&block_hex)?)
+            .map_err(|e| anyhow!("Failed to decode block {}: {}", hash, e))
+    }
+
+    /// Hash of the block containing `txid`, none if unconfirmed
+    ///
+    /// Requires the node to run with `-txindex` for non-wallet transactions.
+    pub async fn get_tx_block_hash(&self, txid: &Txid) -> Result<Option<BlockHash>> {
+        let info: RawTransactionInfo = self
+            .call(
+                "getrawtransaction",
+                serde_json::json!([txid.to_string(), true]),
+            )
+            .await?;
+        info.blockhash
+            .map(|hash| {
+                BlockHash::from_str(&hash)
+                    .map_err(|e| anyhow!("Invalid block hash '{}': {}", hash, e))
+            })
+            .transpose()
+    }
+
+    /// Call a Bitcoin Core RPC method
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T> {
+        let request = serde_json::json!({
+            "jsonrpc": "1.0",
+            "id": "aptos-client-sdk",
+            "method": method,
+            "params": params,
+        });
+
+        let mut http_request = self.http_client.post(&self.url).json(&request);
+        if let Some((user, password)) = &self.basic_auth {
+            http_request = http_request.basic_auth(user, Some(password));
+        }
+
+        // Bitcoin Core reports RPC errors with non-2xx statuses and a JSON body
+        let response = http_request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to send {} request to bitcoind: {}", method, e))?
+            .json::<RpcResponse<T>>()
+            .await
+            .map_err(|e| anyhow!("Failed to parse bitcoind {} response: {}", method, e))?;
+
+        if let Some(error) = response.error {
+            return Err(anyhow!(
+                "bitcoind {} failed ({}): {}",
+                method,
+                error.code,
+                error.message
+            ));
+        }
+        response
+            .result
+            .ok_or_else(|| anyhow!("bitcoind {} returned no result", method))
+    }
+}
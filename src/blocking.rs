@@ -0,0 +1,554 @@
+//! Blocking clients
+//!
+//! [`BridgeClient`] and [`QueryClient`] wrap the async clients and drive them on
+//! an internal current-thread Tokio runtime, for build scripts and tools that
+//! don't run an executor. Enabled by the `blocking` feature.
+//!
+//! Like `reqwest::blocking`, these clients must not be used from inside an async
+//! runtime: blocking on a future there panics.
+
+use crate::btc::{BtcAddress, BtcNetwork};
+use crate::config::{Config, GasSettings, RetryPolicy};
+use crate::contract::ContractSpec;
+use crate::lp_select::{LPConstraints, WithdrawQuote};
+use crate::networks::Network;
+#[cfg(feature = "events-rest")]
+use crate::types::{BridgeEvent, BridgeEventRecord};
+use crate::types::{
+    ClaimLPWithdrawParams, LPInfo, LPStatus, LPWithdraw, LedgerInfo, Peg, RegisterLPParams, Sats,
+    TransactionEvent, TxKind, WithdrawByLPParams,
+};
+use anyhow::{anyhow, Result};
+use aptos_sdk::rest_client::aptos_api_types::TransactionData;
+use aptos_sdk::types::account_address::AccountAddress;
+use aptos_sdk::types::LocalAccount;
+use secrecy::SecretString;
+use std::future::Future;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// Current-thread runtime driving the wrapped client
+struct BlockingRuntime(Runtime);
+
+impl BlockingRuntime {
+    fn new() -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| anyhow!("Failed to start blocking runtime: {}", e))?;
+        Ok(Self(runtime))
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.0.block_on(future)
+    }
+}
+
+/// Blocking wrapper around [`crate::QueryClient`]
+pub struct QueryClient {
+    inner: crate::QueryClient,
+    runtime: BlockingRuntime,
+}
+
+impl QueryClient {
+    /// Create a new query client
+    pub fn new(node_url: &str, aptos_api_key: Option<&str>) -> Result<Self> {
+        Self::from_async(crate::QueryClient::new(node_url, aptos_api_key)?)
+    }
+
+    /// Wrap an async query client
+    pub fn from_async(inner: crate::QueryClient) -> Result<Self> {
+        Ok(Self {
+            inner,
+            runtime: BlockingRuntime::new()?,
+        })
+    }
+
+    /// The wrapped async client
+    pub fn inner(&self) -> &crate::QueryClient {
+        &self.inner
+    }
+
+    /// Get the current ledger version of the node
+    pub fn get_ledger_version(&self) -> Result<u64> {
+        self.runtime.block_on(self.inner.get_ledger_version())
+    }
+
+    /// Get the node's chain id, ledger version and timestamp, epoch and block height
+    pub fn get_ledger_info(&self) -> Result<LedgerInfo> {
+        self.runtime.block_on(self.inner.get_ledger_info())
+    }
+
+    /// Check that the node is on the expected network and not stale
+    pub fn check_node_health(&self, max_staleness: Duration) -> Result<LedgerInfo> {
+        self.runtime
+            .block_on(self.inner.check_node_health(max_staleness))
+    }
+
+    /// Wait until the node's ledger has reached `version`
+    pub fn wait_for_version(&self, version: u64, timeout: Duration) -> Result<u64> {
+        self.runtime
+            .block_on(self.inner.wait_for_version(version, timeout))
+    }
+
+    /// Versions of the transactions committed between two Unix timestamps
+    pub fn get_version_range_for_time(
+        &self,
+        start_secs: u64,
+        end_secs: u64,
+    ) -> Result<Option<RangeInclusive<u64>>> {
+        self.runtime
+            .block_on(self.inner.get_version_range_for_time(start_secs, end_secs))
+    }
+
+    /// Query transaction status
+    pub fn get_transaction_by_hash(&self, tx_hash: &str) -> Result<TransactionData> {
+        self.runtime
+            .block_on(self.inner.get_transaction_by_hash(tx_hash))
+    }
+
+    /// Kind of the transaction with hash `tx_hash`
+    pub fn get_transaction_kind(&self, tx_hash: &str) -> Result<TxKind> {
+        self.runtime
+            .block_on(self.inner.get_transaction_kind(tx_hash))
+    }
+
+    /// Whether `tx_hash` is a committed user transaction
+    pub fn is_user_transaction(&self, tx_hash: &str) -> Result<bool> {
+        self.runtime
+            .block_on(self.inner.is_user_transaction(tx_hash))
+    }
+
+    /// Every event emitted by a user transaction, with its type and JSON data
+    pub fn get_all_events_by_tx_hash(&self, tx_hash: &str) -> Result<Vec<TransactionEvent>> {
+        self.runtime
+            .block_on(self.inner.get_all_events_by_tx_hash(tx_hash))
+    }
+
+    /// Wait until a transaction is committed on chain
+    pub fn wait_for_transaction(
+        &self,
+        tx_hash: &str,
+        timeout: Duration,
+    ) -> Result<TransactionData> {
+        self.runtime
+            .block_on(self.inner.wait_for_transaction(tx_hash, timeout))
+    }
+
+    /// Get the hash of the transaction at `version`
+    pub fn get_tx_hash_by_version(&self, version: u64) -> Result<String> {
+        self.runtime
+            .block_on(self.inner.get_tx_hash_by_version(version))
+    }
+
+    /// Get bridge events from user transaction hash
+    #[cfg(feature = "events-rest")]
+    pub fn get_bridge_events_by_hash(
+        &self,
+        tx_hash: &str,
+        bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEvent>> {
+        self.runtime.block_on(
+            self.inner
+                .get_bridge_events_by_hash(tx_hash, bridge_contract_address),
+        )
+    }
+
+    /// Get bridge events from user transaction hash, with their position and
+    /// transaction metadata
+    #[cfg(feature = "events-rest")]
+    pub fn get_bridge_events_detailed(
+        &self,
+        tx_hash: &str,
+        bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEventRecord>> {
+        self.runtime.block_on(
+            self.inner
+                .get_bridge_events_detailed(tx_hash, bridge_contract_address),
+        )
+    }
+
+    /// Bridge events committed between two Unix timestamps (seconds, both inclusive)
+    #[cfg(feature = "events-rest")]
+    pub fn get_bridge_events_in_time_range(
+        &self,
+        start_secs: u64,
+        end_secs: u64,
+        bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEvent>> {
+        self.runtime
+            .block_on(self.inner.get_bridge_events_in_time_range(
+                start_secs,
+                end_secs,
+                bridge_contract_address,
+            ))
+    }
+}
+
+/// Blocking wrapper around [`crate::BridgeClient`]
+pub struct BridgeClient {
+    inner: crate::BridgeClient,
+    runtime: BlockingRuntime,
+}
+
+impl BridgeClient {
+    /// Create a new Bridge client
+    pub fn new(
+        node_url: &str,
+        aptos_api_key: Option<&str>,
+        private_key: impl Into<SecretString>,
+        bridge_contract_address: &str,
+        btc_light_client: Option<&str>,
+    ) -> Result<Self> {
+        Self::from_async(crate::BridgeClient::new(
+            node_url,
+            aptos_api_key,
+            private_key,
+            bridge_contract_address,
+            btc_light_client,
+        )?)
+    }
+
+    /// Create a new Bridge client signing as `account`
+    pub fn from_account(
+        node_url: &str,
+        aptos_api_key: Option<&str>,
+        account: LocalAccount,
+        bridge_contract_address: &str,
+        btc_light_client: Option<&str>,
+    ) -> Result<Self> {
+        Self::from_async(crate::BridgeClient::from_account(
+            node_url,
+            aptos_api_key,
+            account,
+            bridge_contract_address,
+            btc_light_client,
+        )?)
+    }
+
+    /// Create a Bridge client for a public deployment
+    pub fn for_network(
+        network: Network,
+        private_key: impl Into<SecretString>,
+        aptos_api_key: Option<&str>,
+    ) -> Result<Self> {
+        Self::from_async(crate::BridgeClient::for_network(
+            network,
+            private_key,
+            aptos_api_key,
+        )?)
+    }
+
+    /// Create a Bridge client from a [`Config`]
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Self::from_async(crate::BridgeClient::from_config(config)?)
+    }
+
+    /// Create a Bridge client from environment variables
+    pub fn from_env() -> Result<Self> {
+        Self::from_async(crate::BridgeClient::from_env()?)
+    }
+
+    /// Wrap an async Bridge client
+    pub fn from_async(inner: crate::BridgeClient) -> Result<Self> {
+        Ok(Self {
+            inner,
+            runtime: BlockingRuntime::new()?,
+        })
+    }
+
+    /// The wrapped async client
+    pub fn inner(&self) -> &crate::BridgeClient {
+        &self.inner
+    }
+
+    /// Set the Bitcoin network of the bridge
+    pub fn with_btc_network(mut self, network: BtcNetwork) -> Self {
+        self.inner = self.inner.with_btc_network(network);
+        self
+    }
+
+    /// Set the module and function names of a forked or renamed deployment
+    ///
+    /// Fails if a name is not a valid Move identifier.
+    pub fn with_contract_spec(mut self, contract_spec: ContractSpec) -> Result<Self> {
+        self.inner = self.inner.with_contract_spec(contract_spec)?;
+        Ok(self)
+    }
+
+    /// Refuse to sign transactions for a node that doesn't report `chain_id`
+    pub fn with_expected_chain_id(mut self, chain_id: u8) -> Self {
+        self.inner = self.inner.with_expected_chain_id(chain_id);
+        self
+    }
+
+    /// Set the gas settings of submitted transactions
+    pub fn with_gas_settings(mut self, gas_settings: GasSettings) -> Self {
+        self.inner = self.inner.with_gas_settings(gas_settings);
+        self
+    }
+
+    /// Set the retry policy for submissions and view calls
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.inner = self.inner.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Identify the calling application in the User-Agent of node requests
+    pub fn with_app_id(mut self, app_id: &str) -> Result<Self> {
+        self.inner = self.inner.with_app_id(app_id)?;
+        Ok(self)
+    }
+
+    /// Add a header sent with every request to the node
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self> {
+        self.inner = self.inner.with_header(name, value)?;
+        Ok(self)
+    }
+
+    /// Use proxy, TLS and timeout settings for requests to the node
+    #[cfg(feature = "http-config")]
+    pub fn with_http_config(mut self, http_config: &crate::http::HttpConfig) -> Result<Self> {
+        self.inner = self.inner.with_http_config(http_config)?;
+        Ok(self)
+    }
+
+    /// Bridge contract address
+    pub fn bridge_contract_address(&self) -> AccountAddress {
+        self.inner.bridge_contract_address()
+    }
+
+    /// Mint tokens based on BTC deposits
+    pub fn mint(&self, peg: Peg) -> Result<String> {
+        self.runtime.block_on(self.inner.mint(peg))
+    }
+
+    /// Burn tokens
+    pub fn burn(
+        &self,
+        btc_address: BtcAddress,
+        fee_rate: u64,
+        amount: Sats,
+        operator_id: u64,
+    ) -> Result<String> {
+        self.runtime
+            .block_on(self.inner.burn(btc_address, fee_rate, amount, operator_id))
+    }
+
+    /// Withdraw tokens through LP mode
+    pub fn withdraw_by_lp(&self, params: WithdrawByLPParams) -> Result<String> {
+        self.runtime.block_on(self.inner.withdraw_by_lp(params))
+    }
+
+    /// Claim LP withdrawal
+    pub fn claim_lp_withdraw(&self, params: ClaimLPWithdrawParams) -> Result<String> {
+        self.runtime.block_on(self.inner.claim_lp_withdraw(params))
+    }
+
+    /// Register a new LP
+    pub fn register_lp(&self, params: RegisterLPParams) -> Result<String> {
+        self.runtime.block_on(self.inner.register_lp(params))
+    }
+
+    /// Submit consecutive 80-byte BTC block headers to the light client
+    pub fn submit_block_headers(&self, start_height: u64, headers: &[Vec<u8>]) -> Result<String> {
+        self.runtime
+            .block_on(self.inner.submit_block_headers(start_height, headers))
+    }
+
+    /// Check whether a BTC output has already been minted
+    pub fn is_peg_minted(&self, tx_id: &[u8], tx_out_ix: u64) -> Result<bool> {
+        self.runtime
+            .block_on(self.inner.is_peg_minted(tx_id, tx_out_ix))
+    }
+
+    /// Get minimum confirmations required for BTC transactions
+    pub fn get_min_confirmations(&self) -> Result<u64> {
+        self.runtime.block_on(self.inner.get_min_confirmations())
+    }
+
+    /// Get the latest block height of the light client
+    pub fn get_latest_block_height(&self) -> Result<u64> {
+        self.runtime.block_on(self.inner.get_latest_block_height())
+    }
+
+    /// Get the light client's block hash at `height`
+    pub fn get_block_hash(&self, height: u64) -> Result<Vec<u8>> {
+        self.runtime.block_on(self.inner.get_block_hash(height))
+    }
+
+    /// Get LP withdraw information
+    pub fn get_lp_withdraw(&self, withdraw_id: u64) -> Result<LPWithdraw> {
+        self.runtime
+            .block_on(self.inner.get_lp_withdraw(withdraw_id))
+    }
+
+    /// Get LP status
+    pub fn get_lp_status(&self, lp_id: u64) -> Result<LPStatus> {
+        self.runtime.block_on(self.inner.get_lp_status(lp_id))
+    }
+
+    /// Get the registration of an LP
+    pub fn get_lp_info(&self, lp_id: u64) -> Result<LPInfo> {
+        self.runtime.block_on(self.inner.get_lp_info(lp_id))
+    }
+
+    /// Quote a withdraw of `amount` through `lp_id`, paid out at `fee_rate` sat/vB
+    pub fn quote_withdraw_by_lp(
+        &self,
+        amount: Sats,
+        lp_id: u64,
+        fee_rate: u64,
+    ) -> Result<WithdrawQuote> {
+        self.runtime
+            .block_on(self.inner.quote_withdraw_by_lp(amount, lp_id, fee_rate))
+    }
+
+    /// Cheapest active LP able to serve a withdraw of `amount`
+    pub fn select_lp(&self, amount: Sats, constraints: &LPConstraints) -> Result<LPInfo> {
+        self.runtime
+            .block_on(self.inner.select_lp(amount, constraints))
+    }
+
+    /// Get the LP registered with the Aptos account `aptos_addr`, if any
+    pub fn find_lp_by_address(&self, aptos_addr: &str) -> Result<Option<LPInfo>> {
+        self.runtime
+            .block_on(self.inner.find_lp_by_address(aptos_addr))
+    }
+
+    /// Get BTC peg balance with the given address
+    pub fn get_btc_peg_balance(&self, address: &str) -> Result<Sats> {
+        self.runtime
+            .block_on(self.inner.get_btc_peg_balance(address))
+    }
+
+    /// Get the current ledger version of the node
+    pub fn get_ledger_version(&self) -> Result<u64> {
+        self.runtime.block_on(self.inner.get_ledger_version())
+    }
+
+    /// Get the node's chain id, ledger version and timestamp, epoch and block height
+    pub fn get_ledger_info(&self) -> Result<LedgerInfo> {
+        self.runtime.block_on(self.inner.get_ledger_info())
+    }
+
+    /// Check that the node is on the expected network and not stale
+    pub fn check_node_health(&self, max_staleness: Duration) -> Result<LedgerInfo> {
+        self.runtime
+            .block_on(self.inner.check_node_health(max_staleness))
+    }
+
+    /// Wait until the node's ledger has reached `version`
+    pub fn wait_for_version(&self, version: u64, timeout: Duration) -> Result<u64> {
+        self.runtime
+            .block_on(self.inner.wait_for_version(version, timeout))
+    }
+
+    /// Versions of the transactions committed between two Unix timestamps
+    pub fn get_version_range_for_time(
+        &self,
+        start_secs: u64,
+        end_secs: u64,
+    ) -> Result<Option<RangeInclusive<u64>>> {
+        self.runtime
+            .block_on(self.inner.get_version_range_for_time(start_secs, end_secs))
+    }
+
+    /// Query transaction status
+    pub fn get_transaction_by_hash(&self, tx_hash: &str) -> Result<TransactionData> {
+        self.runtime
+            .block_on(self.inner.get_transaction_by_hash(tx_hash))
+    }
+
+    /// Kind of the transaction with hash `tx_hash`
+    pub fn get_transaction_kind(&self, tx_hash: &str) -> Result<TxKind> {
+        self.runtime
+            .block_on(self.inner.get_transaction_kind(tx_hash))
+    }
+
+    /// Whether `tx_hash` is a committed user transaction
+    pub fn is_user_transaction(&self, tx_hash: &str) -> Result<bool> {
+        self.runtime
+            .block_on(self.inner.is_user_transaction(tx_hash))
+    }
+
+    /// Every event emitted by a user transaction, with its type and JSON data
+    pub fn get_all_events_by_tx_hash(&self, tx_hash: &str) -> Result<Vec<TransactionEvent>> {
+        self.runtime
+            .block_on(self.inner.get_all_events_by_tx_hash(tx_hash))
+    }
+
+    /// Wait until a transaction is committed on chain
+    pub fn wait_for_transaction(
+        &self,
+        tx_hash: &str,
+        timeout: Duration,
+    ) -> Result<TransactionData> {
+        self.runtime
+            .block_on(self.inner.wait_for_transaction(tx_hash, timeout))
+    }
+
+    /// Get bridge events from user transaction hash
+    #[cfg(feature = "events-rest")]
+    pub fn get_bridge_events_by_hash(
+        &self,
+        tx_hash: &str,
+        bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEvent>> {
+        self.runtime.block_on(
+            self.inner
+                .get_bridge_events_by_hash(tx_hash, bridge_contract_address),
+        )
+    }
+
+    /// Get bridge events from user transaction hash, with their position and
+    /// transaction metadata
+    #[cfg(feature = "events-rest")]
+    pub fn get_bridge_events_detailed(
+        &self,
+        tx_hash: &str,
+        bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEventRecord>> {
+        self.runtime.block_on(
+            self.inner
+                .get_bridge_events_detailed(tx_hash, bridge_contract_address),
+        )
+    }
+
+    /// Bridge events committed between two Unix timestamps (seconds, both inclusive)
+    #[cfg(feature = "events-rest")]
+    pub fn get_bridge_events_in_time_range(
+        &self,
+        start_secs: u64,
+        end_secs: u64,
+        bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEvent>> {
+        self.runtime
+            .block_on(self.inner.get_bridge_events_in_time_range(
+                start_secs,
+                end_secs,
+                bridge_contract_address,
+            ))
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::testing::{self, LEDGER_VERSION};
+
+    #[test]
+    fn test_blocking_clients_against_fixtures() {
+        let server_runtime = tokio::runtime::Runtime::new().unwrap();
+        let server = server_runtime.block_on(testing::mock_fullnode());
+        let node_url = testing::fullnode_url(&server);
+
+        let client = BridgeClient::from_async(testing::bridge_client(&server)).unwrap();
+        assert_eq!(client.get_latest_block_height().unwrap(), 850_000);
+        assert_eq!(client.get_lp_status(1).unwrap(), LPStatus::ACTIVE);
+
+        let query_client = QueryClient::new(&node_url, None).unwrap();
+        assert_eq!(query_client.get_ledger_version().unwrap(), LEDGER_VERSION);
+    }
+}
@@ -2,28 +2,54 @@
 //!
 //! Provides core functionality for interacting with Aptos Bridge contracts.
 
+use crate::abi::{entry_function_mismatch, ENTRY_FUNCTIONS};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::audit::AuditLog;
+use crate::bridge_handle::BridgeHandle;
+use crate::btc::{BtcAddress, BtcNetwork};
+use crate::config::{Config, GasSettings, RetryPolicy, TxOptions};
+use crate::contract::{ContractIds, ContractModule, ContractSpec};
+#[cfg(feature = "fee-oracle")]
+use crate::fee_oracle::{FeeOracle, FeeTarget};
+use crate::keys;
+use crate::lp_select::{LPConstraints, WithdrawQuote};
+use crate::networks::Network;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::observer::TxObserver;
+#[cfg(feature = "fee-oracle")]
+use crate::operators::{AutoBurn, OperatorPreferences};
+use crate::payload::PayloadBuilder;
+use crate::request_log::logged;
+use crate::signer::TransactionSigner;
 use crate::types::{
-    constants::*, ClaimLPWithdrawParams, LPStatus, LPWithdraw, Peg, RegisterLPParams,
-    WithdrawByLPParams,
+    constants::*, AlreadyMinted, BridgeConfig, ClaimLPWithdrawParams, EventSchemaVersion,
+    InsufficientBridgeBalance, InsufficientGasFunds, LPInfo, LPStatus, LPWithdraw, LPWithdrawRaw,
+    OperatorInfo, Peg, PegRecord, PegRecordRaw, RegisterLPParams, Sats, WithdrawByLPParams,
+    WithdrawStatus,
 };
-use crate::utils::parse_account_address;
+use crate::utils::{decode_hex, parse_account_address};
 use crate::QueryClient;
 
 use anyhow::{anyhow, Result};
 use aptos_sdk::move_types::identifier::Identifier;
-use aptos_sdk::move_types::language_storage::ModuleId;
-use aptos_sdk::rest_client::aptos_api_types::{EntryFunctionId, IdentifierWrapper, MoveModuleId};
-use aptos_sdk::rest_client::{AptosBaseUrl, ClientBuilder};
+use aptos_sdk::move_types::language_storage::TypeTag;
+use aptos_sdk::rest_client::aptos_api_types::{
+    EntryFunctionId, IdentifierWrapper, MoveFunction, MoveModuleId, MoveType,
+};
 use aptos_sdk::transaction_builder::TransactionBuilder;
 use aptos_sdk::{
     rest_client::{aptos_api_types::ViewRequest, Client},
     types::{
         account_address::AccountAddress,
         chain_id::ChainId,
-        transaction::{EntryFunction, TransactionPayload},
+        transaction::{SignedTransaction, TransactionPayload},
         LocalAccount,
     },
 };
+use secrecy::SecretString;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
 use url::Url;
 
 /// Bridge client
@@ -32,12 +58,31 @@ pub struct BridgeClient {
     rest_client: Client,
     /// Query client
     query_client: QueryClient,
-    /// Local account
-    account: LocalAccount,
+    /// Signer of submitted transactions
+    signer: Arc<dyn TransactionSigner>,
     /// Bridge contract address
     bridge_contract_address: AccountAddress,
     /// BTC Light client
     btc_light_client: Option<AccountAddress>,
+    /// Bitcoin network of the bridge, used to reject addresses from other networks
+    btc_network: Option<BtcNetwork>,
+    /// Module and function identifiers of the deployment
+    contract_ids: ContractIds,
+    /// Gas settings of submitted transactions
+    gas_settings: GasSettings,
+    /// Retry policy for submissions and view calls
+    retry_policy: RetryPolicy,
+    /// BTC fee-rate oracle, defaults to mempool.space
+    #[cfg(feature = "fee-oracle")]
+    fee_oracle: Option<FeeOracle>,
+    /// Audit trail of submitted transactions
+    #[cfg(not(target_arch = "wasm32"))]
+    audit_log: Option<Arc<dyn AuditLog>>,
+    /// Observers of each transaction's lifecycle
+    #[cfg(not(target_arch = "wasm32"))]
+    observers: Vec<Arc<dyn TxObserver>>,
+    /// Check gas and burn funds before signing
+    preflight_checks: bool,
 }
 
 impl BridgeClient {
@@ -45,7 +90,44 @@ impl BridgeClient {
     pub fn new(
         node_url: &str,
         aptos_api_key: Option<&str>,
-        private_key_hex: &str,
+        private_key: impl Into<SecretString>,
+        bridge_contract_address: &str,
+        btc_light_client: Option<&str>,
+    ) -> Result<Self> {
+        Self::from_account(
+            node_url,
+            aptos_api_key,
+            keys::account_from_private_key(&private_key.into())?,
+            bridge_contract_address,
+            btc_light_client,
+        )
+    }
+
+    /// Create new Bridge client signing as `account`
+    ///
+    /// See [`crate::keys`] for loading accounts from keystores, mnemonics and
+    /// Aptos CLI profiles.
+    pub fn from_account(
+        node_url: &str,
+        aptos_api_key: Option<&str>,
+        account: LocalAccount,
+        bridge_contract_address: &str,
+        btc_light_client: Option<&str>,
+    ) -> Result<Self> {
+        Self::from_signer(
+            node_url,
+            aptos_api_key,
+            Arc::new(account),
+            bridge_contract_address,
+            btc_light_client,
+        )
+    }
+
+    /// Create new Bridge client signing with `signer`, e.g. a key held in Vault
+    pub fn from_signer(
+        node_url: &str,
+        aptos_api_key: Option<&str>,
+        signer: Arc<dyn TransactionSigner>,
         bridge_contract_address: &str,
         btc_light_client: Option<&str>,
     ) -> Result<Self> {
@@ -56,169 +138,464 @@ impl BridgeClient {
             .map(|s| parse_account_address(s))
             .transpose()?;
 
-        let aptos_base_url = AptosBaseUrl::Custom(
-            Url::parse(node_url)
-                .map_err(|e| anyhow!("Invalid Aptos node URL '{}': {}", node_url, e))?,
-        );
-
-        // Create REST client
-        let mut client_builder = ClientBuilder::new(aptos_base_url);
-        if let Some(api_key) = aptos_api_key {
-            client_builder = client_builder.api_key(api_key)?;
-        }
-        let rest_client = client_builder.build();
+        Url::parse(node_url)
+            .map_err(|e| anyhow!("Invalid Aptos node URL '{}': {}", node_url, e))?;
 
-        // Create query client
+        // Create query client, whose REST client is shared for submissions
         let query_client = QueryClient::new(node_url, aptos_api_key)?;
-
-        let account = LocalAccount::from_private_key(private_key_hex, 0).map_err(|e| {
-            anyhow!(
-                "Invalid aptos private key format '{}': {}",
-                private_key_hex,
-                e
-            )
-        })?;
+        let rest_client = query_client.rest_client().clone();
 
         Ok(Self {
             rest_client,
             query_client,
-            account,
+            signer,
             bridge_contract_address,
             btc_light_client,
+            btc_network: None,
+            contract_ids: ContractIds::default(),
+            gas_settings: GasSettings::default(),
+            retry_policy: RetryPolicy::default(),
+            #[cfg(feature = "fee-oracle")]
+            fee_oracle: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            audit_log: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            observers: Vec::new(),
+            preflight_checks: false,
         })
     }
 
+    /// Create a Bridge client for a public deployment
+    ///
+    /// Uses the network's fullnode, contract addresses and Bitcoin network.
+    pub fn for_network(
+        network: Network,
+        private_key: impl Into<SecretString>,
+        aptos_api_key: Option<&str>,
+    ) -> Result<Self> {
+        let config = network.config();
+        let mut client = Self::new(
+            config.fullnode_url,
+            aptos_api_key,
+            private_key,
+            network.bridge_contract_address()?,
+            config.btc_light_client,
+        )?
+        .with_btc_network(config.btc_network);
+        if let Some(chain_id) = config.chain_id {
+            client = client.with_expected_chain_id(chain_id);
+        }
+        Ok(client)
+    }
+
+    /// Create a Bridge client from a [`Config`]
+    pub fn from_config(config: &Config) -> Result<Self> {
+        config.validate()?;
+        let mut client = Self::new(
+            config.node_url()?,
+            config.aptos_api_key.as_deref(),
+            config.private_key()?.clone(),
+            config.bridge_contract_address()?,
+            config.btc_light_client(),
+        )?
+        .with_gas_settings(config.gas)
+        .with_retry_policy(config.retry);
+        if let Some(btc_network) = config.btc_network() {
+            client = client.with_btc_network(btc_network);
+        }
+        if let Some(chain_id) = config.network.and_then(|network| network.config().chain_id) {
+            client = client.with_expected_chain_id(chain_id);
+        }
+        Ok(client)
+    }
+
+    /// Create a Bridge client from environment variables
+    ///
+    /// See [`crate::config`] for the variables read.
+    pub fn from_env() -> Result<Self> {
+        Self::from_config(&Config::from_env()?)
+    }
+
+    /// Create new Bridge client and check the deployed contract ABI
+    ///
+    /// Same as [`BridgeClient::new`] followed by [`BridgeClient::check_contract_abi`].
+    pub async fn new_checked(
+        node_url: &str,
+        aptos_api_key: Option<&str>,
+        private_key: impl Into<SecretString>,
+        bridge_contract_address: &str,
+        btc_light_client: Option<&str>,
+    ) -> Result<Self> {
+        let client = Self::new(
+            node_url,
+            aptos_api_key,
+            private_key,
+            bridge_contract_address,
+            btc_light_client,
+        )?;
+        client.check_contract_abi().await?;
+        Ok(client)
+    }
+
+    /// Identify the calling application, sent as
+    /// `User-Agent: fiamma-aptos-client-sdk/<version> <app_id>`
+    pub fn with_app_id(mut self, app_id: &str) -> Result<Self> {
+        self.query_client = self.query_client.with_app_id(app_id)?;
+        self.rest_client = self.query_client.rest_client().clone();
+        Ok(self)
+    }
+
+    /// Add a header sent with every request to the node
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self> {
+        self.query_client = self.query_client.with_header(name, value)?;
+        self.rest_client = self.query_client.rest_client().clone();
+        Ok(self)
+    }
+
+    /// Use proxy, TLS and timeout settings for requests to the node
+    #[cfg(all(feature = "http-config", not(target_arch = "wasm32")))]
+    pub fn with_http_config(mut self, http_config: &crate::http::HttpConfig) -> Result<Self> {
+        self.query_client = self.query_client.with_http_config(http_config)?;
+        self.rest_client = self.query_client.rest_client().clone();
+        Ok(self)
+    }
+
+    /// Set the Bitcoin network of the bridge
+    ///
+    /// When set, `burn`, `withdraw_by_lp`, `register_lp` and
+    /// `quote_withdraw_by_lp` reject Bitcoin addresses that belong to a
+    /// different network.
+    pub fn with_btc_network(mut self, network: BtcNetwork) -> Self {
+        self.btc_network = Some(network);
+        self
+    }
+
+    /// Set the module and function names of a forked or renamed deployment
+    ///
+    /// Fails if a name is not a valid Move identifier.
+    pub fn with_contract_spec(mut self, contract_spec: ContractSpec) -> Result<Self> {
+        self.query_client = self
+            .query_client
+            .with_bridge_module(&contract_spec.bridge_module);
+        self.contract_ids = ContractIds::new(contract_spec)?;
+        Ok(self)
+    }
+
+    /// Set the gas settings of submitted transactions
+    pub fn with_gas_settings(mut self, gas_settings: GasSettings) -> Self {
+        self.gas_settings = gas_settings;
+        self
+    }
+
+    /// Check the sender's APT (and, for `burn`, pegged BTC) balance before
+    /// signing, failing with [`InsufficientGasFunds`] or
+    /// [`InsufficientBridgeBalance`] instead of an on-chain abort (default off)
+    ///
+    /// The checks cost extra view calls per transaction. They apply to the
+    /// transaction methods of this client, `submit_with_options` and
+    /// `sign_only`; [`SubmissionPipeline`](crate::SubmissionPipeline) and
+    /// [`TxQueue`](crate::TxQueue) sign without them.
+    pub fn with_preflight_checks(mut self, enabled: bool) -> Self {
+        self.preflight_checks = enabled;
+        self
+    }
+
+    /// Set the retry policy for submissions and view calls (default: no retries)
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Refuse to sign transactions for a node that doesn't report `chain_id`
+    pub fn with_expected_chain_id(mut self, chain_id: u8) -> Self {
+        self.query_client = self.query_client.with_expected_chain_id(chain_id);
+        self
+    }
+
+    /// Set the event schema version of the deployed bridge contract
+    pub fn with_event_schema_version(mut self, version: EventSchemaVersion) -> Self {
+        self.query_client = self.query_client.with_event_schema_version(version);
+        self
+    }
+
+    /// Set the BTC fee-rate oracle used by `suggest_fee_rate`
+    #[cfg(feature = "fee-oracle")]
+    pub fn with_fee_oracle(mut self, fee_oracle: FeeOracle) -> Self {
+        self.fee_oracle = Some(fee_oracle);
+        self
+    }
+
+    /// Record every signed transaction and its final status in `audit_log`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_audit_log(mut self, audit_log: Arc<dyn AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Call `observer` at each stage of every transaction's lifecycle, after
+    /// any observers added before
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_observer(mut self, observer: Arc<dyn TxObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// Suggest a BTC fee rate (sat/vB) for `burn` and `withdraw_by_lp`
+    #[cfg(feature = "fee-oracle")]
+    pub async fn suggest_fee_rate(&self, target: FeeTarget) -> Result<u64> {
+        match &self.fee_oracle {
+            Some(fee_oracle) => fee_oracle.fee_rate(target).await,
+            None => FeeOracle::mempool_space()?.fee_rate(target).await,
+        }
+    }
+
+    /// Check that the deployed entry functions take the arguments this SDK serializes
+    ///
+    /// Fails with an "SDK vs contract version mismatch" error listing every
+    /// function whose parameters differ.
+    pub async fn check_contract_abi(&self) -> Result<()> {
+        let mut modules: HashMap<&str, Vec<MoveFunction>> = HashMap::new();
+        let mut mismatches = Vec::new();
+        for (contract_module, function, expected) in ENTRY_FUNCTIONS {
+            let address = match contract_module {
+                ContractModule::LightClient => match self.btc_light_client {
+                    Some(btc_light_client) => btc_light_client,
+                    None => continue,
+                },
+                _ => self.bridge_contract_address,
+            };
+            let module = self.contract_ids.spec().module_name(*contract_module);
+            let function = self.contract_ids.spec().function_name(function);
+            if !modules.contains_key(module) {
+                modules.insert(module, self.get_module_functions(address, module).await?);
+            }
+            let found = modules[module]
+                .iter()
+                .find(|f| f.name.0.as_str() == function)
+                .map(|f| {
+                    let params: Vec<String> = f.params.iter().map(|p| p.to_string()).collect();
+                    (f.is_entry, params)
+                });
+            if let Some(mismatch) = entry_function_mismatch(
+                module,
+                function,
+                expected,
+                found
+                    .as_ref()
+                    .map(|(is_entry, params)| (*is_entry, params.as_slice())),
+            ) {
+                mismatches.push(mismatch);
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "SDK vs contract version mismatch: {}",
+                mismatches.join("; ")
+            ))
+        }
+    }
+
+    /// Exposed functions of the contract module `module` published at `address`
+    async fn get_module_functions(
+        &self,
+        address: AccountAddress,
+        module: &str,
+    ) -> Result<Vec<MoveFunction>> {
+        let bytecode = logged(
+            self.query_client.call_log(
+                "GET",
+                format_args!("accounts/{}/module/{}", address, module),
+            ),
+            self.rest_client.get_account_module(address, module),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to fetch {} module ABI: {}", module, e))?
+        .into_inner()
+        .try_parse_abi()
+        .map_err(|e| anyhow!("Failed to parse {} module ABI: {}", module, e))?;
+        bytecode
+            .abi
+            .map(|abi| abi.exposed_functions)
+            .ok_or_else(|| anyhow!("Module {} has no ABI", module))
+    }
+
+    /// Check a Bitcoin address against the configured network
+    pub(crate) fn check_btc_network(&self, address: &BtcAddress) -> Result<()> {
+        match self.btc_network {
+            Some(network) => address.require_network(network),
+            None => Ok(()),
+        }
+    }
+
     /// Mint tokens based on BTC deposits
+    ///
+    /// With the `bitcoin` feature the peg is checked with [`Peg::validate`] first.
+    /// Outputs that already have a peg record are refused with an
+    /// [`AlreadyMinted`] error instead of a transaction that would abort.
     pub async fn mint(&self, peg: Peg) -> Result<String> {
-        // Serialize peg parameters using the new method
-        let args = peg.serialize_to_args()?;
-
-        // Create Entry Function
-        let entry_function = EntryFunction::new(
-            ModuleId::new(
-                self.bridge_contract_address,
-                Identifier::new("bridge").unwrap(),
-            ),
-            Identifier::new("mint").unwrap(),
-            vec![], // No type parameters
-            args,
-        );
+        self.mint_with_type_args(peg, vec![]).await
+    }
 
-        // Execute transaction
-        let tx_hash = self
-            .execute_transaction(TransactionPayload::EntryFunction(entry_function))
-            .await?;
+    /// Mint with Move type arguments, for bridges generic over the pegged asset
+    pub async fn mint_with_type_args(&self, peg: Peg, type_args: Vec<TypeTag>) -> Result<String> {
+        // Catch inconsistent pegs and duplicates before they abort on-chain
+        #[cfg(feature = "bitcoin")]
+        peg.validate()?;
+        self.check_not_minted(&peg, &type_args).await?;
 
-        Ok(tx_hash)
+        let payload = self.payloads().mint(&peg, type_args)?;
+        self.execute_transaction(payload).await
     }
 
     /// Burn tokens
     pub async fn burn(
         &self,
-        btc_address: String,
+        btc_address: BtcAddress,
         fee_rate: u64,
-        amount: u64,
+        amount: Sats,
         operator_id: u64,
     ) -> Result<String> {
-        // Serialize parameters
-        let args = vec![
-            bcs::to_bytes(&btc_address)
-                .map_err(|e| anyhow!("Failed to serialize BTC address: {}", e))?,
-            bcs::to_bytes(&fee_rate).map_err(|e| anyhow!("Failed to serialize fee rate: {}", e))?,
-            bcs::to_bytes(&amount).map_err(|e| anyhow!("Failed to serialize amount: {}", e))?,
-            bcs::to_bytes(&operator_id)
-                .map_err(|e| anyhow!("Failed to serialize operator ID: {}", e))?,
-        ];
-
-        // Create Entry Function
-        let entry_function = EntryFunction::new(
-            ModuleId::new(
-                self.bridge_contract_address,
-                Identifier::new("bridge").unwrap(),
-            ),
-            Identifier::new("burn").unwrap(),
-            vec![], // No type parameters
-            args,
-        );
+        self.burn_with_type_args(btc_address, fee_rate, amount, operator_id, vec![])
+            .await
+    }
 
-        // Execute transaction
-        let tx_hash = self
-            .execute_transaction(TransactionPayload::EntryFunction(entry_function))
-            .await?;
+    /// Burn with Move type arguments, for bridges generic over the pegged asset
+    pub async fn burn_with_type_args(
+        &self,
+        btc_address: BtcAddress,
+        fee_rate: u64,
+        amount: Sats,
+        operator_id: u64,
+        type_args: Vec<TypeTag>,
+    ) -> Result<String> {
+        self.check_btc_network(&btc_address)?;
+        if self.preflight_checks {
+            let address = self.signer.address().to_hex_literal();
+            let balance = self
+                .get_btc_peg_balance_with_type_args(&address, &type_args)
+                .await?;
+            if balance < amount {
+                return Err(InsufficientBridgeBalance {
+                    address,
+                    balance,
+                    required: amount,
+                }
+                .into());
+            }
+        }
 
-        Ok(tx_hash)
+        let payload =
+            self.payloads()
+                .burn(&btc_address, fee_rate, amount, operator_id, type_args)?;
+        self.execute_transaction(payload).await
     }
 
     /// Withdraw tokens through LP mode
     pub async fn withdraw_by_lp(&self, params: WithdrawByLPParams) -> Result<String> {
-        // Serialize parameters using the struct method
-        let args = params.serialize_to_args()?;
-
-        // Create Entry Function
-        let entry_function = EntryFunction::new(
-            ModuleId::new(
-                self.bridge_contract_address,
-                Identifier::new("bridge").unwrap(),
-            ),
-            Identifier::new("withdraw_by_lp").unwrap(),
-            vec![], // No type parameters
-            args,
-        );
+        self.withdraw_by_lp_with_type_args(params, vec![]).await
+    }
 
-        // Execute transaction
-        let tx_hash = self
-            .execute_transaction(TransactionPayload::EntryFunction(entry_function))
-            .await?;
+    /// Withdraw through LP mode with Move type arguments
+    pub async fn withdraw_by_lp_with_type_args(
+        &self,
+        params: WithdrawByLPParams,
+        type_args: Vec<TypeTag>,
+    ) -> Result<String> {
+        self.check_btc_network(&params.btc_address)?;
 
-        Ok(tx_hash)
+        let payload = self.payloads().withdraw_by_lp(&params, type_args)?;
+        self.execute_transaction(payload).await
     }
 
     /// Claim LP withdrawal (single withdrawal)
     pub async fn claim_lp_withdraw(&self, params: ClaimLPWithdrawParams) -> Result<String> {
-        // Serialize parameters using the struct method
-        let args = params.serialize_to_args()?;
-
-        // Create Entry Function
-        let entry_function = EntryFunction::new(
-            ModuleId::new(
-                self.bridge_contract_address,
-                Identifier::new("bridge").unwrap(),
-            ),
-            Identifier::new("claim_lp_withdraw").unwrap(),
-            vec![], // No type parameters
-            args,
-        );
-
-        // Execute transaction
-        let tx_hash = self
-            .execute_transaction(TransactionPayload::EntryFunction(entry_function))
-            .await?;
-
-        Ok(tx_hash)
+        let payload = self.payloads().claim_lp_withdraw(&params)?;
+        self.execute_transaction(payload).await
     }
 
     /// Register a new LP
     pub async fn register_lp(&self, params: RegisterLPParams) -> Result<String> {
-        // Serialize parameters using the struct method
-        let args = params.serialize_to_args()?;
-
-        // Create Entry Function
-        let entry_function = EntryFunction::new(
-            ModuleId::new(
-                self.bridge_contract_address,
-                Identifier::new("lp_manager").unwrap(),
-            ),
-            Identifier::new("register_lp").unwrap(),
-            vec![], // No type parameters
-            args,
-        );
+        self.check_btc_network(&params.bitcoin_addr)?;
 
-        // Execute transaction
-        let tx_hash = self
-            .execute_transaction(TransactionPayload::EntryFunction(entry_function))
-            .await?;
+        let payload = self.payloads().register_lp(&params)?;
+        self.execute_transaction(payload).await
+    }
 
-        Ok(tx_hash)
+    /// Handle for the pegged asset `asset_type` on a multi-asset bridge
+    pub fn asset<'a>(&'a self, asset_type: &'a TypeTag) -> BridgeHandle<'a> {
+        BridgeHandle::new(self, asset_type)
+    }
+
+    /// Bridge contract address
+    pub fn bridge_contract_address(&self) -> AccountAddress {
+        self.bridge_contract_address
+    }
+
+    /// Payload builder for this deployment, for signing transactions elsewhere
+    pub fn payloads(&self) -> PayloadBuilder {
+        let builder = PayloadBuilder::new(self.bridge_contract_address)
+            .with_contract_ids(self.contract_ids.clone());
+        match self.btc_light_client {
+            Some(btc_light_client) => builder.with_light_client(btc_light_client),
+            None => builder,
+        }
+    }
+
+    /// Fail with [`AlreadyMinted`] if the peg's output has a peg record on chain
+    pub(crate) async fn check_not_minted(&self, peg: &Peg, type_args: &[TypeTag]) -> Result<()> {
+        let tx_id = &peg.inclusion_proof.tx_id;
+        if self
+            .is_peg_minted_with_type_args(tx_id, peg.tx_out_ix, type_args)
+            .await?
+        {
+            return Err(AlreadyMinted {
+                tx_id: tx_id.clone(),
+                tx_out_ix: peg.tx_out_ix,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Check whether a BTC output has already been minted
+    ///
+    /// `tx_id` is in internal byte order, as in `TxProof::tx_id`.
+    pub async fn is_peg_minted(&self, tx_id: &[u8], tx_out_ix: u64) -> Result<bool> {
+        self.is_peg_minted_with_type_args(tx_id, tx_out_ix, &[])
+            .await
+    }
+
+    /// Check whether a BTC output has already been minted, with Move type arguments
+    pub async fn is_peg_minted_with_type_args(
+        &self,
+        tx_id: &[u8],
+        tx_out_ix: u64,
+        type_args: &[TypeTag],
+    ) -> Result<bool> {
+        // Construct the view function call
+        let view_request = ViewRequest {
+            function: EntryFunctionId {
+                module: MoveModuleId {
+                    address: self.bridge_contract_address.into(),
+                    name: IdentifierWrapper(self.contract_ids.module(ContractModule::Bridge)),
+                },
+                name: IdentifierWrapper(self.contract_ids.function("is_peg_minted")?),
+            },
+            type_arguments: type_args.iter().map(MoveType::from).collect(),
+            arguments: vec![
+                serde_json::to_value(format!("0x{}", hex::encode(tx_id)))?,
+                serde_json::to_value(tx_out_ix.to_string())?,
+            ],
+        };
+
+        // Call the view function
+        let result = self.call_view(&view_request).await?;
+
+        serde_json::from_value(result)
+            .map_err(|e| anyhow!("Failed to parse is_peg_minted response as bool: {}", e))
     }
 
     /// Get minimum confirmations required for BTC transactions
@@ -228,29 +605,19 @@ impl BridgeClient {
             function: EntryFunctionId {
                 module: MoveModuleId {
                     address: self.bridge_contract_address.into(),
-                    name: IdentifierWrapper(Identifier::new("bridge").unwrap()),
+                    name: IdentifierWrapper(self.contract_ids.module(ContractModule::Bridge)),
                 },
-                name: IdentifierWrapper(Identifier::new("min_confirmations").unwrap()),
+                name: IdentifierWrapper(self.contract_ids.function("min_confirmations")?),
             },
             type_arguments: vec![],
             arguments: vec![],
         };
 
         // Call the view function
-        let response = self
-            .rest_client
-            .view(&view_request, None)
-            .await
-            .map_err(|e| anyhow!("Failed to call min_confirmations view function: {}", e))?;
-
-        // Parse the response
-        let result = response
-            .inner()
-            .get(0)
-            .ok_or_else(|| anyhow!("No response from min_confirmations view function"))?;
+        let result = self.call_view(&view_request).await?;
 
         // Parse as string then convert to u64
-        let str_val: String = serde_json::from_value(result.clone()).map_err(|e| {
+        let str_val: String = serde_json::from_value(result).map_err(|e| {
             anyhow!(
                 "Failed to parse min_confirmations response as string: {}",
                 e
@@ -265,47 +632,26 @@ impl BridgeClient {
 
     /// Get latest block height from BTC light client
     pub async fn get_latest_block_height(&self) -> Result<u64> {
-        if self.btc_light_client.is_none() {
-            return Err(anyhow!(
-                "Aptos Bridge: BTC light client is not set. Please set the BTC light client address when creating the BridgeClient."
-            ));
-        }
-
-        let btc_light_client = self.btc_light_client.unwrap();
+        let btc_light_client = self.light_client_address()?;
 
         // Construct the view function call
         let view_request = ViewRequest {
             function: EntryFunctionId {
                 module: MoveModuleId {
                     address: btc_light_client.into(),
-                    name: IdentifierWrapper(Identifier::new("btc_mirror").unwrap()),
+                    name: IdentifierWrapper(self.contract_ids.module(ContractModule::LightClient)),
                 },
-                name: IdentifierWrapper(Identifier::new("get_latest_block_height").unwrap()),
+                name: IdentifierWrapper(self.contract_ids.function("get_latest_block_height")?),
             },
             type_arguments: vec![],
             arguments: vec![],
         };
 
         // Call the view function
-        let response = self
-            .rest_client
-            .view(&view_request, None)
-            .await
-            .map_err(|e| {
-                anyhow!(
-                    "Failed to call get_latest_block_height view function: {}",
-                    e
-                )
-            })?;
-
-        // Parse the response
-        let result = response
-            .inner()
-            .get(0)
-            .ok_or_else(|| anyhow!("No response from get_latest_block_height view function"))?;
+        let result = self.call_view(&view_request).await?;
 
         // Parse as string then convert to u64
-        let str_val: String = serde_json::from_value(result.clone()).map_err(|e| {
+        let str_val: String = serde_json::from_value(result).map_err(|e| {
             anyhow!(
                 "Failed to parse latest_block_height response as string: {}",
                 e
@@ -318,36 +664,172 @@ impl BridgeClient {
         Ok(latest_block_height)
     }
 
-    /// Get LP withdraw information
-    pub async fn get_lp_withdraw(&self, withdraw_id: u64) -> Result<LPWithdraw> {
+    /// Get the hash of the light client's block at `height`, in internal byte order
+    pub async fn get_block_hash(&self, height: u64) -> Result<Vec<u8>> {
+        let btc_light_client = self.light_client_address()?;
+
         // Construct the view function call
         let view_request = ViewRequest {
             function: EntryFunctionId {
                 module: MoveModuleId {
-                    address: self.bridge_contract_address.into(),
-                    name: IdentifierWrapper(Identifier::new("bridge").unwrap()),
+                    address: btc_light_client.into(),
+                    name: IdentifierWrapper(self.contract_ids.module(ContractModule::LightClient)),
                 },
-                name: IdentifierWrapper(Identifier::new("get_lp_withdraw").unwrap()),
+                name: IdentifierWrapper(self.contract_ids.function("get_block_hash")?),
             },
             type_arguments: vec![],
-            arguments: vec![serde_json::to_value(&withdraw_id.to_string())?],
+            arguments: vec![serde_json::to_value(height.to_string())?],
         };
 
         // Call the view function
-        let response = self
-            .rest_client
-            .view(&view_request, None)
-            .await
-            .map_err(|e| anyhow!("Failed to call get_lp_withdraw view function: {}", e))?;
+        let result = self.call_view(&view_request).await?;
+
+        let hash_hex: String = serde_json::from_value(result)
+            .map_err(|e| anyhow!("Failed to parse block_hash response as string: {}", e))?;
+        decode_hex(&hash_hex)
+    }
+
+    /// Submit consecutive 80-byte BTC block headers to the light client
+    ///
+    /// `headers[0]` is the header at `start_height`.
+    pub async fn submit_block_headers(
+        &self,
+        start_height: u64,
+        headers: &[Vec<u8>],
+    ) -> Result<String> {
+        let payload = self
+            .payloads()
+            .submit_block_headers(start_height, headers)?;
+        self.execute_transaction(payload).await
+    }
+
+    /// Read the peg record of a minted BTC output straight from the bridge's
+    /// peg table, `None` if the output hasn't been minted
+    ///
+    /// `tx_id` is in internal byte order, as in `TxProof::tx_id`.
+    pub async fn get_peg_record(&self, tx_id: &[u8], tx_out_ix: u64) -> Result<Option<PegRecord>> {
+        let handle = self.bridge_table_handle(PEG_TABLE_FIELD).await?;
+        let key = serde_json::json!({
+            "tx_id": format!("0x{}", hex::encode(tx_id)),
+            "tx_out_ix": tx_out_ix.to_string(),
+        });
+        let raw: Option<PegRecordRaw> = self
+            .query_client
+            .get_table_item_typed(
+                handle,
+                &self.bridge_struct_type(PEG_KEY_STRUCT),
+                &self.bridge_struct_type(PEG_RECORD_STRUCT),
+                key,
+            )
+            .await?;
+        Ok(raw.map(PegRecord::try_from).transpose()?)
+    }
+
+    /// Read an LP withdrawal straight from the bridge's withdraw table, `None`
+    /// if there is no withdrawal with that id
+    pub async fn get_withdraw_record(&self, withdraw_id: u64) -> Result<Option<LPWithdraw>> {
+        let handle = self.bridge_table_handle(WITHDRAW_TABLE_FIELD).await?;
+        let raw: Option<LPWithdrawRaw> = self
+            .query_client
+            .get_table_item_typed(
+                handle,
+                "u64",
+                &self.bridge_struct_type(LP_WITHDRAW_STRUCT),
+                serde_json::to_value(withdraw_id.to_string())?,
+            )
+            .await?;
+        Ok(raw.map(LPWithdraw::try_from).transpose()?)
+    }
 
-        // Parse the response
-        let result = response
-            .inner()
-            .get(0)
-            .ok_or_else(|| anyhow!("No response from get_lp_withdraw view function"))?;
+    /// Handle of the `field` table of the bridge's store resource
+    async fn bridge_table_handle(&self, field: &str) -> Result<AccountAddress> {
+        let resource_type = self.bridge_struct_type(BRIDGE_STORE_RESOURCE);
+        let data = self
+            .query_client
+            .get_account_resource(self.bridge_contract_address, &resource_type)
+            .await?
+            .ok_or_else(|| {
+                anyhow!(
+                    "No {} resource at {}",
+                    resource_type,
+                    self.bridge_contract_address.to_hex_literal()
+                )
+            })?;
+        let handle = data[field]["handle"]
+            .as_str()
+            .ok_or_else(|| anyhow!("{} has no {} table", resource_type, field))?;
+        parse_account_address(handle)
+    }
+
+    /// Fully qualified name of a struct of the bridge module
+    fn bridge_struct_type(&self, name: &str) -> String {
+        format!(
+            "{}::{}::{}",
+            self.bridge_contract_address.to_hex_literal(),
+            self.contract_ids.module(ContractModule::Bridge),
+            name
+        )
+    }
+
+    /// Get LP withdraw information
+    pub async fn get_lp_withdraw(&self, withdraw_id: u64) -> Result<LPWithdraw> {
+        let result = self
+            .call_view(&self.lp_withdraw_view_request(withdraw_id)?)
+            .await?;
 
         // Parse LP withdraw data using the struct method
-        LPWithdraw::from_view_response(result)
+        LPWithdraw::from_view_response(&result)
+    }
+
+    /// Get the status of an LP withdraw
+    pub async fn get_withdraw_status(&self, withdraw_id: u64) -> Result<WithdrawStatus> {
+        let result = self
+            .call_view(&self.withdraw_status_view_request(withdraw_id)?)
+            .await?;
+        WithdrawStatus::from_view_response(&result)
+    }
+
+    /// `get_lp_withdraw` view request, for batching with [`QueryClient::view_many`]
+    pub fn lp_withdraw_view_request(&self, withdraw_id: u64) -> Result<ViewRequest> {
+        self.bridge_view_request(
+            "get_lp_withdraw",
+            vec![serde_json::to_value(withdraw_id.to_string())?],
+        )
+    }
+
+    /// `get_withdraw_status` view request, for batching with [`QueryClient::view_many`]
+    pub fn withdraw_status_view_request(&self, withdraw_id: u64) -> Result<ViewRequest> {
+        self.bridge_view_request(
+            "get_withdraw_status",
+            vec![serde_json::to_value(withdraw_id.to_string())?],
+        )
+    }
+
+    /// Get the global bridge configuration
+    pub async fn get_bridge_config(&self) -> Result<BridgeConfig> {
+        let result = self
+            .call_view(&self.bridge_view_request("get_bridge_config", vec![])?)
+            .await?;
+        BridgeConfig::from_view_response(&result)
+    }
+
+    /// View request for `function` of the bridge module
+    fn bridge_view_request(
+        &self,
+        function: &str,
+        arguments: Vec<serde_json::Value>,
+    ) -> Result<ViewRequest> {
+        Ok(ViewRequest {
+            function: EntryFunctionId {
+                module: MoveModuleId {
+                    address: self.bridge_contract_address.into(),
+                    name: IdentifierWrapper(self.contract_ids.module(ContractModule::Bridge)),
+                },
+                name: IdentifierWrapper(self.contract_ids.function(function)?),
+            },
+            type_arguments: vec![],
+            arguments,
+        })
     }
 
     /// Get LP status
@@ -357,63 +839,217 @@ impl BridgeClient {
             function: EntryFunctionId {
                 module: MoveModuleId {
                     address: self.bridge_contract_address.into(),
-                    name: IdentifierWrapper(Identifier::new("lp_manager").unwrap()),
+                    name: IdentifierWrapper(self.contract_ids.module(ContractModule::LpManager)),
                 },
-                name: IdentifierWrapper(Identifier::new("get_lp_status").unwrap()),
+                name: IdentifierWrapper(self.contract_ids.function("get_lp_status")?),
             },
             type_arguments: vec![],
             arguments: vec![serde_json::to_value(&lp_id.to_string())?],
         };
 
         // Call the view function
-        let response = self
-            .rest_client
-            .view(&view_request, None)
+        let result = self.call_view(&view_request).await?;
+
+        // Parse LP status data using the struct method
+        LPStatus::from_view_response(&result)
+    }
+
+    /// Get the registration of an LP: its fee, BTC address and status
+    pub async fn get_lp_info(&self, lp_id: u64) -> Result<LPInfo> {
+        let result = self.call_view(&self.lp_info_view_request(lp_id)?).await?;
+        LPInfo::from_view_response(&result)
+    }
+
+    /// Get the LP registered with the Aptos account `aptos_addr`, if any
+    pub async fn find_lp_by_address(&self, aptos_addr: &str) -> Result<Option<LPInfo>> {
+        let address = parse_account_address(aptos_addr)?;
+        let result = self
+            .call_view(&self.lp_manager_view_request(
+                "get_lp_id_by_address",
+                vec![serde_json::to_value(address.to_hex_literal())?],
+            )?)
+            .await?;
+
+        // Move `Option<u64>` is returned as `{"vec": []}` or `{"vec": ["<id>"]}`
+        let lp_id = match result["vec"].as_array().map(Vec::as_slice) {
+            Some([]) => return Ok(None),
+            Some([lp_id]) => lp_id
+                .as_str()
+                .and_then(|lp_id| lp_id.parse::<u64>().ok())
+                .ok_or_else(|| {
+                    anyhow!("Invalid LP id in get_lp_id_by_address response: {}", lp_id)
+                })?,
+            _ => {
+                return Err(anyhow!(
+                    "Failed to parse get_lp_id_by_address response: {}",
+                    result
+                ))
+            }
+        };
+        self.get_lp_info(lp_id).await.map(Some)
+    }
+
+    /// Cheapest active LP able to serve a withdraw of `amount`
+    ///
+    /// See [`lp_select`](crate::lp_select) for how LPs are compared.
+    pub async fn select_lp(&self, amount: Sats, constraints: &LPConstraints) -> Result<LPInfo> {
+        crate::lp_select::select_lp(self, amount, constraints).await
+    }
+
+    /// Quote a withdraw of `amount` through `lp_id`, paid out at `fee_rate` sat/vB
+    ///
+    /// The network fee is estimated for the script type of the LP's Bitcoin
+    /// address, which pays the withdraw out.
+    pub async fn quote_withdraw_by_lp(
+        &self,
+        amount: Sats,
+        lp_id: u64,
+        fee_rate: u64,
+    ) -> Result<WithdrawQuote> {
+        let lp = self.get_lp_info(lp_id).await?;
+        let lp_address: BtcAddress = lp.bitcoin_addr.parse()?;
+        self.check_btc_network(&lp_address)?;
+        let script_type = lp_address.script_type().ok_or_else(|| {
+            anyhow!(
+                "LP {} Bitcoin address {} has an unsupported script type",
+                lp_id,
+                lp.bitcoin_addr
+            )
+        })?;
+        WithdrawQuote::new(amount, &lp, fee_rate, script_type)
+    }
+
+    /// Get the IDs of every registered LP
+    pub async fn get_lp_ids(&self) -> Result<Vec<u64>> {
+        let result = self
+            .call_view(&self.lp_manager_view_request("get_lp_ids", vec![])?)
+            .await?;
+        parse_ids(result, "get_lp_ids")
+    }
+
+    /// `get_lp_info` view request, for batching with [`QueryClient::view_many`]
+    pub fn lp_info_view_request(&self, lp_id: u64) -> Result<ViewRequest> {
+        self.lp_manager_view_request(
+            "get_lp_info",
+            vec![serde_json::to_value(lp_id.to_string())?],
+        )
+    }
+
+    /// View request for `function` of the LP manager module
+    fn lp_manager_view_request(
+        &self,
+        function: &str,
+        arguments: Vec<serde_json::Value>,
+    ) -> Result<ViewRequest> {
+        Ok(ViewRequest {
+            function: EntryFunctionId {
+                module: MoveModuleId {
+                    address: self.bridge_contract_address.into(),
+                    name: IdentifierWrapper(self.contract_ids.module(ContractModule::LpManager)),
+                },
+                name: IdentifierWrapper(self.contract_ids.function(function)?),
+            },
+            type_arguments: vec![],
+            arguments,
+        })
+    }
+
+    /// Get the IDs of every registered burn operator
+    pub async fn get_operator_ids(&self) -> Result<Vec<u64>> {
+        let result = self
+            .call_view(&self.bridge_view_request("get_operator_ids", vec![])?)
+            .await?;
+        parse_ids(result, "get_operator_ids")
+    }
+
+    /// Get a burn operator's Aptos and BTC addresses and whether it is active
+    pub async fn get_operator_info(&self, operator_id: u64) -> Result<OperatorInfo> {
+        let result = self
+            .call_view(&self.operator_info_view_request(operator_id)?)
+            .await?;
+        OperatorInfo::from_view_response(&result)
+    }
+
+    /// Get every registered burn operator
+    pub async fn get_operators(&self) -> Result<Vec<OperatorInfo>> {
+        let requests = self
+            .get_operator_ids()
+            .await?
+            .into_iter()
+            .map(|operator_id| self.operator_info_view_request(operator_id))
+            .collect::<Result<Vec<_>>>()?;
+        self.view_many(&requests)
             .await
-            .map_err(|e| anyhow!("Failed to call get_lp_status view function: {}", e))?;
+            .into_iter()
+            .map(|result| OperatorInfo::from_view_response(&result?))
+            .collect()
+    }
 
-        // Parse the response
-        let result = response
-            .inner()
-            .get(0)
-            .ok_or_else(|| anyhow!("No response from get_lp_status view function"))?;
+    /// `get_operator_info` view request, for batching with [`QueryClient::view_many`]
+    pub fn operator_info_view_request(&self, operator_id: u64) -> Result<ViewRequest> {
+        self.bridge_view_request(
+            "get_operator_info",
+            vec![serde_json::to_value(operator_id.to_string())?],
+        )
+    }
 
-        // Parse LP status data using the struct method
-        LPStatus::from_view_response(result)
+    /// Burn through an automatically selected operator at a suggested fee rate
+    ///
+    /// Picks the active operator with the fewest pending burns in
+    /// `preferences` (see [`select_operator`](crate::operators::select_operator)),
+    /// asks the fee oracle for a rate meeting `fee_target`, then calls `burn`.
+    #[cfg(feature = "fee-oracle")]
+    pub async fn burn_auto(
+        &self,
+        btc_address: BtcAddress,
+        amount: Sats,
+        fee_target: FeeTarget,
+        preferences: &OperatorPreferences,
+    ) -> Result<AutoBurn> {
+        let operators = self.get_operators().await?;
+        let operator = crate::operators::select_operator(&operators, preferences)?;
+        let fee_rate = self.suggest_fee_rate(fee_target).await?;
+        let tx_hash = self
+            .burn(btc_address, fee_rate, amount, operator.operator_id)
+            .await?;
+        Ok(AutoBurn {
+            tx_hash,
+            operator_id: operator.operator_id,
+            fee_rate,
+        })
     }
 
     /// Get BTC peg balance with the given address
-    pub async fn get_btc_peg_balance(&self, address: &str) -> Result<u64> {
+    pub async fn get_btc_peg_balance(&self, address: &str) -> Result<Sats> {
+        self.get_btc_peg_balance_with_type_args(address, &[]).await
+    }
+
+    /// Get the pegged balance of `address`, with Move type arguments
+    pub async fn get_btc_peg_balance_with_type_args(
+        &self,
+        address: &str,
+        type_args: &[TypeTag],
+    ) -> Result<Sats> {
         // Construct the view function call
         let view_request = ViewRequest {
             function: EntryFunctionId {
                 module: MoveModuleId {
                     address: self.bridge_contract_address.into(),
-                    name: IdentifierWrapper(Identifier::new("btc_peg").unwrap()),
+                    name: IdentifierWrapper(self.contract_ids.module(ContractModule::BtcPeg)),
                 },
-                name: IdentifierWrapper(Identifier::new("balance_of").unwrap()),
+                name: IdentifierWrapper(self.contract_ids.function("balance_of")?),
             },
-            type_arguments: vec![],
+            type_arguments: type_args.iter().map(MoveType::from).collect(),
             arguments: vec![serde_json::to_value(&address)?],
         };
 
         // Call the view function
-        let response = self
-            .rest_client
-            .view(&view_request, None)
-            .await
-            .map_err(|e| anyhow!("Failed to call get_btc_peg_balance view function: {}", e))?;
-
-        // Parse the response
-        let result = response
-            .inner()
-            .get(0)
-            .ok_or_else(|| anyhow!("No response from get_btc_peg_balance view function"))?;
+        let result = self.call_view(&view_request).await?;
 
         // Parse as string then convert to u64
-        let str_val: String = serde_json::from_value(result.clone()).map_err(|e| {
+        let str_val: String = serde_json::from_value(result).map_err(|e| {
             anyhow!(
-                "Failed to parse latest_block_height response as string: {}",
+                "Failed to parse get_btc_peg_balance response as string: {}",
                 e
             )
         })?;
@@ -424,54 +1060,415 @@ impl BridgeClient {
             )
         })?;
 
-        Ok(balance)
+        Ok(Sats(balance))
+    }
+
+    /// Get the total pegged BTC supply
+    pub async fn get_total_peg_supply(&self) -> Result<Sats> {
+        self.get_total_peg_supply_with_type_args(&[]).await
+    }
+
+    /// Get the total pegged supply, with Move type arguments
+    pub async fn get_total_peg_supply_with_type_args(&self, type_args: &[TypeTag]) -> Result<Sats> {
+        let view_request = ViewRequest {
+            function: EntryFunctionId {
+                module: MoveModuleId {
+                    address: self.bridge_contract_address.into(),
+                    name: IdentifierWrapper(self.contract_ids.module(ContractModule::BtcPeg)),
+                },
+                name: IdentifierWrapper(self.contract_ids.function("total_supply")?),
+            },
+            type_arguments: type_args.iter().map(MoveType::from).collect(),
+            arguments: vec![],
+        };
+
+        let result = self.call_view(&view_request).await?;
+        let str_val: String = serde_json::from_value(result)
+            .map_err(|e| anyhow!("Failed to parse total_supply response as string: {}", e))?;
+        let supply: u64 = str_val
+            .parse()
+            .map_err(|e| anyhow!("Failed to convert total_supply response to u64: {}", e))?;
+
+        Ok(Sats(supply))
+    }
+
+    /// BTC light client address, required by light client calls
+    fn light_client_address(&self) -> Result<AccountAddress> {
+        self.payloads().light_client_address()
+    }
+
+    /// Call a view function and return its first return value
+    async fn call_view(&self, view_request: &ViewRequest) -> Result<serde_json::Value> {
+        let function = view_request.function.name.0.as_str();
+
+        let response = self
+            .retry_policy
+            .retry(|| self.query_client.view(view_request))
+            .await
+            // Counted once per call, however many attempts it took
+            .inspect_err(|_| {
+                #[cfg(feature = "metrics")]
+                crate::metrics::VIEW_CALL_ERRORS
+                    .with_label_values(&[function])
+                    .inc();
+            })?;
+
+        response
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No response from {} view function", function))
     }
 
     /// Generic method for executing transactions
     async fn execute_transaction(&self, payload: TransactionPayload) -> Result<String> {
-        let chain_id = self
-            .rest_client
-            .get_index()
+        self.submit_with_options(payload, TxOptions::default())
+            .await
+    }
+
+    /// Sign `payload` with a sequence number allocated by an external nonce
+    /// manager, submit it and return its hash
+    ///
+    /// Shorthand for [`submit_with_options`](Self::submit_with_options) with
+    /// [`TxOptions::with_sequence_number`]. The client doesn't track sequence
+    /// numbers, so the caller is responsible for never reusing one across
+    /// signers or processes.
+    pub async fn submit_with_sequence_number(
+        &self,
+        payload: TransactionPayload,
+        sequence_number: u64,
+    ) -> Result<String> {
+        self.submit_with_options(
+            payload,
+            TxOptions::new().with_sequence_number(sequence_number),
+        )
+        .await
+    }
+
+    /// Sign `payload` with `options` overriding the gas settings and sequence
+    /// number, submit it and return its hash
+    pub async fn submit_with_options(
+        &self,
+        payload: TransactionPayload,
+        options: TxOptions,
+    ) -> Result<String> {
+        #[cfg(feature = "metrics")]
+        let timer = crate::metrics::SUBMISSION_LATENCY
+            .with_label_values(&[&function_label(&payload)])
+            .start_timer();
+
+        let result = async {
+            let signed_transaction = self.sign_checked(payload, &options).await?;
+            self.submit_signed(&signed_transaction).await
+        }
+        .await;
+
+        #[cfg(feature = "metrics")]
+        match &result {
+            Ok(_) => timer.observe_duration(),
+            // A dropped timer records too, so failures would count as latency
+            Err(_) => {
+                timer.stop_and_discard();
+            }
+        }
+
+        result
+    }
+
+    /// APT balance of the signing account, in octas
+    pub async fn get_gas_balance(&self) -> Result<u64> {
+        let view_request = ViewRequest {
+            function: EntryFunctionId {
+                module: MoveModuleId {
+                    address: AccountAddress::ONE.into(),
+                    name: IdentifierWrapper(Identifier::new("coin")?),
+                },
+                name: IdentifierWrapper(Identifier::new("balance")?),
+            },
+            type_arguments: vec![MoveType::from(&TypeTag::from_str(APT_COIN_TYPE)?)],
+            arguments: vec![serde_json::to_value(
+                self.signer.address().to_hex_literal(),
+            )?],
+        };
+        let result = self.call_view(&view_request).await?;
+        let balance: String = serde_json::from_value(result)
+            .map_err(|e| anyhow!("Failed to parse coin::balance response: {}", e))?;
+        balance
+            .parse()
+            .map_err(|e| anyhow!("Invalid coin::balance response '{}': {}", balance, e))
+    }
+
+    /// Fail with [`InsufficientGasFunds`] if the sender can't pay the maximum
+    /// gas fee, which the transaction prologue would reject
+    async fn check_gas_funds(&self, max_gas_amount: u64, gas_unit_price: u64) -> Result<()> {
+        let required = max_gas_amount.saturating_mul(gas_unit_price);
+        let balance = self.get_gas_balance().await?;
+        if balance < required {
+            return Err(InsufficientGasFunds {
+                address: self.signer.address().to_hex_literal(),
+                balance,
+                required,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Gas unit price to sign with under `gas_settings`: the fixed price, else
+    /// the node's estimate for the priority, else [`DEFAULT_GAS_UNIT_PRICE`]
+    async fn gas_unit_price(&self, gas_settings: &GasSettings) -> Result<u64> {
+        match (gas_settings.gas_unit_price, gas_settings.priority) {
+            (Some(gas_unit_price), _) => Ok(gas_unit_price),
+            (None, Some(priority)) => Ok(self
+                .query_client
+                .estimate_gas_price()
+                .await?
+                .price(priority)),
+            (None, None) => Ok(DEFAULT_GAS_UNIT_PRICE),
+        }
+    }
+
+    /// Sign `payload` with `options` after the preflight checks
+    ///
+    /// The gas unit price is resolved once, so the checked fee is the signed one.
+    async fn sign_checked(
+        &self,
+        payload: TransactionPayload,
+        options: &TxOptions,
+    ) -> Result<SignedTransaction> {
+        let gas_settings = options.gas_settings(&self.gas_settings);
+        let max_gas_amount = gas_settings
+            .max_gas_amount
+            .unwrap_or(DEFAULT_MAX_GAS_AMOUNT);
+        let gas_unit_price = self.gas_unit_price(&gas_settings).await?;
+        if self.preflight_checks {
+            self.check_gas_funds(max_gas_amount, gas_unit_price).await?;
+        }
+        let sequence_number = match options.sequence_number {
+            Some(sequence_number) => sequence_number,
+            None => self.sequence_number().await?,
+        };
+        self.build_and_sign(
+            payload,
+            sequence_number,
+            max_gas_amount,
+            gas_unit_price,
+            self.chain_id().await?,
+        )
+        .await
+    }
+
+    /// Sign `payload` without submitting it, returning the signed transaction
+    /// and the hash it will commit under
+    ///
+    /// Record the hash and persist the BCS bytes of the transaction before
+    /// calling [`submit_signed`](Self::submit_signed), so a crash in between
+    /// can be recovered without signing (and possibly executing) it twice.
+    pub async fn sign_only(
+        &self,
+        payload: TransactionPayload,
+        options: TxOptions,
+    ) -> Result<(SignedTransaction, String)> {
+        let signed_transaction = self.sign_checked(payload, &options).await?;
+        let expected_hash = signed_transaction.committed_hash().to_hex_literal();
+        Ok((signed_transaction, expected_hash))
+    }
+
+    /// Sign `payload` with the account's current on-chain sequence number
+    pub(crate) async fn sign_payload(
+        &self,
+        payload: TransactionPayload,
+    ) -> Result<SignedTransaction> {
+        let sequence_number = self.sequence_number().await?;
+        self.sign_payload_with_sequence_number(payload, sequence_number)
             .await
-            .map_err(|e| anyhow!("Failed to get chain ID from Aptos node: {}", e))?
-            .inner()
-            .chain_id;
+    }
 
-        let sequence_number = self
-            .rest_client
-            .get_account_sequence_number(self.account.address())
+    /// Sign `payload` with an explicit sequence number without submitting it,
+    /// for pipelined submission or an external nonce manager
+    pub async fn sign_payload_with_sequence_number(
+        &self,
+        payload: TransactionPayload,
+        sequence_number: u64,
+    ) -> Result<SignedTransaction> {
+        let chain_id = self.chain_id().await?;
+        self.sign_payload_on_chain(payload, sequence_number, chain_id)
             .await
-            .map_err(|e| anyhow!("Failed to get sequence number from Aptos node: {}", e))?;
+    }
 
-        self.account
-            .set_sequence_number(sequence_number.inner().clone());
+    /// Sign `payload` with an explicit sequence number for a chain ID read
+    /// earlier with [`chain_id`](Self::chain_id)
+    pub(crate) async fn sign_payload_on_chain(
+        &self,
+        payload: TransactionPayload,
+        sequence_number: u64,
+        chain_id: u8,
+    ) -> Result<SignedTransaction> {
+        let gas_unit_price = self.gas_unit_price(&self.gas_settings).await?;
+        self.build_and_sign(
+            payload,
+            sequence_number,
+            self.gas_settings
+                .max_gas_amount
+                .unwrap_or(DEFAULT_MAX_GAS_AMOUNT),
+            gas_unit_price,
+            chain_id,
+        )
+        .await
+    }
 
-        // First create account with sequence number 0 to get the address
+    /// Re-sign an expired transaction with a fresh expiration and its gas unit
+    /// price multiplied by `bump_factor`, then submit it and return its hash
+    ///
+    /// The same sequence number is reused, so at most one of the original and
+    /// the replacement can commit.
+    pub async fn resubmit_with_bump(
+        &self,
+        original: &SignedTransaction,
+        bump_factor: f64,
+    ) -> Result<String> {
+        if !(bump_factor >= 1.0 && bump_factor.is_finite()) {
+            return Err(anyhow!(
+                "Invalid gas price bump factor {}, must be at least 1",
+                bump_factor
+            ));
+        }
+        if original.sender() != self.signer.address() {
+            return Err(anyhow!(
+                "Transaction sender {} is not the signing account {}",
+                original.sender(),
+                self.signer.address()
+            ));
+        }
+        if crate::time::unix_timestamp_secs() < original.expiration_timestamp_secs() {
+            return Err(anyhow!(
+                "Transaction {} has not expired yet",
+                original.committed_hash().to_hex_literal()
+            ));
+        }
 
+        let gas_unit_price = (original.gas_unit_price() as f64 * bump_factor).ceil() as u64;
+        let signed_transaction = self
+            .build_and_sign(
+                original.payload().clone(),
+                original.sequence_number(),
+                original.max_gas_amount(),
+                gas_unit_price,
+                self.chain_id().await?,
+            )
+            .await?;
+        self.submit_signed(&signed_transaction).await
+    }
+
+    /// Chain ID reported by the node, checked against the expected one
+    pub(crate) async fn chain_id(&self) -> Result<u8> {
+        let chain_id = logged(
+            self.query_client.call_log("GET", ""),
+            self.rest_client.get_index(),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get chain ID from Aptos node: {}", e))?
+        .inner()
+        .chain_id;
+        self.query_client.check_chain_id(chain_id)?;
+        Ok(chain_id)
+    }
+
+    /// Build a transaction for `chain_id` expiring [`EXPIRATION_TIMESTAMP_SECS`]
+    /// from now and sign it
+    async fn build_and_sign(
+        &self,
+        payload: TransactionPayload,
+        sequence_number: u64,
+        max_gas_amount: u64,
+        gas_unit_price: u64,
+        chain_id: u8,
+    ) -> Result<SignedTransaction> {
         let transaction_builder = TransactionBuilder::new(
             payload,
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs()
-                + EXPIRATION_TIMESTAMP_SECS,
+            crate::time::unix_timestamp_secs() + EXPIRATION_TIMESTAMP_SECS,
             ChainId::new(chain_id),
         )
-        .sender(self.account.address());
+        .sender(self.signer.address())
+        .sequence_number(sequence_number)
+        .max_gas_amount(max_gas_amount)
+        .gas_unit_price(gas_unit_price);
 
-        // Sign transaction
         let signed_transaction = self
-            .account
-            .sign_with_transaction_builder(transaction_builder);
+            .signer
+            .sign_transaction(transaction_builder.build())
+            .await?;
+        #[cfg(not(target_arch = "wasm32"))]
+        crate::observer::notify_built(&self.observers, &signed_transaction).await;
+        Ok(signed_transaction)
+    }
+
+    /// On-chain sequence number of the signing account, i.e. the next one to use
+    pub(crate) async fn sequence_number(&self) -> Result<u64> {
+        let sequence_number = logged(
+            self.query_client
+                .call_log("GET", format_args!("accounts/{}", self.signer.address())),
+            self.rest_client
+                .get_account_sequence_number(self.signer.address()),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get sequence number from Aptos node: {}", e))?;
+        Ok(*sequence_number.inner())
+    }
 
-        // Submit transaction
+    /// Submit a signed transaction and return its hash
+    ///
+    /// Resubmitting the same signed transaction is idempotent, and it may be
+    /// submitted through any node of the same chain.
+    pub async fn submit_signed(&self, signed_transaction: &SignedTransaction) -> Result<String> {
         let response = self
-            .rest_client
-            .submit(&signed_transaction)
-            .await
-            .map_err(|e| anyhow!("Failed to submit transaction to Aptos node: {}", e))?;
+            .retry_policy
+            .retry(|| async {
+                let call_log = self
+                    .query_client
+                    .call_log("POST", "transactions")
+                    .map(|call_log| {
+                        call_log.with_request_body(&format!(
+                            "{{\"sender\":\"{}\",\"sequence_number\":{}}}",
+                            signed_transaction.sender(),
+                            signed_transaction.sequence_number()
+                        ))
+                    });
+                logged(call_log, self.rest_client.submit(signed_transaction))
+                    .await
+                    .map_err(|e| anyhow!("Failed to submit transaction to Aptos node: {}", e))
+            })
+            .await;
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(audit_log) = &self.audit_log {
+            crate::audit::record_submission(
+                audit_log,
+                &self.query_client,
+                signed_transaction,
+                response.as_ref().err(),
+            )
+            .await;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        crate::observer::notify_submission(
+            &self.observers,
+            &self.query_client,
+            signed_transaction,
+            response.as_ref().err(),
+        )
+        .await;
+        let tx_hash = response?.inner().hash.to_string();
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::TRANSACTIONS_SUBMITTED
+            .with_label_values(&[&function_label(signed_transaction.payload())])
+            .inc();
+        #[cfg(feature = "status-server")]
+        crate::status_server::record_submission(
+            &function_label(signed_transaction.payload()),
+            &tx_hash,
+        );
 
-        Ok(response.inner().hash.to_string())
+        Ok(tx_hash)
     }
 
     pub fn validate_aptos_address(address: &str) -> Result<()> {
@@ -479,6 +1476,27 @@ impl BridgeClient {
     }
 }
 
+/// Parse a Move `vector<u64>` view result, returned as decimal strings
+fn parse_ids(result: serde_json::Value, function: &str) -> Result<Vec<u64>> {
+    let ids: Vec<String> = serde_json::from_value(result)
+        .map_err(|e| anyhow!("Failed to parse {} response: {}", function, e))?;
+    ids.iter()
+        .map(|id| {
+            id.parse()
+                .map_err(|e| anyhow!("Invalid id '{}' in {} response: {}", id, function, e))
+        })
+        .collect()
+}
+
+/// Entry function name of `payload`, for metric labels
+#[cfg(feature = "metrics")]
+fn function_label(payload: &TransactionPayload) -> String {
+    match payload {
+        TransactionPayload::EntryFunction(entry_function) => entry_function.function().to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
 impl std::ops::Deref for BridgeClient {
     type Target = QueryClient;
 
@@ -0,0 +1,185 @@
+//! Per-asset bridge handles
+//!
+//! Bridges generic over the pegged fungible asset take the asset type as a Move
+//! type argument. A [`BridgeHandle`] binds one asset type to a shared
+//! [`BridgeClient`], so a single client can serve several pegged assets.
+
+use crate::btc::BtcAddress;
+use crate::types::{Peg, Sats, WithdrawByLPParams};
+use crate::BridgeClient;
+use anyhow::{anyhow, Result};
+use aptos_sdk::move_types::language_storage::TypeTag;
+use std::str::FromStr;
+
+/// Bridge operations for a single pegged asset
+#[derive(Clone, Copy)]
+pub struct BridgeHandle<'a> {
+    /// Client used to submit transactions and call views
+    client: &'a BridgeClient,
+    /// Asset type passed as the type argument
+    asset_type: &'a TypeTag,
+}
+
+impl<'a> BridgeHandle<'a> {
+    /// Bind `asset_type` to `client`
+    pub fn new(client: &'a BridgeClient, asset_type: &'a TypeTag) -> Self {
+        Self { client, asset_type }
+    }
+
+    /// Asset type of this handle
+    pub fn asset_type(&self) -> &TypeTag {
+        self.asset_type
+    }
+
+    /// Mint the asset for a BTC deposit
+    pub async fn mint(&self, peg: Peg) -> Result<String> {
+        self.client.mint_with_type_args(peg, self.type_args()).await
+    }
+
+    /// Burn the asset
+    pub async fn burn(
+        &self,
+        btc_address: BtcAddress,
+        fee_rate: u64,
+        amount: Sats,
+        operator_id: u64,
+    ) -> Result<String> {
+        self.client
+            .burn_with_type_args(btc_address, fee_rate, amount, operator_id, self.type_args())
+            .await
+    }
+
+    /// Withdraw the asset through LP mode
+    pub async fn withdraw_by_lp(&self, params: WithdrawByLPParams) -> Result<String> {
+        self.client
+            .withdraw_by_lp_with_type_args(params, self.type_args())
+            .await
+    }
+
+    /// Check whether a BTC output has already been minted as this asset
+    pub async fn is_peg_minted(&self, tx_id: &[u8], tx_out_ix: u64) -> Result<bool> {
+        self.client
+            .is_peg_minted_with_type_args(tx_id, tx_out_ix, std::slice::from_ref(self.asset_type))
+            .await
+    }
+
+    /// Balance of the asset held by `address`
+    pub async fn balance(&self, address: &str) -> Result<Sats> {
+        self.client
+            .get_btc_peg_balance_with_type_args(address, std::slice::from_ref(self.asset_type))
+            .await
+    }
+
+    fn type_args(&self) -> Vec<TypeTag> {
+        vec![self.asset_type.clone()]
+    }
+}
+
+/// Parse a Move type such as `0x1::object::Object<0x1::fungible_asset::Metadata>`
+pub fn parse_asset_type(asset_type: &str) -> Result<TypeTag> {
+    TypeTag::from_str(asset_type).map_err(|e| anyhow!("Invalid asset type '{}': {}", asset_type, e))
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use serde_json::{json, Value};
+    use wiremock::matchers::{body_partial_json, body_string_contains, method, path};
+    use wiremock::{Mock, MockServer};
+
+    const ASSET_A: &str =
+        "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa::asset::A";
+    const ASSET_B: &str =
+        "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb::asset::B";
+
+    /// Serve `response` for calls of `function` with `asset_type` as type argument
+    async fn mount_asset_view(
+        server: &MockServer,
+        function: &str,
+        asset_type: &str,
+        response: Value,
+    ) {
+        Mock::given(method("POST"))
+            .and(path("/v1/view"))
+            .and(body_string_contains(format!("::{}", function)))
+            .and(body_partial_json(json!({ "type_arguments": [asset_type] })))
+            .respond_with(testing::fullnode_response(&response.to_string()))
+            .with_priority(1)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_handles_share_client_concurrently() {
+        let server = testing::mock_fullnode().await;
+        mount_asset_view(&server, "is_peg_minted", ASSET_A, json!([true])).await;
+        mount_asset_view(&server, "is_peg_minted", ASSET_B, json!([false])).await;
+        mount_asset_view(&server, "balance_of", ASSET_A, json!(["100"])).await;
+        mount_asset_view(&server, "balance_of", ASSET_B, json!(["200"])).await;
+
+        let client = testing::bridge_client(&server);
+        let (asset_a, asset_b) = (
+            parse_asset_type(ASSET_A).unwrap(),
+            parse_asset_type(ASSET_B).unwrap(),
+        );
+        let (handle_a, handle_b) = (
+            BridgeHandle::new(&client, &asset_a),
+            BridgeHandle::new(&client, &asset_b),
+        );
+
+        let (minted_a, minted_b, balance_a, balance_b) = tokio::join!(
+            handle_a.is_peg_minted(&[1u8; 32], 0),
+            handle_b.is_peg_minted(&[1u8; 32], 0),
+            handle_a.balance("0x1"),
+            handle_b.balance("0x1"),
+        );
+        assert!(minted_a.unwrap());
+        assert!(!minted_b.unwrap());
+        assert_eq!(balance_a.unwrap(), Sats::from(100));
+        assert_eq!(balance_b.unwrap(), Sats::from(200));
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[tokio::test]
+    async fn test_concurrent_mints_use_their_asset_type() {
+        use aptos_sdk::types::transaction::TransactionPayload;
+
+        let server = testing::mock_fullnode().await;
+        let chain = testing::MockChain::mount(&server, 0).await;
+        // The deposit is already minted as A only
+        mount_asset_view(&server, "is_peg_minted", ASSET_A, json!([true])).await;
+        mount_asset_view(&server, "is_peg_minted", ASSET_B, json!([false])).await;
+
+        let client = testing::bridge_client(&server);
+        let (asset_a, asset_b) = (
+            parse_asset_type(ASSET_A).unwrap(),
+            parse_asset_type(ASSET_B).unwrap(),
+        );
+        let (handle_a, handle_b) = (
+            BridgeHandle::new(&client, &asset_a),
+            BridgeHandle::new(&client, &asset_b),
+        );
+
+        let (mint_a, mint_b) = tokio::join!(
+            handle_a.mint(testing::deposit_peg()),
+            handle_b.mint(testing::deposit_peg()),
+        );
+        assert!(mint_a
+            .unwrap_err()
+            .downcast_ref::<crate::types::AlreadyMinted>()
+            .is_some());
+        let tx_hash = mint_b.unwrap();
+        assert_eq!(
+            chain.committed(&tx_hash),
+            Some((testing::LEDGER_VERSION + 1, true))
+        );
+
+        let submitted = chain.submitted();
+        assert_eq!(submitted.len(), 1);
+        let TransactionPayload::EntryFunction(entry_function) = submitted[0].payload() else {
+            panic!("mint should submit an entry function");
+        };
+        assert_eq!(entry_function.ty_args(), &[asset_b]);
+    }
+}
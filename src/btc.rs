@@ -0,0 +1,559 @@
+//! Bitcoin address helpers
+//!
+//! Provides a validated, network-aware Bitcoin address type and the script hash
+//! encoding expected by the bridge contract.
+
+use crate::types::{Sats, ScriptType};
+use crate::utils::decode_hex_exact;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Bitcoin network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BtcNetwork {
+    Mainnet,
+    /// Testnet and signet (same address prefixes)
+    Testnet,
+    Regtest,
+}
+
+impl BtcNetwork {
+    /// Human-readable part of segwit addresses on this network
+    pub fn bech32_hrp(self) -> &'static str {
+        match self {
+            BtcNetwork::Mainnet => "bc",
+            BtcNetwork::Testnet => "tb",
+            BtcNetwork::Regtest => "bcrt",
+        }
+    }
+}
+
+impl std::fmt::Display for BtcNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BtcNetwork::Mainnet => write!(f, "mainnet"),
+            BtcNetwork::Testnet => write!(f, "testnet"),
+            BtcNetwork::Regtest => write!(f, "regtest"),
+        }
+    }
+}
+
+impl FromStr for BtcNetwork {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "mainnet" | "bitcoin" => Ok(BtcNetwork::Mainnet),
+            "testnet" | "signet" => Ok(BtcNetwork::Testnet),
+            "regtest" => Ok(BtcNetwork::Regtest),
+            _ => Err(anyhow!(
+                "Invalid Bitcoin network '{}'. Valid networks are: mainnet, testnet, regtest",
+                s
+            )),
+        }
+    }
+}
+
+/// Decoded payload of a Bitcoin address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressPayload {
+    /// Base58 pay-to-pubkey-hash
+    PubkeyHash([u8; 20]),
+    /// Base58 pay-to-script-hash
+    ScriptHash([u8; 20]),
+    /// Bech32/bech32m segwit program
+    WitnessProgram { version: u8, program: Vec<u8> },
+}
+
+/// Validated Bitcoin address
+///
+/// Parsing checks the bech32/bech32m or base58check encoding; use
+/// [`BtcAddress::new`] or [`BtcAddress::require_network`] to also check the network.
+/// Serializes as the plain address string (JSON and BCS).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct BtcAddress {
+    address: String,
+    network: BtcNetwork,
+}
+
+impl BtcAddress {
+    /// Parse an address and check that it belongs to `network`
+    pub fn new(address: &str, network: BtcNetwork) -> Result<Self> {
+        let address: BtcAddress = address.parse()?;
+        address.require_network(network)?;
+        Ok(address)
+    }
+
+    /// Address string
+    pub fn as_str(&self) -> &str {
+        &self.address
+    }
+
+    /// Network the address was encoded for
+    ///
+    /// Base58 addresses share prefixes between testnet and regtest and report
+    /// [`BtcNetwork::Testnet`].
+    pub fn network(&self) -> BtcNetwork {
+        self.network
+    }
+
+    /// Whether the address can be used on `network`
+    pub fn is_valid_for(&self, network: BtcNetwork) -> bool {
+        if self.network == network {
+            return true;
+        }
+        // Base58 testnet prefixes are also used on regtest
+        network == BtcNetwork::Regtest && self.network == BtcNetwork::Testnet && !self.is_segwit()
+    }
+
+    /// Return an error if the address cannot be used on `network`
+    pub fn require_network(&self, network: BtcNetwork) -> Result<()> {
+        if self.is_valid_for(network) {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Bitcoin address '{}' is a {} address, expected {}",
+                self.address,
+                self.network,
+                network
+            ))
+        }
+    }
+
+    /// Decode the address payload
+    pub fn payload(&self) -> AddressPayload {
+        // The address was validated on construction
+        decode_address(&self.address)
+            .map(|(payload, _)| payload)
+            .expect("validated Bitcoin address")
+    }
+
+    /// Script type of the address output
+    pub fn script_type(&self) -> Option<ScriptType> {
+        match self.payload() {
+            AddressPayload::PubkeyHash(_) => Some(ScriptType::P2PKH),
+            AddressPayload::ScriptHash(_) => Some(ScriptType::P2SH),
+            AddressPayload::WitnessProgram { version, program } => match (version, program.len()) {
+                (0, 20) => Some(ScriptType::P2WPKH),
+                (0, 32) => Some(ScriptType::P2WSH),
+                (1, 32) => Some(ScriptType::P2TR),
+                _ => None,
+            },
+        }
+    }
+
+    /// Output script paying to the address
+    #[cfg(feature = "bitcoin")]
+    pub fn script_pubkey(&self) -> bitcoin::ScriptBuf {
+        let bytes = match self.payload() {
+            // OP_DUP OP_HASH160 <hash> OP_EQUALVERIFY OP_CHECKSIG
+            AddressPayload::PubkeyHash(hash) => {
+                [&[0x76, 0xa9, 0x14][..], &hash, &[0x88, 0xac]].concat()
+            }
+            // OP_HASH160 <hash> OP_EQUAL
+            AddressPayload::ScriptHash(hash) => [&[0xa9, 0x14][..], &hash, &[0x87]].concat(),
+            // OP_n <program>
+            AddressPayload::WitnessProgram { version, program } => {
+                let op = if version == 0 { 0x00 } else { 0x50 + version };
+                [&[op, program.len() as u8][..], &program].concat()
+            }
+        };
+        bitcoin::ScriptBuf::from_bytes(bytes)
+    }
+
+    fn is_segwit(&self) -> bool {
+        matches!(self.payload(), AddressPayload::WitnessProgram { .. })
+    }
+}
+
+impl FromStr for BtcAddress {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (_, network) = decode_address(s)?;
+        Ok(Self {
+            address: s.to_string(),
+            network,
+        })
+    }
+}
+
+impl TryFrom<String> for BtcAddress {
+    type Error = anyhow::Error;
+
+    fn try_from(address: String) -> Result<Self> {
+        address.parse()
+    }
+}
+
+impl From<BtcAddress> for String {
+    fn from(address: BtcAddress) -> Self {
+        address.address
+    }
+}
+
+impl std::fmt::Display for BtcAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.address)
+    }
+}
+
+/// Script hash the bridge contract expects for an address
+///
+/// This is the hash or witness program committed to by the output script: the
+/// 20-byte hash for P2PKH/P2SH/P2WPKH, the 32-byte script hash for P2WSH and the
+/// 32-byte output key for P2TR. Fails if the address is not of `script_type`.
+pub fn script_hash_for_address(address: &BtcAddress, script_type: ScriptType) -> Result<Vec<u8>> {
+    if address.script_type() != Some(script_type) {
+        return Err(anyhow!(
+            "Bitcoin address '{}' is not a {:?} address",
+            address,
+            script_type
+        ));
+    }
+
+    Ok(match address.payload() {
+        AddressPayload::PubkeyHash(hash) | AddressPayload::ScriptHash(hash) => hash.to_vec(),
+        AddressPayload::WitnessProgram { program, .. } => program,
+    })
+}
+
+/// Script hash and script type the bridge contract expects for an output script
+#[cfg(feature = "bitcoin")]
+pub(crate) fn script_hash_for_output(script: &bitcoin::Script) -> Result<(Vec<u8>, ScriptType)> {
+    let bytes = script.as_bytes();
+    if script.is_p2pkh() {
+        Ok((bytes[3..23].to_vec(), ScriptType::P2PKH))
+    } else if script.is_p2sh() {
+        Ok((bytes[2..22].to_vec(), ScriptType::P2SH))
+    } else if script.is_p2wpkh() {
+        Ok((bytes[2..].to_vec(), ScriptType::P2WPKH))
+    } else if script.is_p2wsh() {
+        Ok((bytes[2..].to_vec(), ScriptType::P2WSH))
+    } else if script.is_p2tr() {
+        Ok((bytes[2..].to_vec(), ScriptType::P2TR))
+    } else {
+        Err(anyhow!("Unsupported output script: {}", script))
+    }
+}
+
+/// Byte order of a Bitcoin transaction id
+///
+/// Transactions hash to txids in internal order, which is what `TxProof::tx_id`
+/// and the bridge contract use; block explorers and RPC show the bytes reversed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxidByteOrder {
+    /// Byte order of the hash itself, as stored on chain
+    #[default]
+    Internal,
+    /// Reversed order shown by explorers and bitcoind RPC
+    Display,
+}
+
+/// Hex of an internal-order txid as shown by explorers
+pub fn txid_to_display_hex(txid: &[u8]) -> String {
+    let mut bytes = txid.to_vec();
+    bytes.reverse();
+    hex::encode(bytes)
+}
+
+/// Internal-order txid bytes from explorer hex (with or without `0x`)
+pub fn display_hex_to_txid(display_hex: &str) -> Result<Vec<u8>> {
+    let mut txid = decode_txid_hex(display_hex)?;
+    txid.reverse();
+    Ok(txid)
+}
+
+/// Estimated virtual size of a withdrawal transaction, in vbytes
+///
+/// Assumes every input and output uses `script_type`: P2SH inputs are taken as
+/// P2SH-P2WPKH, P2WSH inputs as 2-of-3 multisig and P2TR inputs as key-path spends.
+pub fn estimate_peg_out_vbytes(script_type: ScriptType, n_inputs: u64, n_outputs: u64) -> u64 {
+    // version + locktime + input/output counts
+    let mut weight = 4 * 10;
+    if script_type != ScriptType::P2PKH && n_inputs > 0 {
+        // segwit marker and flag
+        weight += 2;
+    }
+
+    weight += n_inputs.saturating_mul(input_weight(script_type));
+    weight += n_outputs.saturating_mul(4 * output_vbytes(script_type));
+
+    weight.div_ceil(4)
+}
+
+/// Estimated virtual size of a transaction with the given input and output types
+pub(crate) fn estimate_tx_vbytes(inputs: &[ScriptType], outputs: &[ScriptType]) -> u64 {
+    let mut weight = 4 * 10;
+    if inputs
+        .iter()
+        .any(|script_type| *script_type != ScriptType::P2PKH)
+    {
+        weight += 2;
+    }
+
+    weight += inputs.iter().map(|t| input_weight(*t)).sum::<u64>();
+    weight += outputs.iter().map(|t| 4 * output_vbytes(*t)).sum::<u64>();
+
+    weight.div_ceil(4)
+}
+
+/// Weight of an input spending `script_type`
+fn input_weight(script_type: ScriptType) -> u64 {
+    match script_type {
+        ScriptType::P2PKH => 592,
+        ScriptType::P2SH => 364,
+        ScriptType::P2WPKH => 272,
+        ScriptType::P2WSH => 417,
+        ScriptType::P2TR => 230,
+    }
+}
+
+/// Size of an output paying to `script_type`, in vbytes
+fn output_vbytes(script_type: ScriptType) -> u64 {
+    match script_type {
+        ScriptType::P2PKH => 34,
+        ScriptType::P2SH => 32,
+        ScriptType::P2WPKH => 31,
+        ScriptType::P2WSH => 43,
+        ScriptType::P2TR => 43,
+    }
+}
+
+/// Estimated fee of a withdrawal transaction at `fee_rate` sat/vB
+///
+/// Subtract it from the withdrawn amount (`Sats::saturating_sub`) to show the
+/// expected net amount before calling `burn` or `withdraw_by_lp`.
+pub fn estimate_peg_out_fee(
+    script_type: ScriptType,
+    fee_rate: u64,
+    n_inputs: u64,
+    n_outputs: u64,
+) -> Sats {
+    Sats(estimate_peg_out_vbytes(script_type, n_inputs, n_outputs).saturating_mul(fee_rate))
+}
+
+/// Decode a 32-byte txid from hex, keeping its byte order
+pub(crate) fn decode_txid_hex(txid_hex: &str) -> Result<Vec<u8>> {
+    decode_hex_exact(txid_hex, 32).map_err(|e| anyhow!("Invalid txid: {}", e))
+}
+
+/// Decode a bech32/bech32m segwit or base58check address
+fn decode_address(address: &str) -> Result<(AddressPayload, BtcNetwork)> {
+    let lower = address.to_ascii_lowercase();
+    let segwit_network = [
+        BtcNetwork::Regtest,
+        BtcNetwork::Mainnet,
+        BtcNetwork::Testnet,
+    ]
+    .into_iter()
+    .find(|network| lower.starts_with(&format!("{}1", network.bech32_hrp())));
+
+    if let Some(network) = segwit_network {
+        let (_, version, program) = bech32::segwit::decode(address)
+            .map_err(|e| anyhow!("Invalid segwit address '{}': {}", address, e))?;
+        return Ok((
+            AddressPayload::WitnessProgram {
+                version: version.to_u8(),
+                program,
+            },
+            network,
+        ));
+    }
+
+    let data = bs58::decode(address)
+        .with_check(None)
+        .into_vec()
+        .map_err(|e| anyhow!("Invalid base58 address '{}': {}", address, e))?;
+    if data.len() != 21 {
+        return Err(anyhow!(
+            "Invalid base58 address '{}': unexpected payload length {}",
+            address,
+            data.len()
+        ));
+    }
+
+    let mut hash = [0u8; 20];
+    hash.copy_from_slice(&data[1..]);
+    match data[0] {
+        0x00 => Ok((AddressPayload::PubkeyHash(hash), BtcNetwork::Mainnet)),
+        0x05 => Ok((AddressPayload::ScriptHash(hash), BtcNetwork::Mainnet)),
+        0x6f => Ok((AddressPayload::PubkeyHash(hash), BtcNetwork::Testnet)),
+        0xc4 => Ok((AddressPayload::ScriptHash(hash), BtcNetwork::Testnet)),
+        version => Err(anyhow!(
+            "Invalid base58 address '{}': unknown version byte {:#04x}",
+            address,
+            version
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_peg_out_fee() {
+        // 1-in/2-out P2WPKH: 10.5 + 68 + 2 * 31 = 140.5 -> 141 vbytes
+        assert_eq!(estimate_peg_out_vbytes(ScriptType::P2WPKH, 1, 2), 141);
+        // 1-in/2-out P2PKH: 10 + 148 + 2 * 34 = 226 vbytes
+        assert_eq!(estimate_peg_out_vbytes(ScriptType::P2PKH, 1, 2), 226);
+        // 1-in/2-out P2TR: 10.5 + 57.5 + 2 * 43 = 154 vbytes
+        assert_eq!(estimate_peg_out_vbytes(ScriptType::P2TR, 1, 2), 154);
+
+        assert_eq!(
+            estimate_peg_out_fee(ScriptType::P2WPKH, 10, 1, 2),
+            Sats(1410)
+        );
+        assert_eq!(
+            estimate_peg_out_fee(ScriptType::P2WSH, u64::MAX, 1, 1),
+            Sats(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_txid_byte_order() {
+        // Genesis coinbase txid as shown by explorers
+        let display = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+        let txid = display_hex_to_txid(display).unwrap();
+        assert_eq!(txid[0], 0x3b);
+        assert_eq!(txid_to_display_hex(&txid), display);
+        assert_eq!(
+            display_hex_to_txid(&format!("0x{}", display)).unwrap(),
+            txid
+        );
+
+        assert!(display_hex_to_txid("abcd").is_err());
+        assert!(display_hex_to_txid("zz").is_err());
+    }
+
+    #[test]
+    fn test_parse_addresses() {
+        let cases = [
+            ("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", BtcNetwork::Mainnet),
+            ("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy", BtcNetwork::Mainnet),
+            (
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                BtcNetwork::Mainnet,
+            ),
+            (
+                "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+                BtcNetwork::Testnet,
+            ),
+            ("mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn", BtcNetwork::Testnet),
+            (
+                "bcrt1phcnl4zcl2fu047pv4wx6y058v8u0n02at6lthvm7pcf2wrvjm5tqatn90k",
+                BtcNetwork::Regtest,
+            ),
+        ];
+        for (address, network) in cases {
+            let parsed: BtcAddress = address.parse().unwrap();
+            assert_eq!(parsed.network(), network, "{}", address);
+            assert_eq!(parsed.as_str(), address);
+        }
+
+        assert!("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5"
+            .parse::<BtcAddress>()
+            .is_err());
+        assert!("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb"
+            .parse::<BtcAddress>()
+            .is_err());
+        assert!("not an address".parse::<BtcAddress>().is_err());
+    }
+
+    #[test]
+    fn test_network_check() {
+        let regtest = "bcrt1phcnl4zcl2fu047pv4wx6y058v8u0n02at6lthvm7pcf2wrvjm5tqatn90k";
+        assert!(BtcAddress::new(regtest, BtcNetwork::Regtest).is_ok());
+        assert!(BtcAddress::new(regtest, BtcNetwork::Mainnet).is_err());
+
+        // Base58 testnet addresses are accepted on regtest
+        let testnet_p2pkh: BtcAddress = "mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn".parse().unwrap();
+        assert!(testnet_p2pkh.is_valid_for(BtcNetwork::Regtest));
+        assert!(!testnet_p2pkh.is_valid_for(BtcNetwork::Mainnet));
+    }
+
+    #[test]
+    fn test_script_hash_for_address() {
+        let p2wpkh: BtcAddress = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+            .parse()
+            .unwrap();
+        assert_eq!(p2wpkh.script_type(), Some(ScriptType::P2WPKH));
+        assert_eq!(
+            hex::encode(script_hash_for_address(&p2wpkh, ScriptType::P2WPKH).unwrap()),
+            "751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+        assert!(script_hash_for_address(&p2wpkh, ScriptType::P2WSH).is_err());
+
+        let p2pkh: BtcAddress = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".parse().unwrap();
+        assert_eq!(
+            hex::encode(script_hash_for_address(&p2pkh, ScriptType::P2PKH).unwrap()),
+            "62e907b15cbf27d5425399ebf6f0fb50ebb88f18"
+        );
+
+        let p2tr: BtcAddress = "bcrt1phcnl4zcl2fu047pv4wx6y058v8u0n02at6lthvm7pcf2wrvjm5tqatn90k"
+            .parse()
+            .unwrap();
+        assert_eq!(p2tr.script_type(), Some(ScriptType::P2TR));
+        assert_eq!(
+            script_hash_for_address(&p2tr, ScriptType::P2TR)
+                .unwrap()
+                .len(),
+            32
+        );
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[test]
+    fn test_script_pubkey() {
+        for address in [
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+            "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy",
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            "bcrt1phcnl4zcl2fu047pv4wx6y058v8u0n02at6lthvm7pcf2wrvjm5tqatn90k",
+        ] {
+            let expected = address
+                .parse::<bitcoin::Address<_>>()
+                .unwrap()
+                .assume_checked()
+                .script_pubkey();
+            let parsed: BtcAddress = address.parse().unwrap();
+            assert_eq!(parsed.script_pubkey(), expected, "{}", address);
+        }
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[test]
+    fn test_script_hash_for_output() {
+        let address: BtcAddress = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+            .parse()
+            .unwrap();
+        let (hash, script_type) = script_hash_for_output(&address.script_pubkey()).unwrap();
+        assert_eq!(script_type, ScriptType::P2WPKH);
+        assert_eq!(
+            hex::encode(hash),
+            "751e76e8199196d454941c45d1b3a323f1433bd6"
+        );
+
+        assert!(script_hash_for_output(bitcoin::Script::new()).is_err());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let address: BtcAddress = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            bcs::to_bytes(&address).unwrap(),
+            bcs::to_bytes(&address.to_string()).unwrap()
+        );
+        let json = serde_json::to_string(&address).unwrap();
+        assert_eq!(json, "\"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\"");
+        assert_eq!(serde_json::from_str::<BtcAddress>(&json).unwrap(), address);
+        assert!(serde_json::from_str::<BtcAddress>("\"invalid\"").is_err());
+    }
+}
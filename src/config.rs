@@ -0,0 +1,596 @@
+//! Client configuration
+//!
+//! [`Config`] collects the settings needed to build a [`BridgeClient`](crate::BridgeClient)
+//! from environment variables or, with the `config` feature, a TOML or YAML file.
+//! Settings left unset fall back to the presets of the selected [`Network`].
+//!
+//! Environment variables:
+//! - `APTOS_NETWORK`: `mainnet`, `testnet` or `devnet`
+//! - `APTOS_NODE_URL`, `APTOS_API_KEY`, `PRIVATE_KEY`
+//! - `BRIDGE_CONTRACT_ADDRESS`, `BTC_LIGHT_CLIENT`, `BTC_NETWORK`
+//! - `INDEXER_URL`, `GRAPHQL_API_KEY`, `INDEXER_TABLE_PREFIX`
+//! - `MAX_GAS_AMOUNT`, `GAS_UNIT_PRICE`, `GAS_PRIORITY`
+//! - `MAX_RETRIES`, `RETRY_BACKOFF_MS`
+
+use crate::btc::BtcNetwork;
+use crate::networks::Network;
+use crate::utils::parse_account_address;
+use anyhow::{anyhow, Result};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::str::FromStr;
+use std::time::Duration;
+use url::Url;
+
+/// Default number of retries of a failed submission or view call
+///
+/// None: every failed attempt is retried, including rejections that can only
+/// fail again, so retrying is opt-in.
+pub const DEFAULT_MAX_RETRIES: u32 = 0;
+/// Default delay before the first retry, doubled on each attempt
+pub const DEFAULT_RETRY_BACKOFF_MS: u64 = 500;
+
+/// Gas settings of submitted transactions
+///
+/// Unset values use the Aptos SDK defaults,
+/// [`DEFAULT_MAX_GAS_AMOUNT`](crate::types::constants::DEFAULT_MAX_GAS_AMOUNT) and
+/// [`DEFAULT_GAS_UNIT_PRICE`](crate::types::constants::DEFAULT_GAS_UNIT_PRICE).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GasSettings {
+    /// Maximum gas units a transaction may use
+    pub max_gas_amount: Option<u64>,
+    /// Price per gas unit in octas
+    pub gas_unit_price: Option<u64>,
+    /// Price tier of the node's gas estimate, used when `gas_unit_price` is unset
+    pub priority: Option<GasPriority>,
+}
+
+/// Tier of the node's gas price estimate, resolved at submission time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GasPriority {
+    /// Cheapest price likely to be included, for transactions that can wait
+    Deprioritized,
+    /// Current market price
+    Market,
+    /// Price for inclusion ahead of the market
+    Prioritized,
+}
+
+impl FromStr for GasPriority {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "deprioritized" => Ok(GasPriority::Deprioritized),
+            "market" => Ok(GasPriority::Market),
+            "prioritized" => Ok(GasPriority::Prioritized),
+            _ => Err(anyhow!(
+                "Invalid gas priority '{}'. Valid priorities are: deprioritized, market, prioritized",
+                s
+            )),
+        }
+    }
+}
+
+/// Per-transaction overrides of the client's gas settings and sequence number
+///
+/// Unset values fall back to the client's [`GasSettings`] and the account's
+/// on-chain sequence number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxOptions {
+    /// Sequence number allocated by an external nonce manager
+    pub sequence_number: Option<u64>,
+    /// Maximum gas units the transaction may use
+    pub max_gas_amount: Option<u64>,
+    /// Price per gas unit in octas
+    pub gas_unit_price: Option<u64>,
+    /// Price tier of the node's gas estimate, used when `gas_unit_price` is unset
+    pub gas_priority: Option<GasPriority>,
+}
+
+impl TxOptions {
+    /// No overrides
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sign with `sequence_number` instead of the on-chain one
+    ///
+    /// The client doesn't track sequence numbers, so the caller is responsible
+    /// for never reusing one across signers or processes.
+    pub fn with_sequence_number(mut self, sequence_number: u64) -> Self {
+        self.sequence_number = Some(sequence_number);
+        self
+    }
+
+    /// Allow at most `max_gas_amount` gas units
+    pub fn with_max_gas_amount(mut self, max_gas_amount: u64) -> Self {
+        self.max_gas_amount = Some(max_gas_amount);
+        self
+    }
+
+    /// Pay a fixed `gas_unit_price`
+    pub fn with_gas_unit_price(mut self, gas_unit_price: u64) -> Self {
+        self.gas_unit_price = Some(gas_unit_price);
+        self
+    }
+
+    /// Pay the node's estimate for `priority`, resolved at signing time
+    pub fn with_gas_priority(mut self, priority: GasPriority) -> Self {
+        self.gas_priority = Some(priority);
+        self
+    }
+
+    /// `gas_settings` with these overrides applied
+    ///
+    /// A gas priority set here takes precedence over a fixed price in
+    /// `gas_settings`.
+    pub(crate) fn gas_settings(&self, gas_settings: &GasSettings) -> GasSettings {
+        GasSettings {
+            max_gas_amount: self.max_gas_amount.or(gas_settings.max_gas_amount),
+            gas_unit_price: self.gas_unit_price.or(if self.gas_priority.is_some() {
+                None
+            } else {
+                gas_settings.gas_unit_price
+            }),
+            priority: self.gas_priority.or(gas_settings.priority),
+        }
+    }
+}
+
+/// Retry policy for node requests
+///
+/// The default never retries; set `max_retries` to retry with exponential
+/// backoff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry in milliseconds, doubled on each attempt
+    pub backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            backoff_ms: DEFAULT_RETRY_BACKOFF_MS,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            backoff_ms: 0,
+        }
+    }
+
+    /// Run `f` until it succeeds or the retries are used up
+    pub(crate) async fn retry<T, F, Fut>(&self, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt >= self.max_retries => return Err(e),
+                Err(_e) => {
+                    let delay = self.backoff_ms.saturating_mul(1 << attempt.min(16));
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(attempt, delay_ms = delay, error = %_e, "Retrying node request");
+                    crate::time::sleep(Duration::from_millis(delay)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Settings of a bridge client
+///
+/// Serializing a config leaves out `private_key`, so a saved config loads back
+/// without it; supply the key again, e.g. through `PRIVATE_KEY`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Network whose presets fill in unset endpoints and addresses
+    pub network: Option<Network>,
+    /// Aptos fullnode REST URL
+    pub node_url: Option<String>,
+    /// Aptos API key
+    pub aptos_api_key: Option<String>,
+    /// Hex-encoded private key of the signing account, never serialized
+    #[serde(skip_serializing)]
+    pub private_key: Option<SecretString>,
+    /// Bridge contract address
+    pub bridge_contract_address: Option<String>,
+    /// BTC light client address
+    pub btc_light_client: Option<String>,
+    /// Bitcoin network of the bridge
+    pub btc_network: Option<BtcNetwork>,
+    /// Indexer GraphQL URL, used by event monitoring
+    pub indexer_url: Option<String>,
+    /// Indexer GraphQL API key
+    pub graphql_api_key: Option<String>,
+    /// Prefix of the indexer's bridge event tables, for self-hosted processors
+    pub indexer_table_prefix: Option<String>,
+    /// Gas settings
+    pub gas: GasSettings,
+    /// Retry policy
+    pub retry: RetryPolicy,
+}
+
+impl Config {
+    /// Load from environment variables
+    pub fn from_env() -> Result<Self> {
+        Self::default().with_env_overrides()
+    }
+
+    /// Override settings with any environment variables that are set
+    pub fn with_env_overrides(self) -> Result<Self> {
+        self.with_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Parse a TOML document
+    #[cfg(feature = "config")]
+    pub fn from_toml_str(s: &str) -> Result<Self> {
+        toml::from_str(s).map_err(|e| anyhow!("Failed to parse TOML config: {}", e))
+    }
+
+    /// Parse a YAML document
+    #[cfg(feature = "config")]
+    pub fn from_yaml_str(s: &str) -> Result<Self> {
+        serde_yaml::from_str(s).map_err(|e| anyhow!("Failed to parse YAML config: {}", e))
+    }
+
+    /// Load a `.toml`, `.yaml` or `.yml` file
+    #[cfg(feature = "config")]
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read config {}: {}", path.display(), e))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&contents),
+            Some("yaml") | Some("yml") => Self::from_yaml_str(&contents),
+            _ => Err(anyhow!(
+                "Unsupported config format {}, expected .toml, .yaml or .yml",
+                path.display()
+            )),
+        }
+    }
+
+    fn with_vars(mut self, var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        if let Some(network) = var("APTOS_NETWORK") {
+            self.network = Some(network.parse()?);
+        }
+        if let Some(btc_network) = var("BTC_NETWORK") {
+            self.btc_network = Some(btc_network.parse()?);
+        }
+        for (name, field) in [
+            ("APTOS_NODE_URL", &mut self.node_url),
+            ("APTOS_API_KEY", &mut self.aptos_api_key),
+            ("BRIDGE_CONTRACT_ADDRESS", &mut self.bridge_contract_address),
+            ("BTC_LIGHT_CLIENT", &mut self.btc_light_client),
+            ("INDEXER_URL", &mut self.indexer_url),
+            ("GRAPHQL_API_KEY", &mut self.graphql_api_key),
+            ("INDEXER_TABLE_PREFIX", &mut self.indexer_table_prefix),
+        ] {
+            if let Some(value) = var(name) {
+                *field = Some(value);
+            }
+        }
+        if let Some(private_key) = var("PRIVATE_KEY") {
+            self.private_key = Some(private_key.into());
+        }
+        if let Some(value) = var("MAX_GAS_AMOUNT") {
+            self.gas.max_gas_amount = Some(parse_var("MAX_GAS_AMOUNT", &value)?);
+        }
+        if let Some(value) = var("GAS_UNIT_PRICE") {
+            self.gas.gas_unit_price = Some(parse_var("GAS_UNIT_PRICE", &value)?);
+        }
+        if let Some(value) = var("GAS_PRIORITY") {
+            self.gas.priority = Some(parse_var("GAS_PRIORITY", &value)?);
+        }
+        if let Some(value) = var("MAX_RETRIES") {
+            self.retry.max_retries = parse_var("MAX_RETRIES", &value)?;
+        }
+        if let Some(value) = var("RETRY_BACKOFF_MS") {
+            self.retry.backoff_ms = parse_var("RETRY_BACKOFF_MS", &value)?;
+        }
+        Ok(self)
+    }
+
+    /// Fullnode URL, from the setting or the network preset
+    pub fn node_url(&self) -> Result<&str> {
+        self.node_url
+            .as_deref()
+            .or_else(|| self.network.map(|network| network.config().fullnode_url))
+            .ok_or_else(|| anyhow!("Config is missing node_url (or network)"))
+    }
+
+    /// Bridge contract address, from the setting or the network preset
+    pub fn bridge_contract_address(&self) -> Result<&str> {
+        match (&self.bridge_contract_address, self.network) {
+            (Some(address), _) => Ok(address),
+            (None, Some(network)) => network.bridge_contract_address(),
+            (None, None) => Err(anyhow!(
+                "Config is missing bridge_contract_address (or network)"
+            )),
+        }
+    }
+
+    /// BTC light client address, from the setting or the network preset
+    pub fn btc_light_client(&self) -> Option<&str> {
+        self.btc_light_client.as_deref().or_else(|| {
+            self.network
+                .and_then(|network| network.config().btc_light_client)
+        })
+    }
+
+    /// Bitcoin network, from the setting or the network preset
+    pub fn btc_network(&self) -> Option<BtcNetwork> {
+        self.btc_network
+            .or_else(|| self.network.map(|network| network.config().btc_network))
+    }
+
+    /// Indexer GraphQL URL, from the setting or the network preset
+    pub fn indexer_url(&self) -> Option<&str> {
+        self.indexer_url
+            .as_deref()
+            .or_else(|| self.network.map(|network| network.config().indexer_url))
+    }
+
+    /// Private key of the signing account
+    pub fn private_key(&self) -> Result<&SecretString> {
+        self.private_key
+            .as_ref()
+            .filter(|key| !key.expose_secret().is_empty())
+            .ok_or_else(|| anyhow!("Config is missing private_key"))
+    }
+
+    /// Check that every setting needed by `BridgeClient` is present and well-formed
+    pub fn validate(&self) -> Result<()> {
+        let node_url = self.node_url()?;
+        Url::parse(node_url).map_err(|e| anyhow!("Invalid node_url '{}': {}", node_url, e))?;
+        if let Some(indexer_url) = &self.indexer_url {
+            Url::parse(indexer_url)
+                .map_err(|e| anyhow!("Invalid indexer_url '{}': {}", indexer_url, e))?;
+        }
+        self.private_key()?;
+        parse_account_address(self.bridge_contract_address()?)?;
+        if let Some(btc_light_client) = self.btc_light_client() {
+            parse_account_address(btc_light_client)?;
+        }
+        if self.gas.max_gas_amount == Some(0) || self.gas.gas_unit_price == Some(0) {
+            return Err(anyhow!("Gas amount and unit price must be positive"));
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for Config {
+    fn eq(&self, other: &Self) -> bool {
+        // Spelled out to compare the private key, which hides its contents
+        let Config {
+            network,
+            node_url,
+            aptos_api_key,
+            private_key,
+            bridge_contract_address,
+            btc_light_client,
+            btc_network,
+            indexer_url,
+            graphql_api_key,
+            indexer_table_prefix,
+            gas,
+            retry,
+        } = self;
+        *network == other.network
+            && *node_url == other.node_url
+            && *aptos_api_key == other.aptos_api_key
+            && private_key.as_ref().map(|key| key.expose_secret())
+                == other.private_key.as_ref().map(|key| key.expose_secret())
+            && *bridge_contract_address == other.bridge_contract_address
+            && *btc_light_client == other.btc_light_client
+            && *btc_network == other.btc_network
+            && *indexer_url == other.indexer_url
+            && *graphql_api_key == other.graphql_api_key
+            && *indexer_table_prefix == other.indexer_table_prefix
+            && *gas == other.gas
+            && *retry == other.retry
+    }
+}
+
+impl Eq for Config {}
+
+impl std::fmt::Debug for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Keys are redacted so configs can be logged
+        fn redact<T>(key: &Option<T>) -> Option<&'static str> {
+            key.as_ref().map(|_| "<redacted>")
+        }
+        f.debug_struct("Config")
+            .field("network", &self.network)
+            .field("node_url", &self.node_url)
+            .field("aptos_api_key", &redact(&self.aptos_api_key))
+            .field("private_key", &redact(&self.private_key))
+            .field("bridge_contract_address", &self.bridge_contract_address)
+            .field("btc_light_client", &self.btc_light_client)
+            .field("btc_network", &self.btc_network)
+            .field("indexer_url", &self.indexer_url)
+            .field("graphql_api_key", &redact(&self.graphql_api_key))
+            .field("indexer_table_prefix", &self.indexer_table_prefix)
+            .field("gas", &self.gas)
+            .field("retry", &self.retry)
+            .finish()
+    }
+}
+
+fn parse_var<T: FromStr>(name: &str, value: &str) -> Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|e| anyhow!("Invalid {} '{}': {}", name, value, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_tx_options_override_gas_settings() {
+        let gas_settings = GasSettings {
+            max_gas_amount: Some(10_000),
+            gas_unit_price: Some(150),
+            priority: None,
+        };
+
+        assert_eq!(TxOptions::new().gas_settings(&gas_settings), gas_settings);
+        assert_eq!(
+            TxOptions::new()
+                .with_sequence_number(7)
+                .with_max_gas_amount(5_000)
+                .gas_settings(&gas_settings),
+            GasSettings {
+                max_gas_amount: Some(5_000),
+                ..gas_settings
+            }
+        );
+        // A priority replaces the client's fixed price, not a per-call one
+        assert_eq!(
+            TxOptions::new()
+                .with_gas_priority(GasPriority::Prioritized)
+                .gas_settings(&gas_settings),
+            GasSettings {
+                gas_unit_price: None,
+                priority: Some(GasPriority::Prioritized),
+                ..gas_settings
+            }
+        );
+        assert_eq!(
+            TxOptions::new()
+                .with_gas_priority(GasPriority::Prioritized)
+                .with_gas_unit_price(200)
+                .gas_settings(&gas_settings)
+                .gas_unit_price,
+            Some(200)
+        );
+    }
+
+    #[test]
+    fn test_config_env_overrides_network_presets() {
+        let vars: HashMap<&str, &str> = [
+            ("APTOS_NETWORK", "testnet"),
+            ("PRIVATE_KEY", "0x1"),
+            ("BTC_LIGHT_CLIENT", "0x2"),
+            ("GAS_UNIT_PRICE", "150"),
+            ("GAS_PRIORITY", "Prioritized"),
+            ("MAX_RETRIES", "5"),
+        ]
+        .into();
+        let config = Config::default()
+            .with_vars(|name| vars.get(name).map(|v| v.to_string()))
+            .unwrap();
+
+        assert_eq!(
+            config.node_url().unwrap(),
+            Network::Testnet.config().fullnode_url
+        );
+        assert_eq!(
+            config.bridge_contract_address().unwrap(),
+            Network::Testnet.bridge_contract_address().unwrap()
+        );
+        assert_eq!(config.btc_light_client(), Some("0x2"));
+        assert_eq!(config.btc_network(), Some(BtcNetwork::Regtest));
+        assert_eq!(config.gas.gas_unit_price, Some(150));
+        assert_eq!(config.gas.priority, Some(GasPriority::Prioritized));
+        assert_eq!(config.gas.max_gas_amount, None);
+        assert_eq!(config.retry.max_retries, 5);
+        assert_eq!(config.retry.backoff_ms, DEFAULT_RETRY_BACKOFF_MS);
+        assert!(!format!("{:?}", config).contains("0x1"));
+
+        let bad = Config::default()
+            .with_vars(|name| (name == "MAX_GAS_AMOUNT").then(|| "lots".to_string()));
+        assert!(bad.is_err());
+    }
+
+    #[test]
+    fn test_config_validate() {
+        assert!(Config::default().validate().is_err());
+
+        let config = Config {
+            node_url: Some("not a url".to_string()),
+            private_key: Some("0x1".into()),
+            bridge_contract_address: Some("0x1".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        assert!(Config {
+            node_url: Some("http://localhost:8080/v1".to_string()),
+            ..config
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[test]
+    fn test_config_serialization_drops_private_key() {
+        let config = Config {
+            network: Some(Network::Testnet),
+            private_key: Some("0x1".into()),
+            ..Default::default()
+        };
+        assert_eq!(config, config.clone());
+        assert_ne!(
+            config,
+            Config {
+                private_key: Some("0x2".into()),
+                ..config.clone()
+            }
+        );
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("private_key"));
+        let restored: Config = serde_json::from_str(&json).unwrap();
+        assert!(restored.private_key().is_err());
+        assert_eq!(
+            restored,
+            Config {
+                private_key: None,
+                ..config
+            }
+        );
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_config_from_toml() {
+        let config = Config::from_toml_str(
+            r#"
+            network = "devnet"
+            bridge_contract_address = "0xabc"
+
+            [gas]
+            max_gas_amount = 20000
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.network, Some(Network::Devnet));
+        assert_eq!(config.bridge_contract_address().unwrap(), "0xabc");
+        assert_eq!(config.gas.max_gas_amount, Some(20000));
+        assert_eq!(config.retry, RetryPolicy::default());
+        assert_eq!(config.retry.max_retries, 0);
+    }
+}
@@ -0,0 +1,175 @@
+//! BTC deposit confirmation tracking
+//!
+//! The bridge contract counts confirmations against the on-chain light client, so
+//! a deposit is only mintable once both the Bitcoin backend and the light client
+//! have enough blocks on top of it. Submitting earlier aborts on-chain.
+
+use crate::proof_builder::ProofBuilder;
+use crate::types::constants::CONFIRMATION_POLL_INTERVAL_SECS;
+use crate::BridgeClient;
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Tracks confirmations of BTC transactions as seen by the bridge
+#[derive(Clone)]
+pub struct ConfirmationTracker {
+    /// Bridge client used for the light client tip
+    bridge_client: Arc<BridgeClient>,
+    /// Bitcoin backend used to locate transactions
+    proof_builder: ProofBuilder,
+    /// Delay between checks
+    poll_interval: Duration,
+}
+
+impl ConfirmationTracker {
+    /// Create a confirmation tracker
+    pub fn new(bridge_client: Arc<BridgeClient>, proof_builder: ProofBuilder) -> Self {
+        Self {
+            bridge_client,
+            proof_builder,
+            poll_interval: Duration::from_secs(CONFIRMATION_POLL_INTERVAL_SECS),
+        }
+    }
+
+    /// Set the delay between checks
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Confirmations of `txid` counted up to the lower of the backend and light client tips
+    ///
+    /// `txid` is in display (explorer) byte order. Unconfirmed transactions and
+    /// blocks the light client has not reached yet count as 0.
+    pub async fn confirmations(&self, txid: &str) -> Result<u64> {
+        let Some(height) = self.proof_builder.tx_block_height(txid).await? else {
+            return Ok(0);
+        };
+        let backend_tip = self.proof_builder.tip_height().await?;
+        let light_client_tip = self.bridge_client.get_latest_block_height().await?;
+        Ok((backend_tip.min(light_client_tip) + 1).saturating_sub(height))
+    }
+
+    /// Wait until `txid` has at least `required` confirmations; returns the confirmations seen
+    ///
+    /// Fails instead of waiting on if the backend stops knowing `txid`, e.g. after
+    /// it was dropped from the mempool.
+    pub async fn wait_for_confirmations(&self, txid: &str, required: u64) -> Result<u64> {
+        loop {
+            let confirmations = self.confirmations(txid).await?;
+            if confirmations >= required {
+                return Ok(confirmations);
+            }
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(txid, confirmations, required, "Waiting for confirmations");
+            crate::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+#[cfg(all(test, feature = "esplora", feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const TXID: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+    /// Tracker over the fullnode mock, whose light client tip is 850000, and `esplora`
+    fn tracker(fullnode: &MockServer, esplora: &MockServer) -> ConfirmationTracker {
+        ConfirmationTracker::new(
+            Arc::new(testing::bridge_client(fullnode)),
+            ProofBuilder::esplora(&esplora.uri()),
+        )
+        .with_poll_interval(Duration::from_millis(10))
+    }
+
+    fn tx_status(status: serde_json::Value) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_string(status.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_confirmations_counts_to_lower_tip() {
+        let esplora = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/tx/{}/status", TXID)))
+            .respond_with(tx_status(
+                serde_json::json!({ "confirmed": true, "block_height": 849_999 }),
+            ))
+            .mount(&esplora)
+            .await;
+        // The backend sees the block alone at first, then 6 on top of it
+        Mock::given(method("GET"))
+            .and(path("/blocks/tip/height"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("849999"))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&esplora)
+            .await;
+        testing::mount_esplora_tip(&esplora, 850_005, 5).await;
+
+        let fullnode = testing::mock_fullnode().await;
+        let tracker = tracker(&fullnode, &esplora);
+        assert_eq!(tracker.wait_for_confirmations(TXID, 2).await.unwrap(), 2);
+        // Capped by the light client at 850000, not the backend's 850005
+        assert_eq!(tracker.confirmations(TXID).await.unwrap(), 2);
+
+        let tip_polls = esplora
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|request| request.url.path() == "/blocks/tip/height")
+            .count();
+        assert_eq!(tip_polls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_unconfirmed_transaction_has_no_confirmations() {
+        let esplora = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/tx/{}/status", TXID)))
+            .respond_with(tx_status(serde_json::json!({ "confirmed": false })))
+            .mount(&esplora)
+            .await;
+        testing::mount_esplora_tip(&esplora, 850_000, 5).await;
+
+        let fullnode = testing::mock_fullnode().await;
+        let tracker = tracker(&fullnode, &esplora);
+        assert_eq!(tracker.confirmations(TXID).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_confirmations_fails_for_dropped_transaction() {
+        let esplora = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/tx/{}/status", TXID)))
+            .respond_with(tx_status(
+                serde_json::json!({ "confirmed": true, "block_height": 849_999 }),
+            ))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&esplora)
+            .await;
+        // Evicted from the backend after the first check
+        Mock::given(method("GET"))
+            .and(path(format!("/tx/{}/status", TXID)))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Transaction not found"))
+            .mount(&esplora)
+            .await;
+        testing::mount_esplora_tip(&esplora, 850_000, 5).await;
+
+        let fullnode = testing::mock_fullnode().await;
+        let tracker = tracker(&fullnode, &esplora);
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            tracker.wait_for_confirmations(TXID, 6),
+        )
+        .await
+        .expect("dropped transaction should not be waited on");
+        assert!(result.unwrap_err().to_string().contains("404"));
+    }
+}
@@ -0,0 +1,224 @@
+//! Contract module and function names
+//!
+//! The SDK addresses the Move contracts by module and function name. Forks and
+//! renamed deployments can describe their names with a [`ContractSpec`] instead
+//! of patching the crate.
+
+use anyhow::{anyhow, Result};
+use aptos_sdk::move_types::identifier::Identifier;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Move module called by the SDK
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContractModule {
+    /// Bridge module (`bridge`)
+    Bridge,
+    /// LP manager module (`lp_manager`)
+    LpManager,
+    /// BTC peg token module (`btc_peg`)
+    BtcPeg,
+    /// BTC light client module (`btc_mirror`)
+    LightClient,
+}
+
+/// Module and function names of a bridge deployment
+///
+/// Defaults to the names of the reference contracts. Function overrides map the
+/// SDK's name for a function (e.g. `mint`) to the deployed name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContractSpec {
+    /// Bridge module name
+    pub bridge_module: String,
+    /// LP manager module name
+    pub lp_manager_module: String,
+    /// BTC peg token module name
+    pub btc_peg_module: String,
+    /// Light client module name
+    pub light_client_module: String,
+    /// Deployed function names, keyed by SDK function name
+    pub function_overrides: HashMap<String, String>,
+}
+
+impl Default for ContractSpec {
+    fn default() -> Self {
+        Self {
+            bridge_module: "bridge".to_string(),
+            lp_manager_module: "lp_manager".to_string(),
+            btc_peg_module: "btc_peg".to_string(),
+            light_client_module: "btc_mirror".to_string(),
+            function_overrides: HashMap::new(),
+        }
+    }
+}
+
+impl ContractSpec {
+    /// Set the bridge module name
+    pub fn with_bridge_module(mut self, name: &str) -> Self {
+        self.bridge_module = name.to_string();
+        self
+    }
+
+    /// Set the LP manager module name
+    pub fn with_lp_manager_module(mut self, name: &str) -> Self {
+        self.lp_manager_module = name.to_string();
+        self
+    }
+
+    /// Set the BTC peg token module name
+    pub fn with_btc_peg_module(mut self, name: &str) -> Self {
+        self.btc_peg_module = name.to_string();
+        self
+    }
+
+    /// Set the light client module name
+    pub fn with_light_client_module(mut self, name: &str) -> Self {
+        self.light_client_module = name.to_string();
+        self
+    }
+
+    /// Call `deployed_name` wherever the SDK calls `sdk_name`
+    pub fn with_function(mut self, sdk_name: &str, deployed_name: &str) -> Self {
+        self.function_overrides
+            .insert(sdk_name.to_string(), deployed_name.to_string());
+        self
+    }
+
+    /// Deployed name of a module
+    pub fn module_name(&self, module: ContractModule) -> &str {
+        match module {
+            ContractModule::Bridge => &self.bridge_module,
+            ContractModule::LpManager => &self.lp_manager_module,
+            ContractModule::BtcPeg => &self.btc_peg_module,
+            ContractModule::LightClient => &self.light_client_module,
+        }
+    }
+
+    /// Deployed name of a function
+    pub fn function_name<'a>(&'a self, sdk_name: &'a str) -> &'a str {
+        self.function_overrides
+            .get(sdk_name)
+            .map_or(sdk_name, String::as_str)
+    }
+
+    /// Check that every configured name is a valid Move identifier
+    pub fn validate(&self) -> Result<()> {
+        ContractIds::new(self.clone()).map(|_| ())
+    }
+}
+
+/// Identifiers of a validated [`ContractSpec`], built once when a client is
+/// configured so call paths don't re-validate names
+#[derive(Debug, Clone)]
+pub(crate) struct ContractIds {
+    spec: ContractSpec,
+    bridge: Identifier,
+    lp_manager: Identifier,
+    btc_peg: Identifier,
+    light_client: Identifier,
+    /// Deployed function identifiers, keyed by SDK function name
+    function_overrides: HashMap<String, Identifier>,
+}
+
+impl ContractIds {
+    /// Validate `spec` and build its identifiers
+    pub(crate) fn new(spec: ContractSpec) -> Result<Self> {
+        let function_overrides = spec
+            .function_overrides
+            .iter()
+            .map(|(sdk_name, deployed_name)| Ok((sdk_name.clone(), identifier(deployed_name)?)))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            bridge: identifier(&spec.bridge_module)?,
+            lp_manager: identifier(&spec.lp_manager_module)?,
+            btc_peg: identifier(&spec.btc_peg_module)?,
+            light_client: identifier(&spec.light_client_module)?,
+            function_overrides,
+            spec,
+        })
+    }
+
+    /// Names the identifiers were built from
+    pub(crate) fn spec(&self) -> &ContractSpec {
+        &self.spec
+    }
+
+    /// Deployed identifier of a module
+    pub(crate) fn module(&self, module: ContractModule) -> Identifier {
+        match module {
+            ContractModule::Bridge => self.bridge.clone(),
+            ContractModule::LpManager => self.lp_manager.clone(),
+            ContractModule::BtcPeg => self.btc_peg.clone(),
+            ContractModule::LightClient => self.light_client.clone(),
+        }
+    }
+
+    /// Deployed identifier of a function
+    ///
+    /// Only fails if `sdk_name` itself is not an identifier, which the SDK's
+    /// own function names never are.
+    pub(crate) fn function(&self, sdk_name: &str) -> Result<Identifier> {
+        match self.function_overrides.get(sdk_name) {
+            Some(deployed) => Ok(deployed.clone()),
+            None => identifier(sdk_name),
+        }
+    }
+}
+
+impl Default for ContractIds {
+    fn default() -> Self {
+        Self::new(ContractSpec::default()).expect("default contract names are valid identifiers")
+    }
+}
+
+fn identifier(name: &str) -> Result<Identifier> {
+    Identifier::new(name).map_err(|e| anyhow!("Invalid Move identifier '{}': {}", name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contract_spec_overrides() {
+        let spec = ContractSpec::default()
+            .with_bridge_module("bridge_v2")
+            .with_function("mint", "mint_btc");
+        assert_eq!(spec.module_name(ContractModule::Bridge), "bridge_v2");
+        assert_eq!(spec.module_name(ContractModule::LightClient), "btc_mirror");
+        assert_eq!(spec.function_name("mint"), "mint_btc");
+        assert_eq!(spec.function_name("burn"), "burn");
+        assert!(spec.validate().is_ok());
+
+        assert!(ContractSpec::default()
+            .with_light_client_module("not valid")
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_contract_ids() {
+        let ids = ContractIds::new(
+            ContractSpec::default()
+                .with_lp_manager_module("lp_v2")
+                .with_function("mint", "mint_btc"),
+        )
+        .unwrap();
+        assert_eq!(ids.module(ContractModule::LpManager).as_str(), "lp_v2");
+        assert_eq!(ids.module(ContractModule::Bridge).as_str(), "bridge");
+        assert_eq!(ids.function("mint").unwrap().as_str(), "mint_btc");
+        assert_eq!(ids.function("burn").unwrap().as_str(), "burn");
+
+        assert!(
+            ContractIds::new(ContractSpec::default().with_function("mint", "mint-btc")).is_err()
+        );
+    }
+
+    #[test]
+    fn test_contract_spec_partial_json() {
+        let spec: ContractSpec = serde_json::from_str(r#"{"bridge_module": "fork"}"#).unwrap();
+        assert_eq!(spec.bridge_module, "fork");
+        assert_eq!(spec.lp_manager_module, "lp_manager");
+    }
+}
@@ -0,0 +1,415 @@
+//! Local end-to-end harness
+//!
+//! Starts an Aptos localnet (through the `aptos` CLI) and a regtest `bitcoind`,
+//! publishes the bridge Move package from the deployer account and exposes the
+//! SDK clients wired to both, so release checks can run mint → burn → claim
+//! round trips without public infrastructure. Enabled by the `e2e` feature.
+//!
+//! The Move package is not part of this crate; point `BRIDGE_MOVE_PACKAGE` at a
+//! checkout whose light client is initialized with the regtest genesis block.
+//! Child processes are killed when the harness is dropped.
+
+use crate::abort::execution_error;
+use crate::bitcoind::BitcoindRpc;
+use crate::btc::BtcNetwork;
+use crate::faucet::FaucetClient;
+use crate::header_sync::HeaderSyncService;
+use crate::proof_builder::ProofBuilder;
+use crate::types::Sats;
+use crate::{BridgeClient, QueryClient};
+use anyhow::{anyhow, Result};
+use aptos_sdk::rest_client::aptos_api_types::TransactionData;
+use aptos_sdk::types::LocalAccount;
+use bitcoin::consensus::encode::{deserialize_hex, serialize_hex};
+use bitcoin::{Address, Amount, OutPoint, Transaction, TxOut, Txid};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::{Child, Command};
+
+/// Private key of the localnet deployer (test-only, never funded outside localnet)
+const DEPLOYER_PRIVATE_KEY: &str =
+    "0xe2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2e2";
+/// Octas requested from the localnet faucet for the deployer
+const DEPLOYER_FUNDING_OCTAS: u64 = 10_000_000_000;
+/// Regtest RPC credentials
+const BITCOIND_RPC_USER: &str = "e2e";
+const BITCOIND_RPC_PASSWORD: &str = "e2e";
+/// Blocks mined at startup so the wallet has mature coinbase outputs
+const INITIAL_BLOCKS: u64 = 101;
+/// REST port of `aptos node run-local-testnet`
+const APTOS_NODE_PORT: u16 = 8080;
+/// Faucet port of `aptos node run-local-testnet`
+const APTOS_FAUCET_PORT: u16 = 8081;
+/// Delay between readiness and confirmation checks
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Harness settings
+#[derive(Debug, Clone)]
+pub struct E2eConfig {
+    /// `aptos` CLI binary
+    pub aptos_cli: PathBuf,
+    /// `bitcoind` binary
+    pub bitcoind: PathBuf,
+    /// Bridge Move package directory
+    pub move_package: PathBuf,
+    /// Named address the package is published under
+    pub named_address: String,
+    /// Directory for node data and logs
+    pub work_dir: PathBuf,
+    /// Regtest RPC port
+    pub bitcoind_rpc_port: u16,
+    /// How long to wait for the nodes to come up
+    pub startup_timeout: Duration,
+}
+
+impl E2eConfig {
+    /// Defaults for a package at `move_package`
+    pub fn new(move_package: impl Into<PathBuf>) -> Self {
+        Self {
+            aptos_cli: PathBuf::from("aptos"),
+            bitcoind: PathBuf::from("bitcoind"),
+            move_package: move_package.into(),
+            named_address: "bridge".to_string(),
+            work_dir: std::env::temp_dir().join("aptos-client-sdk-e2e"),
+            bitcoind_rpc_port: 18443,
+            startup_timeout: Duration::from_secs(120),
+        }
+    }
+
+    /// Read `BRIDGE_MOVE_PACKAGE` (required), `APTOS_CLI`, `BITCOIND` and `E2E_WORK_DIR`
+    pub fn from_env() -> Result<Self> {
+        let move_package = std::env::var("BRIDGE_MOVE_PACKAGE")
+            .map_err(|_| anyhow!("BRIDGE_MOVE_PACKAGE must point to the bridge Move package"))?;
+        let mut config = Self::new(move_package);
+        if let Ok(aptos_cli) = std::env::var("APTOS_CLI") {
+            config.aptos_cli = aptos_cli.into();
+        }
+        if let Ok(bitcoind) = std::env::var("BITCOIND") {
+            config.bitcoind = bitcoind.into();
+        }
+        if let Ok(work_dir) = std::env::var("E2E_WORK_DIR") {
+            config.work_dir = work_dir.into();
+        }
+        Ok(config)
+    }
+}
+
+/// Running localnet and regtest node with the bridge deployed
+pub struct E2eHarness {
+    config: E2eConfig,
+    deployer_address: String,
+    bitcoind_rpc: BitcoindRpc,
+    _aptos_node: Child,
+    _bitcoind: Child,
+}
+
+impl E2eHarness {
+    /// Start both nodes, fund the deployer, publish the package and mine spendable coins
+    pub async fn start(config: E2eConfig) -> Result<Self> {
+        std::fs::create_dir_all(&config.work_dir).map_err(|e| {
+            anyhow!(
+                "Failed to create work dir {}: {}",
+                config.work_dir.display(),
+                e
+            )
+        })?;
+        let deployer = LocalAccount::from_private_key(DEPLOYER_PRIVATE_KEY, 0)
+            .map_err(|e| anyhow!("Invalid deployer key: {}", e))?;
+        let deployer_address = deployer.address().to_hex_literal();
+
+        let aptos_node = spawn(
+            Command::new(&config.aptos_cli)
+                .args([
+                    "node",
+                    "run-local-testnet",
+                    "--with-faucet",
+                    "--force-restart",
+                ])
+                .arg("--assume-yes")
+                .arg("--test-dir")
+                .arg(config.work_dir.join("aptos")),
+            &config.work_dir.join("aptos.log"),
+        )?;
+
+        let bitcoind_dir = config.work_dir.join("bitcoind");
+        let _ = std::fs::remove_dir_all(&bitcoind_dir);
+        std::fs::create_dir_all(&bitcoind_dir)
+            .map_err(|e| anyhow!("Failed to create {}: {}", bitcoind_dir.display(), e))?;
+        let bitcoind = spawn(
+            Command::new(&config.bitcoind)
+                .arg("-regtest")
+                .arg(format!("-datadir={}", bitcoind_dir.display()))
+                .arg(format!("-rpcport={}", config.bitcoind_rpc_port))
+                .arg(format!("-rpcuser={}", BITCOIND_RPC_USER))
+                .arg(format!("-rpcpassword={}", BITCOIND_RPC_PASSWORD))
+                .args(["-server=1", "-txindex=1", "-fallbackfee=0.0001"]),
+            &config.work_dir.join("bitcoind.log"),
+        )?;
+
+        let bitcoind_rpc =
+            BitcoindRpc::new(&format!("http://127.0.0.1:{}", config.bitcoind_rpc_port))
+                .with_basic_auth(BITCOIND_RPC_USER, BITCOIND_RPC_PASSWORD);
+
+        let harness = Self {
+            config,
+            deployer_address,
+            bitcoind_rpc,
+            _aptos_node: aptos_node,
+            _bitcoind: bitcoind,
+        };
+        harness.wait_until_ready().await?;
+        harness
+            .fund(&harness.deployer_address, DEPLOYER_FUNDING_OCTAS)
+            .await?;
+        harness.publish_package().await?;
+
+        let _: serde_json::Value = harness
+            .bitcoind_rpc
+            .call("createwallet", serde_json::json!(["e2e"]))
+            .await?;
+        harness.mine(INITIAL_BLOCKS).await?;
+
+        Ok(harness)
+    }
+
+    /// Aptos localnet REST URL
+    pub fn node_url(&self) -> String {
+        format!("http://127.0.0.1:{}/v1", APTOS_NODE_PORT)
+    }
+
+    /// Regtest RPC URL
+    pub fn bitcoind_rpc_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.config.bitcoind_rpc_port)
+    }
+
+    /// Address the bridge and light client are published at
+    pub fn deployer_address(&self) -> &str {
+        &self.deployer_address
+    }
+
+    /// Regtest RPC client
+    pub fn bitcoind(&self) -> BitcoindRpc {
+        self.bitcoind_rpc.clone()
+    }
+
+    /// Proof builder backed by the regtest node
+    pub fn proof_builder(&self) -> ProofBuilder {
+        ProofBuilder::bitcoind(&self.bitcoind_rpc_url())
+            .with_basic_auth(BITCOIND_RPC_USER, BITCOIND_RPC_PASSWORD)
+    }
+
+    /// Bridge client signing as the deployer
+    pub fn bridge_client(&self) -> Result<BridgeClient> {
+        Ok(BridgeClient::new(
+            &self.node_url(),
+            None,
+            DEPLOYER_PRIVATE_KEY,
+            &self.deployer_address,
+            Some(&self.deployer_address),
+        )?
+        .with_btc_network(BtcNetwork::Regtest))
+    }
+
+    /// Fund an Aptos account from the localnet faucet
+    pub async fn fund(&self, address: &str, octas: u64) -> Result<()> {
+        FaucetClient::new(&format!("http://127.0.0.1:{}", APTOS_FAUCET_PORT))?
+            .fund(address, octas)
+            .await?;
+        Ok(())
+    }
+
+    /// Mine `blocks` regtest blocks to the wallet
+    pub async fn mine(&self, blocks: u64) -> Result<()> {
+        let address = self.new_address().await?;
+        let _: Vec<String> = self
+            .bitcoind_rpc
+            .call("generatetoaddress", serde_json::json!([blocks, address]))
+            .await?;
+        Ok(())
+    }
+
+    /// New wallet address (bech32 regtest)
+    pub async fn new_address(&self) -> Result<String> {
+        self.bitcoind_rpc
+            .call("getnewaddress", serde_json::json!([]))
+            .await
+    }
+
+    /// Pay `amount` to `address` from the wallet; returns the txid and output index
+    pub async fn send_to_address(&self, address: &str, amount: Sats) -> Result<(Txid, u32)> {
+        let txid: String = self
+            .bitcoind_rpc
+            .call(
+                "sendtoaddress",
+                serde_json::json!([address, Amount::from_sat(amount.0).to_btc()]),
+            )
+            .await?;
+        let txid = Txid::from_str(&txid).map_err(|e| anyhow!("Invalid txid '{}': {}", txid, e))?;
+
+        let script_pubkey = Address::from_str(address)
+            .map_err(|e| anyhow!("Invalid address '{}': {}", address, e))?
+            .assume_checked()
+            .script_pubkey();
+        let tx = self.get_transaction(&txid).await?;
+        let vout = tx
+            .output
+            .iter()
+            .position(|output| output.script_pubkey == script_pubkey)
+            .ok_or_else(|| anyhow!("Transaction {} does not pay {}", txid, address))?;
+        Ok((txid, vout as u32))
+    }
+
+    /// Output `vout` of a wallet or mempool transaction, for PSBT inputs
+    pub async fn utxo(&self, txid: &Txid, vout: u32) -> Result<(OutPoint, TxOut)> {
+        let tx = self.get_transaction(txid).await?;
+        let txout = tx
+            .output
+            .get(vout as usize)
+            .cloned()
+            .ok_or_else(|| anyhow!("Transaction {} has no output {}", txid, vout))?;
+        Ok((OutPoint::new(*txid, vout), txout))
+    }
+
+    /// Sign `tx` with the wallet and broadcast it
+    pub async fn sign_and_broadcast(&self, tx: &Transaction) -> Result<Txid> {
+        #[derive(Deserialize)]
+        struct SignResult {
+            hex: String,
+            complete: bool,
+        }
+
+        let signed: SignResult = self
+            .bitcoind_rpc
+            .call(
+                "signrawtransactionwithwallet",
+                serde_json::json!([serialize_hex(tx)]),
+            )
+            .await?;
+        if !signed.complete {
+            return Err(anyhow!(
+                "Wallet could not sign every input of {}",
+                tx.compute_txid()
+            ));
+        }
+        let txid: String = self
+            .bitcoind_rpc
+            .call("sendrawtransaction", serde_json::json!([signed.hex]))
+            .await?;
+        Txid::from_str(&txid).map_err(|e| anyhow!("Invalid txid '{}': {}", txid, e))
+    }
+
+    /// Submit every regtest header the light client is missing
+    pub async fn sync_headers(&self, bridge_client: Arc<BridgeClient>) -> Result<u64> {
+        HeaderSyncService::new(bridge_client, self.bitcoind())
+            .sync_once()
+            .await
+    }
+
+    /// Wait for an Aptos transaction and fail unless it succeeded
+    pub async fn wait_for_success(
+        &self,
+        bridge_client: &BridgeClient,
+        tx_hash: &str,
+    ) -> Result<()> {
+        match bridge_client
+            .wait_for_transaction(tx_hash, self.config.startup_timeout)
+            .await?
+        {
+            TransactionData::OnChain(txn) if txn.info.status().is_success() => Ok(()),
+            TransactionData::OnChain(txn) => {
+                Err(execution_error("Transaction", tx_hash, txn.info.status()))
+            }
+            TransactionData::Pending(_) => Err(anyhow!("Transaction {} is still pending", tx_hash)),
+        }
+    }
+
+    async fn get_transaction(&self, txid: &Txid) -> Result<Transaction> {
+        let hex: String = self
+            .bitcoind_rpc
+            .call(
+                "getrawtransaction",
+                serde_json::json!([txid.to_string(), false]),
+            )
+            .await?;
+        deserialize_hex(&hex).map_err(|e| anyhow!("Invalid transaction {}: {}", txid, e))
+    }
+
+    async fn wait_until_ready(&self) -> Result<()> {
+        let deadline = Instant::now() + self.config.startup_timeout;
+        let query_client = QueryClient::new(&self.node_url(), None)?;
+        loop {
+            let aptos_ready = query_client.get_ledger_version().await.is_ok();
+            let bitcoind_ready = self.bitcoind_rpc.get_block_count().await.is_ok();
+            if aptos_ready && bitcoind_ready {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "Nodes not ready within {:?} (aptos: {}, bitcoind: {}); see logs in {}",
+                    self.config.startup_timeout,
+                    aptos_ready,
+                    bitcoind_ready,
+                    self.config.work_dir.display()
+                ));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    async fn publish_package(&self) -> Result<()> {
+        let status = Command::new(&self.config.aptos_cli)
+            .args([
+                "move",
+                "publish",
+                "--assume-yes",
+                "--skip-fetch-latest-git-deps",
+            ])
+            .arg("--package-dir")
+            .arg(&self.config.move_package)
+            .arg("--named-addresses")
+            .arg(format!(
+                "{}={}",
+                self.config.named_address, self.deployer_address
+            ))
+            .arg("--private-key")
+            .arg(DEPLOYER_PRIVATE_KEY)
+            .arg("--url")
+            .arg(self.node_url())
+            .status()
+            .await
+            .map_err(|e| anyhow!("Failed to run {}: {}", self.config.aptos_cli.display(), e))?;
+        if !status.success() {
+            return Err(anyhow!(
+                "Publishing {} failed: {}",
+                self.config.move_package.display(),
+                status
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Spawn a long-running node, logging to `log_path` and killed on drop
+fn spawn(command: &mut Command, log_path: &Path) -> Result<Child> {
+    let log = std::fs::File::create(log_path)
+        .map_err(|e| anyhow!("Failed to create {}: {}", log_path.display(), e))?;
+    let stderr = log
+        .try_clone()
+        .map_err(|e| anyhow!("Failed to open {}: {}", log_path.display(), e))?;
+    command
+        .stdout(Stdio::from(log))
+        .stderr(Stdio::from(stderr))
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| {
+            anyhow!(
+                "Failed to start {:?}: {}",
+                command.as_std().get_program(),
+                e
+            )
+        })
+}
@@ -0,0 +1,263 @@
+//! At-least-once event delivery with explicit acknowledgement
+//!
+//! [`AckingMonitor`] hands out each event with an [`AckToken`] instead of
+//! calling an [`EventHandler`](super::EventHandler). The cursor saved in a
+//! [`CursorStore`] only moves past an event once it and every earlier event
+//! have been acknowledged, so after a restart every unacknowledged event is
+//! delivered again. Consumers writing to external systems ack once the write
+//! is durable; they must tolerate the redelivery of events written just
+//! before a crash.
+
+use super::EventMonitor;
+use crate::json_file::{read_json, write_json_atomic};
+use crate::types::BridgeEvent;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Durable storage of an [`AckingMonitor`]'s cursor
+#[async_trait]
+pub trait CursorStore: Send + Sync {
+    /// Saved cursor, or `None` before the first save
+    async fn load(&self) -> Result<Option<u64>>;
+
+    /// Save the version up to which every event is acknowledged
+    async fn save(&self, version: u64) -> Result<()>;
+}
+
+/// Store keeping the cursor as a decimal number in a file
+pub struct FileCursorStore {
+    path: std::path::PathBuf,
+}
+
+impl FileCursorStore {
+    /// Create a store backed by `path`, which is created on first save
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl CursorStore for FileCursorStore {
+    async fn load(&self) -> Result<Option<u64>> {
+        read_json(&self.path, "cursor").await
+    }
+
+    async fn save(&self, version: u64) -> Result<()> {
+        write_json_atomic(&self.path, "cursor", &version).await
+    }
+}
+
+/// Delivered events and acknowledgement progress
+struct AckState {
+    /// Delivery sequence number of the first entry of `outstanding`
+    base_seq: u64,
+    /// Version and acknowledgement of delivered events, from the oldest
+    /// unacknowledged one, in delivery (version) order
+    outstanding: VecDeque<(u64, bool)>,
+    /// Every event at or below this version is acknowledged
+    acked_through: u64,
+    /// Highest version delivered so far
+    delivered_through: u64,
+}
+
+impl AckState {
+    fn ack(&mut self, seq: u64) {
+        let Some(index) = seq.checked_sub(self.base_seq) else {
+            return;
+        };
+        if let Some(entry) = self.outstanding.get_mut(index as usize) {
+            entry.1 = true;
+        }
+        while self.outstanding.front().is_some_and(|&(_, acked)| acked) {
+            self.outstanding.pop_front();
+            self.base_seq += 1;
+        }
+        self.acked_through = match self.outstanding.front() {
+            // Events sharing its version may be acknowledged, but not all are
+            Some(&(version, _)) => self.acked_through.max(version.saturating_sub(1)),
+            None => self.delivered_through,
+        };
+    }
+}
+
+impl std::fmt::Debug for AckState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AckState")
+            .field("outstanding", &self.outstanding.len())
+            .field("acked_through", &self.acked_through)
+            .finish()
+    }
+}
+
+/// Acknowledges one delivered event
+#[derive(Debug)]
+pub struct AckToken {
+    seq: u64,
+    state: Arc<Mutex<AckState>>,
+}
+
+impl AckToken {
+    /// Mark the event as processed
+    ///
+    /// The cursor is saved on the next [`AckingMonitor::commit`] or
+    /// [`AckingMonitor::poll`]. A dropped token is never acknowledged and
+    /// holds the cursor back until the monitor is restarted.
+    pub fn ack(self) {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .ack(self.seq);
+    }
+}
+
+/// Event awaiting acknowledgement
+#[derive(Debug)]
+pub struct PendingEvent {
+    /// The event
+    pub event: BridgeEvent,
+    /// Token to acknowledge it with
+    pub token: AckToken,
+}
+
+/// Event monitor delivering at least once, with explicit acknowledgement
+pub struct AckingMonitor {
+    monitor: EventMonitor,
+    store: Arc<dyn CursorStore>,
+    state: Arc<Mutex<AckState>>,
+    /// Last cursor written to the store
+    saved_cursor: u64,
+}
+
+impl AckingMonitor {
+    /// Resume after the cursor in `store`, or after `monitor`'s start version
+    /// if none is saved
+    ///
+    /// `monitor`'s handler is not called in this mode.
+    pub async fn new(monitor: EventMonitor, store: Arc<dyn CursorStore>) -> Result<Self> {
+        let saved_cursor = store.load().await?.unwrap_or(monitor.start_version());
+        Ok(Self {
+            monitor,
+            store,
+            state: Arc::new(Mutex::new(AckState {
+                base_seq: 0,
+                outstanding: VecDeque::new(),
+                acked_through: saved_cursor,
+                delivered_through: saved_cursor,
+            })),
+            saved_cursor,
+        })
+    }
+
+    /// Save acknowledged progress, then fetch events after the last delivered one
+    pub async fn poll(&mut self) -> Result<Vec<PendingEvent>> {
+        self.commit().await?;
+        let delivered_through = self.lock().delivered_through;
+        let events = self.monitor.fetch_after(delivered_through).await?;
+
+        let mut state = self.lock();
+        let mut pending = Vec::with_capacity(events.len());
+        for event in events {
+            let version = event
+                .version()
+                .unwrap_or(state.delivered_through)
+                .max(state.delivered_through);
+            let seq = state.base_seq + state.outstanding.len() as u64;
+            state.outstanding.push_back((version, false));
+            state.delivered_through = version;
+            pending.push(PendingEvent {
+                event,
+                token: AckToken {
+                    seq,
+                    state: self.state.clone(),
+                },
+            });
+        }
+        Ok(pending)
+    }
+
+    /// Save the cursor if acknowledgements have moved it, and return it
+    pub async fn commit(&mut self) -> Result<u64> {
+        let cursor = self.cursor();
+        if cursor > self.saved_cursor {
+            self.store.save(cursor).await?;
+            self.saved_cursor = cursor;
+        }
+        Ok(cursor)
+    }
+
+    /// Version up to which every delivered event is acknowledged
+    pub fn cursor(&self) -> u64 {
+        self.lock().acked_through
+    }
+
+    /// Delivered events not yet acknowledged
+    pub fn outstanding(&self) -> usize {
+        self.lock()
+            .outstanding
+            .iter()
+            .filter(|&&(_, acked)| !acked)
+            .count()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, AckState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use crate::{BurnEvent, EventHandler, MintEvent, WithdrawByLPEvent};
+
+    struct NoopHandler;
+
+    #[async_trait]
+    impl EventHandler for NoopHandler {
+        async fn handle_mint(&self, _event: MintEvent) -> Result<()> {
+            Ok(())
+        }
+        async fn handle_burn(&self, _event: BurnEvent) -> Result<()> {
+            Ok(())
+        }
+        async fn handle_withdraw_by_lp(&self, _event: WithdrawByLPEvent) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cursor_advances_past_contiguous_acks() {
+        let graphql = testing::mock_graphql().await;
+        let fullnode = testing::mock_fullnode().await;
+        let monitor = EventMonitor::new(
+            &graphql.uri(),
+            "key",
+            &testing::fullnode_url(&fullnode),
+            None,
+            Box::new(NoopHandler),
+            0,
+        )
+        .unwrap();
+        let path = std::env::temp_dir().join(format!("ack-cursor-{}", std::process::id()));
+        let store = Arc::new(FileCursorStore::new(&path));
+        let mut acking = AckingMonitor::new(monitor, store.clone()).await.unwrap();
+
+        let mut pending = acking.poll().await.unwrap().into_iter();
+        let (first, second, third) = (
+            pending.next().unwrap(),
+            pending.next().unwrap(),
+            pending.next().unwrap(),
+        );
+        third.token.ack();
+        second.token.ack();
+        assert_eq!(acking.commit().await.unwrap(), 0);
+        assert_eq!(acking.outstanding(), 1);
+
+        first.token.ack();
+        assert_eq!(acking.commit().await.unwrap(), 6_543_300);
+        assert_eq!(store.load().await.unwrap(), Some(6_543_300));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,915 @@
+//! GraphQL event monitor
+//!
+//! Polls the Aptos indexer for bridge events and dispatches them to an
+//! [`EventHandler`]. Enabled by the `events-graphql` feature.
+
+use super::indexer::{filter_variable, EventFilter, GraphQLAuth, IndexerClient};
+use super::schema::IndexerSchema;
+use super::EventHandler;
+use crate::config::RetryPolicy;
+use crate::types::{BurnEventRaw, FieldParseError, MintEventRaw, WithdrawByLPEventRaw};
+use crate::{BridgeEvent, BridgeEventKind, BurnEvent, MintEvent, WithdrawByLPEvent};
+
+use anyhow::{anyhow, Result};
+use futures::stream::{self, Stream, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Event tables of a poll; tables excluded by the filter are absent
+#[derive(Default, Deserialize)]
+struct GraphQLData {
+    #[serde(default)]
+    bridge_mint_events: Vec<MintEventRaw>,
+    #[serde(default)]
+    bridge_burn_events: Vec<BurnEventRaw>,
+    #[serde(default)]
+    bridge_withdraw_by_lp_events: Vec<WithdrawByLPEventRaw>,
+}
+
+/// Version bounds for a GraphQL event query
+#[derive(Debug, Clone, Copy)]
+struct VersionRange {
+    start: u64,
+    /// Inclusive upper bound; `None` means unbounded
+    end: Option<u64>,
+    /// Whether `start` itself is included
+    start_inclusive: bool,
+}
+
+impl VersionRange {
+    /// Versions strictly greater than `start`
+    fn after(start: u64) -> Self {
+        Self {
+            start,
+            end: None,
+            start_inclusive: false,
+        }
+    }
+
+    /// Versions in `[start, end]`
+    fn between(start: u64, end: u64) -> Self {
+        Self {
+            start,
+            end: Some(end),
+            start_inclusive: true,
+        }
+    }
+
+    /// Hasura comparison expression for the `version` column
+    fn where_clause(&self) -> &'static str {
+        if self.start_inclusive {
+            "{_gte: $startVersion, _lte: $endVersion}"
+        } else {
+            "{_gt: $startVersion, _lte: $endVersion}"
+        }
+    }
+}
+
+/// What the monitor does with an event whose fields fail to parse
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseFailurePolicy {
+    /// Fail the poll with the parse error
+    #[default]
+    Abort,
+    /// Log the malformed event and continue without it
+    Skip,
+}
+
+/// Stage of a poll that failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PollErrorKind {
+    /// The indexer request failed or returned GraphQL errors
+    Indexer,
+    /// An event's fields failed to parse
+    Parse,
+    /// The event handler returned an error
+    Handler,
+}
+
+/// Failed poll of [`EventMonitor::process`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PollError {
+    /// Stage that failed
+    pub kind: PollErrorKind,
+    /// Error message
+    pub message: String,
+    /// Failure time, seconds since the Unix epoch
+    pub unix_timestamp: u64,
+}
+
+/// Health of an [`EventMonitor`], for readiness probes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MonitorHealth {
+    /// Time of the last successful poll, seconds since the Unix epoch
+    pub last_success_unix_timestamp: Option<u64>,
+    /// Polls failed since the last successful one
+    pub consecutive_failures: u32,
+    /// Most recent failure, kept after later successes
+    pub last_error: Option<PollError>,
+}
+
+impl MonitorHealth {
+    /// Whether the last poll succeeded, at most `max_age` ago
+    pub fn is_ready(&self, max_age: Duration) -> bool {
+        self.consecutive_failures == 0
+            && self.last_success_unix_timestamp.is_some_and(|timestamp| {
+                crate::time::unix_timestamp_secs().saturating_sub(timestamp) <= max_age.as_secs()
+            })
+    }
+}
+
+/// Outcome of one [`EventMonitor::process_report`] poll
+#[derive(Debug, Clone)]
+pub struct ProcessReport {
+    /// Events handled successfully, in version order
+    pub events: Vec<BridgeEvent>,
+    /// Rows returned by the indexer
+    pub fetched: usize,
+    /// Events handed to the handler without error
+    pub handled: usize,
+    /// Malformed events skipped under [`ParseFailurePolicy::Skip`]
+    pub skipped: usize,
+    /// Events the handler failed on; dispatch stops at the first failure
+    pub failed: usize,
+    /// Version up to which every event was handled, safe to persist as the
+    /// next start version
+    pub new_cursor: u64,
+    /// Highest version among the decoded events
+    pub max_seen_version: Option<u64>,
+    /// Time taken by the poll
+    pub duration: Duration,
+}
+
+/// Event monitor
+pub struct EventMonitor {
+    indexer: IndexerClient,
+    event_filter: EventFilter,
+    parse_failure_policy: ParseFailurePolicy,
+    handler: Box<dyn EventHandler>,
+    last_processed_version: u64,
+    query_client: crate::QueryClient,
+    health: Mutex<MonitorHealth>,
+}
+
+impl EventMonitor {
+    /// Create new event monitor
+    ///
+    /// `graphql_api_key` is sent as `Authorization: Bearer <key>`; use
+    /// [`EventMonitor::with_graphql_auth`] for other authentication modes.
+    pub fn new(
+        graphql_url: &str,
+        graphql_api_key: &str,
+        node_url: &str,
+        aptos_api_key: Option<&str>,
+        handler: Box<dyn EventHandler>,
+        last_processed_version: u64,
+    ) -> Result<Self> {
+        let query_client = crate::QueryClient::new(node_url, aptos_api_key)?;
+        let indexer = IndexerClient::new(
+            graphql_url,
+            GraphQLAuth::Bearer(graphql_api_key.to_string()),
+        )?;
+
+        Ok(Self {
+            indexer,
+            event_filter: EventFilter::default(),
+            parse_failure_policy: ParseFailurePolicy::default(),
+            handler,
+            last_processed_version,
+            query_client,
+            health: Mutex::new(MonitorHealth::default()),
+        })
+    }
+
+    /// Replace the GraphQL authentication mode
+    pub fn with_graphql_auth(mut self, auth: GraphQLAuth) -> Self {
+        self.indexer = self.indexer.with_auth(auth);
+        self
+    }
+
+    /// Add an extra header sent with every GraphQL request
+    pub fn with_graphql_header(mut self, name: &str, value: &str) -> Self {
+        self.indexer = self.indexer.with_header(name, value);
+        self
+    }
+
+    /// Identify the calling application in the User-Agent of GraphQL and node requests
+    pub fn with_app_id(mut self, app_id: &str) -> Result<Self> {
+        self.query_client = self.query_client.with_app_id(app_id)?;
+        self.indexer = self.indexer.with_app_id(app_id);
+        Ok(self)
+    }
+
+    /// Add a header sent with every GraphQL and node request
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self> {
+        self.query_client = self.query_client.with_header(name, value)?;
+        self.indexer = self.indexer.with_header(name, value);
+        Ok(self)
+    }
+
+    /// Use non-default indexer table and column names (self-hosted processors)
+    pub fn with_indexer_schema(mut self, schema: IndexerSchema) -> Self {
+        self.indexer = self.indexer.with_schema(schema);
+        self
+    }
+
+    /// Only fetch events matching `filter`, filtered by the indexer
+    ///
+    /// Applies to [`EventMonitor::process`] and replays alike.
+    pub fn with_event_filter(mut self, filter: EventFilter) -> Self {
+        self.event_filter = filter;
+        self
+    }
+
+    /// Set how events with unparseable fields are handled
+    pub fn with_parse_failure_policy(mut self, policy: ParseFailurePolicy) -> Self {
+        self.parse_failure_policy = policy;
+        self
+    }
+
+    /// Use a preconfigured HTTP client (proxy, pool size, TLS) for GraphQL requests
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.indexer = self.indexer.with_http_client(http_client);
+        self
+    }
+
+    /// Set the GraphQL request timeout
+    ///
+    /// Replaces the HTTP client with a default one using the given timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Result<Self> {
+        self.indexer = self.indexer.with_timeout(timeout)?;
+        Ok(self)
+    }
+
+    /// Retry failed GraphQL requests (default: no retries)
+    pub fn with_graphql_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.indexer = self.indexer.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Use proxy, TLS and timeout settings for GraphQL and node requests
+    ///
+    /// Replaces the HTTP client; the GraphQL timeout stays at its default
+    /// unless the config sets one.
+    #[cfg(all(feature = "http-config", not(target_arch = "wasm32")))]
+    pub fn with_http_config(mut self, http_config: &crate::http::HttpConfig) -> Result<Self> {
+        self.indexer = self.indexer.with_http_config(http_config)?;
+        self.query_client = self.query_client.with_http_config(http_config)?;
+        Ok(self)
+    }
+
+    /// Indexer client the monitor queries, to run other queries against the
+    /// same endpoint with the same settings
+    pub fn indexer(&self) -> &IndexerClient {
+        &self.indexer
+    }
+
+    /// Process events from given start version
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "event_monitor.process",
+            skip(self),
+            fields(start_version = self.last_processed_version)
+        )
+    )]
+    pub async fn process(&self) -> Result<Vec<BridgeEvent>> {
+        let mut report = self.new_report();
+        let result = self.poll(&mut report).await;
+        self.record_health(&result);
+        result.map(|()| report.events).map_err(|(_, e)| e)
+    }
+
+    /// Like [`EventMonitor::process`], returning counts and the cursor to persist
+    ///
+    /// A handler failure stops dispatch and is reported in
+    /// [`ProcessReport::failed`] rather than as an error; indexer and parse
+    /// failures are errors.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "event_monitor.process_report",
+            skip(self),
+            fields(start_version = self.last_processed_version)
+        )
+    )]
+    pub async fn process_report(&self) -> Result<ProcessReport> {
+        let mut report = self.new_report();
+        let result = self.poll(&mut report).await;
+        self.record_health(&result);
+        match result {
+            Ok(()) | Err((PollErrorKind::Handler, _)) => Ok(report),
+            Err((_, e)) => Err(e),
+        }
+    }
+
+    /// Empty report starting at the current cursor
+    fn new_report(&self) -> ProcessReport {
+        ProcessReport {
+            events: Vec::new(),
+            fetched: 0,
+            handled: 0,
+            skipped: 0,
+            failed: 0,
+            new_cursor: self.last_processed_version,
+            max_seen_version: None,
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// Record a poll's outcome for [`EventMonitor::health`]
+    fn record_health(&self, result: &std::result::Result<(), (PollErrorKind, anyhow::Error)>) {
+        let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(()) => {
+                health.last_success_unix_timestamp = Some(crate::time::unix_timestamp_secs());
+                health.consecutive_failures = 0;
+            }
+            Err((kind, e)) => {
+                health.consecutive_failures += 1;
+                health.last_error = Some(PollError {
+                    kind: *kind,
+                    message: e.to_string(),
+                    unix_timestamp: crate::time::unix_timestamp_secs(),
+                });
+            }
+        }
+    }
+
+    /// Outcome of recent [`EventMonitor::process`] calls; replays are not counted
+    pub fn health(&self) -> MonitorHealth {
+        self.health
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Fetch and handle new events into `report`, tagging failures with their stage
+    async fn poll(
+        &self,
+        report: &mut ProcessReport,
+    ) -> std::result::Result<(), (PollErrorKind, anyhow::Error)> {
+        let started = crate::time::Instant::now();
+        let result = self.poll_inner(report).await;
+        report.duration = started.elapsed();
+        result
+    }
+
+    async fn poll_inner(
+        &self,
+        report: &mut ProcessReport,
+    ) -> std::result::Result<(), (PollErrorKind, anyhow::Error)> {
+        let data = self
+            .query_graphql(VersionRange::after(self.last_processed_version))
+            .await
+            .map_err(|e| (PollErrorKind::Indexer, e))?;
+        report.fetched = data.bridge_mint_events.len()
+            + data.bridge_burn_events.len()
+            + data.bridge_withdraw_by_lp_events.len();
+        let events = self
+            .decode_events(data)
+            .await
+            .map_err(|e| (PollErrorKind::Parse, e))?;
+        report.skipped = report.fetched - events.len();
+        report.max_seen_version = events.iter().filter_map(BridgeEvent::version).max();
+        #[cfg(feature = "metrics")]
+        self.record_indexer_lag(&events).await;
+
+        for (index, event) in events.iter().enumerate() {
+            if let Err(e) = self.handle_event(event).await {
+                report.failed = 1;
+                // Events sharing the failed event's version are not all handled
+                let failed_version = event.version();
+                report.new_cursor = events[..index]
+                    .iter()
+                    .filter_map(BridgeEvent::version)
+                    .filter(|&version| Some(version) < failed_version)
+                    .max()
+                    .unwrap_or(self.last_processed_version);
+                return Err((PollErrorKind::Handler, e));
+            }
+            report.handled += 1;
+            report.events.push(event.clone());
+        }
+        report.new_cursor = report
+            .max_seen_version
+            .unwrap_or(self.last_processed_version)
+            .max(self.last_processed_version);
+        Ok(())
+    }
+
+    /// Re-fetch and re-dispatch historical events in `[from_version, to_version]`
+    ///
+    /// Events are handled in version order. The live cursor used by
+    /// [`EventMonitor::process`] is left untouched.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "event_monitor.replay", skip(self))
+    )]
+    pub async fn replay(&self, from_version: u64, to_version: u64) -> Result<Vec<BridgeEvent>> {
+        if from_version > to_version {
+            return Err(anyhow!(
+                "Invalid replay range: from_version {} is greater than to_version {}",
+                from_version,
+                to_version
+            ));
+        }
+
+        let events = self
+            .fetch_events(VersionRange::between(from_version, to_version))
+            .await?;
+        self.handle_events(&events).await?;
+        Ok(events)
+    }
+
+    /// Re-fetch and re-dispatch events committed between two Unix timestamps
+    /// (seconds, both inclusive)
+    ///
+    /// The times are translated to versions through the fullnode with
+    /// [`QueryClient::get_version_range_for_time`](crate::QueryClient::get_version_range_for_time).
+    pub async fn replay_time_range(
+        &self,
+        start_secs: u64,
+        end_secs: u64,
+    ) -> Result<Vec<BridgeEvent>> {
+        match self
+            .query_client
+            .get_version_range_for_time(start_secs, end_secs)
+            .await?
+        {
+            Some(versions) => self.replay(*versions.start(), *versions.end()).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Replay `[from_version, to_version]` as a stream, `chunk_size` versions per query
+    ///
+    /// Each chunk is fetched and dispatched to the handler only once the
+    /// events of the previous one have been consumed, so memory stays bounded
+    /// by one chunk however long the range. The stream ends after the first
+    /// error.
+    pub fn replay_stream(
+        &self,
+        from_version: u64,
+        to_version: u64,
+        chunk_size: u64,
+    ) -> impl Stream<Item = Result<BridgeEvent>> + '_ {
+        let chunk_size = chunk_size.max(1);
+        stream::try_unfold(Some(from_version), move |next| async move {
+            let Some(start) = next else {
+                return Ok(None);
+            };
+            // An inverted range fails in `replay` on the first chunk
+            let end = start.saturating_add(chunk_size - 1).min(to_version);
+            let events = self.replay(start, end).await?;
+            let next = (end < to_version).then(|| end + 1);
+            Ok::<_, anyhow::Error>(Some((events, next)))
+        })
+        .map_ok(|events| stream::iter(events.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Version the live cursor starts after
+    pub(super) fn start_version(&self) -> u64 {
+        self.last_processed_version
+    }
+
+    /// Fetch events after `version` without dispatching them
+    pub(super) async fn fetch_after(&self, version: u64) -> Result<Vec<BridgeEvent>> {
+        self.fetch_events(VersionRange::after(version)).await
+    }
+
+    /// Fetch events from GraphQL
+    async fn fetch_events(&self, range: VersionRange) -> Result<Vec<BridgeEvent>> {
+        let data = self.query_graphql(range).await?;
+        self.decode_events(data).await
+    }
+
+    /// Convert fetched events, in version order
+    async fn decode_events(&self, data: GraphQLData) -> Result<Vec<BridgeEvent>> {
+        let mut events = Vec::new();
+        events.extend(self.process_mint_events(data.bridge_mint_events).await?);
+        events.extend(self.process_burn_events(data.bridge_burn_events).await?);
+        events.extend(
+            self.process_withdraw_by_lp_events(data.bridge_withdraw_by_lp_events)
+                .await?,
+        );
+
+        // Sort by version
+        events.sort_by_key(|event| match event {
+            BridgeEvent::Mint(e) => e.version.unwrap_or(0),
+            BridgeEvent::Burn(e) => e.version.unwrap_or(0),
+            BridgeEvent::WithdrawByLP(e) => e.version.unwrap_or(0),
+        });
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            count = events.len(),
+            max_version = events.last().map(event_version),
+            "Fetched bridge events"
+        );
+
+        Ok(events)
+    }
+
+    /// Execute GraphQL query
+    async fn query_graphql(&self, range: VersionRange) -> Result<GraphQLData> {
+        let schema = self.indexer.schema();
+        let version = schema.column("version");
+        let mut declarations = String::new();
+        let mut tables = String::new();
+        let mut variables = serde_json::json!({
+            "startVersion": range.start,
+            "endVersion": range.end.unwrap_or(u64::MAX),
+        });
+        for kind in [
+            BridgeEventKind::Burn,
+            BridgeEventKind::Mint,
+            BridgeEventKind::WithdrawByLP,
+        ] {
+            if !self.event_filter.includes(kind) {
+                continue;
+            }
+            let name = filter_variable(kind);
+            declarations.push_str(&format!(", ${}: {}", name, schema.bool_exp_type(kind)));
+            variables[name] = self.event_filter.condition(kind);
+            tables.push_str(&schema.selection(
+                kind,
+                &format!(
+                    "where: {{_and: [{{{version}: {where_clause}}}, ${name}]}}, order_by: {{{version}: asc}}",
+                    version = version,
+                    where_clause = range.where_clause(),
+                    name = name,
+                ),
+            ));
+        }
+        if tables.is_empty() {
+            return Ok(GraphQLData::default());
+        }
+        let query = format!(
+            "query GetBridgeEvents($startVersion: numeric!, $endVersion: numeric!{}) {{{}\n}}",
+            declarations, tables
+        );
+
+        self.indexer.query(&query, &variables).await
+    }
+
+    /// Process mint events
+    async fn process_mint_events(&self, raw_events: Vec<MintEventRaw>) -> Result<Vec<BridgeEvent>> {
+        let mut events = Vec::new();
+        for raw in raw_events {
+            match self.create_mint_event(raw).await {
+                Ok(event) => events.push(event),
+                Err(e) => self.handle_parse_failure(e)?,
+            }
+        }
+        Ok(events)
+    }
+
+    /// Process burn events
+    async fn process_burn_events(&self, raw_events: Vec<BurnEventRaw>) -> Result<Vec<BridgeEvent>> {
+        let mut events = Vec::new();
+        for raw in raw_events {
+            match self.create_burn_event(raw).await {
+                Ok(event) => events.push(event),
+                Err(e) => self.handle_parse_failure(e)?,
+            }
+        }
+        Ok(events)
+    }
+
+    /// Process WithdrawByLP events
+    async fn process_withdraw_by_lp_events(
+        &self,
+        raw_events: Vec<WithdrawByLPEventRaw>,
+    ) -> Result<Vec<BridgeEvent>> {
+        let mut events = Vec::new();
+        for raw in raw_events {
+            match self.create_withdraw_by_lp_event(raw).await {
+                Ok(event) => events.push(event),
+                Err(e) => self.handle_parse_failure(e)?,
+            }
+        }
+        Ok(events)
+    }
+
+    /// Create mint event from raw data
+    async fn create_mint_event(&self, raw: MintEventRaw) -> Result<BridgeEvent> {
+        let mut event = MintEvent::try_from(raw)?;
+
+        if let Some(version) = event.version {
+            event.transaction_hash = self.lookup_tx_hash(version).await;
+        }
+
+        Ok(BridgeEvent::Mint(event))
+    }
+
+    /// Create burn event from raw data
+    async fn create_burn_event(&self, raw: BurnEventRaw) -> Result<BridgeEvent> {
+        let mut event = BurnEvent::try_from(raw)?;
+
+        if let Some(version) = event.version {
+            event.transaction_hash = self.lookup_tx_hash(version).await;
+        }
+
+        Ok(BridgeEvent::Burn(event))
+    }
+
+    /// Create WithdrawByLP event from raw data
+    async fn create_withdraw_by_lp_event(&self, raw: WithdrawByLPEventRaw) -> Result<BridgeEvent> {
+        let mut event = WithdrawByLPEvent::try_from(raw)?;
+
+        if let Some(version) = event.version {
+            event.transaction_hash = self.lookup_tx_hash(version).await;
+        }
+
+        Ok(BridgeEvent::WithdrawByLP(event))
+    }
+
+    /// Apply the parse failure policy to an event conversion error
+    fn handle_parse_failure(&self, error: anyhow::Error) -> Result<()> {
+        match self.parse_failure_policy {
+            ParseFailurePolicy::Skip if error.is::<FieldParseError>() => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %error, "Skipping malformed bridge event");
+                Ok(())
+            }
+            _ => Err(error),
+        }
+    }
+
+    /// Look up the transaction hash for a version, logging failures
+    async fn lookup_tx_hash(&self, version: u64) -> Option<String> {
+        match self.query_client.get_tx_hash_by_version(version).await {
+            Ok(tx_hash) => Some(tx_hash),
+            Err(e) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(version, error = %e, "Failed to get transaction hash for version");
+                #[cfg(not(feature = "tracing"))]
+                let _ = e;
+                None
+            }
+        }
+    }
+
+    /// Handle all events
+    async fn handle_events(&self, events: &[BridgeEvent]) -> Result<()> {
+        for event in events {
+            self.handle_event(event).await?;
+        }
+        Ok(())
+    }
+
+    /// Dispatch one event to the handler
+    async fn handle_event(&self, event: &BridgeEvent) -> Result<()> {
+        let result = match event {
+            BridgeEvent::Mint(mint_event) => self.handler.handle_mint(mint_event.clone()).await,
+            BridgeEvent::Burn(burn_event) => self.handler.handle_burn(burn_event.clone()).await,
+            BridgeEvent::WithdrawByLP(withdraw_by_lp_event) => {
+                self.handler
+                    .handle_withdraw_by_lp(withdraw_by_lp_event.clone())
+                    .await
+            }
+        };
+
+        #[cfg(feature = "metrics")]
+        {
+            let event_type = event_type_label(event);
+            match &result {
+                Ok(()) => crate::metrics::EVENTS_PROCESSED
+                    .with_label_values(&[event_type])
+                    .inc(),
+                Err(_) => crate::metrics::HANDLER_FAILURES
+                    .with_label_values(&[event_type])
+                    .inc(),
+            }
+        }
+
+        result
+    }
+
+    /// Update the indexer lag gauge from the node's ledger version
+    #[cfg(feature = "metrics")]
+    async fn record_indexer_lag(&self, events: &[BridgeEvent]) {
+        let cursor = events
+            .iter()
+            .filter_map(|event| match event {
+                BridgeEvent::Mint(e) => e.version,
+                BridgeEvent::Burn(e) => e.version,
+                BridgeEvent::WithdrawByLP(e) => e.version,
+            })
+            .max()
+            .unwrap_or(self.last_processed_version);
+
+        if let Ok(ledger_version) = self.query_client.get_ledger_version().await {
+            crate::metrics::INDEXER_LAG.set(ledger_version.saturating_sub(cursor) as i64);
+        }
+    }
+}
+
+/// Aptos version of a bridge event (0 when unknown)
+#[cfg(feature = "tracing")]
+fn event_version(event: &BridgeEvent) -> u64 {
+    match event {
+        BridgeEvent::Mint(e) => e.version.unwrap_or(0),
+        BridgeEvent::Burn(e) => e.version.unwrap_or(0),
+        BridgeEvent::WithdrawByLP(e) => e.version.unwrap_or(0),
+    }
+}
+
+/// Metric label for a bridge event
+#[cfg(feature = "metrics")]
+fn event_type_label(event: &BridgeEvent) -> &'static str {
+    match event {
+        BridgeEvent::Mint(_) => "mint",
+        BridgeEvent::Burn(_) => "burn",
+        BridgeEvent::WithdrawByLP(_) => "withdraw_by_lp",
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Handler recording the versions it handled, failing on events of `fail_on`
+    struct Recorder {
+        handled: Arc<Mutex<Vec<u64>>>,
+        fail_on: Option<BridgeEventKind>,
+    }
+
+    impl Recorder {
+        fn handle(&self, kind: BridgeEventKind, version: Option<u64>) -> Result<()> {
+            if self.fail_on == Some(kind) {
+                return Err(anyhow!("Handler failed on {:?}", kind));
+            }
+            self.handled.lock().unwrap().push(version.unwrap());
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl EventHandler for Recorder {
+        async fn handle_mint(&self, event: MintEvent) -> Result<()> {
+            self.handle(BridgeEventKind::Mint, event.version)
+        }
+        async fn handle_burn(&self, event: BurnEvent) -> Result<()> {
+            self.handle(BridgeEventKind::Burn, event.version)
+        }
+        async fn handle_withdraw_by_lp(&self, event: WithdrawByLPEvent) -> Result<()> {
+            self.handle(BridgeEventKind::WithdrawByLP, event.version)
+        }
+    }
+
+    /// Monitor over `graphql` starting after version 6543000, and the versions
+    /// its handler handled
+    fn new_monitor(
+        graphql: &MockServer,
+        fullnode: &MockServer,
+        fail_on: Option<BridgeEventKind>,
+    ) -> (EventMonitor, Arc<Mutex<Vec<u64>>>) {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let handler = Recorder {
+            handled: handled.clone(),
+            fail_on,
+        };
+        let monitor = EventMonitor::new(
+            &graphql.uri(),
+            "key",
+            &testing::fullnode_url(fullnode),
+            None,
+            Box::new(handler),
+            6_543_000,
+        )
+        .unwrap();
+        (monitor, handled)
+    }
+
+    /// The events fixture, one mint, burn and LP withdrawal at increasing versions
+    fn fixture() -> Value {
+        serde_json::from_str(testing::fixtures::GRAPHQL_BRIDGE_EVENTS).unwrap()
+    }
+
+    /// GraphQL mock answering every query with `body`
+    async fn mock_graphql(body: Value) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_report_advances_cursor_past_handled_events() {
+        let graphql = testing::mock_graphql().await;
+        let fullnode = testing::mock_fullnode().await;
+        let (monitor, handled) = new_monitor(&graphql, &fullnode, None);
+
+        let report = monitor.process_report().await.unwrap();
+        assert_eq!(
+            (
+                report.fetched,
+                report.handled,
+                report.skipped,
+                report.failed
+            ),
+            (3, 3, 0, 0)
+        );
+        assert_eq!(report.events.len(), 3);
+        assert_eq!(report.new_cursor, 6_543_300);
+        assert_eq!(report.max_seen_version, Some(6_543_300));
+        assert_eq!(*handled.lock().unwrap(), [6_543_100, 6_543_200, 6_543_300]);
+    }
+
+    #[tokio::test]
+    async fn test_failing_handler_leaves_cursor_before_failing_event() {
+        let graphql = testing::mock_graphql().await;
+        let fullnode = testing::mock_fullnode().await;
+
+        let (monitor, handled) = new_monitor(&graphql, &fullnode, Some(BridgeEventKind::Burn));
+        let report = monitor.process_report().await.unwrap();
+        assert_eq!(
+            (
+                report.fetched,
+                report.handled,
+                report.skipped,
+                report.failed
+            ),
+            (3, 1, 0, 1)
+        );
+        assert_eq!(report.events.len(), 1);
+        // The burn at 6543200 and the withdrawal after it are left for the next poll
+        assert_eq!(report.new_cursor, 6_543_100);
+        assert_eq!(report.max_seen_version, Some(6_543_300));
+        assert_eq!(*handled.lock().unwrap(), [6_543_100]);
+        let health = monitor.health();
+        assert_eq!(health.consecutive_failures, 1);
+        assert_eq!(health.last_error.unwrap().kind, PollErrorKind::Handler);
+
+        // A failure on the first event keeps the start version
+        let (monitor, handled) = new_monitor(&graphql, &fullnode, Some(BridgeEventKind::Mint));
+        let report = monitor.process_report().await.unwrap();
+        assert_eq!((report.handled, report.failed), (0, 1));
+        assert_eq!(report.new_cursor, 6_543_000);
+        assert!(handled.lock().unwrap().is_empty());
+        assert!(monitor.process().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failing_handler_leaves_cursor_before_shared_version() {
+        // The burn and the LP withdrawal are in one transaction
+        let mut body = fixture();
+        body["data"]["bridge_withdraw_by_lp_events"][0]["version"] = json!("6543200");
+        let graphql = mock_graphql(body).await;
+        let fullnode = testing::mock_fullnode().await;
+        let (monitor, handled) =
+            new_monitor(&graphql, &fullnode, Some(BridgeEventKind::WithdrawByLP));
+
+        let report = monitor.process_report().await.unwrap();
+        assert_eq!((report.handled, report.failed), (2, 1));
+        // The burn was handled, but its version is not done
+        assert_eq!(*handled.lock().unwrap(), [6_543_100, 6_543_200]);
+        assert_eq!(report.new_cursor, 6_543_100);
+    }
+
+    #[tokio::test]
+    async fn test_report_counts_skipped_events() {
+        let mut body = fixture();
+        body["data"]["bridge_burn_events"][0]["amount"] = json!("lots");
+        let graphql = mock_graphql(body).await;
+        let fullnode = testing::mock_fullnode().await;
+
+        let (monitor, handled) = new_monitor(&graphql, &fullnode, None);
+        let monitor = monitor.with_parse_failure_policy(ParseFailurePolicy::Skip);
+        let report = monitor.process_report().await.unwrap();
+        assert_eq!(
+            (
+                report.fetched,
+                report.handled,
+                report.skipped,
+                report.failed
+            ),
+            (3, 2, 1, 0)
+        );
+        assert_eq!(report.new_cursor, 6_543_300);
+        assert_eq!(*handled.lock().unwrap(), [6_543_100, 6_543_300]);
+
+        // Aborting on the malformed event handles nothing
+        let (monitor, handled) = new_monitor(&graphql, &fullnode, None);
+        let err = monitor.process_report().await.unwrap_err();
+        assert!(err.downcast_ref::<FieldParseError>().is_some());
+        assert_eq!(
+            monitor.health().last_error.unwrap().kind,
+            PollErrorKind::Parse
+        );
+        assert!(handled.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,624 @@
+//! Aptos indexer GraphQL client
+//!
+//! [`IndexerClient`] sends queries to the indexer's GraphQL endpoint with the
+//! SDK's authentication, headers, logging and retries. Applications can run
+//! their own queries with [`IndexerClient::query`] or a typed [`IndexerQuery`];
+//! [`IndexerClient::get_events_for_address`] pages through an account's bridge
+//! events for wallet history screens. `EventMonitor` polls through the same
+//! client. Enabled by the `events-graphql` feature.
+
+use super::schema::IndexerSchema;
+use crate::config::RetryPolicy;
+use crate::types::constants::GRAPHQL_REQUEST_TIMEOUT_SECS;
+use crate::types::{
+    BridgeEvent, BridgeEventKind, BurnEvent, BurnEventRaw, MintEvent, MintEventRaw,
+    WithdrawByLPEvent, WithdrawByLPEventRaw,
+};
+use crate::utils::parse_account_address;
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+#[derive(Serialize)]
+struct GraphQLRequest<'a> {
+    query: &'a str,
+    variables: Value,
+}
+
+#[derive(Deserialize)]
+struct GraphQLResponse<T> {
+    data: Option<T>,
+    errors: Option<Vec<Value>>,
+}
+
+/// Authentication mode for the GraphQL endpoint
+#[derive(Debug, Clone)]
+pub enum GraphQLAuth {
+    /// No authentication header
+    None,
+    /// `Authorization: Bearer <token>` (Aptos Build / hosted no-code indexer)
+    Bearer(String),
+    /// `x-hasura-admin-secret: <secret>` (self-hosted Hasura)
+    HasuraAdminSecret(String),
+    /// Arbitrary header name and value
+    Header { name: String, value: String },
+}
+
+impl GraphQLAuth {
+    /// Credentials to redact from logged calls
+    fn secrets(&self) -> Vec<&str> {
+        match self {
+            GraphQLAuth::None => Vec::new(),
+            GraphQLAuth::Bearer(token) => vec![token.as_str()],
+            GraphQLAuth::HasuraAdminSecret(secret) => vec![secret.as_str()],
+            GraphQLAuth::Header { value, .. } => vec![value.as_str()],
+        }
+    }
+
+    /// Apply the authentication header to a request
+    fn apply(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self {
+            GraphQLAuth::None => request,
+            GraphQLAuth::Bearer(token) => {
+                request.header("Authorization", format!("Bearer {}", token))
+            }
+            GraphQLAuth::HasuraAdminSecret(secret) => {
+                request.header("x-hasura-admin-secret", secret)
+            }
+            GraphQLAuth::Header { name, value } => request.header(name.as_str(), value.as_str()),
+        }
+    }
+}
+
+/// GraphQL query with typed variables and response data
+///
+/// ```ignore
+/// struct WithdrawsByLp(u64);
+///
+/// impl IndexerQuery for WithdrawsByLp {
+///     type Variables = serde_json::Value;
+///     type Data = serde_json::Value;
+///
+///     fn query(&self) -> &str {
+///         "query($lp: numeric!) { bridge_withdraw_by_lp_events(where: {lp_id: {_eq: $lp}}) { withdraw_id amount } }"
+///     }
+///
+///     fn variables(&self) -> Self::Variables {
+///         serde_json::json!({ "lp": self.0 })
+///     }
+/// }
+/// ```
+pub trait IndexerQuery {
+    /// Variables sent with the query
+    type Variables: Serialize;
+    /// Shape of the response's `data` field
+    type Data: DeserializeOwned;
+
+    /// Query document
+    fn query(&self) -> &str;
+
+    /// Variables for this run of the query
+    fn variables(&self) -> Self::Variables;
+}
+
+/// Tables of an address query; kinds not queried are absent
+#[derive(Deserialize)]
+struct AddressEventsData {
+    #[serde(default)]
+    bridge_mint_events: Vec<MintEventRaw>,
+    #[serde(default)]
+    bridge_burn_events: Vec<BurnEventRaw>,
+    #[serde(default)]
+    bridge_withdraw_by_lp_events: Vec<WithdrawByLPEventRaw>,
+}
+
+/// One page of an account's bridge events
+#[derive(Debug, Clone)]
+pub struct EventPage {
+    /// Events, newest first
+    pub events: Vec<BridgeEvent>,
+    /// Cursor of the next, older page; `None` on the last page
+    pub next_cursor: Option<u64>,
+}
+
+/// Extra Hasura `where` conditions per event table, applied by the indexer
+///
+/// Conditions use the table's columns, e.g.
+/// `json!({"amount": {"_gte": "100000"}})` or
+/// `json!({"operator_id": {"_eq": 3}})`, and are combined with the version
+/// and address conditions of each query.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    mint: Option<Value>,
+    burn: Option<Value>,
+    withdraw_by_lp: Option<Value>,
+    /// Kinds queried at all; `None` for every kind
+    kinds: Option<Vec<BridgeEventKind>>,
+}
+
+impl EventFilter {
+    /// Filter matching every event
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a condition on `kind`'s table; conditions on the same table are ANDed
+    pub fn with(mut self, kind: BridgeEventKind, condition: Value) -> Result<Self> {
+        if !condition.is_object() {
+            return Err(anyhow!(
+                "{:?} event filter must be a JSON object, got {}",
+                kind,
+                condition
+            ));
+        }
+        let slot = match kind {
+            BridgeEventKind::Mint => &mut self.mint,
+            BridgeEventKind::Burn => &mut self.burn,
+            BridgeEventKind::WithdrawByLP => &mut self.withdraw_by_lp,
+        };
+        *slot = Some(match slot.take() {
+            Some(previous) => serde_json::json!({ "_and": [previous, condition] }),
+            None => condition,
+        });
+        Ok(self)
+    }
+
+    /// Only query the tables of `kinds`
+    pub fn only(mut self, kinds: &[BridgeEventKind]) -> Self {
+        self.kinds = Some(kinds.to_vec());
+        self
+    }
+
+    /// Whether `kind`'s table is queried
+    pub(super) fn includes(&self, kind: BridgeEventKind) -> bool {
+        self.kinds
+            .as_ref()
+            .is_none_or(|kinds| kinds.contains(&kind))
+    }
+
+    /// Condition on `kind`'s table; `{}` matches every row
+    pub(super) fn condition(&self, kind: BridgeEventKind) -> Value {
+        match kind {
+            BridgeEventKind::Mint => &self.mint,
+            BridgeEventKind::Burn => &self.burn,
+            BridgeEventKind::WithdrawByLP => &self.withdraw_by_lp,
+        }
+        .clone()
+        .unwrap_or_else(|| serde_json::json!({}))
+    }
+}
+
+/// GraphQL variable carrying the filter of `kind`'s table
+pub(super) fn filter_variable(kind: BridgeEventKind) -> &'static str {
+    match kind {
+        BridgeEventKind::Mint => "mintWhere",
+        BridgeEventKind::Burn => "burnWhere",
+        BridgeEventKind::WithdrawByLP => "withdrawByLpWhere",
+    }
+}
+
+/// Client for the Aptos indexer's GraphQL endpoint
+#[derive(Debug, Clone)]
+pub struct IndexerClient {
+    graphql_url: String,
+    graphql_auth: GraphQLAuth,
+    graphql_headers: Vec<(String, String)>,
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    app_id: Option<String>,
+    http_client: reqwest::Client,
+    retry_policy: RetryPolicy,
+    schema: IndexerSchema,
+}
+
+impl IndexerClient {
+    /// Create a client for the indexer at `graphql_url`
+    pub fn new(graphql_url: &str, graphql_auth: GraphQLAuth) -> Result<Self> {
+        Ok(Self {
+            graphql_url: graphql_url.to_string(),
+            graphql_auth,
+            graphql_headers: Vec::new(),
+            app_id: None,
+            http_client: build_http_client(Duration::from_secs(GRAPHQL_REQUEST_TIMEOUT_SECS))?,
+            retry_policy: RetryPolicy::none(),
+            schema: IndexerSchema::default(),
+        })
+    }
+
+    /// Replace the authentication mode
+    pub fn with_auth(mut self, graphql_auth: GraphQLAuth) -> Self {
+        self.graphql_auth = graphql_auth;
+        self
+    }
+
+    /// Add an extra header sent with every request
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.graphql_headers
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Identify the calling application in the User-Agent
+    pub fn with_app_id(mut self, app_id: &str) -> Self {
+        self.app_id = Some(app_id.to_string());
+        self
+    }
+
+    /// Use a preconfigured HTTP client (proxy, pool size, TLS)
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Set the request timeout
+    ///
+    /// Replaces the HTTP client with a default one using the given timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Result<Self> {
+        self.http_client = build_http_client(timeout)?;
+        Ok(self)
+    }
+
+    /// Retry failed requests, including GraphQL errors (default: no retries)
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Use proxy, TLS and timeout settings
+    ///
+    /// Replaces the HTTP client; the timeout stays at its default unless the
+    /// config sets one.
+    #[cfg(all(feature = "http-config", not(target_arch = "wasm32")))]
+    pub fn with_http_config(mut self, http_config: &crate::http::HttpConfig) -> Result<Self> {
+        self.http_client = http_config
+            .client_builder()?
+            .timeout(http_config.timeout_or(Duration::from_secs(GRAPHQL_REQUEST_TIMEOUT_SECS)))
+            .build()
+            .map_err(|e| anyhow!("Failed to build GraphQL HTTP client: {}", e))?;
+        Ok(self)
+    }
+
+    /// Use non-default table and column names (self-hosted processors)
+    pub fn with_schema(mut self, schema: IndexerSchema) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Table and column names used in the SDK's queries
+    pub fn schema(&self) -> &IndexerSchema {
+        &self.schema
+    }
+
+    /// GraphQL endpoint URL
+    pub fn url(&self) -> &str {
+        &self.graphql_url
+    }
+
+    /// Run a typed query
+    pub async fn run<Q: IndexerQuery>(&self, query: &Q) -> Result<Q::Data> {
+        self.query(query.query(), &query.variables()).await
+    }
+
+    /// Run `query` with `variables` and deserialize the response's `data`
+    ///
+    /// Fails on transport errors, non-JSON responses and GraphQL `errors`.
+    pub async fn query<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: &impl Serialize,
+    ) -> Result<T> {
+        let variables = serde_json::to_value(variables)
+            .map_err(|e| anyhow!("Failed to serialize GraphQL variables: {}", e))?;
+        let request = GraphQLRequest { query, variables };
+        self.retry_policy.retry(|| self.send(&request)).await
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: &GraphQLRequest<'_>) -> Result<T> {
+        let mut http_request = self
+            .graphql_auth
+            .apply(self.http_client.post(&self.graphql_url));
+        // Browsers set their own User-Agent
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            http_request = http_request.header(
+                reqwest::header::USER_AGENT,
+                crate::utils::sdk_user_agent(self.app_id.as_deref()),
+            );
+        }
+        for (name, value) in &self.graphql_headers {
+            http_request = http_request.header(name.as_str(), value.as_str());
+        }
+
+        let call_log =
+            crate::request_log::start("POST", &self.graphql_url, &self.graphql_auth.secrets()).map(
+                |call_log| {
+                    call_log.with_request_body(&serde_json::to_string(request).unwrap_or_default())
+                },
+            );
+        let response = match http_request.json(request).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                if let Some(call_log) = call_log {
+                    call_log.finish_error(&e);
+                }
+                return Err(anyhow!(
+                    "Failed to send GraphQL request to {}: {}",
+                    self.graphql_url,
+                    e
+                ));
+            }
+        };
+        let status = response.status().as_u16();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| anyhow!("Failed to read GraphQL response: {}", e))?;
+        if let Some(call_log) = call_log {
+            call_log.finish_http(status, &body);
+        }
+        let response: GraphQLResponse<T> = serde_json::from_str(&body)
+            .map_err(|e| anyhow!("Failed to parse GraphQL response: {}", e))?;
+
+        if let Some(errors) = response.errors {
+            #[cfg(feature = "tracing")]
+            tracing::error!(?errors, "GraphQL query returned errors");
+            return Err(anyhow!("GraphQL errors: {:?}", errors));
+        }
+
+        response
+            .data
+            .ok_or_else(|| anyhow!("No data in GraphQL response"))
+    }
+
+    /// Bridge events of `address`, newest first
+    ///
+    /// Mints are matched on their recipient, burns and LP withdrawals on their
+    /// sender. Start with `cursor` `None` and pass each page's `next_cursor` to
+    /// get the next one. A page holds at most `limit` events, except that the
+    /// events of one transaction are never split across pages. Transaction
+    /// hashes are not filled in; look them up with
+    /// [`QueryClient::get_tx_hash_by_version`](crate::QueryClient::get_tx_hash_by_version).
+    pub async fn get_events_for_address(
+        &self,
+        address: &str,
+        kinds: &[BridgeEventKind],
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Result<EventPage> {
+        self.get_filtered_events_for_address(address, kinds, &EventFilter::new(), cursor, limit)
+            .await
+    }
+
+    /// Like [`IndexerClient::get_events_for_address`], keeping only events
+    /// matching `filter`
+    pub async fn get_filtered_events_for_address(
+        &self,
+        address: &str,
+        kinds: &[BridgeEventKind],
+        filter: &EventFilter,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Result<EventPage> {
+        let address = parse_account_address(address)
+            .map_err(|e| anyhow!("Invalid account address '{}': {}", address, e))?
+            .to_hex_literal();
+        let limit = limit.max(1);
+
+        let mut declarations = String::new();
+        let mut tables = String::new();
+        let mut variables = serde_json::json!({
+            "address": address,
+            "before": cursor.unwrap_or(u64::MAX),
+            "limit": limit,
+        });
+        for kind in [
+            BridgeEventKind::Mint,
+            BridgeEventKind::Burn,
+            BridgeEventKind::WithdrawByLP,
+        ] {
+            if kinds.contains(&kind) && filter.includes(kind) {
+                let name = filter_variable(kind);
+                declarations.push_str(&format!(", ${}: {}", name, self.schema.bool_exp_type(kind)));
+                variables[name] = filter.condition(kind);
+                let address_column = match kind {
+                    BridgeEventKind::Mint => "to_address",
+                    BridgeEventKind::Burn | BridgeEventKind::WithdrawByLP => "from_address",
+                };
+                tables.push_str(&self.schema.selection(
+                    kind,
+                    &format!(
+                        "where: {{_and: [{{{address}: {{_eq: $address}}, {version}: {{_lt: $before}}}}, ${name}]}}, order_by: {{{version}: desc}}, limit: $limit",
+                        address = self.schema.column(address_column),
+                        version = self.schema.column("version"),
+                        name = name,
+                    ),
+                ));
+            }
+        }
+        if tables.is_empty() {
+            return Ok(EventPage {
+                events: Vec::new(),
+                next_cursor: None,
+            });
+        }
+        let query = format!(
+            "query AddressEvents($address: String!, $before: numeric!, $limit: Int!{}) {{{}\n}}",
+            declarations, tables
+        );
+
+        let data: AddressEventsData = self.query(&query, &variables).await?;
+
+        let limit = limit as usize;
+        // A full table may have older rows
+        let mut has_more = [
+            data.bridge_mint_events.len(),
+            data.bridge_burn_events.len(),
+            data.bridge_withdraw_by_lp_events.len(),
+        ]
+        .iter()
+        .any(|&len| len >= limit);
+
+        let mut events = Vec::new();
+        for raw in data.bridge_mint_events {
+            events.push(BridgeEvent::Mint(MintEvent::try_from(raw)?));
+        }
+        for raw in data.bridge_burn_events {
+            events.push(BridgeEvent::Burn(BurnEvent::try_from(raw)?));
+        }
+        for raw in data.bridge_withdraw_by_lp_events {
+            events.push(BridgeEvent::WithdrawByLP(WithdrawByLPEvent::try_from(raw)?));
+        }
+        events.sort_by_key(|event| std::cmp::Reverse(event.version().unwrap_or(0)));
+
+        if events.len() > limit {
+            has_more = true;
+            let boundary = events[limit - 1].version();
+            let keep = events
+                .iter()
+                .take_while(|event| event.version() >= boundary)
+                .count();
+            events.truncate(keep);
+        }
+
+        Ok(EventPage {
+            next_cursor: if has_more {
+                events.last().and_then(BridgeEvent::version)
+            } else {
+                None
+            },
+            events,
+        })
+    }
+}
+
+/// Build the default HTTP client used for GraphQL requests
+fn build_http_client(timeout: Duration) -> Result<reqwest::Client> {
+    let builder = reqwest::Client::builder().timeout(timeout);
+    #[cfg(not(target_arch = "wasm32"))]
+    let builder = {
+        use crate::types::constants::{HTTP_CONNECT_TIMEOUT_SECS, HTTP_TCP_KEEPALIVE_SECS};
+        builder
+            .connect_timeout(Duration::from_secs(HTTP_CONNECT_TIMEOUT_SECS))
+            .tcp_keepalive(Duration::from_secs(HTTP_TCP_KEEPALIVE_SECS))
+    };
+    builder
+        .build()
+        .map_err(|e| anyhow!("Failed to build GraphQL HTTP client: {}", e))
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    const ADDRESS: &str = "0x2823126c1fd6124b0496b89dcb1de2ae0a71011baadf058c6a12ee22d0024cbe";
+
+    #[tokio::test]
+    async fn test_get_events_for_address_pages() {
+        let server = testing::mock_graphql().await;
+        let client = IndexerClient::new(&server.uri(), GraphQLAuth::None).unwrap();
+        let all = [
+            BridgeEventKind::Mint,
+            BridgeEventKind::Burn,
+            BridgeEventKind::WithdrawByLP,
+        ];
+
+        let page = client
+            .get_events_for_address(ADDRESS, &all, None, 2)
+            .await
+            .unwrap();
+        let kinds: Vec<_> = page.events.iter().map(BridgeEvent::kind).collect();
+        assert_eq!(
+            kinds,
+            [BridgeEventKind::WithdrawByLP, BridgeEventKind::Burn]
+        );
+        assert_eq!(page.next_cursor, Some(6543200));
+
+        let page = client
+            .get_events_for_address(ADDRESS, &all, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(page.events.len(), 3);
+        assert_eq!(page.next_cursor, None);
+
+        assert!(client
+            .get_events_for_address(ADDRESS, &[], None, 10)
+            .await
+            .unwrap()
+            .events
+            .is_empty());
+    }
+
+    #[test]
+    fn test_event_filter_conditions() {
+        let filter = EventFilter::new()
+            .with(
+                BridgeEventKind::Burn,
+                serde_json::json!({"amount": {"_gte": "100000"}}),
+            )
+            .unwrap()
+            .with(
+                BridgeEventKind::Burn,
+                serde_json::json!({"operator_id": {"_eq": 3}}),
+            )
+            .unwrap();
+        assert_eq!(
+            filter.condition(BridgeEventKind::Burn),
+            serde_json::json!({"_and": [{"amount": {"_gte": "100000"}}, {"operator_id": {"_eq": 3}}]})
+        );
+        assert_eq!(
+            filter.condition(BridgeEventKind::Mint),
+            serde_json::json!({})
+        );
+        assert!(EventFilter::new()
+            .with(BridgeEventKind::Mint, serde_json::json!("amount > 5"))
+            .is_err());
+    }
+
+    struct MintAmounts;
+
+    #[derive(Deserialize)]
+    struct MintAmountsData {
+        bridge_mint_events: Vec<MintAmount>,
+    }
+
+    #[derive(Deserialize)]
+    struct MintAmount {
+        amount: String,
+    }
+
+    impl IndexerQuery for MintAmounts {
+        type Variables = Value;
+        type Data = MintAmountsData;
+
+        fn query(&self) -> &str {
+            "query { bridge_mint_events { amount } }"
+        }
+
+        fn variables(&self) -> Value {
+            serde_json::json!({})
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_typed_query() {
+        let server = testing::mock_graphql().await;
+        let client = IndexerClient::new(&server.uri(), GraphQLAuth::None).unwrap();
+        let data = client.run(&MintAmounts).await.unwrap();
+        assert_eq!(data.bridge_mint_events.len(), 1);
+        assert!(!data.bridge_mint_events[0].amount.is_empty());
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                r#"{"errors":[{"message":"field not found"}]}"#,
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+        let client = IndexerClient::new(&server.uri(), GraphQLAuth::None).unwrap();
+        let error = client.run(&MintAmounts).await.unwrap_err();
+        assert!(error.to_string().contains("field not found"));
+    }
+}
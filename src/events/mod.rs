@@ -0,0 +1,52 @@
+//! Bridge events
+//!
+//! [`EventHandler`] receives decoded bridge events. With the `events-graphql`
+//! feature, [`EventMonitor`] (native only) polls the indexer and dispatches to a handler; with
+//! `events-rest`, `QueryClient::get_bridge_events_by_hash` decodes the events of a
+//! single transaction from the fullnode. [`IndexerClient`] pages through an
+//! account's events for history views.
+
+#[cfg(all(feature = "events-graphql", not(target_arch = "wasm32")))]
+mod ack;
+#[cfg(all(feature = "events-graphql", not(target_arch = "wasm32")))]
+mod graphql;
+#[cfg(feature = "events-graphql")]
+mod indexer;
+#[cfg(all(feature = "events-graphql", not(target_arch = "wasm32")))]
+mod operator;
+#[cfg(feature = "events-rest")]
+pub(crate) mod rest;
+#[cfg(feature = "events-graphql")]
+mod schema;
+
+#[cfg(all(feature = "events-graphql", not(target_arch = "wasm32")))]
+pub use ack::{AckToken, AckingMonitor, CursorStore, FileCursorStore, PendingEvent};
+#[cfg(all(feature = "events-graphql", not(target_arch = "wasm32")))]
+pub use graphql::{
+    EventMonitor, MonitorHealth, ParseFailurePolicy, PollError, PollErrorKind, ProcessReport,
+};
+#[cfg(feature = "events-graphql")]
+pub use indexer::{EventFilter, EventPage, GraphQLAuth, IndexerClient, IndexerQuery};
+#[cfg(all(feature = "events-graphql", not(target_arch = "wasm32")))]
+pub use operator::OperatorWatcher;
+#[cfg(feature = "events-rest")]
+pub use rest::BridgeEventDecoder;
+#[cfg(feature = "events-graphql")]
+pub use schema::IndexerSchema;
+
+use crate::types::{BurnEvent, MintEvent, ReorgDetected, WithdrawByLPEvent};
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Event handler trait
+#[async_trait]
+pub trait EventHandler: Send + Sync {
+    async fn handle_mint(&self, event: MintEvent) -> Result<()>;
+    async fn handle_burn(&self, event: BurnEvent) -> Result<()>;
+    async fn handle_withdraw_by_lp(&self, event: WithdrawByLPEvent) -> Result<()>;
+
+    /// Called by a [`ReorgDetector`](crate::ReorgDetector) when the BTC chain reorganizes
+    async fn handle_reorg(&self, _event: ReorgDetected) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+//! Burns assigned to one operator
+//!
+//! Each operator node only fulfils the withdrawals addressed to it.
+//! [`OperatorWatcher`] polls the indexer for burns with its `operator_id`,
+//! filtered server-side, and yields them as a stream.
+
+use super::indexer::EventFilter;
+use super::EventMonitor;
+use crate::types::constants::OPERATOR_WATCH_POLL_INTERVAL_SECS;
+use crate::types::{BridgeEvent, BridgeEventKind, BurnEvent};
+use anyhow::Result;
+use futures::stream::{self, Stream};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Stream of the burns assigned to one operator
+pub struct OperatorWatcher {
+    monitor: EventMonitor,
+    operator_id: u64,
+    poll_interval: Duration,
+}
+
+/// Progress of [`OperatorWatcher::stream`]
+struct WatchState {
+    /// Version of the last burn fetched
+    cursor: u64,
+    /// Fetched burns not yielded yet
+    buffer: VecDeque<BurnEvent>,
+    /// Whether the next poll waits for the poll interval first
+    wait: bool,
+}
+
+impl EventMonitor {
+    /// Watch the burns assigned to `operator_id`, after the monitor's start
+    /// version
+    ///
+    /// Replaces the monitor's event filter; mints and LP withdrawals are not
+    /// queried. The handler is not called.
+    pub fn for_operator(self, operator_id: u64) -> Result<OperatorWatcher> {
+        let filter = EventFilter::new().only(&[BridgeEventKind::Burn]).with(
+            BridgeEventKind::Burn,
+            serde_json::json!({ "operator_id": { "_eq": operator_id } }),
+        )?;
+        Ok(OperatorWatcher {
+            monitor: self.with_event_filter(filter),
+            operator_id,
+            poll_interval: Duration::from_secs(OPERATOR_WATCH_POLL_INTERVAL_SECS),
+        })
+    }
+}
+
+impl OperatorWatcher {
+    /// Set the delay after a poll that found no new burns or failed
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Operator whose burns are watched
+    pub fn operator_id(&self) -> u64 {
+        self.operator_id
+    }
+
+    /// Burns in version order, polling until the stream is dropped
+    ///
+    /// A failed poll yields its error and is retried after the poll interval;
+    /// the stream never ends on its own.
+    pub fn stream(&self) -> impl Stream<Item = Result<BurnEvent>> + '_ {
+        let state = WatchState {
+            cursor: self.monitor.start_version(),
+            buffer: VecDeque::new(),
+            wait: false,
+        };
+        stream::unfold(state, move |mut state| async move {
+            loop {
+                if let Some(burn) = state.buffer.pop_front() {
+                    return Some((Ok(burn), state));
+                }
+                if state.wait {
+                    crate::time::sleep(self.poll_interval).await;
+                }
+                match self.monitor.fetch_after(state.cursor).await {
+                    Ok(events) => {
+                        for event in events {
+                            if let BridgeEvent::Burn(burn) = event {
+                                state.cursor = state.cursor.max(burn.version.unwrap_or(0));
+                                state.buffer.push_back(burn);
+                            }
+                        }
+                        state.wait = state.buffer.is_empty();
+                    }
+                    Err(e) => {
+                        state.wait = true;
+                        return Some((Err(e), state));
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use crate::testing;
+    use crate::{BurnEvent, EventHandler, EventMonitor, MintEvent, WithdrawByLPEvent};
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use futures::StreamExt;
+
+    struct NoopHandler;
+
+    #[async_trait]
+    impl EventHandler for NoopHandler {
+        async fn handle_mint(&self, _event: MintEvent) -> Result<()> {
+            Ok(())
+        }
+        async fn handle_burn(&self, _event: BurnEvent) -> Result<()> {
+            Ok(())
+        }
+        async fn handle_withdraw_by_lp(&self, _event: WithdrawByLPEvent) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_operator_stream_yields_burns() {
+        let graphql = testing::mock_graphql().await;
+        let fullnode = testing::mock_fullnode().await;
+        let watcher = EventMonitor::new(
+            &graphql.uri(),
+            "key",
+            &testing::fullnode_url(&fullnode),
+            None,
+            Box::new(NoopHandler),
+            0,
+        )
+        .unwrap()
+        .for_operator(1)
+        .unwrap();
+
+        let burns: Vec<_> = watcher.stream().take(1).collect().await;
+        let burn = burns[0].as_ref().unwrap();
+        assert_eq!(burn.version, Some(6_543_200));
+    }
+}
@@ -0,0 +1,312 @@
+//! Fullnode event decoding
+//!
+//! Decodes the BCS data of bridge events emitted by a committed transaction, as
+//! returned by the fullnode REST API. Enabled by the `events-rest` feature.
+
+use crate::types::{
+    BridgeEvent, BurnEvent, EventSchemaVersion, MintEvent, Sats, WithdrawByLPEvent,
+};
+use crate::utils::parse_account_address;
+use anyhow::{anyhow, Result};
+use aptos_sdk::move_types::language_storage::{StructTag, TypeTag};
+use aptos_sdk::types::account_address::AccountAddress;
+use aptos_sdk::types::contract_event::ContractEvent;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// BCS-compatible Mint event structure, borrowing from the event data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct MintEventBCS<'a> {
+    pub to_address: [u8; 32], // AccountAddress as fixed-size array
+    pub amount: u64,
+    #[serde(borrow)]
+    pub btc_tx_id: Cow<'a, [u8]>,
+    pub btc_block_num: u64,
+}
+
+/// BCS-compatible Burn event structure (schema V1)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct BurnEventBCSV1<'a> {
+    pub from_address: [u8; 32], // AccountAddress as fixed-size array
+    #[serde(borrow)]
+    pub btc_address: Cow<'a, str>,
+    pub fee_rate: u64,
+    pub amount: u64,
+    pub operator_id: u64,
+}
+
+/// BCS-compatible Burn event structure (schema V2, adds the on-chain burn timestamp)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct BurnEventBCSV2<'a> {
+    pub from_address: [u8; 32], // AccountAddress as fixed-size array
+    #[serde(borrow)]
+    pub btc_address: Cow<'a, str>,
+    pub fee_rate: u64,
+    pub amount: u64,
+    pub operator_id: u64,
+    pub timestamp: u64,
+}
+
+/// BCS-compatible WithdrawByLP event structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct WithdrawByLPEventBCS<'a> {
+    pub from_address: [u8; 32], // AccountAddress as fixed-size array
+    pub withdraw_id: u64,
+    #[serde(borrow)]
+    pub btc_address: Cow<'a, str>, // BCS will handle variable-length encoding
+    pub fee_rate: u64,
+    pub amount: u64,
+    pub lp_id: u64,
+    pub receive_min_amount: u64,
+}
+
+/// Hex literal of a BCS account address
+fn address_hex(bytes: &[u8; 32]) -> String {
+    AccountAddress::new(*bytes).to_hex_literal()
+}
+
+impl From<MintEventBCS<'_>> for MintEvent {
+    fn from(bcs: MintEventBCS<'_>) -> Self {
+        MintEvent {
+            to_address: address_hex(&bcs.to_address),
+            amount: Sats(bcs.amount),
+            btc_tx_id: hex::encode(&bcs.btc_tx_id),
+            btc_block_num: bcs.btc_block_num,
+            timestamp: None, // Not available in BCS events
+            version: None,
+            transaction_hash: None,
+        }
+    }
+}
+
+impl From<BurnEventBCSV1<'_>> for BurnEvent {
+    fn from(bcs: BurnEventBCSV1<'_>) -> Self {
+        BurnEvent {
+            from_address: address_hex(&bcs.from_address),
+            btc_address: bcs.btc_address.into_owned(),
+            fee_rate: bcs.fee_rate,
+            amount: Sats(bcs.amount),
+            operator_id: bcs.operator_id,
+            timestamp: None, // Not available in BCS events
+            version: None,
+            transaction_hash: None,
+        }
+    }
+}
+
+impl From<BurnEventBCSV2<'_>> for BurnEvent {
+    fn from(bcs: BurnEventBCSV2<'_>) -> Self {
+        BurnEvent {
+            from_address: address_hex(&bcs.from_address),
+            btc_address: bcs.btc_address.into_owned(),
+            fee_rate: bcs.fee_rate,
+            amount: Sats(bcs.amount),
+            operator_id: bcs.operator_id,
+            timestamp: Some(bcs.timestamp),
+            version: None,
+            transaction_hash: None,
+        }
+    }
+}
+
+/// All known schema versions, preferred version first
+fn decode_order(preferred: EventSchemaVersion) -> [EventSchemaVersion; 2] {
+    match preferred {
+        EventSchemaVersion::V1 => [EventSchemaVersion::V1, EventSchemaVersion::V2],
+        EventSchemaVersion::V2 => [EventSchemaVersion::V2, EventSchemaVersion::V1],
+    }
+}
+
+/// Decode BCS `Burn` event data, trying the preferred schema version first
+pub(crate) fn decode_burn_event(data: &[u8], preferred: EventSchemaVersion) -> Result<BurnEvent> {
+    let mut errors = Vec::new();
+    for version in decode_order(preferred) {
+        let decoded = match version {
+            EventSchemaVersion::V1 => bcs::from_bytes::<BurnEventBCSV1>(data).map(BurnEvent::from),
+            EventSchemaVersion::V2 => bcs::from_bytes::<BurnEventBCSV2>(data).map(BurnEvent::from),
+        };
+        match decoded {
+            Ok(event) => return Ok(event),
+            Err(e) => errors.push(format!("{:?}: {}", version, e)),
+        }
+    }
+    Err(anyhow!(
+        "Failed to deserialize burn event data with any known schema ({})",
+        errors.join("; ")
+    ))
+}
+
+impl From<WithdrawByLPEventBCS<'_>> for WithdrawByLPEvent {
+    fn from(bcs: WithdrawByLPEventBCS<'_>) -> Self {
+        WithdrawByLPEvent {
+            from_address: address_hex(&bcs.from_address),
+            withdraw_id: bcs.withdraw_id,
+            btc_address: bcs.btc_address.into_owned(),
+            fee_rate: bcs.fee_rate,
+            amount: Sats(bcs.amount),
+            lp_id: bcs.lp_id,
+            receive_min_amount: Sats(bcs.receive_min_amount),
+            timestamp: None, // Not available in BCS events
+            version: None,
+            transaction_hash: None,
+        }
+    }
+}
+
+/// Decodes the bridge events of one contract
+///
+/// The bridge address is parsed once, and events are matched on their type tag
+/// without formatting it, so events of other contracts cost a few comparisons.
+/// Event fields are deserialized borrowing the event data and copied once into
+/// the returned event. Build one decoder per scan and reuse it for every event.
+#[derive(Debug, Clone)]
+pub struct BridgeEventDecoder {
+    bridge_contract_address: AccountAddress,
+    bridge_module: String,
+    schema_version: EventSchemaVersion,
+}
+
+impl BridgeEventDecoder {
+    /// Decoder for events of `bridge_module` at `bridge_contract_address`
+    pub fn new(
+        bridge_contract_address: &str,
+        bridge_module: &str,
+        schema_version: EventSchemaVersion,
+    ) -> Result<Self> {
+        let bridge_contract_address = parse_account_address(bridge_contract_address)
+            .map_err(|e| anyhow!("Invalid bridge contract address: {}", e))?;
+        Ok(Self {
+            bridge_contract_address,
+            bridge_module: bridge_module.to_string(),
+            schema_version,
+        })
+    }
+
+    /// Decode `event` if it is a bridge event
+    pub fn decode(&self, event: &ContractEvent) -> Result<Option<BridgeEvent>> {
+        let TypeTag::Struct(struct_tag) = event.type_tag() else {
+            return Ok(None);
+        };
+        if struct_tag.address != self.bridge_contract_address
+            || struct_tag.module.as_str() != self.bridge_module
+        {
+            return Ok(None);
+        }
+
+        let event_data = event.event_data();
+        let bridge_event = match struct_tag.name.as_str() {
+            "Mint" => {
+                let mint_bcs: MintEventBCS =
+                    bcs::from_bytes(event_data).map_err(|e| decode_error("mint", e, struct_tag))?;
+                BridgeEvent::Mint(mint_bcs.into())
+            }
+            "Burn" => {
+                let burn_event = decode_burn_event(event_data, self.schema_version)
+                    .map_err(|e| anyhow!("{} (type: {})", e, struct_tag.to_canonical_string()))?;
+                BridgeEvent::Burn(burn_event)
+            }
+            "WithdrawByLP" => {
+                let withdraw_bcs: WithdrawByLPEventBCS = bcs::from_bytes(event_data)
+                    .map_err(|e| decode_error("withdraw", e, struct_tag))?;
+                BridgeEvent::WithdrawByLP(withdraw_bcs.into())
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(bridge_event))
+    }
+}
+
+fn decode_error(kind: &str, e: bcs::Error, struct_tag: &StructTag) -> anyhow::Error {
+    anyhow!(
+        "Failed to deserialize {} event data: {} (type: {})",
+        kind,
+        e,
+        struct_tag.to_canonical_string()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_burn_event_versions() {
+        let v1 = BurnEventBCSV1 {
+            from_address: [1u8; 32],
+            btc_address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into(),
+            fee_rate: 5,
+            amount: 250000,
+            operator_id: 1,
+        };
+        let v2 = BurnEventBCSV2 {
+            from_address: v1.from_address,
+            btc_address: v1.btc_address.clone(),
+            fee_rate: v1.fee_rate,
+            amount: v1.amount,
+            operator_id: v1.operator_id,
+            timestamp: 1714564800,
+        };
+        let v1_bytes = bcs::to_bytes(&v1).unwrap();
+        let v2_bytes = bcs::to_bytes(&v2).unwrap();
+
+        for preferred in [EventSchemaVersion::V1, EventSchemaVersion::V2] {
+            let old = decode_burn_event(&v1_bytes, preferred).unwrap();
+            assert_eq!(old.amount, Sats(250000));
+            assert_eq!(old.timestamp, None);
+
+            let new = decode_burn_event(&v2_bytes, preferred).unwrap();
+            assert_eq!(new.amount, Sats(250000));
+            assert_eq!(new.timestamp, Some(1714564800));
+        }
+
+        assert!(decode_burn_event(&[0u8; 3], EventSchemaVersion::V1).is_err());
+    }
+
+    fn contract_event(type_tag: &str, data: Vec<u8>) -> ContractEvent {
+        ContractEvent::new_v2(type_tag.parse::<TypeTag>().unwrap(), data).unwrap()
+    }
+
+    #[test]
+    fn test_bridge_event_decoder() {
+        let decoder = BridgeEventDecoder::new("0xb", "bridge", EventSchemaVersion::V1).unwrap();
+        let mint = bcs::to_bytes(&MintEventBCS {
+            to_address: [2u8; 32],
+            amount: 100000,
+            btc_tx_id: Cow::Owned(vec![0xab; 32]),
+            btc_block_num: 840000,
+        })
+        .unwrap();
+
+        let event = decoder
+            .decode(&contract_event("0xb::bridge::Mint", mint.clone()))
+            .unwrap();
+        match event {
+            Some(BridgeEvent::Mint(mint)) => {
+                assert_eq!(mint.amount, Sats(100000));
+                assert_eq!(mint.btc_tx_id, "ab".repeat(32));
+                assert_eq!(mint.to_address, format!("0x{}", "02".repeat(32)));
+            }
+            other => panic!("expected a mint event, got {:?}", other),
+        }
+
+        // Other contracts, modules and events are skipped without decoding
+        for type_tag in [
+            "0xc::bridge::Mint",
+            "0xb::other::Mint",
+            "0xb::bridge::Paused",
+        ] {
+            assert!(decoder
+                .decode(&contract_event(type_tag, mint.clone()))
+                .unwrap()
+                .is_none());
+        }
+        assert!(decoder
+            .decode(&contract_event("u64", mint))
+            .unwrap()
+            .is_none());
+        assert!(decoder
+            .decode(&contract_event("0xb::bridge::Mint", vec![1, 2]))
+            .is_err());
+    }
+}
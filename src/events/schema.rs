@@ -0,0 +1,207 @@
+//! Table and column names of the indexer's bridge event tables
+//!
+//! The default names are those of the hosted Fiamma processor. Self-hosted
+//! processors may prefix or rename tables and columns; queries then select
+//! the deployment's names under GraphQL aliases, so responses keep the
+//! default shape.
+
+use crate::types::BridgeEventKind;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// Columns selected from the mint table
+const MINT_FIELDS: &[&str] = &[
+    "amount",
+    "btc_block_num",
+    "btc_tx_id",
+    "timestamp",
+    "to_address",
+    "version",
+];
+/// Columns selected from the burn table
+const BURN_FIELDS: &[&str] = &[
+    "amount",
+    "btc_address",
+    "fee_rate",
+    "from_address",
+    "operator_id",
+    "timestamp",
+    "version",
+];
+/// Columns selected from the LP withdrawal table
+const WITHDRAW_BY_LP_FIELDS: &[&str] = &[
+    "amount",
+    "btc_address",
+    "fee_rate",
+    "from_address",
+    "lp_id",
+    "receive_min_amount",
+    "timestamp",
+    "version",
+    "withdraw_id",
+];
+
+/// Indexer table and column names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexerSchema {
+    mint_table: String,
+    burn_table: String,
+    withdraw_by_lp_table: String,
+    /// Deployment column names, keyed by default column name
+    columns: HashMap<String, String>,
+}
+
+impl Default for IndexerSchema {
+    fn default() -> Self {
+        Self {
+            mint_table: default_table(BridgeEventKind::Mint).to_string(),
+            burn_table: default_table(BridgeEventKind::Burn).to_string(),
+            withdraw_by_lp_table: default_table(BridgeEventKind::WithdrawByLP).to_string(),
+            columns: HashMap::new(),
+        }
+    }
+}
+
+impl IndexerSchema {
+    /// Default table and column names
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prefix the default table names, e.g. `fiamma_` for `fiamma_bridge_mint_events`
+    pub fn with_table_prefix(self, prefix: &str) -> Result<Self> {
+        [
+            BridgeEventKind::Mint,
+            BridgeEventKind::Burn,
+            BridgeEventKind::WithdrawByLP,
+        ]
+        .into_iter()
+        .try_fold(self, |schema, kind| {
+            schema.with_table(kind, &format!("{}{}", prefix, default_table(kind)))
+        })
+    }
+
+    /// Name the table holding `kind` events
+    pub fn with_table(mut self, kind: BridgeEventKind, table: &str) -> Result<Self> {
+        check_name(table)?;
+        *match kind {
+            BridgeEventKind::Mint => &mut self.mint_table,
+            BridgeEventKind::Burn => &mut self.burn_table,
+            BridgeEventKind::WithdrawByLP => &mut self.withdraw_by_lp_table,
+        } = table.to_string();
+        Ok(self)
+    }
+
+    /// Rename a column in every table, e.g. `version` to `transaction_version`
+    pub fn with_column(mut self, default_column: &str, column: &str) -> Result<Self> {
+        if ![MINT_FIELDS, BURN_FIELDS, WITHDRAW_BY_LP_FIELDS]
+            .iter()
+            .any(|fields| fields.contains(&default_column))
+        {
+            return Err(anyhow!("Unknown bridge event column '{}'", default_column));
+        }
+        check_name(column)?;
+        self.columns
+            .insert(default_column.to_string(), column.to_string());
+        Ok(self)
+    }
+
+    /// Table holding `kind` events
+    pub fn table(&self, kind: BridgeEventKind) -> &str {
+        match kind {
+            BridgeEventKind::Mint => &self.mint_table,
+            BridgeEventKind::Burn => &self.burn_table,
+            BridgeEventKind::WithdrawByLP => &self.withdraw_by_lp_table,
+        }
+    }
+
+    /// Deployment name of a default column
+    pub fn column<'a>(&'a self, default_column: &'a str) -> &'a str {
+        self.columns
+            .get(default_column)
+            .map(String::as_str)
+            .unwrap_or(default_column)
+    }
+
+    /// Hasura type of `where` conditions on `kind`'s table
+    pub(super) fn bool_exp_type(&self, kind: BridgeEventKind) -> String {
+        format!("{}_bool_exp!", self.table(kind))
+    }
+
+    /// Selection of `kind`'s table with `arguments`, aliased to the default
+    /// table and column names
+    pub(super) fn selection(&self, kind: BridgeEventKind, arguments: &str) -> String {
+        let fields = match kind {
+            BridgeEventKind::Mint => MINT_FIELDS,
+            BridgeEventKind::Burn => BURN_FIELDS,
+            BridgeEventKind::WithdrawByLP => WITHDRAW_BY_LP_FIELDS,
+        }
+        .iter()
+        .map(|field| match self.column(field) {
+            column if column == *field => column.to_string(),
+            column => format!("{}: {}", field, column),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+        let table = match self.table(kind) {
+            table if table == default_table(kind) => table.to_string(),
+            table => format!("{}: {}", default_table(kind), table),
+        };
+        format!(
+            "\n    {}({}) {{\n        {}\n    }}",
+            table, arguments, fields
+        )
+    }
+}
+
+/// Table name of the hosted processor, and the response key in every query
+fn default_table(kind: BridgeEventKind) -> &'static str {
+    match kind {
+        BridgeEventKind::Mint => "bridge_mint_events",
+        BridgeEventKind::Burn => "bridge_burn_events",
+        BridgeEventKind::WithdrawByLP => "bridge_withdraw_by_lp_events",
+    }
+}
+
+/// Reject names that are not GraphQL identifiers, which would break or
+/// inject into generated queries
+fn check_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric());
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!("Invalid GraphQL table or column name '{}'", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_aliases_renamed_names() {
+        let schema = IndexerSchema::new()
+            .with_table_prefix("fiamma_")
+            .unwrap()
+            .with_column("version", "transaction_version")
+            .unwrap();
+        assert_eq!(
+            schema.table(BridgeEventKind::Mint),
+            "fiamma_bridge_mint_events"
+        );
+        let selection = schema.selection(BridgeEventKind::Mint, "limit: 1");
+        assert!(selection.contains("bridge_mint_events: fiamma_bridge_mint_events(limit: 1)"));
+        assert!(selection.contains("version: transaction_version"));
+        assert!(selection.contains("amount, btc_block_num"));
+
+        assert!(IndexerSchema::new()
+            .with_table_prefix("bad-prefix")
+            .is_err());
+        assert!(IndexerSchema::new().with_column("amount", "x }").is_err());
+        assert!(IndexerSchema::new().with_column("amt", "amount").is_err());
+    }
+}
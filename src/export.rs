@@ -0,0 +1,315 @@
+//! CSV and Parquet export of bridge events
+//!
+//! Every event becomes one [`EventRow`] with the columns in [`COLUMNS`], in that
+//! order, whatever its type; fields an event doesn't have are left empty. Feed
+//! it events from `EventMonitor::replay` or an `EventStore`. Parquet output
+//! needs the `parquet` feature.
+
+use crate::types::{BridgeEvent, Sats};
+use anyhow::{anyhow, Result};
+use std::io::Write;
+
+/// Export columns, in file order
+pub const COLUMNS: &[&str] = &[
+    "event_type",
+    "version",
+    "transaction_hash",
+    "timestamp",
+    "address",
+    "amount_sats",
+    "btc_tx_id",
+    "btc_block_num",
+    "btc_address",
+    "fee_rate",
+    "operator_id",
+    "withdraw_id",
+    "lp_id",
+    "receive_min_amount_sats",
+];
+
+/// One exported event, flattened to the export schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventRow {
+    /// `mint`, `burn` or `withdraw_by_lp`
+    pub event_type: &'static str,
+    /// Ledger version
+    pub version: Option<u64>,
+    /// Aptos transaction hash
+    pub transaction_hash: Option<String>,
+    /// Seconds since the Unix epoch
+    pub timestamp: Option<u64>,
+    /// Mint recipient, or burn/withdraw sender
+    pub address: String,
+    /// Amount in satoshis
+    pub amount_sats: u64,
+    /// Mint BTC transaction ID, internal byte order
+    pub btc_tx_id: Option<String>,
+    /// Mint BTC block height
+    pub btc_block_num: Option<u64>,
+    /// Burn/withdraw BTC destination
+    pub btc_address: Option<String>,
+    /// Burn/withdraw fee rate
+    pub fee_rate: Option<u64>,
+    /// Burn operator ID
+    pub operator_id: Option<u64>,
+    /// LP withdraw ID
+    pub withdraw_id: Option<u64>,
+    /// LP ID
+    pub lp_id: Option<u64>,
+    /// LP withdraw minimum receive amount in satoshis
+    pub receive_min_amount_sats: Option<u64>,
+}
+
+impl From<&BridgeEvent> for EventRow {
+    fn from(event: &BridgeEvent) -> Self {
+        let empty = |event_type, address: &str, amount: Sats| EventRow {
+            event_type,
+            version: None,
+            transaction_hash: None,
+            timestamp: None,
+            address: address.to_string(),
+            amount_sats: amount.to_sat(),
+            btc_tx_id: None,
+            btc_block_num: None,
+            btc_address: None,
+            fee_rate: None,
+            operator_id: None,
+            withdraw_id: None,
+            lp_id: None,
+            receive_min_amount_sats: None,
+        };
+        match event {
+            BridgeEvent::Mint(e) => EventRow {
+                version: e.version,
+                transaction_hash: e.transaction_hash.clone(),
+                timestamp: e.timestamp,
+                btc_tx_id: Some(e.btc_tx_id.clone()),
+                btc_block_num: Some(e.btc_block_num),
+                ..empty("mint", &e.to_address, e.amount)
+            },
+            BridgeEvent::Burn(e) => EventRow {
+                version: e.version,
+                transaction_hash: e.transaction_hash.clone(),
+                timestamp: e.timestamp,
+                btc_address: Some(e.btc_address.clone()),
+                fee_rate: Some(e.fee_rate),
+                operator_id: Some(e.operator_id),
+                ..empty("burn", &e.from_address, e.amount)
+            },
+            BridgeEvent::WithdrawByLP(e) => EventRow {
+                version: e.version,
+                transaction_hash: e.transaction_hash.clone(),
+                timestamp: e.timestamp,
+                btc_address: Some(e.btc_address.clone()),
+                fee_rate: Some(e.fee_rate),
+                withdraw_id: Some(e.withdraw_id),
+                lp_id: Some(e.lp_id),
+                receive_min_amount_sats: Some(e.receive_min_amount.to_sat()),
+                ..empty("withdraw_by_lp", &e.from_address, e.amount)
+            },
+        }
+    }
+}
+
+impl EventRow {
+    /// Field values in [`COLUMNS`] order, empty for missing fields
+    fn fields(&self) -> [String; 14] {
+        fn opt<T: ToString>(value: &Option<T>) -> String {
+            value.as_ref().map(T::to_string).unwrap_or_default()
+        }
+        [
+            self.event_type.to_string(),
+            opt(&self.version),
+            opt(&self.transaction_hash),
+            opt(&self.timestamp),
+            self.address.clone(),
+            self.amount_sats.to_string(),
+            opt(&self.btc_tx_id),
+            opt(&self.btc_block_num),
+            opt(&self.btc_address),
+            opt(&self.fee_rate),
+            opt(&self.operator_id),
+            opt(&self.withdraw_id),
+            opt(&self.lp_id),
+            opt(&self.receive_min_amount_sats),
+        ]
+    }
+}
+
+/// Streaming CSV writer; the header is written on creation
+pub struct CsvEventWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> CsvEventWriter<W> {
+    /// Write the header to `writer`
+    pub fn new(mut writer: W) -> Result<Self> {
+        writeln!(writer, "{}", COLUMNS.join(","))
+            .map_err(|e| anyhow!("Failed to write CSV header: {}", e))?;
+        Ok(Self { writer })
+    }
+
+    /// Append one event
+    pub fn write_event(&mut self, event: &BridgeEvent) -> Result<()> {
+        let line = EventRow::from(event)
+            .fields()
+            .iter()
+            .map(|field| csv_escape(field))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(self.writer, "{}", line).map_err(|e| anyhow!("Failed to write CSV row: {}", e))
+    }
+
+    /// Append `events` in order
+    pub fn write_events(&mut self, events: &[BridgeEvent]) -> Result<()> {
+        events.iter().try_for_each(|event| self.write_event(event))
+    }
+
+    /// Flush and return the underlying writer
+    pub fn finish(mut self) -> Result<W> {
+        self.writer
+            .flush()
+            .map_err(|e| anyhow!("Failed to flush CSV: {}", e))?;
+        Ok(self.writer)
+    }
+}
+
+/// Write `events` as CSV with a header row
+pub fn write_csv<W: Write>(writer: W, events: &[BridgeEvent]) -> Result<()> {
+    let mut csv = CsvEventWriter::new(writer)?;
+    csv.write_events(events)?;
+    csv.finish().map(drop)
+}
+
+/// Quote a field if it contains a delimiter, quote or line break (RFC 4180)
+fn csv_escape(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// Arrow schema of Parquet exports, matching [`COLUMNS`]
+#[cfg(feature = "parquet")]
+pub fn parquet_schema() -> arrow_schema::Schema {
+    use arrow_schema::{DataType, Field, Schema};
+
+    Schema::new(
+        COLUMNS
+            .iter()
+            .map(|&name| {
+                let (data_type, nullable) = match name {
+                    "event_type" | "address" => (DataType::Utf8, false),
+                    "amount_sats" => (DataType::UInt64, false),
+                    "transaction_hash" | "btc_tx_id" | "btc_address" => (DataType::Utf8, true),
+                    _ => (DataType::UInt64, true),
+                };
+                Field::new(name, data_type, nullable)
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Write `events` as a Parquet file with the [`parquet_schema`] schema
+#[cfg(feature = "parquet")]
+pub fn write_parquet<W: Write + Send>(writer: W, events: &[BridgeEvent]) -> Result<()> {
+    use arrow_array::{ArrayRef, RecordBatch, StringArray, UInt64Array};
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    let rows: Vec<EventRow> = events.iter().map(EventRow::from).collect();
+    let strings = |f: fn(&EventRow) -> Option<&str>| -> ArrayRef {
+        Arc::new(StringArray::from(rows.iter().map(f).collect::<Vec<_>>()))
+    };
+    let numbers = |f: fn(&EventRow) -> Option<u64>| -> ArrayRef {
+        Arc::new(UInt64Array::from(rows.iter().map(f).collect::<Vec<_>>()))
+    };
+    let columns = vec![
+        strings(|r| Some(r.event_type)),
+        numbers(|r| r.version),
+        strings(|r| r.transaction_hash.as_deref()),
+        numbers(|r| r.timestamp),
+        strings(|r| Some(r.address.as_str())),
+        numbers(|r| Some(r.amount_sats)),
+        strings(|r| r.btc_tx_id.as_deref()),
+        numbers(|r| r.btc_block_num),
+        strings(|r| r.btc_address.as_deref()),
+        numbers(|r| r.fee_rate),
+        numbers(|r| r.operator_id),
+        numbers(|r| r.withdraw_id),
+        numbers(|r| r.lp_id),
+        numbers(|r| r.receive_min_amount_sats),
+    ];
+
+    let schema = Arc::new(parquet_schema());
+    let batch = RecordBatch::try_new(schema.clone(), columns)
+        .map_err(|e| anyhow!("Failed to build record batch: {}", e))?;
+    let mut parquet = ArrowWriter::try_new(writer, schema, None)
+        .map_err(|e| anyhow!("Failed to create Parquet writer: {}", e))?;
+    parquet
+        .write(&batch)
+        .map_err(|e| anyhow!("Failed to write Parquet: {}", e))?;
+    parquet
+        .close()
+        .map_err(|e| anyhow!("Failed to finish Parquet: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BurnEvent, MintEvent};
+
+    fn events() -> Vec<BridgeEvent> {
+        vec![
+            BridgeEvent::Mint(MintEvent {
+                to_address: "0x1".to_string(),
+                amount: Sats(500_000),
+                btc_tx_id: "ab".repeat(32),
+                btc_block_num: 840_000,
+                timestamp: Some(1_700_000_000),
+                version: Some(7),
+                transaction_hash: Some("0xabc".to_string()),
+            }),
+            BridgeEvent::Burn(BurnEvent {
+                from_address: "0x2".to_string(),
+                btc_address: "bc1q,\"odd\"".to_string(),
+                fee_rate: 10,
+                amount: Sats(1_000),
+                operator_id: 3,
+                timestamp: None,
+                version: Some(9),
+                transaction_hash: None,
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut out = Vec::new();
+        write_csv(&mut out, &events()).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], COLUMNS.join(","));
+        assert_eq!(
+            lines[1],
+            format!(
+                "mint,7,0xabc,1700000000,0x1,500000,{},840000,,,,,,",
+                "ab".repeat(32)
+            )
+        );
+        assert_eq!(lines[2], "burn,9,,,0x2,1000,,,\"bc1q,\"\"odd\"\"\",10,3,,,");
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_write_parquet() {
+        let mut out = Vec::new();
+        write_parquet(&mut out, &events()).unwrap();
+        assert!(out.starts_with(b"PAR1"));
+        assert!(out.ends_with(b"PAR1"));
+    }
+}
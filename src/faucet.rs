@@ -0,0 +1,121 @@
+//! APT faucet client for devnet, testnet and localnets
+//!
+//! Funds fresh accounts through the faucet's `POST /mint` endpoint, so
+//! integration tests and examples don't need pre-funded keys. The testnet
+//! faucet only serves requests with an auth token.
+
+use crate::networks::Network;
+use crate::utils::parse_account_address;
+use anyhow::{anyhow, Result};
+use url::Url;
+
+/// Client of an Aptos faucet
+#[derive(Debug, Clone)]
+pub struct FaucetClient {
+    /// Faucet base URL
+    url: Url,
+    /// HTTP client
+    http_client: reqwest::Client,
+    /// Sent as `Authorization: Bearer <token>`
+    auth_token: Option<String>,
+}
+
+impl FaucetClient {
+    /// Create a client for the faucet at `url`
+    pub fn new(url: &str) -> Result<Self> {
+        let url = Url::parse(url).map_err(|e| anyhow!("Invalid faucet URL '{}': {}", url, e))?;
+        Ok(Self {
+            url,
+            http_client: reqwest::Client::new(),
+            auth_token: None,
+        })
+    }
+
+    /// Create a client for `network`'s public faucet
+    pub fn for_network(network: Network) -> Result<Self> {
+        let url = network
+            .config()
+            .faucet_url
+            .ok_or_else(|| anyhow!("The {} Aptos network has no faucet", network))?;
+        Self::new(url)
+    }
+
+    /// Authenticate with `token`
+    pub fn with_auth_token(mut self, token: &str) -> Self {
+        self.auth_token = Some(token.to_string());
+        self
+    }
+
+    /// Use a preconfigured HTTP client (proxies, TLS settings, timeouts)
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Fund `address` with `octas`, creating the account if needed
+    ///
+    /// Returns the hashes of the faucet's transactions; wait for them with
+    /// `QueryClient::wait_for_transaction` before spending the funds.
+    pub async fn fund(&self, address: &str, octas: u64) -> Result<Vec<String>> {
+        let address = parse_account_address(address)?.to_hex_literal();
+        let mut url = self
+            .url
+            .join("mint")
+            .map_err(|e| anyhow!("Invalid faucet URL '{}': {}", self.url, e))?;
+        url.query_pairs_mut()
+            .append_pair("amount", &octas.to_string())
+            .append_pair("address", &address);
+
+        let mut request = self.http_client.post(url);
+        if let Some(token) = &self.auth_token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| anyhow!("Failed to fund {} from the faucet: {}", address, e))?;
+        response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse faucet response for {}: {}", address, e))
+    }
+}
+
+/// Fund `address` with `octas` from `network`'s public faucet
+pub async fn fund_account_with_faucet(
+    network: Network,
+    address: &str,
+    octas: u64,
+) -> Result<Vec<String>> {
+    FaucetClient::for_network(network)?
+        .fund(address, octas)
+        .await
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_fund_posts_mint_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/mint"))
+            .and(query_param("amount", "100000000"))
+            .and(query_param("address", "0x2"))
+            .and(header("authorization", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(["0xabc"]))
+            .mount(&server)
+            .await;
+
+        let faucet = FaucetClient::new(&server.uri())
+            .unwrap()
+            .with_auth_token("secret");
+        assert_eq!(faucet.fund("0x2", 100_000_000).await.unwrap(), ["0xabc"]);
+        assert!(faucet.fund("not-an-address", 1).await.is_err());
+        assert!(FaucetClient::for_network(Network::Mainnet).is_err());
+    }
+}
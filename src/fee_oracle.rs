@@ -0,0 +1,118 @@
+//! BTC fee-rate oracle
+//!
+//! Fetches recommended fee rates from a mempool.space compatible endpoint, to
+//! feed the `fee_rate` argument of `burn` and `withdraw_by_lp`.
+
+use crate::types::constants::FEE_ORACLE_REQUEST_TIMEOUT_SECS;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Default mempool.space fee recommendation endpoint
+pub const MEMPOOL_SPACE_FEES_URL: &str = "https://mempool.space/api/v1/fees/recommended";
+
+/// Confirmation target for a fee rate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeTarget {
+    /// Next block
+    Fastest,
+    /// Within about 30 minutes
+    #[default]
+    HalfHour,
+    /// Within about an hour
+    Hour,
+}
+
+/// Recommended fee rates in sat/vB
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeRates {
+    /// Next block
+    pub fastest_fee: u64,
+    /// Within about 30 minutes
+    pub half_hour_fee: u64,
+    /// Within about an hour
+    pub hour_fee: u64,
+}
+
+impl FeeRates {
+    /// Fee rate for a confirmation target
+    pub fn for_target(&self, target: FeeTarget) -> u64 {
+        match target {
+            FeeTarget::Fastest => self.fastest_fee,
+            FeeTarget::HalfHour => self.half_hour_fee,
+            FeeTarget::Hour => self.hour_fee,
+        }
+    }
+}
+
+/// Fee-rate oracle client
+#[derive(Debug, Clone)]
+pub struct FeeOracle {
+    /// Fee recommendation endpoint
+    url: String,
+    /// HTTP client
+    http_client: reqwest::Client,
+}
+
+impl FeeOracle {
+    /// Create an oracle for a mempool.space compatible endpoint
+    pub fn new(url: &str) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(FEE_ORACLE_REQUEST_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| anyhow!("Failed to build fee oracle HTTP client: {}", e))?;
+
+        Ok(Self {
+            url: url.to_string(),
+            http_client,
+        })
+    }
+
+    /// Create an oracle for mempool.space
+    pub fn mempool_space() -> Result<Self> {
+        Self::new(MEMPOOL_SPACE_FEES_URL)
+    }
+
+    /// Use a preconfigured HTTP client (proxies, TLS settings, timeouts)
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Fetch current recommended fee rates
+    pub async fn fee_rates(&self) -> Result<FeeRates> {
+        self.http_client
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to send fee rate request to {}: {}", self.url, e))?
+            .error_for_status()
+            .map_err(|e| anyhow!("Fee rate request to {} failed: {}", self.url, e))?
+            .json::<FeeRates>()
+            .await
+            .map_err(|e| anyhow!("Failed to parse fee rate response: {}", e))
+    }
+
+    /// Fetch the recommended fee rate for a confirmation target
+    pub async fn fee_rate(&self, target: FeeTarget) -> Result<u64> {
+        Ok(self.fee_rates().await?.for_target(target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mempool_space_response() {
+        let response =
+            r#"{"fastestFee":25,"halfHourFee":18,"hourFee":12,"economyFee":6,"minimumFee":3}"#;
+        let rates: FeeRates = serde_json::from_str(response).unwrap();
+
+        assert_eq!(rates.for_target(FeeTarget::Fastest), 25);
+        assert_eq!(rates.for_target(FeeTarget::HalfHour), 18);
+        assert_eq!(rates.for_target(FeeTarget::Hour), 12);
+    }
+}
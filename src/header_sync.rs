@@ -0,0 +1,373 @@
+//! BTC light-client header sync
+//!
+//! Keeps the on-chain BTC light client at bitcoind's tip: finds where the two
+//! chains agree, then submits the missing headers in batches through the light
+//! client's `submit_block_headers(start_height: u64, headers: vector<vector<u8>>)`,
+//! whose deployed signature `BridgeClient::check_contract_abi` verifies.
+
+use crate::abort::execution_error;
+use crate::bitcoind::BitcoindRpc;
+use crate::types::constants::{
+    HEADER_SYNC_BATCH_SIZE, HEADER_SYNC_MAX_REORG_DEPTH, HEADER_SYNC_MAX_RETRIES,
+    HEADER_SYNC_POLL_INTERVAL_SECS, TRANSACTION_COMMIT_TIMEOUT_SECS,
+};
+use crate::BridgeClient;
+use anyhow::{anyhow, Result};
+use aptos_sdk::rest_client::aptos_api_types::TransactionData;
+use bitcoin::hashes::Hash;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Light-client header sync service
+pub struct HeaderSyncService {
+    /// Bridge client with the light client configured
+    bridge_client: Arc<BridgeClient>,
+    /// Source of headers
+    bitcoind: BitcoindRpc,
+    /// Headers per submission
+    batch_size: usize,
+    /// Attempts per batch before giving up
+    max_retries: u32,
+    /// Deepest divergence between the light client and bitcoind that is repaired
+    max_reorg_depth: u64,
+    /// Delay between syncs in `run`, and base delay between retries
+    poll_interval: Duration,
+}
+
+impl HeaderSyncService {
+    /// Create a header sync service
+    pub fn new(bridge_client: Arc<BridgeClient>, bitcoind: BitcoindRpc) -> Self {
+        Self {
+            bridge_client,
+            bitcoind,
+            batch_size: HEADER_SYNC_BATCH_SIZE,
+            max_retries: HEADER_SYNC_MAX_RETRIES,
+            max_reorg_depth: HEADER_SYNC_MAX_REORG_DEPTH,
+            poll_interval: Duration::from_secs(HEADER_SYNC_POLL_INTERVAL_SECS),
+        }
+    }
+
+    /// Set the number of headers per submission
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set the attempts per batch before giving up
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the deepest divergence that is repaired automatically
+    pub fn with_max_reorg_depth(mut self, max_reorg_depth: u64) -> Self {
+        self.max_reorg_depth = max_reorg_depth;
+        self
+    }
+
+    /// Set the delay between syncs in `run`
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Number of blocks the light client is behind bitcoind
+    pub async fn lag(&self) -> Result<u64> {
+        let light_client_tip = self.bridge_client.get_latest_block_height().await?;
+        let bitcoind_tip = self.bitcoind.get_block_count().await?;
+        let lag = bitcoind_tip.saturating_sub(light_client_tip);
+        #[cfg(feature = "metrics")]
+        crate::metrics::LIGHT_CLIENT_LAG.set(lag as i64);
+        Ok(lag)
+    }
+
+    /// Sync forever, sleeping `poll_interval` between rounds
+    pub async fn run(&self) -> Result<()> {
+        loop {
+            match self.sync_once().await {
+                Ok(submitted) if submitted > 0 => {
+                    #[cfg(feature = "tracing")]
+                    tracing::info!(submitted, "Synced light client headers");
+                }
+                Ok(_) => {}
+                Err(_error) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(error = %_error, "Light client header sync failed");
+                }
+            }
+            crate::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Submit every header the light client is missing; returns the number submitted
+    pub async fn sync_once(&self) -> Result<u64> {
+        let bitcoind_tip = self.bitcoind.get_block_count().await?;
+        let start_height = self.find_fork_point().await? + 1;
+        if start_height > bitcoind_tip {
+            #[cfg(feature = "metrics")]
+            crate::metrics::LIGHT_CLIENT_LAG.set(0);
+            return Ok(0);
+        }
+
+        let mut height = start_height;
+        while height <= bitcoind_tip {
+            let end = bitcoind_tip.min(height + self.batch_size as u64 - 1);
+            let mut headers = Vec::with_capacity((end - height + 1) as usize);
+            for h in height..=end {
+                let hash = self.bitcoind.get_block_hash(h).await?;
+                let header = self.bitcoind.get_block_header(&hash).await?;
+                headers.push(bitcoin::consensus::encode::serialize(&header));
+            }
+            self.submit_with_retry(height, &headers).await?;
+            #[cfg(feature = "metrics")]
+            crate::metrics::LIGHT_CLIENT_LAG.set((bitcoind_tip - end) as i64);
+            height = end + 1;
+        }
+
+        Ok(bitcoind_tip - start_height + 1)
+    }
+
+    /// Highest height where the light client and bitcoind agree on the block hash
+    async fn find_fork_point(&self) -> Result<u64> {
+        let light_client_tip = self.bridge_client.get_latest_block_height().await?;
+        let bitcoind_tip = self.bitcoind.get_block_count().await?;
+
+        let mut height = light_client_tip.min(bitcoind_tip);
+        loop {
+            let light_client_hash = self.bridge_client.get_block_hash(height).await?;
+            let bitcoind_hash = self.bitcoind.get_block_hash(height).await?;
+            if light_client_hash == bitcoind_hash.to_byte_array() {
+                break;
+            }
+            if light_client_tip - height >= self.max_reorg_depth || height == 0 {
+                return Err(anyhow!(
+                    "Light client diverges from bitcoind by more than {} blocks below height {}",
+                    self.max_reorg_depth,
+                    light_client_tip
+                ));
+            }
+            height -= 1;
+        }
+
+        #[cfg(feature = "tracing")]
+        if height < light_client_tip {
+            tracing::warn!(
+                fork_height = height,
+                light_client_tip,
+                "Light client is on a stale fork, resubmitting from fork point"
+            );
+        }
+        Ok(height)
+    }
+
+    /// Submit one batch and wait for it to commit, retrying with linear backoff
+    async fn submit_with_retry(&self, start_height: u64, headers: &[Vec<u8>]) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match self.submit_batch(start_height, headers).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt >= self.max_retries => {
+                    return Err(anyhow!(
+                        "Failed to submit headers from height {} after {} attempts: {}",
+                        start_height,
+                        attempt,
+                        e
+                    ));
+                }
+                Err(_error) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        start_height,
+                        attempt,
+                        error = %_error,
+                        "Header submission failed, retrying"
+                    );
+                    crate::time::sleep(self.poll_interval * attempt).await;
+                }
+            }
+        }
+    }
+
+    async fn submit_batch(&self, start_height: u64, headers: &[Vec<u8>]) -> Result<()> {
+        let tx_hash = self
+            .bridge_client
+            .submit_block_headers(start_height, headers)
+            .await?;
+        let transaction = self
+            .bridge_client
+            .wait_for_transaction(
+                &tx_hash,
+                Duration::from_secs(TRANSACTION_COMMIT_TIMEOUT_SECS),
+            )
+            .await?;
+        if let TransactionData::OnChain(txn) = &transaction {
+            let status = txn.info.status();
+            if !status.is_success() {
+                return Err(execution_error("Header submission", &tx_hash, status));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::testing::{self, MockBitcoind, MockChain};
+    use aptos_sdk::types::transaction::TransactionPayload;
+    use bitcoin::block::Header;
+    use wiremock::MockServer;
+
+    /// Serve `chain` (from height 0) as the light client's view of Bitcoin
+    async fn mount_light_client(server: &MockServer, chain: &[Header]) {
+        testing::mount_view(
+            server,
+            "get_latest_block_height",
+            serde_json::json!([(chain.len() - 1).to_string()]),
+        )
+        .await;
+        for (height, header) in chain.iter().enumerate() {
+            testing::mount_view_with_args(
+                server,
+                "get_block_hash",
+                serde_json::json!([height.to_string()]),
+                serde_json::json!([format!(
+                    "0x{}",
+                    hex::encode(header.block_hash().to_byte_array())
+                )]),
+            )
+            .await;
+        }
+    }
+
+    fn service(fullnode: &MockServer, bitcoind: &MockServer) -> HeaderSyncService {
+        HeaderSyncService::new(
+            Arc::new(testing::bridge_client(fullnode)),
+            BitcoindRpc::new(&bitcoind.uri()),
+        )
+        .with_poll_interval(Duration::from_millis(1))
+    }
+
+    /// Start height and headers of each submitted `submit_block_headers`
+    fn submissions(chain: &MockChain) -> Vec<(u64, Vec<Vec<u8>>)> {
+        chain
+            .submitted()
+            .iter()
+            .map(|txn| match txn.payload() {
+                TransactionPayload::EntryFunction(entry_function) => {
+                    assert_eq!(entry_function.module().name().as_str(), "btc_mirror");
+                    assert_eq!(entry_function.function().as_str(), "submit_block_headers");
+                    let args = entry_function.args();
+                    (
+                        bcs::from_bytes(&args[0]).unwrap(),
+                        bcs::from_bytes(&args[1]).unwrap(),
+                    )
+                }
+                payload => panic!("unexpected payload {:?}", payload),
+            })
+            .collect()
+    }
+
+    fn serialized(headers: &[Header]) -> Vec<Vec<u8>> {
+        headers
+            .iter()
+            .map(bitcoin::consensus::encode::serialize)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_sync_once_submits_missing_headers_in_batches() {
+        let bitcoin_chain = testing::btc_headers(bitcoin::BlockHash::all_zeros(), 11, 0);
+        let fullnode = testing::mock_fullnode().await;
+        let chain = MockChain::mount(&fullnode, 0).await;
+        mount_light_client(&fullnode, &bitcoin_chain[..=7]).await;
+        let bitcoind = MockServer::start().await;
+        MockBitcoind::mount(&bitcoind, bitcoin_chain.clone()).await;
+        let service = service(&fullnode, &bitcoind).with_batch_size(2);
+
+        assert_eq!(service.lag().await.unwrap(), 3);
+        assert_eq!(service.sync_once().await.unwrap(), 3);
+        assert_eq!(
+            submissions(&chain),
+            [
+                (8, serialized(&bitcoin_chain[8..=9])),
+                (10, serialized(&bitcoin_chain[10..=10])),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_once_is_idle_at_bitcoind_tip() {
+        let bitcoin_chain = testing::btc_headers(bitcoin::BlockHash::all_zeros(), 5, 0);
+        let fullnode = testing::mock_fullnode().await;
+        let chain = MockChain::mount(&fullnode, 0).await;
+        mount_light_client(&fullnode, &bitcoin_chain).await;
+        let bitcoind = MockServer::start().await;
+        MockBitcoind::mount(&bitcoind, bitcoin_chain).await;
+
+        assert_eq!(
+            service(&fullnode, &bitcoind)
+                .await
+                .sync_once()
+                .await
+                .unwrap(),
+            0
+        );
+        assert!(chain.submitted().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_fork_point_resubmits_stale_fork() {
+        // The light client followed a fork from height 7 that bitcoind abandoned
+        let mut bitcoin_chain = testing::btc_headers(bitcoin::BlockHash::all_zeros(), 7, 0);
+        let mut stale_chain = bitcoin_chain.clone();
+        let fork_parent = bitcoin_chain[6].block_hash();
+        bitcoin_chain.extend(testing::btc_headers(fork_parent, 4, 0));
+        stale_chain.extend(testing::btc_headers(fork_parent, 3, 1));
+        let fullnode = testing::mock_fullnode().await;
+        let chain = MockChain::mount(&fullnode, 0).await;
+        mount_light_client(&fullnode, &stale_chain).await;
+        let bitcoind = MockServer::start().await;
+        MockBitcoind::mount(&bitcoind, bitcoin_chain.clone()).await;
+        let service = service(&fullnode, &bitcoind);
+
+        assert_eq!(service.find_fork_point().await.unwrap(), 6);
+        assert_eq!(service.sync_once().await.unwrap(), 4);
+        assert_eq!(
+            submissions(&chain),
+            [(7, serialized(&bitcoin_chain[7..=10]))]
+        );
+
+        // A fork deeper than the repairable depth is left alone
+        let service = service.with_max_reorg_depth(2);
+        let err = service.sync_once().await.unwrap_err();
+        assert!(err.to_string().contains("more than 2 blocks"), "{}", err);
+        assert_eq!(chain.submitted().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_submit_with_retry_resubmits_failed_batch() {
+        let bitcoin_chain = testing::btc_headers(bitcoin::BlockHash::all_zeros(), 3, 0);
+        let headers = serialized(&bitcoin_chain[1..]);
+        let fullnode = testing::mock_fullnode().await;
+        let chain = MockChain::mount(&fullnode, 0).await;
+        let bitcoind = MockServer::start().await;
+        let service = service(&fullnode, &bitcoind).with_max_retries(2);
+
+        // The first submission aborts on chain; the retry commits
+        chain.abort(0);
+        service.submit_with_retry(1, &headers).await.unwrap();
+        assert_eq!(
+            submissions(&chain),
+            [(1, headers.clone()), (1, headers.clone())]
+        );
+
+        // Both attempts abort
+        chain.abort(2);
+        chain.abort(3);
+        let err = service.submit_with_retry(1, &headers).await.unwrap_err();
+        assert!(err.to_string().contains("after 2 attempts"), "{}", err);
+        assert_eq!(chain.submitted().len(), 4);
+    }
+}
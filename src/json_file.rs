@@ -0,0 +1,89 @@
+//! JSON files for the file-backed stores
+//!
+//! Files are replaced through a temporary file that is flushed to disk before
+//! it is renamed over the old one, so a crash leaves either the old or the new
+//! contents, never a truncated file. The rename itself isn't synced: a crash
+//! right after a write may still come back with the old contents.
+
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// Contents of the file at `path`, or `None` if it doesn't exist
+///
+/// `what` names the contents in error messages.
+pub(crate) async fn read_json<T: DeserializeOwned>(path: &Path, what: &str) -> Result<Option<T>> {
+    match tokio::fs::read(path).await {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| anyhow!("Failed to parse {} {}: {}", what, path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(anyhow!("Failed to read {} {}: {}", what, path.display(), e)),
+    }
+}
+
+/// Replace the file at `path` with `value`, creating its directory if needed
+///
+/// `what` names the contents in error messages.
+pub(crate) async fn write_json_atomic<T: Serialize>(
+    path: &Path,
+    what: &str,
+    value: &T,
+) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| anyhow!("Failed to create directory {}: {}", dir.display(), e))?;
+    }
+
+    let bytes = serde_json::to_vec_pretty(value)
+        .map_err(|e| anyhow!("Failed to serialize {}: {}", what, e))?;
+    let tmp_path = tmp_path(path);
+    let write = async {
+        let mut file = tokio::fs::File::create(&tmp_path).await?;
+        file.write_all(&bytes).await?;
+        file.sync_all().await
+    };
+    write
+        .await
+        .map_err(|e| anyhow!("Failed to write {} {}: {}", what, tmp_path.display(), e))?;
+    tokio::fs::rename(&tmp_path, path)
+        .await
+        .map_err(|e| anyhow!("Failed to write {} {}: {}", what, path.display(), e))
+}
+
+/// `path` with `.tmp` appended, next to it so the rename stays on one filesystem
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    PathBuf::from(tmp_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_then_read_round_trips() {
+        let dir = std::env::temp_dir().join(format!("json-file-{}", std::process::id()));
+        let path = dir.join("nested").join("value.json");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(read_json::<Vec<u64>>(&path, "value").await.unwrap(), None);
+        write_json_atomic(&path, "value", &[1u64, 2]).await.unwrap();
+        write_json_atomic(&path, "value", &[3u64]).await.unwrap();
+        assert_eq!(
+            read_json::<Vec<u64>>(&path, "value").await.unwrap(),
+            Some(vec![3])
+        );
+        assert!(!tmp_path(&path).exists());
+
+        std::fs::write(&path, "not json").unwrap();
+        let err = read_json::<Vec<u64>>(&path, "value").await.unwrap_err();
+        assert!(err.to_string().starts_with("Failed to parse value"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,200 @@
+//! Signing account loading
+//!
+//! Besides raw hex keys, accounts can be loaded from a BIP-39 mnemonic, an
+//! encrypted Web3 Secret Storage keystore (`keystore` feature) or an Aptos CLI
+//! profile (`config` feature), and passed to [`BridgeClient::from_account`](crate::BridgeClient::from_account).
+//! Key bytes are kept in buffers that are zeroed on drop, and errors never
+//! include key material.
+
+use crate::utils::strip_hex_prefix;
+use anyhow::{anyhow, Result};
+use aptos_sdk::crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey, ED25519_PRIVATE_KEY_LENGTH};
+use aptos_sdk::types::transaction::authenticator::AuthenticationKey;
+use aptos_sdk::types::LocalAccount;
+use secrecy::{ExposeSecret, SecretString};
+#[cfg(any(feature = "config", feature = "keystore"))]
+use std::path::Path;
+#[cfg(feature = "config")]
+use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+/// Derivation path of the first account, as used by Aptos wallets
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/637'/0'/0'/0'";
+
+/// Prefix of AIP-80 formatted Ed25519 private keys
+const AIP80_ED25519_PREFIX: &str = "ed25519-priv-";
+
+/// Account for a hex-encoded Ed25519 private key, `0x`- or AIP-80-prefixed or bare
+pub fn account_from_private_key(private_key: &SecretString) -> Result<LocalAccount> {
+    let hex_key = private_key.expose_secret();
+    let hex_key = hex_key
+        .strip_prefix(AIP80_ED25519_PREFIX)
+        .unwrap_or(hex_key);
+
+    let mut key_bytes = Zeroizing::new([0u8; ED25519_PRIVATE_KEY_LENGTH]);
+    hex::decode_to_slice(strip_hex_prefix(hex_key), key_bytes.as_mut_slice()).map_err(|_| {
+        anyhow!(
+            "Invalid Aptos private key: expected {} hex-encoded bytes",
+            ED25519_PRIVATE_KEY_LENGTH
+        )
+    })?;
+    account_from_key_bytes(key_bytes.as_slice())
+}
+
+/// Account derived from a BIP-39 mnemonic along `derivation_path`
+///
+/// Use [`DEFAULT_DERIVATION_PATH`] for the wallet's first account.
+pub fn account_from_mnemonic(
+    mnemonic: &SecretString,
+    derivation_path: &str,
+) -> Result<LocalAccount> {
+    LocalAccount::from_derive_path(derivation_path, mnemonic.expose_secret(), 0).map_err(|_| {
+        anyhow!(
+            "Failed to derive account at '{}': invalid mnemonic or derivation path",
+            derivation_path
+        )
+    })
+}
+
+/// Account from an encrypted Web3 Secret Storage (v3) keystore file
+///
+/// Keystores written by `eth-keystore`, geth or similar tools work as long as
+/// they hold a 32-byte Ed25519 private key.
+#[cfg(feature = "keystore")]
+pub fn account_from_keystore(
+    path: impl AsRef<Path>,
+    passphrase: &SecretString,
+) -> Result<LocalAccount> {
+    let path = path.as_ref();
+    let key_bytes = Zeroizing::new(
+        eth_keystore::decrypt_key(path, passphrase.expose_secret())
+            .map_err(|e| anyhow!("Failed to decrypt keystore {}: {}", path.display(), e))?,
+    );
+    account_from_key_bytes(&key_bytes)
+}
+
+/// Account of `profile` in the Aptos CLI config
+///
+/// Reads `.aptos/config.yaml` in the working directory if present, like the CLI
+/// does, and `~/.aptos/config.yaml` otherwise.
+#[cfg(feature = "config")]
+pub fn account_from_aptos_profile(profile: &str) -> Result<LocalAccount> {
+    let workspace = PathBuf::from(".aptos").join("config.yaml");
+    let path = if workspace.exists() {
+        workspace
+    } else {
+        std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(|home| PathBuf::from(home).join(".aptos").join("config.yaml"))
+            .ok_or_else(|| anyhow!("Cannot locate the Aptos CLI config: no home directory"))?
+    };
+    account_from_aptos_config(path, profile)
+}
+
+/// Account of `profile` in an Aptos CLI config file
+#[cfg(feature = "config")]
+pub fn account_from_aptos_config(path: impl AsRef<Path>, profile: &str) -> Result<LocalAccount> {
+    #[derive(serde::Deserialize)]
+    struct CliConfig {
+        #[serde(default)]
+        profiles: std::collections::HashMap<String, CliProfile>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct CliProfile {
+        private_key: Option<SecretString>,
+    }
+
+    let path = path.as_ref();
+    let contents = Zeroizing::new(
+        std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?,
+    );
+    // Parse errors are reported without serde_yaml's message, which can quote the file
+    let config: CliConfig = serde_yaml::from_str(&contents)
+        .map_err(|_| anyhow!("Failed to parse Aptos CLI config {}", path.display()))?;
+    let private_key = config
+        .profiles
+        .get(profile)
+        .ok_or_else(|| anyhow!("Profile '{}' not found in {}", profile, path.display()))?
+        .private_key
+        .as_ref()
+        .ok_or_else(|| {
+            anyhow!(
+                "Profile '{}' in {} has no private key (hardware wallet profiles aren't supported)",
+                profile,
+                path.display()
+            )
+        })?;
+    account_from_private_key(private_key)
+}
+
+fn account_from_key_bytes(key_bytes: &[u8]) -> Result<LocalAccount> {
+    let private_key = Ed25519PrivateKey::try_from(key_bytes)
+        .map_err(|_| anyhow!("Invalid Aptos private key: not a valid Ed25519 key"))?;
+    let address =
+        AuthenticationKey::ed25519(&Ed25519PublicKey::from(&private_key)).account_address();
+    Ok(LocalAccount::new(address, private_key, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE_KEY: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[test]
+    fn test_account_from_private_key() {
+        let account = account_from_private_key(&SecretString::from(PRIVATE_KEY)).unwrap();
+        let aip80 = format!("{}{}", AIP80_ED25519_PREFIX, PRIVATE_KEY);
+        let aip80_account = account_from_private_key(&SecretString::from(aip80)).unwrap();
+        assert_eq!(account.address(), aip80_account.address());
+
+        let not_hex = "zz".repeat(ED25519_PRIVATE_KEY_LENGTH);
+        for key in ["deadbeef", not_hex.as_str()] {
+            let error = account_from_private_key(&SecretString::from(key))
+                .err()
+                .unwrap();
+            assert!(!error.to_string().contains(key));
+        }
+    }
+
+    #[test]
+    fn test_account_from_mnemonic() {
+        let mnemonic = SecretString::from(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        );
+        let first = account_from_mnemonic(&mnemonic, DEFAULT_DERIVATION_PATH).unwrap();
+        let second = account_from_mnemonic(&mnemonic, "m/44'/637'/1'/0'/0'").unwrap();
+        assert_ne!(first.address(), second.address());
+
+        let error = account_from_mnemonic(
+            &SecretString::from("not a mnemonic"),
+            DEFAULT_DERIVATION_PATH,
+        )
+        .err()
+        .unwrap();
+        assert!(!error.to_string().contains("not a mnemonic"));
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_account_from_aptos_config() {
+        let path = std::env::temp_dir().join(format!("aptos-config-{}.yaml", std::process::id()));
+        std::fs::write(
+            &path,
+            format!(
+                "---\nprofiles:\n  default:\n    network: Devnet\n    private_key: \"{}{}\"\n  ledger:\n    network: Mainnet\n",
+                AIP80_ED25519_PREFIX, PRIVATE_KEY
+            ),
+        )
+        .unwrap();
+
+        let account = account_from_aptos_config(&path, "default").unwrap();
+        let expected = account_from_private_key(&SecretString::from(PRIVATE_KEY)).unwrap();
+        assert_eq!(account.address(), expected.address());
+        assert!(account_from_aptos_config(&path, "ledger").is_err());
+        assert!(account_from_aptos_config(&path, "missing").is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
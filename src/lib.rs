@@ -1,16 +1,178 @@
+pub mod abi;
+pub mod abort;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod api;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod audit;
+#[cfg(feature = "bitcoind")]
+pub mod bitcoind;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
 pub mod bridge_client;
+pub mod bridge_handle;
+pub mod btc;
+pub mod config;
+#[cfg(any(feature = "bitcoind", feature = "esplora"))]
+pub mod confirmations;
+pub mod contract;
+#[cfg(all(feature = "e2e", not(target_arch = "wasm32")))]
+pub mod e2e;
+#[cfg(all(feature = "postgres", not(target_arch = "wasm32")))]
+pub mod event_store;
 pub mod events;
+pub mod export;
+#[cfg(feature = "faucet")]
+pub mod faucet;
+#[cfg(feature = "fee-oracle")]
+pub mod fee_oracle;
+#[cfg(feature = "bitcoind")]
+pub mod header_sync;
+#[cfg(all(feature = "http-config", not(target_arch = "wasm32")))]
+pub mod http;
+#[cfg(not(target_arch = "wasm32"))]
+mod json_file;
+pub mod keys;
+#[cfg(feature = "events-rest")]
+pub mod lp_report;
+pub mod lp_select;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+pub mod mock;
+pub mod networks;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod observer;
+pub mod operators;
+pub mod payload;
+#[cfg(all(
+    any(feature = "bitcoind", feature = "esplora"),
+    not(target_arch = "wasm32")
+))]
+pub mod payout_watcher;
+#[cfg(all(
+    any(feature = "bitcoind", feature = "esplora"),
+    feature = "events-rest",
+    not(target_arch = "wasm32")
+))]
+pub mod peg_in;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod peg_out;
+pub mod pipeline;
+#[cfg(any(feature = "bitcoind", feature = "esplora"))]
+pub mod proof_builder;
+#[cfg(feature = "bitcoin")]
+pub mod psbt;
 pub mod query_client;
+pub mod reconcile;
+pub mod reorg;
+pub mod request_log;
+pub mod signer;
+pub mod stats;
+#[cfg(all(feature = "status-server", not(target_arch = "wasm32")))]
+pub mod status_server;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+#[cfg(feature = "test-utils")]
+pub mod testing;
+pub mod threshold;
+mod time;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tx_queue;
 pub mod types;
 pub mod utils;
+#[cfg(all(feature = "vault", not(target_arch = "wasm32")))]
+pub mod vault;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod withdraw_tracker;
 
 // Re-export commonly used types and functions
+#[cfg(not(target_arch = "wasm32"))]
+pub use api::{BridgeApi, BridgeQueryApi};
+#[cfg(not(target_arch = "wasm32"))]
+pub use audit::{AuditLog, JsonlAuditLog};
+#[cfg(feature = "bitcoind")]
+pub use bitcoind::BitcoindRpc;
 pub use bridge_client::BridgeClient;
-pub use events::{EventHandler, EventMonitor};
+pub use bridge_handle::{parse_asset_type, BridgeHandle};
+pub use btc::{
+    display_hex_to_txid, estimate_peg_out_fee, script_hash_for_address, txid_to_display_hex,
+    BtcAddress, BtcNetwork, TxidByteOrder,
+};
+pub use config::{Config, GasPriority, GasSettings, RetryPolicy, TxOptions};
+#[cfg(any(feature = "bitcoind", feature = "esplora"))]
+pub use confirmations::ConfirmationTracker;
+pub use contract::{ContractModule, ContractSpec};
+#[cfg(all(feature = "postgres", not(target_arch = "wasm32")))]
+pub use event_store::{EventStore, EventVolume};
+#[cfg(feature = "events-rest")]
+pub use events::BridgeEventDecoder;
+pub use events::EventHandler;
+#[cfg(all(feature = "events-graphql", not(target_arch = "wasm32")))]
+pub use events::{
+    AckToken, AckingMonitor, CursorStore, EventMonitor, FileCursorStore, MonitorHealth,
+    OperatorWatcher, ParseFailurePolicy, PollError, PollErrorKind, ProcessReport,
+};
+#[cfg(feature = "events-graphql")]
+pub use events::{EventFilter, EventPage, GraphQLAuth, IndexerClient, IndexerQuery, IndexerSchema};
+#[cfg(feature = "faucet")]
+pub use faucet::FaucetClient;
+#[cfg(feature = "fee-oracle")]
+pub use fee_oracle::{FeeOracle, FeeTarget};
+#[cfg(feature = "bitcoind")]
+pub use header_sync::HeaderSyncService;
+#[cfg(all(feature = "http-config", not(target_arch = "wasm32")))]
+pub use http::HttpConfig;
+#[cfg(feature = "events-rest")]
+pub use lp_report::{LPClaim, LPReport};
+pub use lp_select::{LPConstraints, WithdrawQuote};
+#[cfg(all(feature = "test-utils", not(target_arch = "wasm32")))]
+pub use mock::{MockBridgeClient, MockCall};
+pub use networks::{Network, NetworkConfig};
+#[cfg(not(target_arch = "wasm32"))]
+pub use observer::{TxMetadata, TxObserver};
+pub use operators::{AutoBurn, OperatorPreferences};
+pub use payload::PayloadBuilder;
+#[cfg(all(
+    any(feature = "bitcoind", feature = "esplora"),
+    not(target_arch = "wasm32")
+))]
+pub use payout_watcher::{PayoutMissing, PayoutObserved, PayoutObserver, PayoutWatcher};
+#[cfg(all(
+    any(feature = "bitcoind", feature = "esplora"),
+    feature = "events-rest",
+    not(target_arch = "wasm32")
+))]
+pub use peg_in::{FilePegInStore, PegInService, PegInStore};
+#[cfg(not(target_arch = "wasm32"))]
+pub use peg_out::{ExpectedPayout, FilePegOutStore, PegOutPayer, PegOutService, PegOutStore};
+pub use pipeline::{PipelineOutcome, SubmissionPipeline};
+#[cfg(any(feature = "bitcoind", feature = "esplora"))]
+pub use proof_builder::ProofBuilder;
+#[cfg(feature = "bitcoin")]
+pub use psbt::{PayoutPsbt, PayoutPsbtBuilder};
 pub use query_client::QueryClient;
+pub use reconcile::{CustodyUtxo, ReserveReport};
+pub use reorg::ReorgDetector;
+pub use signer::TransactionSigner;
+pub use stats::BridgeStats;
+#[cfg(all(feature = "status-server", not(target_arch = "wasm32")))]
+pub use status_server::StatusServer;
+pub use threshold::{SigningService, ThresholdSigner};
+#[cfg(not(target_arch = "wasm32"))]
+pub use tx_queue::{FileTxQueueStore, TxIntent, TxQueue, TxQueueStore};
+#[cfg(all(feature = "webhook", not(target_arch = "wasm32")))]
+pub use withdraw_tracker::WebhookAlertSink;
+#[cfg(not(target_arch = "wasm32"))]
+pub use withdraw_tracker::{
+    TrackedWithdraw, WithdrawAlert, WithdrawAlertKind, WithdrawObserver, WithdrawPhase,
+    WithdrawSla, WithdrawTracker, WithdrawTransition,
+};
 
 // Re-export main data types (excluding error types)
 pub use types::{
-    BridgeEvent, BurnEvent, ClaimLPWithdrawParams, LPInfo, LPStatus, LPWithdraw, MintEvent, Peg,
-    RegisterLPParams, ScriptType, TxProof, WithdrawByLPEvent, WithdrawByLPParams,
+    AccountResource, BridgeConfig, BridgeEvent, BridgeEventKind, BridgeEventRecord, BurnEvent,
+    ClaimLPWithdrawParams, EventSchemaVersion, GasEstimate, LPInfo, LPStatus, LPWithdraw,
+    LedgerInfo, MintEvent, OperatorInfo, OrphanedBlock, Peg, PegRecord, RegisterLPParams,
+    ReorgDetected, Sats, ScriptType, TransactionEvent, TxKind, TxProof, WithdrawByLPEvent,
+    WithdrawByLPParams, WithdrawStatus,
 };
@@ -0,0 +1,193 @@
+//! LP earnings and performance report
+//!
+//! The LP's withdraws are found by scanning `WithdrawByLP` events, and their
+//! amounts, request times and statuses are read from the `get_lp_withdraw` and
+//! `get_withdraw_status` views. Payouts aren't emitted as events, so fees and
+//! fulfillment latency come from the LP's own [`LPClaim`] records.
+
+use crate::types::constants::TRANSACTION_SCAN_PAGE_SIZE;
+use crate::types::{BridgeEvent, LPWithdraw, Sats, WithdrawStatus};
+use crate::BridgeClient;
+use anyhow::Result;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+/// A `claim_lp_withdraw` the LP submitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LPClaim {
+    /// Claimed withdraw
+    pub withdraw_id: u64,
+    /// BTC paid to the receiver
+    pub amount_paid: Sats,
+    /// Claim time, seconds since the Unix epoch
+    pub claimed_at: u64,
+}
+
+/// Earnings and performance of one LP
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LPReport {
+    /// LP ID
+    pub lp_id: u64,
+    /// Withdraws requested from the LP
+    pub withdraw_count: u64,
+    /// Withdraws the LP claimed
+    pub fulfilled: u64,
+    /// Withdraws that expired unclaimed
+    pub expired: u64,
+    /// Withdraws still open
+    pub pending: u64,
+    /// Withdrawn amount minus BTC paid, over claimed withdraws with a claim record
+    pub fees_earned: Sats,
+    /// Mean seconds from request to claim, over claimed withdraws with a claim record
+    pub average_fulfillment_secs: Option<u64>,
+    /// Amount of the pending withdraws
+    pub pending_exposure: Sats,
+}
+
+impl LPReport {
+    /// Report on the withdraws requested from `lp_id` within `versions`
+    ///
+    /// Streams the range with [`QueryClient::stream_bridge_events`](crate::QueryClient::stream_bridge_events),
+    /// then reads two views per withdraw with [`QueryClient::view_many`](crate::QueryClient::view_many).
+    pub async fn generate(
+        client: &BridgeClient,
+        lp_id: u64,
+        versions: RangeInclusive<u64>,
+        claims: &[LPClaim],
+    ) -> Result<Self> {
+        let bridge_contract_address = client.bridge_contract_address().to_hex_literal();
+        let mut events = std::pin::pin!(client.stream_bridge_events(
+            versions,
+            &bridge_contract_address,
+            TRANSACTION_SCAN_PAGE_SIZE
+        ));
+
+        let mut requests = Vec::new();
+        while let Some(event) = events.try_next().await? {
+            if let BridgeEvent::WithdrawByLP(event) = event {
+                if event.lp_id == lp_id {
+                    requests.push(client.lp_withdraw_view_request(event.withdraw_id)?);
+                    requests.push(client.withdraw_status_view_request(event.withdraw_id)?);
+                }
+            }
+        }
+
+        let mut results = client.view_many(&requests).await.into_iter();
+        let mut withdraws = Vec::with_capacity(requests.len() / 2);
+        while let (Some(withdraw), Some(status)) = (results.next(), results.next()) {
+            withdraws.push((
+                LPWithdraw::from_view_response(&withdraw?)?,
+                WithdrawStatus::from_view_response(&status?)?,
+            ));
+        }
+        Ok(Self::from_withdraws(lp_id, &withdraws, claims))
+    }
+
+    /// Report from withdraws already read from the views
+    pub fn from_withdraws(
+        lp_id: u64,
+        withdraws: &[(LPWithdraw, WithdrawStatus)],
+        claims: &[LPClaim],
+    ) -> Self {
+        let claims: HashMap<u64, &LPClaim> = claims
+            .iter()
+            .map(|claim| (claim.withdraw_id, claim))
+            .collect();
+        let mut report = Self {
+            lp_id,
+            ..Self::default()
+        };
+        let (mut latency_total, mut latency_count) = (0u64, 0u64);
+
+        for (withdraw, status) in withdraws {
+            report.withdraw_count += 1;
+            match status {
+                WithdrawStatus::PENDING => {
+                    report.pending += 1;
+                    report.pending_exposure = Sats(
+                        report
+                            .pending_exposure
+                            .0
+                            .saturating_add(withdraw.withdraw_amount.0),
+                    );
+                }
+                WithdrawStatus::EXPIRED => report.expired += 1,
+                WithdrawStatus::CLAIMED => {
+                    report.fulfilled += 1;
+                    if let Some(claim) = claims.get(&withdraw.id) {
+                        let fee = withdraw.withdraw_amount.saturating_sub(claim.amount_paid);
+                        report.fees_earned = Sats(report.fees_earned.0.saturating_add(fee.0));
+                        latency_total = latency_total
+                            .saturating_add(claim.claimed_at.saturating_sub(withdraw.timestamp));
+                        latency_count += 1;
+                    }
+                }
+            }
+        }
+
+        report.average_fulfillment_secs =
+            (latency_count > 0).then(|| latency_total / latency_count);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn withdraw(id: u64, amount: u64, timestamp: u64) -> LPWithdraw {
+        LPWithdraw {
+            id,
+            withdraw_amount: Sats(amount),
+            receiver_addr: "0x1".to_string(),
+            receiver_script_hash: "00".to_string(),
+            receive_min_amount: Sats(amount - 100),
+            fee_rate: 5,
+            timestamp,
+            lp_id: 4,
+        }
+    }
+
+    fn claim(withdraw_id: u64, amount_paid: u64, claimed_at: u64) -> LPClaim {
+        LPClaim {
+            withdraw_id,
+            amount_paid: Sats(amount_paid),
+            claimed_at,
+        }
+    }
+
+    #[test]
+    fn test_lp_report_from_withdraws() {
+        let withdraws = vec![
+            (withdraw(1, 10_000, 1_000), WithdrawStatus::CLAIMED),
+            (withdraw(2, 20_000, 2_000), WithdrawStatus::CLAIMED),
+            (withdraw(3, 30_000, 3_000), WithdrawStatus::CLAIMED),
+            (withdraw(4, 40_000, 4_000), WithdrawStatus::EXPIRED),
+            (withdraw(5, 50_000, 5_000), WithdrawStatus::PENDING),
+        ];
+        let claims = [claim(1, 9_950, 1_600), claim(2, 19_900, 3_200)];
+        let report = LPReport::from_withdraws(4, &withdraws, &claims);
+
+        assert_eq!(report.withdraw_count, 5);
+        assert_eq!(report.fulfilled, 3);
+        assert_eq!(report.expired, 1);
+        assert_eq!(report.pending, 1);
+        assert_eq!(report.fees_earned, Sats(150));
+        assert_eq!(report.average_fulfillment_secs, Some(900));
+        assert_eq!(report.pending_exposure, Sats(50_000));
+    }
+
+    #[test]
+    fn test_lp_report_without_claims() {
+        let report = LPReport::from_withdraws(
+            4,
+            &[(withdraw(1, 10_000, 1_000), WithdrawStatus::CLAIMED)],
+            &[],
+        );
+        assert_eq!(report.fulfilled, 1);
+        assert_eq!(report.fees_earned, Sats::ZERO);
+        assert_eq!(report.average_fulfillment_secs, None);
+    }
+}
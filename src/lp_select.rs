@@ -0,0 +1,278 @@
+//! LP selection and quotes for withdrawals
+//!
+//! [`select_lp`] reads every registered LP with the `get_lp_ids` and
+//! `get_lp_info` views and picks the cheapest active one that fits the
+//! [`LPConstraints`]. LP liquidity isn't tracked on chain, so capacities are
+//! supplied by the caller, e.g. from the LPs' published limits.
+//!
+//! A [`WithdrawQuote`] shows what the receiver can expect from an LP and
+//! fills in `receive_min_amount` for `withdraw_by_lp`.
+
+use crate::btc::{estimate_peg_out_fee, script_hash_for_address, BtcAddress};
+use crate::types::{LPInfo, LPStatus, Sats, ScriptType, WithdrawByLPParams};
+use crate::BridgeClient;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+/// Requirements an LP must meet to serve a withdraw
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LPConstraints {
+    /// Highest acceptable fee rate, in basis points
+    pub max_fee_bps: Option<u64>,
+    /// Highest acceptable fee on the withdrawn amount
+    pub max_fee: Option<Sats>,
+    /// Known capacity per LP ID; LPs without an entry are assumed to have enough
+    pub capacities: HashMap<u64, Sats>,
+    /// LPs never to select
+    pub excluded: HashSet<u64>,
+}
+
+impl LPConstraints {
+    /// No requirements beyond the LP being active
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject LPs charging more than `max_fee_bps` basis points
+    pub fn with_max_fee_bps(mut self, max_fee_bps: u64) -> Self {
+        self.max_fee_bps = Some(max_fee_bps);
+        self
+    }
+
+    /// Reject LPs whose fee on the withdrawn amount exceeds `max_fee`
+    pub fn with_max_fee(mut self, max_fee: Sats) -> Self {
+        self.max_fee = Some(max_fee);
+        self
+    }
+
+    /// Set the BTC `lp_id` can pay out in one withdraw
+    pub fn with_capacity(mut self, lp_id: u64, capacity: Sats) -> Self {
+        self.capacities.insert(lp_id, capacity);
+        self
+    }
+
+    /// Never select `lp_id`
+    pub fn excluding(mut self, lp_id: u64) -> Self {
+        self.excluded.insert(lp_id);
+        self
+    }
+
+    /// Whether `lp` can serve a withdraw of `amount`
+    pub fn allows(&self, lp: &LPInfo, amount: Sats) -> bool {
+        lp.status == LPStatus::ACTIVE
+            && !self.excluded.contains(&lp.lp_id)
+            && self.max_fee_bps.is_none_or(|max| lp.lp_fee <= max)
+            && self.max_fee.is_none_or(|max| lp.fee_for(amount) <= max)
+            && self
+                .capacities
+                .get(&lp.lp_id)
+                .is_none_or(|capacity| *capacity >= amount)
+    }
+}
+
+/// Cheapest registered LP able to serve a withdraw of `amount`
+///
+/// LPs whose info can't be read or parsed are skipped with a warning, so one
+/// broken record doesn't block withdraws through the others.
+pub async fn select_lp(
+    client: &BridgeClient,
+    amount: Sats,
+    constraints: &LPConstraints,
+) -> Result<LPInfo> {
+    let lp_ids = client.get_lp_ids().await?;
+    let requests = lp_ids
+        .iter()
+        .map(|lp_id| client.lp_info_view_request(*lp_id))
+        .collect::<Result<Vec<_>>>()?;
+    let lps: Vec<LPInfo> = lp_ids
+        .iter()
+        .zip(client.view_many(&requests).await)
+        .filter_map(|(_lp_id, result)| {
+            match result.and_then(|response| LPInfo::from_view_response(&response)) {
+                Ok(lp) => Some(lp),
+                Err(_error) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(lp_id = _lp_id, error = %_error, "Skipping unreadable LP");
+                    None
+                }
+            }
+        })
+        .collect();
+    select_from(&lps, amount, constraints)
+}
+
+/// Cheapest LP in `lps` able to serve a withdraw of `amount`
+///
+/// Ties on the fee go to the LP with the largest known capacity, then to the
+/// lowest LP ID, so the choice is deterministic.
+pub fn select_from(lps: &[LPInfo], amount: Sats, constraints: &LPConstraints) -> Result<LPInfo> {
+    lps.iter()
+        .filter(|lp| constraints.allows(lp, amount))
+        .min_by_key(|lp| {
+            (
+                lp.lp_fee,
+                Reverse(constraints.capacities.get(&lp.lp_id).copied()),
+                lp.lp_id,
+            )
+        })
+        .cloned()
+        .ok_or_else(|| {
+            anyhow!(
+                "No active LP can serve a withdraw of {} ({} LPs checked)",
+                amount,
+                lps.len()
+            )
+        })
+}
+
+/// Expected amounts of a withdraw through an LP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WithdrawQuote {
+    /// Withdrawn amount
+    pub amount: Sats,
+    /// LP serving the withdraw
+    pub lp_id: u64,
+    /// BTC fee rate of the payout, in sat/vB
+    pub fee_rate: u64,
+    /// Fee the LP charges on `amount`
+    pub lp_fee: Sats,
+    /// Estimated fee of the LP's 1-input, 2-output payout transaction
+    pub network_fee_estimate: Sats,
+    /// `amount` less both fees: the least BTC the receiver accepts
+    pub receive_min_amount: Sats,
+}
+
+impl WithdrawQuote {
+    /// Quote a withdraw of `amount` through `lp`, paid out at `fee_rate` from
+    /// a `script_type` wallet
+    ///
+    /// Fails if the LP isn't active or the fees take the whole amount.
+    pub fn new(amount: Sats, lp: &LPInfo, fee_rate: u64, script_type: ScriptType) -> Result<Self> {
+        if lp.status != LPStatus::ACTIVE {
+            return Err(anyhow!("LP {} is {:?}", lp.lp_id, lp.status));
+        }
+        let lp_fee = lp.fee_for(amount);
+        let network_fee_estimate = estimate_peg_out_fee(script_type, fee_rate, 1, 2);
+        let receive_min_amount = amount
+            .checked_sub(lp_fee)
+            .and_then(|amount| amount.checked_sub(network_fee_estimate))
+            .filter(|amount| *amount > Sats::ZERO)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Fees of {} (LP) and {} (network) exceed the withdrawn {}",
+                    lp_fee,
+                    network_fee_estimate,
+                    amount
+                )
+            })?;
+        Ok(Self {
+            amount,
+            lp_id: lp.lp_id,
+            fee_rate,
+            lp_fee,
+            network_fee_estimate,
+            receive_min_amount,
+        })
+    }
+
+    /// `withdraw_by_lp` parameters paying the quoted amounts to `btc_address`
+    pub fn to_params(
+        &self,
+        withdraw_id: u64,
+        btc_address: BtcAddress,
+    ) -> Result<WithdrawByLPParams> {
+        let script_type = btc_address
+            .script_type()
+            .ok_or_else(|| anyhow!("Unsupported receiver address {}", btc_address))?;
+        Ok(WithdrawByLPParams {
+            withdraw_id,
+            receiver_script_hash: script_hash_for_address(&btc_address, script_type)?,
+            btc_address,
+            receive_min_amount: self.receive_min_amount,
+            lp_id: self.lp_id,
+            amount: self.amount,
+            fee_rate: self.fee_rate,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lp(lp_id: u64, lp_fee: u64, status: LPStatus) -> LPInfo {
+        LPInfo {
+            lp_id,
+            bitcoin_addr: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+            lp_addr: format!("0x{}", lp_id),
+            lp_fee,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_select_cheapest_viable_lp() {
+        let lps = [
+            lp(1, 30, LPStatus::ACTIVE),
+            lp(2, 10, LPStatus::SUSPENDED),
+            lp(3, 20, LPStatus::ACTIVE),
+            lp(4, 20, LPStatus::ACTIVE),
+            lp(5, 20, LPStatus::ACTIVE),
+        ];
+        let amount = Sats(1_000_000);
+
+        // Equal fees: the larger known capacity wins, then the lower ID
+        let constraints = LPConstraints::new().with_capacity(5, Sats(2_000_000));
+        assert_eq!(select_from(&lps, amount, &constraints).unwrap().lp_id, 5);
+        assert_eq!(
+            select_from(&lps, amount, &LPConstraints::new())
+                .unwrap()
+                .lp_id,
+            3
+        );
+
+        let constraints = LPConstraints::new()
+            .with_capacity(3, Sats(500_000))
+            .excluding(4)
+            .excluding(5);
+        assert_eq!(select_from(&lps, amount, &constraints).unwrap().lp_id, 1);
+
+        assert_eq!(lps[0].fee_for(amount), Sats(3_000));
+        let constraints = constraints.with_max_fee(Sats(2_999));
+        assert!(select_from(&lps, amount, &constraints).is_err());
+        let constraints = LPConstraints::new().with_max_fee_bps(15);
+        assert!(select_from(&lps, amount, &constraints).is_err());
+    }
+
+    #[test]
+    fn test_withdraw_quote() {
+        let quote = WithdrawQuote::new(
+            Sats(1_000_000),
+            &lp(1, 30, LPStatus::ACTIVE),
+            10,
+            ScriptType::P2WPKH,
+        )
+        .unwrap();
+        assert_eq!(quote.lp_fee, Sats(3_000));
+        // 1-in/2-out P2WPKH is 141 vbytes
+        assert_eq!(quote.network_fee_estimate, Sats(1_410));
+        assert_eq!(quote.receive_min_amount, Sats(995_590));
+
+        let address: BtcAddress = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+            .parse()
+            .unwrap();
+        let params = quote.to_params(7, address).unwrap();
+        assert_eq!(params.receive_min_amount, quote.receive_min_amount);
+        assert_eq!(params.receiver_script_hash.len(), 20);
+
+        let lp = lp(1, 30, LPStatus::ACTIVE);
+        assert!(WithdrawQuote::new(Sats(1_000), &lp, 10, ScriptType::P2WPKH).is_err());
+        let suspended = LPInfo {
+            status: LPStatus::SUSPENDED,
+            ..lp
+        };
+        assert!(WithdrawQuote::new(Sats(1_000_000), &suspended, 10, ScriptType::P2WPKH).is_err());
+    }
+}
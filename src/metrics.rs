@@ -0,0 +1,119 @@
+//! Prometheus metrics
+//!
+//! Available with the `metrics` feature. Metrics are collected into process-wide
+//! collectors; call [`register`] with the embedding service's registry to expose them.
+
+use anyhow::{anyhow, Result};
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry};
+use std::sync::LazyLock;
+
+/// Transactions successfully submitted, by entry function
+pub static TRANSACTIONS_SUBMITTED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "aptos_bridge_transactions_submitted_total",
+            "Transactions submitted to the Aptos node",
+        ),
+        &["function"],
+    )
+    .expect("valid metric definition")
+});
+
+/// Time from building to accepted submission, by entry function
+pub static SUBMISSION_LATENCY: LazyLock<HistogramVec> = LazyLock::new(|| {
+    HistogramVec::new(
+        HistogramOpts::new(
+            "aptos_bridge_transaction_submission_seconds",
+            "Latency of building, signing and submitting a transaction",
+        ),
+        &["function"],
+    )
+    .expect("valid metric definition")
+});
+
+/// Failed view function calls, by view function
+pub static VIEW_CALL_ERRORS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "aptos_bridge_view_call_errors_total",
+            "View function calls that failed",
+        ),
+        &["function"],
+    )
+    .expect("valid metric definition")
+});
+
+/// Bridge events dispatched to the handler, by event type
+pub static EVENTS_PROCESSED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "aptos_bridge_events_processed_total",
+            "Bridge events handled by the event monitor",
+        ),
+        &["event_type"],
+    )
+    .expect("valid metric definition")
+});
+
+/// Event handler errors, by event type
+pub static HANDLER_FAILURES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "aptos_bridge_handler_failures_total",
+            "Event handler invocations that returned an error",
+        ),
+        &["event_type"],
+    )
+    .expect("valid metric definition")
+});
+
+/// Ledger versions between the node tip and the monitor cursor
+pub static INDEXER_LAG: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "aptos_bridge_indexer_lag_versions",
+        "Ledger versions between the node tip and the last processed event version",
+    )
+    .expect("valid metric definition")
+});
+
+/// Blocks between bitcoind's tip and the light client, as seen by header sync
+pub static LIGHT_CLIENT_LAG: LazyLock<IntGauge> = LazyLock::new(|| {
+    IntGauge::new(
+        "aptos_bridge_light_client_lag_blocks",
+        "BTC blocks the light client is behind the header source",
+    )
+    .expect("valid metric definition")
+});
+
+/// Register all SDK metrics with the given registry
+pub fn register(registry: &Registry) -> Result<()> {
+    registry
+        .register(Box::new(TRANSACTIONS_SUBMITTED.clone()))
+        .and_then(|_| registry.register(Box::new(SUBMISSION_LATENCY.clone())))
+        .and_then(|_| registry.register(Box::new(VIEW_CALL_ERRORS.clone())))
+        .and_then(|_| registry.register(Box::new(EVENTS_PROCESSED.clone())))
+        .and_then(|_| registry.register(Box::new(HANDLER_FAILURES.clone())))
+        .and_then(|_| registry.register(Box::new(INDEXER_LAG.clone())))
+        .and_then(|_| registry.register(Box::new(LIGHT_CLIENT_LAG.clone())))
+        .map_err(|e| anyhow!("Failed to register bridge metrics: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register() {
+        let registry = Registry::new();
+        assert!(register(&registry).is_ok());
+
+        EVENTS_PROCESSED.with_label_values(&["mint"]).inc();
+        let families = registry.gather();
+        assert!(families
+            .iter()
+            .any(|f| f.get_name() == "aptos_bridge_events_processed_total"));
+
+        // Registering twice with the same registry is rejected
+        assert!(register(&registry).is_err());
+    }
+}
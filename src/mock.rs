@@ -0,0 +1,527 @@
+//! In-memory bridge client for tests
+//!
+//! [`MockBridgeClient`] implements [`BridgeApi`] and [`BridgeQueryApi`] against
+//! programmable in-memory state and records every call, so application logic
+//! written against the traits can be unit-tested without a node. Enabled by the
+//! `test-utils` feature.
+
+use crate::api::{BridgeApi, BridgeQueryApi};
+use crate::btc::BtcAddress;
+use crate::types::{
+    AlreadyMinted, BridgeEvent, ClaimLPWithdrawParams, LPInfo, LPStatus, LPWithdraw, Peg,
+    RegisterLPParams, Sats, WithdrawByLPParams, WithdrawStatus,
+};
+use anyhow::{anyhow, Result};
+use aptos_sdk::rest_client::aptos_api_types::TransactionData;
+use aptos_sdk::types::account_address::AccountAddress;
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Call recorded by [`MockBridgeClient`]
+#[derive(Debug, Clone)]
+pub enum MockCall {
+    GetLedgerVersion,
+    GetTransactionByHash(String),
+    WaitForTransaction(String),
+    GetTxHashByVersion(u64),
+    GetBridgeEventsByHash(String),
+    Mint(Peg),
+    Burn {
+        btc_address: BtcAddress,
+        fee_rate: u64,
+        amount: Sats,
+        operator_id: u64,
+    },
+    WithdrawByLP(WithdrawByLPParams),
+    ClaimLPWithdraw(ClaimLPWithdrawParams),
+    RegisterLP(RegisterLPParams),
+    SubmitBlockHeaders {
+        start_height: u64,
+        headers: Vec<Vec<u8>>,
+    },
+    IsPegMinted {
+        tx_id: Vec<u8>,
+        tx_out_ix: u64,
+    },
+    GetMinConfirmations,
+    GetLatestBlockHeight,
+    GetBlockHash(u64),
+    GetLPWithdraw(u64),
+    GetWithdrawStatus(u64),
+    GetLPStatus(u64),
+    GetLPInfo(u64),
+    FindLPByAddress(String),
+    GetBtcPegBalance(String),
+}
+
+impl MockCall {
+    /// Name of the trait method that was called
+    pub fn method(&self) -> &'static str {
+        match self {
+            MockCall::GetLedgerVersion => "get_ledger_version",
+            MockCall::GetTransactionByHash(_) => "get_transaction_by_hash",
+            MockCall::WaitForTransaction(_) => "wait_for_transaction",
+            MockCall::GetTxHashByVersion(_) => "get_tx_hash_by_version",
+            MockCall::GetBridgeEventsByHash(_) => "get_bridge_events_by_hash",
+            MockCall::Mint(_) => "mint",
+            MockCall::Burn { .. } => "burn",
+            MockCall::WithdrawByLP(_) => "withdraw_by_lp",
+            MockCall::ClaimLPWithdraw(_) => "claim_lp_withdraw",
+            MockCall::RegisterLP(_) => "register_lp",
+            MockCall::SubmitBlockHeaders { .. } => "submit_block_headers",
+            MockCall::IsPegMinted { .. } => "is_peg_minted",
+            MockCall::GetMinConfirmations => "get_min_confirmations",
+            MockCall::GetLatestBlockHeight => "get_latest_block_height",
+            MockCall::GetBlockHash(_) => "get_block_hash",
+            MockCall::GetLPWithdraw(_) => "get_lp_withdraw",
+            MockCall::GetWithdrawStatus(_) => "get_withdraw_status",
+            MockCall::GetLPStatus(_) => "get_lp_status",
+            MockCall::GetLPInfo(_) => "get_lp_info",
+            MockCall::FindLPByAddress(_) => "find_lp_by_address",
+            MockCall::GetBtcPegBalance(_) => "get_btc_peg_balance",
+        }
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    calls: Vec<MockCall>,
+    failures: HashMap<String, VecDeque<String>>,
+    next_tx: u64,
+    ledger_version: u64,
+    transactions: HashMap<String, TransactionData>,
+    tx_hashes_by_version: HashMap<u64, String>,
+    #[cfg_attr(not(feature = "events-rest"), allow(dead_code))]
+    bridge_events: HashMap<String, Vec<BridgeEvent>>,
+    minted: HashSet<(Vec<u8>, u64)>,
+    min_confirmations: u64,
+    latest_block_height: u64,
+    block_hashes: HashMap<u64, Vec<u8>>,
+    lp_withdraws: HashMap<u64, LPWithdraw>,
+    withdraw_statuses: HashMap<u64, WithdrawStatus>,
+    lp_statuses: HashMap<u64, LPStatus>,
+    lp_infos: HashMap<u64, LPInfo>,
+    balances: HashMap<String, Sats>,
+}
+
+impl MockState {
+    fn next_tx_hash(&mut self) -> String {
+        self.next_tx += 1;
+        format!("0x{:064x}", self.next_tx)
+    }
+}
+
+/// In-memory [`BridgeApi`] with programmable responses and call recording
+///
+/// Submissions return generated transaction hashes. `mint` marks the peg as
+/// minted and, like `BridgeClient::mint`, refuses it with `AlreadyMinted` when it
+/// already is;
+/// `submit_block_headers` advances the light client height. Views return what
+/// was set with the `set_*` methods, or an error when nothing was set.
+pub struct MockBridgeClient {
+    bridge_contract_address: AccountAddress,
+    state: Mutex<MockState>,
+}
+
+impl Default for MockBridgeClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockBridgeClient {
+    /// Create a mock with bridge contract address `0x1`
+    pub fn new() -> Self {
+        Self {
+            bridge_contract_address: AccountAddress::ONE,
+            state: Mutex::new(MockState::default()),
+        }
+    }
+
+    /// Set the bridge contract address
+    pub fn with_bridge_contract_address(mut self, address: AccountAddress) -> Self {
+        self.bridge_contract_address = address;
+        self
+    }
+
+    /// Calls made so far, oldest first
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state().calls.clone()
+    }
+
+    /// Calls made so far to `method`
+    pub fn calls_to(&self, method: &str) -> Vec<MockCall> {
+        self.state()
+            .calls
+            .iter()
+            .filter(|call| call.method() == method)
+            .cloned()
+            .collect()
+    }
+
+    /// Forget the recorded calls
+    pub fn clear_calls(&self) {
+        self.state().calls.clear();
+    }
+
+    /// Make the next call to `method` fail with `message`
+    ///
+    /// Failures queue up: calling this twice fails the next two calls.
+    pub fn fail_next(&self, method: &str, message: &str) {
+        self.state()
+            .failures
+            .entry(method.to_string())
+            .or_default()
+            .push_back(message.to_string());
+    }
+
+    /// Set the ledger version
+    pub fn set_ledger_version(&self, version: u64) {
+        self.state().ledger_version = version;
+    }
+
+    /// Return `transaction` for `tx_hash`
+    pub fn insert_transaction(&self, tx_hash: &str, transaction: TransactionData) {
+        self.state()
+            .transactions
+            .insert(tx_hash.to_string(), transaction);
+    }
+
+    /// Return `tx_hash` for `version`
+    pub fn set_tx_hash_by_version(&self, version: u64, tx_hash: &str) {
+        self.state()
+            .tx_hashes_by_version
+            .insert(version, tx_hash.to_string());
+    }
+
+    /// Return `events` for `tx_hash`
+    pub fn set_bridge_events(&self, tx_hash: &str, events: Vec<BridgeEvent>) {
+        self.state()
+            .bridge_events
+            .insert(tx_hash.to_string(), events);
+    }
+
+    /// Mark a BTC output as minted
+    pub fn set_peg_minted(&self, tx_id: &[u8], tx_out_ix: u64) {
+        self.state().minted.insert((tx_id.to_vec(), tx_out_ix));
+    }
+
+    /// Set the minimum confirmations
+    pub fn set_min_confirmations(&self, min_confirmations: u64) {
+        self.state().min_confirmations = min_confirmations;
+    }
+
+    /// Set the light client height
+    pub fn set_latest_block_height(&self, height: u64) {
+        self.state().latest_block_height = height;
+    }
+
+    /// Set the light client's block hash at `height`
+    pub fn set_block_hash(&self, height: u64, hash: Vec<u8>) {
+        self.state().block_hashes.insert(height, hash);
+    }
+
+    /// Return `withdraw` for its id
+    pub fn set_lp_withdraw(&self, withdraw: LPWithdraw) {
+        self.state().lp_withdraws.insert(withdraw.id, withdraw);
+    }
+
+    /// Set the status of an LP withdraw (default `PENDING`)
+    pub fn set_withdraw_status(&self, withdraw_id: u64, status: WithdrawStatus) {
+        self.state().withdraw_statuses.insert(withdraw_id, status);
+    }
+
+    /// Set the status of an LP
+    pub fn set_lp_status(&self, lp_id: u64, status: LPStatus) {
+        self.state().lp_statuses.insert(lp_id, status);
+    }
+
+    /// Return `info` for its LP id and Aptos address
+    pub fn set_lp_info(&self, info: LPInfo) {
+        self.state().lp_infos.insert(info.lp_id, info);
+    }
+
+    /// Set the BTC peg balance of `address`
+    pub fn set_btc_peg_balance(&self, address: &str, balance: Sats) {
+        self.state().balances.insert(address.to_string(), balance);
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record `call` and return the state, or the programmed failure
+    fn record(&self, call: MockCall) -> Result<MutexGuard<'_, MockState>> {
+        let mut state = self.state();
+        let method = call.method();
+        state.calls.push(call);
+        match state.failures.get_mut(method).and_then(VecDeque::pop_front) {
+            Some(message) => Err(anyhow!("{}", message)),
+            None => Ok(state),
+        }
+    }
+
+    /// Record a submission and return a generated transaction hash
+    fn submit(&self, call: MockCall) -> Result<String> {
+        Ok(self.record(call)?.next_tx_hash())
+    }
+}
+
+#[async_trait]
+impl BridgeQueryApi for MockBridgeClient {
+    async fn get_ledger_version(&self) -> Result<u64> {
+        Ok(self.record(MockCall::GetLedgerVersion)?.ledger_version)
+    }
+
+    async fn get_transaction_by_hash(&self, tx_hash: &str) -> Result<TransactionData> {
+        self.record(MockCall::GetTransactionByHash(tx_hash.to_string()))?
+            .transactions
+            .get(tx_hash)
+            .cloned()
+            .ok_or_else(|| anyhow!("MockBridgeClient: no transaction {}", tx_hash))
+    }
+
+    async fn wait_for_transaction(
+        &self,
+        tx_hash: &str,
+        timeout: Duration,
+    ) -> Result<TransactionData> {
+        self.record(MockCall::WaitForTransaction(tx_hash.to_string()))?
+            .transactions
+            .get(tx_hash)
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!(
+                    "Transaction {} was not committed within {:?}",
+                    tx_hash,
+                    timeout
+                )
+            })
+    }
+
+    async fn get_tx_hash_by_version(&self, version: u64) -> Result<String> {
+        self.record(MockCall::GetTxHashByVersion(version))?
+            .tx_hashes_by_version
+            .get(&version)
+            .cloned()
+            .ok_or_else(|| anyhow!("MockBridgeClient: no transaction at version {}", version))
+    }
+
+    #[cfg(feature = "events-rest")]
+    async fn get_bridge_events_by_hash(
+        &self,
+        tx_hash: &str,
+        _bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEvent>> {
+        Ok(self
+            .record(MockCall::GetBridgeEventsByHash(tx_hash.to_string()))?
+            .bridge_events
+            .get(tx_hash)
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl BridgeApi for MockBridgeClient {
+    fn bridge_contract_address(&self) -> AccountAddress {
+        self.bridge_contract_address
+    }
+
+    async fn mint(&self, peg: Peg) -> Result<String> {
+        let key = (peg.inclusion_proof.tx_id.clone(), peg.tx_out_ix);
+        let mut state = self.record(MockCall::Mint(peg))?;
+        if !state.minted.insert(key.clone()) {
+            return Err(AlreadyMinted {
+                tx_id: key.0,
+                tx_out_ix: key.1,
+            }
+            .into());
+        }
+        Ok(state.next_tx_hash())
+    }
+
+    async fn burn(
+        &self,
+        btc_address: BtcAddress,
+        fee_rate: u64,
+        amount: Sats,
+        operator_id: u64,
+    ) -> Result<String> {
+        self.submit(MockCall::Burn {
+            btc_address,
+            fee_rate,
+            amount,
+            operator_id,
+        })
+    }
+
+    async fn withdraw_by_lp(&self, params: WithdrawByLPParams) -> Result<String> {
+        self.submit(MockCall::WithdrawByLP(params))
+    }
+
+    async fn claim_lp_withdraw(&self, params: ClaimLPWithdrawParams) -> Result<String> {
+        self.submit(MockCall::ClaimLPWithdraw(params))
+    }
+
+    async fn register_lp(&self, params: RegisterLPParams) -> Result<String> {
+        self.submit(MockCall::RegisterLP(params))
+    }
+
+    async fn submit_block_headers(&self, start_height: u64, headers: &[Vec<u8>]) -> Result<String> {
+        let tx_hash = self.submit(MockCall::SubmitBlockHeaders {
+            start_height,
+            headers: headers.to_vec(),
+        })?;
+        if !headers.is_empty() {
+            let mut state = self.state();
+            let tip = start_height + headers.len() as u64 - 1;
+            state.latest_block_height = state.latest_block_height.max(tip);
+        }
+        Ok(tx_hash)
+    }
+
+    async fn is_peg_minted(&self, tx_id: &[u8], tx_out_ix: u64) -> Result<bool> {
+        let state = self.record(MockCall::IsPegMinted {
+            tx_id: tx_id.to_vec(),
+            tx_out_ix,
+        })?;
+        Ok(state.minted.contains(&(tx_id.to_vec(), tx_out_ix)))
+    }
+
+    async fn get_min_confirmations(&self) -> Result<u64> {
+        Ok(self
+            .record(MockCall::GetMinConfirmations)?
+            .min_confirmations)
+    }
+
+    async fn get_latest_block_height(&self) -> Result<u64> {
+        Ok(self
+            .record(MockCall::GetLatestBlockHeight)?
+            .latest_block_height)
+    }
+
+    async fn get_block_hash(&self, height: u64) -> Result<Vec<u8>> {
+        self.record(MockCall::GetBlockHash(height))?
+            .block_hashes
+            .get(&height)
+            .cloned()
+            .ok_or_else(|| anyhow!("MockBridgeClient: no block hash at height {}", height))
+    }
+
+    async fn get_lp_withdraw(&self, withdraw_id: u64) -> Result<LPWithdraw> {
+        self.record(MockCall::GetLPWithdraw(withdraw_id))?
+            .lp_withdraws
+            .get(&withdraw_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("MockBridgeClient: no LP withdraw {}", withdraw_id))
+    }
+
+    async fn get_withdraw_status(&self, withdraw_id: u64) -> Result<WithdrawStatus> {
+        Ok(self
+            .record(MockCall::GetWithdrawStatus(withdraw_id))?
+            .withdraw_statuses
+            .get(&withdraw_id)
+            .copied()
+            .unwrap_or(WithdrawStatus::PENDING))
+    }
+
+    async fn get_lp_status(&self, lp_id: u64) -> Result<LPStatus> {
+        Ok(self
+            .record(MockCall::GetLPStatus(lp_id))?
+            .lp_statuses
+            .get(&lp_id)
+            .copied()
+            .unwrap_or(LPStatus::UNREGISTERED))
+    }
+
+    async fn get_lp_info(&self, lp_id: u64) -> Result<LPInfo> {
+        self.record(MockCall::GetLPInfo(lp_id))?
+            .lp_infos
+            .get(&lp_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("MockBridgeClient: no LP {}", lp_id))
+    }
+
+    async fn find_lp_by_address(&self, aptos_addr: &str) -> Result<Option<LPInfo>> {
+        Ok(self
+            .record(MockCall::FindLPByAddress(aptos_addr.to_string()))?
+            .lp_infos
+            .values()
+            .find(|info| info.lp_addr == aptos_addr)
+            .cloned())
+    }
+
+    async fn get_btc_peg_balance(&self, address: &str) -> Result<Sats> {
+        Ok(self
+            .record(MockCall::GetBtcPegBalance(address.to_string()))?
+            .balances
+            .get(address)
+            .copied()
+            .unwrap_or(Sats(0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ScriptType, TxProof};
+
+    fn peg() -> Peg {
+        Peg {
+            to: "0x2".to_string(),
+            value: Sats(50_000),
+            block_num: 100,
+            inclusion_proof: TxProof {
+                block_header: vec![0; 80],
+                tx_id: vec![7; 32],
+                tx_index: 0,
+                merkle_proof: vec![],
+                raw_tx: vec![],
+            },
+            tx_out_ix: 1,
+            dest_script_hash: vec![1; 32],
+            script_type: ScriptType::P2WPKH,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_mint_records_and_rejects_duplicates() {
+        let mock = MockBridgeClient::new();
+        let client: &dyn BridgeApi = &mock;
+
+        assert!(!client.is_peg_minted(&[7; 32], 1).await.unwrap());
+        let tx_hash = client.mint(peg()).await.unwrap();
+        assert_eq!(tx_hash.len(), 66);
+        assert!(client.is_peg_minted(&[7; 32], 1).await.unwrap());
+
+        let err = client.mint(peg()).await.unwrap_err();
+        let already_minted = err.downcast_ref::<AlreadyMinted>().unwrap();
+        assert_eq!(already_minted.tx_out_ix, 1);
+
+        assert_eq!(mock.calls_to("mint").len(), 2);
+        assert_eq!(mock.calls().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_mock_programmed_state_and_failures() {
+        let mock = MockBridgeClient::new();
+        mock.set_latest_block_height(100);
+        mock.set_lp_status(3, LPStatus::ACTIVE);
+        mock.fail_next("get_latest_block_height", "node unavailable");
+
+        assert!(mock.get_latest_block_height().await.is_err());
+        assert_eq!(mock.get_latest_block_height().await.unwrap(), 100);
+
+        mock.submit_block_headers(101, &[vec![0; 80], vec![0; 80]])
+            .await
+            .unwrap();
+        assert_eq!(mock.get_latest_block_height().await.unwrap(), 102);
+
+        assert_eq!(mock.get_lp_status(3).await.unwrap(), LPStatus::ACTIVE);
+        assert_eq!(mock.get_lp_status(4).await.unwrap(), LPStatus::UNREGISTERED);
+        assert!(mock.get_block_hash(100).await.is_err());
+    }
+}
@@ -0,0 +1,141 @@
+//! Network presets
+//!
+//! Endpoints and contract addresses of the public bridge deployments, so clients
+//! and services don't have to hard-code them.
+
+use crate::btc::BtcNetwork;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Aptos network with a public bridge deployment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Devnet,
+}
+
+/// Well-known settings of a network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkConfig {
+    /// Aptos fullnode REST URL
+    pub fullnode_url: &'static str,
+    /// Aptos indexer GraphQL URL
+    pub indexer_url: &'static str,
+    /// Bridge contract address, if the bridge is deployed
+    pub bridge_contract_address: Option<&'static str>,
+    /// BTC light client address, if deployed
+    pub btc_light_client: Option<&'static str>,
+    /// Bitcoin network the bridge is pegged to
+    pub btc_network: BtcNetwork,
+    /// Aptos chain id, if stable (devnet's changes on every reset)
+    pub chain_id: Option<u8>,
+    /// APT faucet URL, if the network has one
+    pub faucet_url: Option<&'static str>,
+}
+
+/// Aptos mainnet
+pub const MAINNET: NetworkConfig = NetworkConfig {
+    fullnode_url: "https://fullnode.mainnet.aptoslabs.com/v1",
+    indexer_url: "https://api.mainnet.aptoslabs.com/v1/graphql",
+    bridge_contract_address: Some(
+        "0x094ff62f3aca82c9f6cb5f80d987f18ea834692425f97aaa4bdf8f73c3c0a7ee",
+    ),
+    btc_light_client: None,
+    btc_network: BtcNetwork::Mainnet,
+    chain_id: Some(1),
+    faucet_url: None,
+};
+
+/// Aptos testnet, pegged to a regtest Bitcoin chain
+pub const TESTNET: NetworkConfig = NetworkConfig {
+    fullnode_url: "https://fullnode.testnet.aptoslabs.com/v1",
+    indexer_url: "https://api.testnet.aptoslabs.com/v1/graphql",
+    bridge_contract_address: Some(
+        "0xeed4b8e27b6bd68e902e0e20633814d0d6d1a1c096763507fcaf058854a5b9b4",
+    ),
+    btc_light_client: Some("0x749e2800973809a39eb72ed6e38f154151cef1213b2e72e031ad86875bbc051a"),
+    btc_network: BtcNetwork::Regtest,
+    chain_id: Some(2),
+    faucet_url: Some("https://faucet.testnet.aptoslabs.com"),
+};
+
+/// Aptos devnet
+pub const DEVNET: NetworkConfig = NetworkConfig {
+    fullnode_url: "https://fullnode.devnet.aptoslabs.com/v1",
+    indexer_url: "https://api.devnet.aptoslabs.com/v1/graphql",
+    bridge_contract_address: None,
+    btc_light_client: None,
+    btc_network: BtcNetwork::Regtest,
+    chain_id: None,
+    faucet_url: Some("https://faucet.devnet.aptoslabs.com"),
+};
+
+impl Network {
+    /// Settings of this network
+    pub fn config(self) -> &'static NetworkConfig {
+        match self {
+            Network::Mainnet => &MAINNET,
+            Network::Testnet => &TESTNET,
+            Network::Devnet => &DEVNET,
+        }
+    }
+
+    /// Bridge contract address, or an error if the network has no bridge
+    pub fn bridge_contract_address(self) -> Result<&'static str> {
+        self.config()
+            .bridge_contract_address
+            .ok_or_else(|| anyhow!("No bridge is deployed on the {} Aptos network", self))
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Testnet => write!(f, "testnet"),
+            Network::Devnet => write!(f, "devnet"),
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            "devnet" => Ok(Network::Devnet),
+            _ => Err(anyhow!(
+                "Invalid Aptos network '{}'. Valid networks are: mainnet, testnet, devnet",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::parse_account_address;
+
+    #[test]
+    fn test_network_presets() {
+        for network in [Network::Mainnet, Network::Testnet, Network::Devnet] {
+            let config = network.config();
+            assert_eq!(network.to_string().parse::<Network>().unwrap(), network);
+            assert!(config.fullnode_url.contains(&network.to_string()));
+            for address in [config.bridge_contract_address, config.btc_light_client]
+                .into_iter()
+                .flatten()
+            {
+                assert!(parse_account_address(address).is_ok());
+            }
+        }
+        assert!(Network::Devnet.bridge_contract_address().is_err());
+        assert!("localnet".parse::<Network>().is_err());
+    }
+}
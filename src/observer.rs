@@ -0,0 +1,210 @@
+//! Transaction lifecycle observers
+//!
+//! A [`TxObserver`] added via [`BridgeClient::with_observer`](crate::BridgeClient::with_observer)
+//! is called when each transaction is signed, accepted or rejected by the node,
+//! and once it commits, so metrics, audit trails and progress indicators don't
+//! need to wrap every submitting method.
+
+use crate::types::constants::TRANSACTION_COMMIT_TIMEOUT_SECS;
+use crate::QueryClient;
+use aptos_sdk::rest_client::aptos_api_types::TransactionData;
+use aptos_sdk::types::transaction::{SignedTransaction, TransactionPayload};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Transaction an observer is called about
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxMetadata {
+    /// Entry function, `address::module::function`
+    pub function: String,
+    /// Sender address
+    pub sender: String,
+    /// Sender sequence number
+    pub sequence_number: u64,
+    /// Maximum gas units the transaction may use
+    pub max_gas_amount: u64,
+    /// Price per gas unit in octas
+    pub gas_unit_price: u64,
+    /// Seconds since the Unix epoch after which it can no longer commit
+    pub expiration_timestamp_secs: u64,
+    /// Transaction hash
+    pub tx_hash: String,
+}
+
+impl TxMetadata {
+    /// Metadata of a signed transaction
+    pub fn new(signed_transaction: &SignedTransaction) -> Self {
+        let function = match signed_transaction.payload() {
+            TransactionPayload::EntryFunction(entry_function) => format!(
+                "{}::{}::{}",
+                entry_function.module().address().to_hex_literal(),
+                entry_function.module().name(),
+                entry_function.function()
+            ),
+            _ => "unknown".to_string(),
+        };
+        Self {
+            function,
+            sender: signed_transaction.sender().to_hex_literal(),
+            sequence_number: signed_transaction.sequence_number(),
+            max_gas_amount: signed_transaction.max_gas_amount(),
+            gas_unit_price: signed_transaction.gas_unit_price(),
+            expiration_timestamp_secs: signed_transaction.expiration_timestamp_secs(),
+            tx_hash: signed_transaction.committed_hash().to_hex_literal(),
+        }
+    }
+}
+
+/// Called at each stage of a submitted transaction's lifecycle
+///
+/// Hooks can't fail the transaction: it may already be on its way. All hooks
+/// default to doing nothing.
+#[async_trait]
+pub trait TxObserver: Send + Sync {
+    /// Called after the transaction is signed, before it's submitted
+    async fn on_built(&self, _tx: &TxMetadata) {}
+
+    /// Called once the node accepts the transaction
+    async fn on_submitted(&self, _tx: &TxMetadata) {}
+
+    /// Called once the transaction is on chain, whether or not it succeeded
+    async fn on_committed(&self, _tx: &TxMetadata, _version: u64, _success: bool) {}
+
+    /// Called if the node rejects the transaction, or it isn't seen on chain
+    /// within the commit timeout
+    async fn on_failed(&self, _tx: &TxMetadata, _error: &str) {}
+}
+
+/// Notify `observers` that `signed_transaction` was signed
+pub(crate) async fn notify_built(
+    observers: &[Arc<dyn TxObserver>],
+    signed_transaction: &SignedTransaction,
+) {
+    if observers.is_empty() {
+        return;
+    }
+    let tx = TxMetadata::new(signed_transaction);
+    for observer in observers {
+        observer.on_built(&tx).await;
+    }
+}
+
+/// Notify `observers` of a submission attempt and, once it's accepted, of its
+/// commitment
+///
+/// Commitment is awaited on a spawned task so submission isn't delayed.
+pub(crate) async fn notify_submission(
+    observers: &[Arc<dyn TxObserver>],
+    query_client: &QueryClient,
+    signed_transaction: &SignedTransaction,
+    submit_error: Option<&anyhow::Error>,
+) {
+    if observers.is_empty() {
+        return;
+    }
+    let tx = TxMetadata::new(signed_transaction);
+    if let Some(e) = submit_error {
+        for observer in observers {
+            observer.on_failed(&tx, &e.to_string()).await;
+        }
+        return;
+    }
+    for observer in observers {
+        observer.on_submitted(&tx).await;
+    }
+
+    let observers = observers.to_vec();
+    let query_client = query_client.clone();
+    tokio::spawn(async move {
+        let outcome = match query_client
+            .wait_for_transaction(
+                &tx.tx_hash,
+                Duration::from_secs(TRANSACTION_COMMIT_TIMEOUT_SECS),
+            )
+            .await
+        {
+            Ok(TransactionData::OnChain(txn)) => Ok((txn.version, txn.info.status().is_success())),
+            Ok(TransactionData::Pending(_)) => Err("Transaction is still pending".to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        for observer in &observers {
+            match &outcome {
+                Ok((version, success)) => observer.on_committed(&tx, *version, *success).await,
+                Err(error) => observer.on_failed(&tx, error).await,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::account_from_private_key;
+    use aptos_sdk::types::chain_id::ChainId;
+    use aptos_sdk::types::transaction::{RawTransaction, Script};
+    use secrecy::SecretString;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl TxObserver for Recorder {
+        async fn on_built(&self, tx: &TxMetadata) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("built {}", tx.function));
+        }
+
+        async fn on_failed(&self, tx: &TxMetadata, error: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("failed {} {}", tx.sequence_number, error));
+        }
+    }
+
+    fn signed_transaction() -> SignedTransaction {
+        let account = account_from_private_key(&SecretString::from(
+            "0x1111111111111111111111111111111111111111111111111111111111111111",
+        ))
+        .unwrap();
+        account.sign_transaction(RawTransaction::new(
+            account.address(),
+            7,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            1_000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_observers_notified_of_build_and_rejection() {
+        let recorder = Arc::new(Recorder::default());
+        let observers: Vec<Arc<dyn TxObserver>> = vec![recorder.clone()];
+        let signed_transaction = signed_transaction();
+        let query_client = QueryClient::new("http://localhost:8080/v1", None).unwrap();
+
+        notify_built(&observers, &signed_transaction).await;
+        notify_submission(
+            &observers,
+            &query_client,
+            &signed_transaction,
+            Some(&anyhow::anyhow!("mempool is full")),
+        )
+        .await;
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![
+                "built unknown".to_string(),
+                "failed 7 mempool is full".to_string()
+            ]
+        );
+    }
+}
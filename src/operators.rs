@@ -0,0 +1,103 @@
+//! Burn operator selection
+//!
+//! Operators are read from the bridge's `get_operator_ids` and
+//! `get_operator_info` views. Pending burn counts aren't tracked on chain, so
+//! callers that watch the queues (e.g. from `Burn` events) pass them in
+//! [`OperatorPreferences`] to spread burns across operators.
+
+use crate::types::OperatorInfo;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Hints for choosing a burn operator
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperatorPreferences {
+    /// Pending burns per operator ID; operators without an entry count as idle
+    pub queue_lengths: HashMap<u64, u64>,
+    /// Operators never to select
+    pub excluded: HashSet<u64>,
+}
+
+impl OperatorPreferences {
+    /// No hints: the active operator with the lowest ID is selected
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of burns `operator_id` has yet to pay out
+    pub fn with_queue_length(mut self, operator_id: u64, pending: u64) -> Self {
+        self.queue_lengths.insert(operator_id, pending);
+        self
+    }
+
+    /// Never select `operator_id`
+    pub fn excluding(mut self, operator_id: u64) -> Self {
+        self.excluded.insert(operator_id);
+        self
+    }
+}
+
+/// Burn submitted by `BridgeClient::burn_auto`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoBurn {
+    /// Burn transaction hash
+    pub tx_hash: String,
+    /// Operator the burn was assigned to
+    pub operator_id: u64,
+    /// BTC fee rate of the payout, in sat/vB
+    pub fee_rate: u64,
+}
+
+/// Active operator with the shortest queue, ties going to the lowest ID
+pub fn select_operator(
+    operators: &[OperatorInfo],
+    preferences: &OperatorPreferences,
+) -> Result<OperatorInfo> {
+    operators
+        .iter()
+        .filter(|operator| {
+            operator.is_active && !preferences.excluded.contains(&operator.operator_id)
+        })
+        .min_by_key(|operator| {
+            (
+                preferences
+                    .queue_lengths
+                    .get(&operator.operator_id)
+                    .copied()
+                    .unwrap_or(0),
+                operator.operator_id,
+            )
+        })
+        .cloned()
+        .ok_or_else(|| anyhow!("No active burn operator ({} registered)", operators.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operator(operator_id: u64, is_active: bool) -> OperatorInfo {
+        OperatorInfo {
+            operator_id,
+            operator_addr: format!("0x{}", operator_id),
+            btc_addr: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+            is_active,
+        }
+    }
+
+    #[test]
+    fn test_select_operator() {
+        let operators = [operator(1, false), operator(2, true), operator(3, true)];
+        let select = |preferences: &OperatorPreferences| {
+            select_operator(&operators, preferences).map(|operator| operator.operator_id)
+        };
+
+        assert_eq!(select(&OperatorPreferences::new()).unwrap(), 2);
+        let busy = OperatorPreferences::new().with_queue_length(2, 5);
+        assert_eq!(select(&busy).unwrap(), 3);
+        assert_eq!(select(&busy.clone().with_queue_length(3, 9)).unwrap(), 2);
+        let preferences = OperatorPreferences::new().excluding(2).excluding(3);
+        assert!(select(&preferences).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+//! Transaction payload builders
+//!
+//! [`PayloadBuilder`] produces the entry-function payloads [`BridgeClient`](crate::BridgeClient)
+//! submits, without a node connection or signer, so wallets (including WASM
+//! builds in the browser) can construct bridge transactions, sign them with their
+//! own key management and inspect the BCS arguments.
+
+use crate::btc::BtcAddress;
+use crate::contract::{ContractIds, ContractModule, ContractSpec};
+use crate::types::{ClaimLPWithdrawParams, Peg, RegisterLPParams, Sats, WithdrawByLPParams};
+use anyhow::{anyhow, Result};
+use aptos_sdk::move_types::language_storage::{ModuleId, TypeTag};
+use aptos_sdk::types::account_address::AccountAddress;
+use aptos_sdk::types::transaction::{EntryFunction, TransactionPayload};
+
+/// Builder of bridge entry-function payloads for one deployment
+#[derive(Debug, Clone)]
+pub struct PayloadBuilder {
+    /// Bridge contract address
+    bridge_contract_address: AccountAddress,
+    /// BTC light client address
+    btc_light_client: Option<AccountAddress>,
+    /// Module and function identifiers of the deployment
+    contract_ids: ContractIds,
+}
+
+impl PayloadBuilder {
+    /// Payload builder for the bridge at `bridge_contract_address`
+    pub fn new(bridge_contract_address: AccountAddress) -> Self {
+        Self {
+            bridge_contract_address,
+            btc_light_client: None,
+            contract_ids: ContractIds::default(),
+        }
+    }
+
+    /// Set the BTC light client address, required for header submissions
+    pub fn with_light_client(mut self, btc_light_client: AccountAddress) -> Self {
+        self.btc_light_client = Some(btc_light_client);
+        self
+    }
+
+    /// Set the module and function names of a forked or renamed deployment
+    ///
+    /// Fails if a name is not a valid Move identifier.
+    pub fn with_contract_spec(mut self, contract_spec: ContractSpec) -> Result<Self> {
+        self.contract_ids = ContractIds::new(contract_spec)?;
+        Ok(self)
+    }
+
+    /// Use identifiers already validated by a client
+    pub(crate) fn with_contract_ids(mut self, contract_ids: ContractIds) -> Self {
+        self.contract_ids = contract_ids;
+        self
+    }
+
+    /// `bridge::mint` payload
+    pub fn mint(&self, peg: &Peg, type_args: Vec<TypeTag>) -> Result<TransactionPayload> {
+        self.entry_function(
+            self.bridge_contract_address,
+            ContractModule::Bridge,
+            "mint",
+            type_args,
+            peg.to_bcs_args()?,
+        )
+    }
+
+    /// `bridge::burn` payload
+    pub fn burn(
+        &self,
+        btc_address: &BtcAddress,
+        fee_rate: u64,
+        amount: Sats,
+        operator_id: u64,
+        type_args: Vec<TypeTag>,
+    ) -> Result<TransactionPayload> {
+        let args = vec![
+            bcs::to_bytes(btc_address)
+                .map_err(|e| anyhow!("Failed to serialize BTC address: {}", e))?,
+            bcs::to_bytes(&fee_rate).map_err(|e| anyhow!("Failed to serialize fee rate: {}", e))?,
+            bcs::to_bytes(&amount).map_err(|e| anyhow!("Failed to serialize amount: {}", e))?,
+            bcs::to_bytes(&operator_id)
+                .map_err(|e| anyhow!("Failed to serialize operator ID: {}", e))?,
+        ];
+        self.entry_function(
+            self.bridge_contract_address,
+            ContractModule::Bridge,
+            "burn",
+            type_args,
+            args,
+        )
+    }
+
+    /// `bridge::withdraw_by_lp` payload
+    pub fn withdraw_by_lp(
+        &self,
+        params: &WithdrawByLPParams,
+        type_args: Vec<TypeTag>,
+    ) -> Result<TransactionPayload> {
+        self.entry_function(
+            self.bridge_contract_address,
+            ContractModule::Bridge,
+            "withdraw_by_lp",
+            type_args,
+            params.to_bcs_args()?,
+        )
+    }
+
+    /// `bridge::claim_lp_withdraw` payload
+    pub fn claim_lp_withdraw(&self, params: &ClaimLPWithdrawParams) -> Result<TransactionPayload> {
+        self.entry_function(
+            self.bridge_contract_address,
+            ContractModule::Bridge,
+            "claim_lp_withdraw",
+            vec![],
+            params.to_bcs_args()?,
+        )
+    }
+
+    /// `lp_manager::register_lp` payload
+    pub fn register_lp(&self, params: &RegisterLPParams) -> Result<TransactionPayload> {
+        self.entry_function(
+            self.bridge_contract_address,
+            ContractModule::LpManager,
+            "register_lp",
+            vec![],
+            params.to_bcs_args()?,
+        )
+    }
+
+    /// Light client header submission payload
+    ///
+    /// `headers[0]` is the 80-byte header at `start_height`.
+    pub fn submit_block_headers(
+        &self,
+        start_height: u64,
+        headers: &[Vec<u8>],
+    ) -> Result<TransactionPayload> {
+        let btc_light_client = self.light_client_address()?;
+        if let Some(header) = headers.iter().find(|header| header.len() != 80) {
+            return Err(anyhow!(
+                "Invalid block header: expected 80 bytes, got {}",
+                header.len()
+            ));
+        }
+
+        let args = vec![
+            bcs::to_bytes(&start_height)
+                .map_err(|e| anyhow!("Failed to serialize start_height: {}", e))?,
+            bcs::to_bytes(headers).map_err(|e| anyhow!("Failed to serialize headers: {}", e))?,
+        ];
+        self.entry_function(
+            btc_light_client,
+            ContractModule::LightClient,
+            "submit_block_headers",
+            vec![],
+            args,
+        )
+    }
+
+    /// BTC light client address, or an error if it is not set
+    pub(crate) fn light_client_address(&self) -> Result<AccountAddress> {
+        self.btc_light_client.ok_or_else(|| {
+            anyhow!(
+                "Aptos Bridge: BTC light client is not set. Please set the BTC light client address when creating the BridgeClient."
+            )
+        })
+    }
+
+    fn entry_function(
+        &self,
+        address: AccountAddress,
+        module: ContractModule,
+        function: &str,
+        type_args: Vec<TypeTag>,
+        args: Vec<Vec<u8>>,
+    ) -> Result<TransactionPayload> {
+        Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(address, self.contract_ids.module(module)),
+            self.contract_ids.function(function)?,
+            type_args,
+            args,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btc::BtcNetwork;
+
+    fn builder() -> PayloadBuilder {
+        PayloadBuilder::new(AccountAddress::from_hex_literal("0xb").unwrap())
+    }
+
+    fn entry_function(payload: TransactionPayload) -> EntryFunction {
+        match payload {
+            TransactionPayload::EntryFunction(entry_function) => entry_function,
+            _ => panic!("expected an entry function payload"),
+        }
+    }
+
+    #[test]
+    fn test_burn_payload() {
+        let btc_address = BtcAddress::new(
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+            BtcNetwork::Mainnet,
+        )
+        .unwrap();
+        let entry_function = entry_function(
+            builder()
+                .burn(&btc_address, 5, Sats(1_000), 2, vec![])
+                .unwrap(),
+        );
+
+        assert_eq!(entry_function.module().name().as_str(), "bridge");
+        assert_eq!(entry_function.function().as_str(), "burn");
+        assert_eq!(entry_function.args()[2], bcs::to_bytes(&1_000u64).unwrap());
+    }
+
+    #[test]
+    fn test_submit_block_headers_requires_light_client() {
+        assert!(builder().submit_block_headers(1, &[vec![0; 80]]).is_err());
+
+        let builder = builder().with_light_client(AccountAddress::from_hex_literal("0xc").unwrap());
+        assert!(builder.submit_block_headers(1, &[vec![0; 79]]).is_err());
+        let entry_function =
+            entry_function(builder.submit_block_headers(1, &[vec![0; 80]]).unwrap());
+        assert_eq!(
+            *entry_function.module().address(),
+            AccountAddress::from_hex_literal("0xc").unwrap()
+        );
+    }
+}
@@ -0,0 +1,714 @@
+//! BTC payout watcher
+//!
+//! Takes `Burn` and `WithdrawByLP` events and scans each new Bitcoin block for
+//! the payout owed to the receiver: an output to the burn's address carrying at
+//! least its [`ExpectedPayout::min_amount`]. A [`PayoutObserved`] notification is
+//! sent when one is found, and a [`PayoutMissing`] notification if none shows up
+//! within the configured number of blocks. Plug the watcher into an
+//! `EventMonitor` as its event handler.
+//!
+//! Blocks are scanned once they have the configured number of confirmations,
+//! and each is checked to extend the last one scanned. On a reorg deeper than
+//! that the watcher rescans from the fork point; payouts already reported from
+//! the orphaned blocks are not retracted. Notifications are delivered at least
+//! once: a payout stays watched until every observer has accepted it.
+//!
+//! As an event handler, the watcher logs and skips requests it can't work out a
+//! payout for (a network fee above the amount, an address on another network),
+//! so they don't stop the monitor.
+
+use crate::btc::BtcNetwork;
+use crate::events::EventHandler;
+use crate::peg_out::{ExpectedPayout, PegOutRequest};
+use crate::proof_builder::ProofBuilder;
+use crate::types::constants::{
+    PAYOUT_WATCH_CONFIRMATIONS, PAYOUT_WATCH_POLL_INTERVAL_SECS, PAYOUT_WATCH_TIMEOUT_BLOCKS,
+};
+use crate::types::{BurnEvent, MintEvent, Sats, ScriptType, WithdrawByLPEvent};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use bitcoin::{Block, BlockHash};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Payout found on the Bitcoin chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayoutObserved {
+    /// Withdrawal the payout fulfils
+    pub request: PegOutRequest,
+    /// Payout transaction, display byte order
+    pub txid: String,
+    /// Output index of the payout
+    pub vout: u32,
+    /// Amount paid to the receiver
+    pub amount: Sats,
+    /// Height of the block containing the payout
+    pub block_height: u64,
+}
+
+/// Payout not found within the watch window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayoutMissing {
+    /// Withdrawal left unpaid
+    pub request: PegOutRequest,
+    /// Payout that was expected
+    pub expected: ExpectedPayout,
+    /// First block scanned for the payout
+    pub from_height: u64,
+    /// Last block scanned for the payout
+    pub to_height: u64,
+}
+
+/// Receiver of payout notifications
+#[async_trait]
+pub trait PayoutObserver: Send + Sync {
+    /// Called once a withdrawal's payout is found
+    async fn on_observed(&self, payout: &PayoutObserved) -> Result<()>;
+
+    /// Called once a withdrawal's watch window closes without a payout
+    async fn on_missing(&self, _payout: &PayoutMissing) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Payout being watched for
+struct WatchedPayout {
+    request: PegOutRequest,
+    expected: ExpectedPayout,
+    /// First block scanned, set by the first poll after the request is watched
+    from_height: Option<u64>,
+}
+
+/// Blocks scanned so far and the payouts still owed
+#[derive(Default)]
+struct WatchState {
+    /// Next block to scan, set by the first poll
+    next_height: Option<u64>,
+    /// Watched payouts by request id
+    pending: BTreeMap<String, WatchedPayout>,
+    /// Hashes of the latest scanned blocks by height
+    scanned: BTreeMap<u64, BlockHash>,
+}
+
+/// Watches the Bitcoin chain for the payouts of burns and LP withdrawals
+pub struct PayoutWatcher {
+    /// Bitcoin backend read for new blocks
+    proof_builder: ProofBuilder,
+    /// Network of the receiver addresses
+    network: BtcNetwork,
+    /// Script type of the operator wallet funding burn payouts
+    operator_input: ScriptType,
+    /// Receivers of notifications
+    observers: Vec<Arc<dyn PayoutObserver>>,
+    /// Blocks to wait for a payout before reporting it missing
+    timeout_blocks: u64,
+    /// Confirmations a block needs before it's scanned
+    confirmations: u64,
+    /// Delay between polls in `run`
+    poll_interval: Duration,
+    state: Mutex<WatchState>,
+}
+
+impl PayoutWatcher {
+    /// Watch for payouts on `network` through `proof_builder`
+    pub fn new(proof_builder: ProofBuilder, network: BtcNetwork) -> Self {
+        Self {
+            proof_builder,
+            network,
+            operator_input: ScriptType::P2WPKH,
+            observers: Vec::new(),
+            timeout_blocks: PAYOUT_WATCH_TIMEOUT_BLOCKS,
+            confirmations: PAYOUT_WATCH_CONFIRMATIONS,
+            poll_interval: Duration::from_secs(PAYOUT_WATCH_POLL_INTERVAL_SECS),
+            state: Mutex::new(WatchState::default()),
+        }
+    }
+
+    /// Send notifications to `observer`
+    pub fn with_observer(mut self, observer: Arc<dyn PayoutObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// Set the script type of the operator wallet funding burn payouts, which
+    /// sets their expected network fee (default: P2WPKH)
+    pub fn with_operator_input_type(mut self, operator_input: ScriptType) -> Self {
+        self.operator_input = operator_input;
+        self
+    }
+
+    /// Report payouts missing after `timeout_blocks` blocks without them
+    pub fn with_timeout_blocks(mut self, timeout_blocks: u64) -> Self {
+        self.timeout_blocks = timeout_blocks.max(1);
+        self
+    }
+
+    /// Scan blocks once they have `confirmations` confirmations (default: 6)
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations.max(1);
+        self
+    }
+
+    /// Start scanning at `height` instead of the chain tip at the first poll,
+    /// to catch payouts made before the watcher started
+    pub fn with_start_height(mut self, height: u64) -> Self {
+        self.state.get_mut().next_height = Some(height);
+        self
+    }
+
+    /// Set the delay between polls in `run`
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Watch for the payout of `request`; returns false if it's already watched
+    pub async fn watch(&self, request: PegOutRequest) -> Result<bool> {
+        let id = request.id()?;
+        let expected = request.expected_payout(self.network, self.operator_input)?;
+        let mut state = self.state.lock().await;
+        if state.pending.contains_key(&id) {
+            return Ok(false);
+        }
+        state.pending.insert(
+            id,
+            WatchedPayout {
+                request,
+                expected,
+                from_height: None,
+            },
+        );
+        Ok(true)
+    }
+
+    /// Ids of the requests still awaiting their payout
+    pub async fn pending(&self) -> Vec<String> {
+        self.state.lock().await.pending.keys().cloned().collect()
+    }
+
+    /// Poll forever, sleeping `poll_interval` between polls
+    pub async fn run(&self) -> Result<()> {
+        loop {
+            if let Err(_error) = self.poll().await {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %_error, "Payout watcher poll failed");
+            }
+            crate::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Scan the blocks confirmed since the last poll and notify observers of
+    /// the payouts found and the watch windows closed
+    ///
+    /// A payout leaves the watch list only once every observer returns `Ok`;
+    /// if one fails, the poll stops and the payout is reported again by the
+    /// next one.
+    pub async fn poll(&self) -> Result<()> {
+        let tip = self.proof_builder.tip_height().await?;
+        // Highest block with enough confirmations
+        let confirmed_tip = (tip + 1).saturating_sub(self.confirmations);
+        let mut state = self.state.lock().await;
+        let next_height = *state.next_height.get_or_insert(confirmed_tip);
+        for watched in state.pending.values_mut() {
+            watched.from_height.get_or_insert(next_height);
+        }
+
+        for height in next_height..=confirmed_tip {
+            let block = self.proof_builder.block_at(height).await?;
+            let parent = height
+                .checked_sub(1)
+                .and_then(|parent| state.scanned.get(&parent));
+            if parent.is_some_and(|parent| *parent != block.header.prev_blockhash) {
+                let resume_height = self.find_fork(&state.scanned).await?;
+                state.scanned.split_off(&resume_height);
+                state.next_height = Some(resume_height);
+                return Err(anyhow!(
+                    "Bitcoin reorg below {} confirmations at height {}; rescanning from {}",
+                    self.confirmations,
+                    height,
+                    resume_height
+                ));
+            }
+
+            for (id, observed) in find_payouts(&state.pending, &block, height) {
+                for observer in &self.observers {
+                    observer.on_observed(&observed).await?;
+                }
+                state.pending.remove(&id);
+            }
+            state.scanned.insert(height, block.block_hash());
+            while state.scanned.len() as u64 > self.timeout_blocks {
+                state.scanned.pop_first();
+            }
+            state.next_height = Some(height + 1);
+        }
+
+        let expired: Vec<String> = state
+            .pending
+            .iter()
+            .filter(|(_, watched)| {
+                watched.from_height.is_some_and(|from_height| {
+                    confirmed_tip + 1 >= from_height + self.timeout_blocks
+                })
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in expired {
+            let Some(watched) = state.pending.get(&id) else {
+                continue;
+            };
+            let missing = PayoutMissing {
+                from_height: watched.from_height.unwrap_or(confirmed_tip),
+                to_height: confirmed_tip,
+                request: watched.request.clone(),
+                expected: watched.expected.clone(),
+            };
+            for observer in &self.observers {
+                observer.on_missing(&missing).await?;
+            }
+            state.pending.remove(&id);
+        }
+        Ok(())
+    }
+
+    /// Height to resume scanning at after a reorg: just above the latest
+    /// scanned block still on the chain, or the oldest one if none is
+    async fn find_fork(&self, scanned: &BTreeMap<u64, BlockHash>) -> Result<u64> {
+        for (height, block_hash) in scanned.iter().rev() {
+            if self.proof_builder.block_at(*height).await?.block_hash() == *block_hash {
+                return Ok(height + 1);
+            }
+        }
+        Ok(scanned.keys().next().copied().unwrap_or_default())
+    }
+}
+
+/// Payouts in `block` that fulfil requests in `pending`, with their request ids
+///
+/// Each output fulfils at most one payout, the earliest-keyed one it matches.
+fn find_payouts(
+    pending: &BTreeMap<String, WatchedPayout>,
+    block: &Block,
+    height: u64,
+) -> Vec<(String, PayoutObserved)> {
+    let mut paid = BTreeSet::new();
+    let mut observed = Vec::new();
+    for tx in &block.txdata {
+        for (vout, output) in tx.output.iter().enumerate() {
+            let amount = Sats(output.value.to_sat());
+            let Some((id, watched)) = pending.iter().find(|(id, watched)| {
+                !paid.contains(*id)
+                    && output.script_pubkey == watched.expected.script_pubkey()
+                    && amount >= watched.expected.min_amount
+            }) else {
+                continue;
+            };
+            paid.insert(id.clone());
+            observed.push((
+                id.clone(),
+                PayoutObserved {
+                    request: watched.request.clone(),
+                    txid: tx.compute_txid().to_string(),
+                    vout: vout as u32,
+                    amount,
+                    block_height: height,
+                },
+            ));
+        }
+    }
+    observed
+}
+
+#[async_trait]
+impl EventHandler for PayoutWatcher {
+    async fn handle_mint(&self, _event: MintEvent) -> Result<()> {
+        Ok(())
+    }
+
+    async fn handle_burn(&self, event: BurnEvent) -> Result<()> {
+        self.watch_event(PegOutRequest::Burn(event)).await;
+        Ok(())
+    }
+
+    async fn handle_withdraw_by_lp(&self, event: WithdrawByLPEvent) -> Result<()> {
+        self.watch_event(PegOutRequest::WithdrawByLP(event)).await;
+        Ok(())
+    }
+}
+
+impl PayoutWatcher {
+    /// Watch the request of an event, logging one without a payout to watch
+    /// for rather than failing, which would hold the monitor's cursor on it
+    async fn watch_event(&self, request: PegOutRequest) {
+        if let Err(_e) = self.watch(request).await {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(error = %_e, "Skipping peg-out request without a payout to watch");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::block::{Header, Version};
+    use bitcoin::hashes::Hash;
+    use bitcoin::{absolute, Amount, BlockHash, CompactTarget, Transaction, TxMerkleNode, TxOut};
+
+    /// Key of `burn_event(500_000)`
+    const BURN_ID: &str = "burn:500000:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4:500000";
+
+    fn burn_event(amount: u64) -> BurnEvent {
+        BurnEvent {
+            from_address: "0x1".to_string(),
+            btc_address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+            fee_rate: 10,
+            amount: Sats(amount),
+            operator_id: 1,
+            timestamp: None,
+            version: Some(amount),
+            transaction_hash: None,
+        }
+    }
+
+    fn block(outputs: Vec<TxOut>) -> Block {
+        Block {
+            header: Header {
+                version: Version::TWO,
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: 0,
+                bits: CompactTarget::from_consensus(0),
+                nonce: 0,
+            },
+            txdata: vec![Transaction {
+                version: bitcoin::transaction::Version::TWO,
+                lock_time: absolute::LockTime::ZERO,
+                input: vec![],
+                output: outputs,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_find_payouts_matches_address_and_min_amount() {
+        let mut pending = BTreeMap::new();
+        for amount in [500_000, 800_000] {
+            let request = PegOutRequest::Burn(burn_event(amount));
+            pending.insert(
+                request.id().unwrap(),
+                WatchedPayout {
+                    expected: request
+                        .expected_payout(BtcNetwork::Mainnet, ScriptType::P2WPKH)
+                        .unwrap(),
+                    request,
+                    from_height: Some(100),
+                },
+            );
+        }
+        let script_pubkey = pending.values().next().unwrap().expected.script_pubkey();
+        let min_amount = pending[BURN_ID].expected.min_amount;
+
+        let observed = find_payouts(
+            &pending,
+            &block(vec![
+                // Underpaid
+                TxOut {
+                    value: Amount::from_sat(min_amount.0 - 1),
+                    script_pubkey: script_pubkey.clone(),
+                },
+                TxOut {
+                    value: Amount::from_sat(min_amount.0),
+                    script_pubkey,
+                },
+            ]),
+            101,
+        );
+
+        assert_eq!(observed.len(), 1);
+        let (id, observed) = &observed[0];
+        assert_eq!(id, BURN_ID);
+        assert_eq!(observed.vout, 1);
+        assert_eq!(observed.block_height, 101);
+        assert_eq!(observed.request.id().unwrap(), BURN_ID);
+        // Removal is left to the caller once observers accept the payout
+        assert_eq!(pending.len(), 2);
+    }
+
+    #[cfg(feature = "esplora")]
+    #[tokio::test]
+    async fn test_watch_keys_burns_by_transaction() {
+        let watcher = PayoutWatcher::new(
+            ProofBuilder::esplora("http://127.0.0.1:1"),
+            BtcNetwork::Mainnet,
+        );
+        let first = burn_event(500_000);
+        // Same transaction, other amount
+        let second = BurnEvent {
+            amount: Sats(400_000),
+            ..first.clone()
+        };
+        // The first burn as the REST source reports it
+        let from_rest = BurnEvent {
+            transaction_hash: Some(format!("0x{}", "ab".repeat(32))),
+            ..first.clone()
+        };
+
+        assert!(watcher
+            .watch(PegOutRequest::Burn(first.clone()))
+            .await
+            .unwrap());
+        assert!(watcher.watch(PegOutRequest::Burn(second)).await.unwrap());
+        assert!(!watcher.watch(PegOutRequest::Burn(from_rest)).await.unwrap());
+        assert_eq!(
+            watcher.pending().await,
+            [
+                "burn:500000:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4:400000",
+                BURN_ID
+            ]
+        );
+
+        let unkeyed = BurnEvent {
+            version: None,
+            ..first
+        };
+        assert!(watcher.watch(PegOutRequest::Burn(unkeyed)).await.is_err());
+    }
+
+    #[cfg(feature = "esplora")]
+    #[tokio::test]
+    async fn test_handler_skips_unpayable_requests() {
+        let watcher = PayoutWatcher::new(
+            ProofBuilder::esplora("http://127.0.0.1:1"),
+            BtcNetwork::Mainnet,
+        );
+        // The network fee alone exceeds the burned amount
+        watcher.handle_burn(burn_event(1_000)).await.unwrap();
+        let testnet_receiver = BurnEvent {
+            btc_address: "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx".to_string(),
+            ..burn_event(500_000)
+        };
+        watcher.handle_burn(testnet_receiver).await.unwrap();
+        assert!(watcher.pending().await.is_empty());
+
+        watcher.handle_burn(burn_event(500_000)).await.unwrap();
+        assert_eq!(watcher.pending().await.len(), 1);
+    }
+
+    #[cfg(all(feature = "esplora", feature = "test-utils"))]
+    mod poll {
+        use super::*;
+        use crate::testing;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use wiremock::MockServer;
+
+        /// Records notifications, failing the first `failures` of them
+        #[derive(Default)]
+        struct Recorder {
+            failures: AtomicUsize,
+            observed: Mutex<Vec<(String, u64)>>,
+            missing: Mutex<Vec<(String, u64, u64)>>,
+        }
+
+        impl Recorder {
+            fn failing(failures: usize) -> Self {
+                Self {
+                    failures: AtomicUsize::new(failures),
+                    ..Self::default()
+                }
+            }
+
+            fn fail(&self) -> Result<()> {
+                match self
+                    .failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                {
+                    Ok(_) => Err(anyhow!("observer unavailable")),
+                    Err(_) => Ok(()),
+                }
+            }
+        }
+
+        #[async_trait]
+        impl PayoutObserver for Recorder {
+            async fn on_observed(&self, payout: &PayoutObserved) -> Result<()> {
+                self.fail()?;
+                self.observed
+                    .lock()
+                    .await
+                    .push((payout.request.id()?, payout.block_height));
+                Ok(())
+            }
+
+            async fn on_missing(&self, payout: &PayoutMissing) -> Result<()> {
+                self.fail()?;
+                self.missing.lock().await.push((
+                    payout.request.id()?,
+                    payout.from_height,
+                    payout.to_height,
+                ));
+                Ok(())
+            }
+        }
+
+        fn tx(lock_time: u32, output: Vec<TxOut>) -> Transaction {
+            Transaction {
+                version: bitcoin::transaction::Version::TWO,
+                lock_time: absolute::LockTime::from_consensus(lock_time),
+                input: vec![bitcoin::TxIn::default()],
+                output,
+            }
+        }
+
+        /// `count` blocks on `parent`, the ones at heights in `payouts` (from
+        /// `height`) paying the burn; `fork` tells forks of the same parent apart
+        fn chain(
+            parent: BlockHash,
+            height: u64,
+            count: u64,
+            fork: u32,
+            payouts: &[u64],
+        ) -> Vec<Block> {
+            let expected = PegOutRequest::Burn(burn_event(500_000))
+                .expected_payout(BtcNetwork::Mainnet, ScriptType::P2WPKH)
+                .unwrap();
+            let mut blocks: Vec<Block> = Vec::new();
+            for height in height..height + count {
+                let mut txdata = vec![tx(fork, vec![])];
+                if payouts.contains(&height) {
+                    txdata.push(tx(
+                        height as u32,
+                        vec![TxOut {
+                            value: Amount::from_sat(expected.min_amount.0),
+                            script_pubkey: expected.script_pubkey(),
+                        }],
+                    ));
+                }
+                let parent = blocks.last().map_or(parent, |block| block.block_hash());
+                blocks.push(testing::btc_block(parent, txdata));
+            }
+            blocks
+        }
+
+        async fn mount_chain(server: &MockServer, height: u64, blocks: &[Block]) {
+            for (i, block) in blocks.iter().enumerate() {
+                testing::mount_esplora_block(server, height + i as u64, block).await;
+            }
+        }
+
+        async fn watcher(server: &MockServer, recorder: Arc<Recorder>) -> PayoutWatcher {
+            let watcher =
+                PayoutWatcher::new(ProofBuilder::esplora(&server.uri()), BtcNetwork::Mainnet)
+                    .with_start_height(100)
+                    .with_observer(recorder);
+            watcher
+                .watch(PegOutRequest::Burn(burn_event(500_000)))
+                .await
+                .unwrap();
+            watcher
+        }
+
+        #[tokio::test]
+        async fn test_poll_waits_for_confirmations() {
+            let server = MockServer::start().await;
+            let blocks = chain(BlockHash::all_zeros(), 100, 3, 0, &[101]);
+            mount_chain(&server, 100, &blocks).await;
+            testing::mount_esplora_tip(&server, 101, 5).await;
+            let recorder = Arc::new(Recorder::default());
+            let watcher = watcher(&server, recorder.clone())
+                .await
+                .with_confirmations(2);
+
+            // Block 101 has one confirmation
+            watcher.poll().await.unwrap();
+            assert!(recorder.observed.lock().await.is_empty());
+            assert_eq!(watcher.pending().await, [BURN_ID]);
+
+            testing::mount_esplora_tip(&server, 102, 4).await;
+            watcher.poll().await.unwrap();
+            assert_eq!(
+                *recorder.observed.lock().await,
+                [(BURN_ID.to_string(), 101)]
+            );
+            assert!(watcher.pending().await.is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_poll_keeps_payout_until_observer_accepts() {
+            let server = MockServer::start().await;
+            let blocks = chain(BlockHash::all_zeros(), 100, 2, 0, &[101]);
+            mount_chain(&server, 100, &blocks).await;
+            testing::mount_esplora_tip(&server, 101, 5).await;
+            let recorder = Arc::new(Recorder::failing(1));
+            let watcher = watcher(&server, recorder.clone())
+                .await
+                .with_confirmations(1);
+
+            assert!(watcher.poll().await.is_err());
+            assert_eq!(watcher.pending().await, [BURN_ID]);
+
+            // The failed block is scanned again
+            watcher.poll().await.unwrap();
+            assert_eq!(
+                *recorder.observed.lock().await,
+                [(BURN_ID.to_string(), 101)]
+            );
+            assert!(watcher.pending().await.is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_poll_keeps_missing_payout_until_observer_accepts() {
+            let server = MockServer::start().await;
+            let blocks = chain(BlockHash::all_zeros(), 100, 3, 0, &[]);
+            mount_chain(&server, 100, &blocks).await;
+            testing::mount_esplora_tip(&server, 102, 5).await;
+            let recorder = Arc::new(Recorder::failing(1));
+            let watcher = watcher(&server, recorder.clone())
+                .await
+                .with_confirmations(1)
+                .with_timeout_blocks(3);
+
+            assert!(watcher.poll().await.is_err());
+            assert_eq!(watcher.pending().await, [BURN_ID]);
+
+            watcher.poll().await.unwrap();
+            assert_eq!(
+                *recorder.missing.lock().await,
+                [(BURN_ID.to_string(), 100, 102)]
+            );
+            assert!(watcher.pending().await.is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_poll_rescans_from_fork_after_reorg() {
+            let server = MockServer::start().await;
+            let stale = chain(BlockHash::all_zeros(), 100, 3, 0, &[]);
+            mount_chain(&server, 100, &stale).await;
+            testing::mount_esplora_tip(&server, 102, 5).await;
+            let recorder = Arc::new(Recorder::default());
+            let watcher = watcher(&server, recorder.clone())
+                .await
+                .with_confirmations(1);
+            watcher.poll().await.unwrap();
+            assert!(recorder.observed.lock().await.is_empty());
+
+            // 101 and 102 are replaced by a longer branch paying at 102
+            server.reset().await;
+            let branch = chain(stale[0].block_hash(), 101, 3, 1, &[102]);
+            mount_chain(&server, 100, &stale[..1]).await;
+            mount_chain(&server, 101, &branch).await;
+            testing::mount_esplora_tip(&server, 103, 5).await;
+
+            let error = watcher.poll().await.unwrap_err();
+            assert!(error.to_string().contains("rescanning from 101"));
+            watcher.poll().await.unwrap();
+            assert_eq!(
+                *recorder.observed.lock().await,
+                [(BURN_ID.to_string(), 102)]
+            );
+            assert!(watcher.pending().await.is_empty());
+        }
+    }
+}
@@ -0,0 +1,636 @@
+//! Peg-in orchestration
+//!
+//! Drives a BTC deposit from confirmation to minted tokens: waits for the
+//! contract's minimum confirmations, builds the proof, submits `mint` and returns
+//! the resulting `MintEvent`. Progress is saved after every step so an
+//! interrupted peg-in resumes where it stopped.
+
+use crate::abort::execution_error;
+use crate::confirmations::ConfirmationTracker;
+use crate::json_file::{read_json, write_json_atomic};
+use crate::proof_builder::ProofBuilder;
+use crate::types::constants::{PEG_IN_POLL_INTERVAL_SECS, TRANSACTION_COMMIT_TIMEOUT_SECS};
+use crate::types::{AlreadyMinted, BridgeEvent, MintEvent, Peg, ReorgDetected};
+use crate::BridgeClient;
+use anyhow::{anyhow, Result};
+use aptos_sdk::rest_client::aptos_api_types::TransactionData;
+use async_trait::async_trait;
+use bitcoin::hashes::{sha256d, Hash};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Progress of a peg-in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum PegInState {
+    /// Waiting for the deposit to reach the minimum confirmations
+    AwaitingConfirmations,
+    /// Proof built, `mint` not yet submitted
+    ProofReady { peg: Peg },
+    /// `mint` submitted, waiting for commitment
+    Submitted { tx_hash: String },
+    /// Tokens minted
+    Completed { event: MintEvent },
+}
+
+impl PegInState {
+    /// Stage name, as used in the serialized state
+    pub fn stage(&self) -> &'static str {
+        match self {
+            PegInState::AwaitingConfirmations => "awaiting_confirmations",
+            PegInState::ProofReady { .. } => "proof_ready",
+            PegInState::Submitted { .. } => "submitted",
+            PegInState::Completed { .. } => "completed",
+        }
+    }
+}
+
+/// A single peg-in and its progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PegInJob {
+    /// BTC deposit txid, display byte order
+    pub btc_txid: String,
+    /// Deposit output index
+    pub vout: u32,
+    /// Aptos recipient address
+    pub recipient: String,
+    /// Current progress
+    pub state: PegInState,
+}
+
+impl PegInJob {
+    /// Create a job for a new deposit
+    pub fn new(btc_txid: &str, vout: u32, recipient: &str) -> Self {
+        Self {
+            btc_txid: btc_txid.to_string(),
+            vout,
+            recipient: recipient.to_string(),
+            state: PegInState::AwaitingConfirmations,
+        }
+    }
+
+    /// Key identifying the deposit output
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.btc_txid, self.vout)
+    }
+
+    /// Drop a proof built on a block orphaned by `reorg`; returns whether it was dropped
+    ///
+    /// Only unsubmitted proofs are reset; the job then waits for confirmations again.
+    pub fn invalidate_if_orphaned(&mut self, reorg: &ReorgDetected) -> bool {
+        let PegInState::ProofReady { peg } = &self.state else {
+            return false;
+        };
+        let block_hash = sha256d::Hash::hash(&peg.inclusion_proof.block_header);
+        if !reorg.orphans(block_hash.as_byte_array()) {
+            return false;
+        }
+        self.state = PegInState::AwaitingConfirmations;
+        true
+    }
+
+    /// Return a job whose `mint` failed on chain to waiting for confirmations,
+    /// so the next run rebuilds the proof and submits again
+    pub fn reset_after_failed_mint(&mut self) -> bool {
+        if !matches!(self.state, PegInState::Submitted { .. }) {
+            return false;
+        }
+        self.state = PegInState::AwaitingConfirmations;
+        true
+    }
+}
+
+/// Result of waiting for a submitted `mint`
+enum MintOutcome {
+    /// Committed with its `Mint` event
+    Minted(MintEvent),
+    /// Committed but failed
+    Failed(anyhow::Error),
+}
+
+/// Persistent storage for peg-in progress
+#[async_trait]
+pub trait PegInStore: Send + Sync {
+    /// Load a job by id, if one was saved
+    async fn load(&self, id: &str) -> Result<Option<PegInJob>>;
+
+    /// Save a job, replacing any previous state
+    async fn save(&self, job: &PegInJob) -> Result<()>;
+}
+
+/// Store keeping one JSON file per job in a directory
+#[derive(Debug, Clone)]
+pub struct FilePegInStore {
+    /// Directory holding job files
+    dir: PathBuf,
+}
+
+impl FilePegInStore {
+    /// Create a store in `dir`, which is created on first save
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id.replace(':', "-")))
+    }
+}
+
+#[async_trait]
+impl PegInStore for FilePegInStore {
+    async fn load(&self, id: &str) -> Result<Option<PegInJob>> {
+        read_json(&self.path(id), "peg-in job").await
+    }
+
+    async fn save(&self, job: &PegInJob) -> Result<()> {
+        write_json_atomic(&self.path(&job.id()), "peg-in job", job).await
+    }
+}
+
+/// End-to-end peg-in service
+pub struct PegInService {
+    /// Bridge client used for views and `mint`
+    bridge_client: Arc<BridgeClient>,
+    /// Bitcoin proof source
+    proof_builder: ProofBuilder,
+    /// Progress store, none to keep progress in memory only
+    store: Option<Arc<dyn PegInStore>>,
+    /// Gate on the contract's minimum confirmations
+    confirmation_tracker: ConfirmationTracker,
+    /// How long to wait for the `mint` transaction to commit
+    commit_timeout: Duration,
+}
+
+impl PegInService {
+    /// Create a peg-in service
+    pub fn new(bridge_client: Arc<BridgeClient>, proof_builder: ProofBuilder) -> Self {
+        let confirmation_tracker =
+            ConfirmationTracker::new(bridge_client.clone(), proof_builder.clone())
+                .with_poll_interval(Duration::from_secs(PEG_IN_POLL_INTERVAL_SECS));
+        Self {
+            bridge_client,
+            proof_builder,
+            store: None,
+            confirmation_tracker,
+            commit_timeout: Duration::from_secs(TRANSACTION_COMMIT_TIMEOUT_SECS),
+        }
+    }
+
+    /// Persist progress so interrupted peg-ins can resume
+    pub fn with_store(mut self, store: Arc<dyn PegInStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Set the delay between confirmation checks
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.confirmation_tracker = self.confirmation_tracker.with_poll_interval(poll_interval);
+        self
+    }
+
+    /// Set how long to wait for the `mint` transaction to commit
+    pub fn with_commit_timeout(mut self, commit_timeout: Duration) -> Self {
+        self.commit_timeout = commit_timeout;
+        self
+    }
+
+    /// Peg in output `vout` of `btc_txid` to `recipient`
+    ///
+    /// Resumes saved progress for the same output if the store has any.
+    pub async fn peg_in(&self, btc_txid: &str, vout: u32, recipient: &str) -> Result<MintEvent> {
+        let mut job = PegInJob::new(btc_txid, vout, recipient);
+        if let Some(store) = &self.store {
+            if let Some(saved) = store.load(&job.id()).await? {
+                if saved.recipient != recipient {
+                    return Err(anyhow!(
+                        "Peg-in {} was started for recipient {}",
+                        saved.id(),
+                        saved.recipient
+                    ));
+                }
+                job = saved;
+            }
+        }
+        self.run(job).await
+    }
+
+    /// Reset saved job `id` if its proof was built on a block orphaned by `reorg`
+    pub async fn invalidate_orphaned(&self, id: &str, reorg: &ReorgDetected) -> Result<bool> {
+        let Some(store) = &self.store else {
+            return Ok(false);
+        };
+        let Some(mut job) = store.load(id).await? else {
+            return Ok(false);
+        };
+        if !job.invalidate_if_orphaned(reorg) {
+            return Ok(false);
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(peg_in = %job.id(), %reorg, "Peg-in proof orphaned by reorg");
+        store.save(&job).await?;
+        Ok(true)
+    }
+
+    /// Drive a job to completion, saving progress after each step
+    ///
+    /// An output minted by another transaction completes the job with its
+    /// existing mint. A `mint` that fails on chain resets the job to wait for
+    /// confirmations again and returns the failure.
+    pub async fn run(&self, mut job: PegInJob) -> Result<MintEvent> {
+        loop {
+            let next = match &job.state {
+                PegInState::AwaitingConfirmations => PegInState::ProofReady {
+                    peg: self.wait_for_confirmations(&job).await?,
+                },
+                PegInState::ProofReady { peg } => match self.submit_mint(peg).await {
+                    Ok(tx_hash) => PegInState::Submitted { tx_hash },
+                    Err(e) if e.downcast_ref::<AlreadyMinted>().is_some() => {
+                        PegInState::Completed {
+                            event: self.existing_mint(peg).await,
+                        }
+                    }
+                    Err(e) => return Err(e),
+                },
+                PegInState::Submitted { tx_hash } => match self.wait_for_mint(tx_hash).await? {
+                    MintOutcome::Minted(event) => PegInState::Completed { event },
+                    MintOutcome::Failed(error) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(peg_in = %job.id(), error = %error, "Peg-in mint failed");
+                        job.reset_after_failed_mint();
+                        self.save(&job).await?;
+                        return Err(error);
+                    }
+                },
+                PegInState::Completed { event } => return Ok(event.clone()),
+            };
+
+            #[cfg(feature = "tracing")]
+            tracing::info!(peg_in = %job.id(), stage = next.stage(), "Peg-in progressed");
+            job.state = next;
+            self.save(&job).await?;
+        }
+    }
+
+    /// Save a job if the service has a store
+    async fn save(&self, job: &PegInJob) -> Result<()> {
+        match &self.store {
+            Some(store) => store.save(job).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Wait until the deposit has the contract's minimum confirmations and build its peg
+    async fn wait_for_confirmations(&self, job: &PegInJob) -> Result<Peg> {
+        let min_confirmations = self.bridge_client.get_min_confirmations().await?;
+        self.confirmation_tracker
+            .wait_for_confirmations(&job.btc_txid, min_confirmations)
+            .await?;
+        self.proof_builder
+            .build_peg(&job.btc_txid, job.vout, &job.recipient)
+            .await
+    }
+
+    /// Submit `mint`; outputs minted by another transaction fail with `AlreadyMinted`
+    async fn submit_mint(&self, peg: &Peg) -> Result<String> {
+        self.bridge_client.mint(peg.clone()).await
+    }
+
+    /// Mint of a peg whose output was minted by another transaction
+    ///
+    /// Read from the bridge's peg record; if that can't be read, the peg itself
+    /// describes the mint, without the transaction's version and hash.
+    async fn existing_mint(&self, peg: &Peg) -> MintEvent {
+        let tx_id = &peg.inclusion_proof.tx_id;
+        let (to_address, amount, btc_block_num) = match self
+            .bridge_client
+            .get_peg_record(tx_id, peg.tx_out_ix)
+            .await
+        {
+            Ok(Some(record)) => (record.to, record.value, record.block_num),
+            Ok(None) => (peg.to.clone(), peg.value, peg.block_num),
+            Err(_e) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %_e, "Failed to read peg record of minted output");
+                (peg.to.clone(), peg.value, peg.block_num)
+            }
+        };
+        MintEvent {
+            to_address,
+            amount,
+            btc_tx_id: hex::encode(tx_id),
+            btc_block_num,
+            timestamp: None,
+            version: None,
+            transaction_hash: None,
+        }
+    }
+
+    /// Wait for the `mint` transaction and extract its `MintEvent`
+    async fn wait_for_mint(&self, tx_hash: &str) -> Result<MintOutcome> {
+        let transaction = self
+            .bridge_client
+            .wait_for_transaction(tx_hash, self.commit_timeout)
+            .await?;
+        if let TransactionData::OnChain(txn) = &transaction {
+            let status = txn.info.status();
+            if !status.is_success() {
+                return Ok(MintOutcome::Failed(execution_error(
+                    "Mint transaction",
+                    tx_hash,
+                    status,
+                )));
+            }
+        }
+
+        let contract_address = self
+            .bridge_client
+            .bridge_contract_address()
+            .to_hex_literal();
+        self.bridge_client
+            .get_bridge_events_by_hash(tx_hash, &contract_address)
+            .await?
+            .into_iter()
+            .find_map(|event| match event {
+                BridgeEvent::Mint(event) => Some(MintOutcome::Minted(event)),
+                _ => None,
+            })
+            .ok_or_else(|| anyhow!("Mint transaction {} emitted no Mint event", tx_hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btc::txid_to_display_hex;
+    use crate::types::{OrphanedBlock, Sats, TxProof};
+
+    #[test]
+    fn test_reset_after_failed_mint() {
+        let mut job = PegInJob::new(&"ab".repeat(32), 0, "0x1");
+        assert!(!job.reset_after_failed_mint());
+        assert_eq!(job.state.stage(), "awaiting_confirmations");
+
+        job.state = PegInState::Submitted {
+            tx_hash: "0xabc".to_string(),
+        };
+        assert!(job.reset_after_failed_mint());
+        assert_eq!(job.state.stage(), "awaiting_confirmations");
+    }
+
+    #[test]
+    fn test_invalidate_if_orphaned() {
+        let block_header = vec![7u8; 80];
+        let peg = Peg::new(
+            "0x1".to_string(),
+            Sats(1_000),
+            100,
+            TxProof {
+                block_header: block_header.clone(),
+                tx_id: vec![1u8; 32],
+                tx_index: 0,
+                merkle_proof: vec![],
+                raw_tx: vec![],
+            },
+            0,
+            vec![2u8; 20],
+        );
+        let mut job = PegInJob::new(&"ab".repeat(32), 0, "0x1");
+        job.state = PegInState::ProofReady { peg };
+
+        let block_hash = sha256d::Hash::hash(&block_header);
+        let mut reorg = ReorgDetected {
+            fork_height: 99,
+            depth: 1,
+            orphaned_blocks: vec![OrphanedBlock {
+                height: 100,
+                hash: txid_to_display_hex(&[0u8; 32]),
+            }],
+            new_tip_height: 101,
+        };
+        assert!(!job.invalidate_if_orphaned(&reorg));
+        assert_eq!(job.state.stage(), "proof_ready");
+
+        reorg.orphaned_blocks[0].hash = txid_to_display_hex(block_hash.as_byte_array());
+        assert!(job.invalidate_if_orphaned(&reorg));
+        assert_eq!(job.state.stage(), "awaiting_confirmations");
+    }
+
+    #[cfg(all(feature = "test-utils", feature = "esplora"))]
+    mod service {
+        use super::*;
+        use crate::testing::{self, MockChain, BRIDGE_ADDRESS};
+        use aptos_sdk::types::contract_event::ContractEvent;
+        use std::collections::HashMap;
+        use std::sync::Mutex;
+        use wiremock::matchers::{method, path_regex};
+        use wiremock::Mock;
+
+        #[derive(Default)]
+        struct MemoryStore(Mutex<HashMap<String, PegInJob>>);
+
+        #[async_trait]
+        impl PegInStore for MemoryStore {
+            async fn load(&self, id: &str) -> Result<Option<PegInJob>> {
+                Ok(self.0.lock().unwrap().get(id).cloned())
+            }
+
+            async fn save(&self, job: &PegInJob) -> Result<()> {
+                self.0.lock().unwrap().insert(job.id(), job.clone());
+                Ok(())
+            }
+        }
+
+        /// Deposit peg and its txid
+        fn peg() -> (Peg, String) {
+            let peg = testing::deposit_peg();
+            let txid = txid_to_display_hex(&peg.inclusion_proof.tx_id);
+            (peg, txid)
+        }
+
+        fn service(server: &wiremock::MockServer, store: Arc<dyn PegInStore>) -> PegInService {
+            PegInService::new(
+                Arc::new(testing::bridge_client(server)),
+                ProofBuilder::esplora("http://127.0.0.1:1"),
+            )
+            .with_store(store)
+        }
+
+        #[tokio::test]
+        async fn test_resume_completed_job() {
+            let server = testing::mock_fullnode().await;
+            let store = Arc::new(MemoryStore::default());
+            let (peg, txid) = peg();
+            let mut job = PegInJob::new(&txid, 0, "0x1");
+            let event = MintEvent {
+                to_address: "0x1".to_string(),
+                amount: peg.value,
+                btc_tx_id: hex::encode(&peg.inclusion_proof.tx_id),
+                btc_block_num: 100,
+                timestamp: None,
+                version: Some(42),
+                transaction_hash: Some("0xabc".to_string()),
+            };
+            job.state = PegInState::Completed {
+                event: event.clone(),
+            };
+            store.save(&job).await.unwrap();
+
+            let service = service(&server, store);
+            let resumed = service.peg_in(&txid, 0, "0x1").await.unwrap();
+            assert_eq!(resumed.version, Some(42));
+            assert!(service.peg_in(&txid, 0, "0x2").await.is_err());
+        }
+
+        #[tokio::test]
+        async fn test_resume_already_minted_completes_from_peg_record() {
+            let server = testing::mock_fullnode().await;
+            testing::mount_view(&server, "is_peg_minted", serde_json::json!([true])).await;
+            Mock::given(method("GET"))
+                .and(path_regex(
+                    r"^/v1/accounts/[^/]+/resource/.*::bridge::BridgeStore$",
+                ))
+                .respond_with(testing::fullnode_response(
+                    &serde_json::json!({
+                        "type": format!("{}::bridge::BridgeStore", BRIDGE_ADDRESS),
+                        "data": {
+                            "pegs": { "handle": "0xaa" },
+                            "lp_withdraws": { "handle": "0xbb" },
+                        },
+                    })
+                    .to_string(),
+                ))
+                .mount(&server)
+                .await;
+            Mock::given(method("POST"))
+                .and(path_regex(r"^/v1/tables/[^/]+/item$"))
+                .respond_with(testing::fullnode_response(
+                    r#"{"to":"0x1","value":"50000","block_num":"101"}"#,
+                ))
+                .mount(&server)
+                .await;
+
+            // Crashed after the mint was submitted but before the state was saved
+            let store = Arc::new(MemoryStore::default());
+            let (peg, txid) = peg();
+            let mut job = PegInJob::new(&txid, 0, "0x1");
+            job.state = PegInState::ProofReady { peg: peg.clone() };
+            store.save(&job).await.unwrap();
+
+            let service = service(&server, store.clone());
+            let event = service.peg_in(&txid, 0, "0x1").await.unwrap();
+            assert_eq!(event.amount, Sats(50_000));
+            assert_eq!(event.btc_block_num, 101);
+            assert_eq!(event.btc_tx_id, hex::encode(&peg.inclusion_proof.tx_id));
+            assert_eq!(
+                store.load(&job.id()).await.unwrap().unwrap().state.stage(),
+                "completed"
+            );
+        }
+
+        #[tokio::test]
+        async fn test_resume_submitted_keeps_state_until_committed() {
+            let server = testing::mock_fullnode().await;
+            let store = Arc::new(MemoryStore::default());
+            let (_, txid) = peg();
+            let mut job = PegInJob::new(&txid, 0, "0x1");
+            job.state = PegInState::Submitted {
+                tx_hash: format!("0x{}", "ab".repeat(32)),
+            };
+            store.save(&job).await.unwrap();
+
+            // Not found on chain within the timeout: still submitted, not reset
+            let service = service(&server, store.clone()).with_commit_timeout(Duration::ZERO);
+            assert!(service.peg_in(&txid, 0, "0x1").await.is_err());
+            assert_eq!(
+                store.load(&job.id()).await.unwrap().unwrap().state.stage(),
+                "submitted"
+            );
+        }
+
+        /// `Mint` event of `peg`, as the bridge emits it
+        fn mint_event(peg: &Peg) -> ContractEvent {
+            let to_address: [u8; 32] = crate::utils::parse_account_address(&peg.to)
+                .unwrap()
+                .into_bytes();
+            let data = bcs::to_bytes(&(
+                to_address,
+                peg.value.to_sat(),
+                peg.inclusion_proof.tx_id.clone(),
+                peg.block_num,
+            ))
+            .unwrap();
+            ContractEvent::new_v2(
+                format!("{}::bridge::Mint", BRIDGE_ADDRESS).parse().unwrap(),
+                data,
+            )
+            .unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_proof_ready_job_mints_and_completes() {
+            let server = testing::mock_fullnode().await;
+            let chain = MockChain::mount(&server, 0).await;
+            let (peg, txid) = peg();
+            chain.emit(0, vec![mint_event(&peg)]);
+            let dir = std::env::temp_dir().join(format!("peg-in-mint-{}", std::process::id()));
+            let store = Arc::new(FilePegInStore::new(&dir));
+            let mut job = PegInJob::new(&txid, 0, "0x1");
+            job.state = PegInState::ProofReady { peg: peg.clone() };
+            store.save(&job).await.unwrap();
+
+            let event = service(&server, store.clone())
+                .peg_in(&txid, 0, "0x1")
+                .await
+                .unwrap();
+            assert_eq!(event.amount, peg.value);
+            assert_eq!(event.btc_tx_id, hex::encode(&peg.inclusion_proof.tx_id));
+            assert_eq!(chain.submitted().len(), 1);
+
+            // A restarted service finds the completed job without minting again
+            let reopened = Arc::new(FilePegInStore::new(&dir));
+            assert_eq!(
+                reopened
+                    .load(&job.id())
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .state
+                    .stage(),
+                "completed"
+            );
+            let resumed = service(&server, reopened)
+                .peg_in(&txid, 0, "0x1")
+                .await
+                .unwrap();
+            assert_eq!(resumed.amount, event.amount);
+            assert_eq!(chain.submitted().len(), 1);
+
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_failed_mint_waits_for_confirmations_again() {
+            let server = testing::mock_fullnode().await;
+            let chain = MockChain::mount(&server, 0).await;
+            chain.abort(0);
+            let store = Arc::new(MemoryStore::default());
+            let (peg, txid) = peg();
+            let mut job = PegInJob::new(&txid, 0, "0x1");
+            job.state = PegInState::ProofReady { peg };
+            store.save(&job).await.unwrap();
+
+            assert!(service(&server, store.clone())
+                .peg_in(&txid, 0, "0x1")
+                .await
+                .is_err());
+            assert_eq!(chain.submitted().len(), 1);
+            assert_eq!(
+                store.load(&job.id()).await.unwrap().unwrap().state.stage(),
+                "awaiting_confirmations"
+            );
+        }
+    }
+}
@@ -0,0 +1,700 @@
+//! Peg-out watcher
+//!
+//! Queues `Burn` and `WithdrawByLP` events as pending BTC payouts, per operator or
+//! LP, and lets the payer mark them fulfilled. Plug the service into an
+//! `EventMonitor` as its event handler.
+
+use crate::btc::{estimate_tx_vbytes, script_hash_for_address, BtcAddress, BtcNetwork};
+use crate::events::EventHandler;
+use crate::json_file::{read_json, write_json_atomic};
+#[cfg(any(feature = "bitcoind", feature = "esplora"))]
+use crate::types::ClaimLPWithdrawParams;
+use crate::types::{BurnEvent, MintEvent, Sats, ScriptType, WithdrawByLPEvent};
+#[cfg(any(feature = "bitcoind", feature = "esplora"))]
+use crate::utils::parse_account_address;
+#[cfg(any(feature = "bitcoind", feature = "esplora"))]
+use crate::{BridgeClient, ProofBuilder};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Party responsible for paying out a withdrawal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PegOutPayer {
+    /// Bridge operator, for burns
+    Operator(u64),
+    /// Liquidity provider, for LP withdrawals
+    LP(u64),
+}
+
+/// Withdrawal awaiting its BTC payout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PegOutRequest {
+    /// Burn served by an operator
+    Burn(BurnEvent),
+    /// Withdrawal served by an LP
+    WithdrawByLP(WithdrawByLPEvent),
+}
+
+impl PegOutRequest {
+    /// Stable key of the request
+    ///
+    /// LP withdrawals are keyed by withdraw id; burns by the Aptos transaction
+    /// (version, or hash if the version is unknown), destination address and
+    /// amount, which the REST and indexer sources both report, so a burn seen
+    /// through either gets the same key. Identical burns in one transaction
+    /// share a key. Burns with neither a version nor a hash can't be told apart
+    /// and have no key.
+    pub fn id(&self) -> Result<String> {
+        match self {
+            PegOutRequest::Burn(event) => {
+                let origin = event
+                    .version
+                    .map(|version| version.to_string())
+                    .or_else(|| event.transaction_hash.clone())
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Burn to {} has no version or transaction hash",
+                            event.btc_address
+                        )
+                    })?;
+                Ok(format!(
+                    "burn:{}:{}:{}",
+                    origin, event.btc_address, event.amount.0
+                ))
+            }
+            PegOutRequest::WithdrawByLP(event) => Ok(format!("lp_withdraw:{}", event.withdraw_id)),
+        }
+    }
+
+    /// Party responsible for the payout
+    pub fn payer(&self) -> PegOutPayer {
+        match self {
+            PegOutRequest::Burn(event) => PegOutPayer::Operator(event.operator_id),
+            PegOutRequest::WithdrawByLP(event) => PegOutPayer::LP(event.lp_id),
+        }
+    }
+
+    /// Destination BTC address
+    pub fn btc_address(&self) -> &str {
+        match self {
+            PegOutRequest::Burn(event) => &event.btc_address,
+            PegOutRequest::WithdrawByLP(event) => &event.btc_address,
+        }
+    }
+
+    /// Withdrawn amount
+    pub fn amount(&self) -> Sats {
+        match self {
+            PegOutRequest::Burn(event) => event.amount,
+            PegOutRequest::WithdrawByLP(event) => event.amount,
+        }
+    }
+
+    /// Requested BTC fee rate (sat/vB)
+    pub fn fee_rate(&self) -> u64 {
+        match self {
+            PegOutRequest::Burn(event) => event.fee_rate,
+            PegOutRequest::WithdrawByLP(event) => event.fee_rate,
+        }
+    }
+
+    /// BTC output the payer must create on `network`
+    ///
+    /// `operator_input` is the script type of the operator wallet funding burn
+    /// payouts; it's unused for LP withdrawals.
+    pub fn expected_payout(
+        &self,
+        network: BtcNetwork,
+        operator_input: ScriptType,
+    ) -> Result<ExpectedPayout> {
+        match self {
+            PegOutRequest::Burn(event) => ExpectedPayout::for_burn(event, network, operator_input),
+            PegOutRequest::WithdrawByLP(event) => {
+                ExpectedPayout::for_withdraw_by_lp(event, network)
+            }
+        }
+    }
+}
+
+/// BTC output a payer must create to fulfil a withdrawal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedPayout {
+    /// Receiver address
+    pub address: BtcAddress,
+    /// Script type of the receiver output
+    pub script_type: ScriptType,
+    /// Hash or witness program committed to by the receiver output script
+    pub script_hash: Vec<u8>,
+    /// Network fee the payer may deduct from the withdrawn amount
+    pub network_fee: Sats,
+    /// Least amount the receiver output must carry
+    pub min_amount: Sats,
+}
+
+impl ExpectedPayout {
+    /// Payout of a burn: the burned amount less the estimated fee of the
+    /// operator's payout at the burn's fee rate
+    ///
+    /// The payout spends one `operator_input` output of the operator wallet and
+    /// creates the receiver output and a change output back to the wallet.
+    pub fn for_burn(
+        event: &BurnEvent,
+        network: BtcNetwork,
+        operator_input: ScriptType,
+    ) -> Result<Self> {
+        let address = BtcAddress::new(&event.btc_address, network)?;
+        // Unknown witness versions are sized like taproot outputs
+        let script_type = address.script_type().unwrap_or(ScriptType::P2TR);
+        let vbytes = estimate_tx_vbytes(&[operator_input], &[script_type, operator_input]);
+        let network_fee = Sats(vbytes.saturating_mul(event.fee_rate));
+        let min_amount = event
+            .amount
+            .checked_sub(network_fee)
+            .filter(|amount| *amount > Sats::ZERO)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Network fee of {} exceeds the burned {}",
+                    network_fee,
+                    event.amount
+                )
+            })?;
+        Self::new(address, script_type, network_fee, min_amount)
+    }
+
+    /// Payout of an LP withdrawal: at least the receiver's minimum
+    pub fn for_withdraw_by_lp(event: &WithdrawByLPEvent, network: BtcNetwork) -> Result<Self> {
+        let address = BtcAddress::new(&event.btc_address, network)?;
+        let script_type = address.script_type().unwrap_or(ScriptType::P2TR);
+        let network_fee = event.amount.saturating_sub(event.receive_min_amount);
+        Self::new(address, script_type, network_fee, event.receive_min_amount)
+    }
+
+    fn new(
+        address: BtcAddress,
+        script_type: ScriptType,
+        network_fee: Sats,
+        min_amount: Sats,
+    ) -> Result<Self> {
+        let script_hash = script_hash_for_address(&address, script_type)?;
+        Ok(Self {
+            address,
+            script_type,
+            script_hash,
+            network_fee,
+            min_amount,
+        })
+    }
+
+    /// Output script the payout must pay to
+    #[cfg(feature = "bitcoin")]
+    pub fn script_pubkey(&self) -> bitcoin::ScriptBuf {
+        self.address.script_pubkey()
+    }
+
+    /// Whether an output paying `amount` to `address` fulfils the payout
+    ///
+    /// Addresses are compared by payload, so encodings of the same script
+    /// (such as upper- and lowercase bech32) match.
+    pub fn is_paid_by(&self, address: &BtcAddress, amount: Sats) -> bool {
+        address.payload() == self.address.payload() && amount >= self.min_amount
+    }
+}
+
+/// Persistent peg-out queue
+///
+/// Fulfilled ids are remembered so replayed events are not queued again.
+#[async_trait]
+pub trait PegOutStore: Send + Sync {
+    /// Queue a request; returns false if it is already pending or fulfilled
+    async fn insert(&self, request: PegOutRequest) -> Result<bool>;
+
+    /// All pending requests
+    async fn pending(&self) -> Result<Vec<PegOutRequest>>;
+
+    /// Mark a pending request fulfilled and return it
+    async fn fulfill(&self, id: &str) -> Result<Option<PegOutRequest>>;
+}
+
+/// Queue state as persisted by `FilePegOutStore`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PegOutQueue {
+    pending: BTreeMap<String, PegOutRequest>,
+    fulfilled: BTreeSet<String>,
+}
+
+/// Store keeping the queue in a single JSON file
+pub struct FilePegOutStore {
+    /// Queue file
+    path: PathBuf,
+    /// Serializes read-modify-write cycles
+    lock: Mutex<()>,
+}
+
+impl FilePegOutStore {
+    /// Create a store backed by `path`, which is created on first write
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    async fn read(&self) -> Result<PegOutQueue> {
+        Ok(read_json(&self.path, "peg-out queue")
+            .await?
+            .unwrap_or_default())
+    }
+
+    async fn write(&self, queue: &PegOutQueue) -> Result<()> {
+        write_json_atomic(&self.path, "peg-out queue", queue).await
+    }
+}
+
+#[async_trait]
+impl PegOutStore for FilePegOutStore {
+    async fn insert(&self, request: PegOutRequest) -> Result<bool> {
+        let _guard = self.lock.lock().await;
+        let mut queue = self.read().await?;
+        let id = request.id()?;
+        if queue.fulfilled.contains(&id) || queue.pending.contains_key(&id) {
+            return Ok(false);
+        }
+        queue.pending.insert(id, request);
+        self.write(&queue).await?;
+        Ok(true)
+    }
+
+    async fn pending(&self) -> Result<Vec<PegOutRequest>> {
+        let _guard = self.lock.lock().await;
+        Ok(self.read().await?.pending.into_values().collect())
+    }
+
+    async fn fulfill(&self, id: &str) -> Result<Option<PegOutRequest>> {
+        let _guard = self.lock.lock().await;
+        let mut queue = self.read().await?;
+        let request = queue.pending.remove(id);
+        if request.is_some() {
+            queue.fulfilled.insert(id.to_string());
+            self.write(&queue).await?;
+        }
+        Ok(request)
+    }
+}
+
+/// Peg-out watcher service
+///
+/// Cheap to clone; pass a clone to `EventMonitor` as its handler and keep one to
+/// query and fulfill the queue.
+#[derive(Clone)]
+pub struct PegOutService {
+    /// Pending withdrawal queue
+    store: Arc<dyn PegOutStore>,
+    /// Payers whose withdrawals are queued, none to queue all
+    payers: Option<HashSet<PegOutPayer>>,
+    /// Client and proof source for `claim_lp_withdraw`
+    #[cfg(any(feature = "bitcoind", feature = "esplora"))]
+    claimer: Option<(Arc<BridgeClient>, ProofBuilder)>,
+}
+
+impl PegOutService {
+    /// Create a service queuing withdrawals in `store`
+    pub fn new(store: Arc<dyn PegOutStore>) -> Self {
+        Self {
+            store,
+            payers: None,
+            #[cfg(any(feature = "bitcoind", feature = "esplora"))]
+            claimer: None,
+        }
+    }
+
+    /// Only queue withdrawals for `payer`; call once per operator/LP served
+    pub fn with_payer(mut self, payer: PegOutPayer) -> Self {
+        self.payers.get_or_insert_with(HashSet::new).insert(payer);
+        self
+    }
+
+    /// Enable `claim_lp_withdraw` using this client and proof source
+    #[cfg(any(feature = "bitcoind", feature = "esplora"))]
+    pub fn with_claimer(
+        mut self,
+        bridge_client: Arc<BridgeClient>,
+        proof_builder: ProofBuilder,
+    ) -> Self {
+        self.claimer = Some((bridge_client, proof_builder));
+        self
+    }
+
+    /// All pending withdrawals
+    pub async fn pending(&self) -> Result<Vec<PegOutRequest>> {
+        self.store.pending().await
+    }
+
+    /// Pending withdrawals for one payer
+    pub async fn pending_for(&self, payer: PegOutPayer) -> Result<Vec<PegOutRequest>> {
+        Ok(self
+            .store
+            .pending()
+            .await?
+            .into_iter()
+            .filter(|request| request.payer() == payer)
+            .collect())
+    }
+
+    /// Mark a withdrawal paid out and remove it from the queue
+    pub async fn mark_fulfilled(&self, id: &str) -> Result<PegOutRequest> {
+        self.store
+            .fulfill(id)
+            .await?
+            .ok_or_else(|| anyhow!("No pending peg-out request {}", id))
+    }
+
+    /// Claim an LP withdrawal paid out by output `vout` of `btc_txid`, then mark it fulfilled
+    ///
+    /// The output must pay the withdrawal's BTC address at least its minimum
+    /// amount. Returns the `claim_lp_withdraw` transaction hash.
+    #[cfg(any(feature = "bitcoind", feature = "esplora"))]
+    pub async fn claim_lp_withdraw(&self, id: &str, btc_txid: &str, vout: u32) -> Result<String> {
+        let (bridge_client, proof_builder) = self
+            .claimer
+            .as_ref()
+            .ok_or_else(|| anyhow!("PegOutService has no claimer configured"))?;
+
+        let withdraw = match self
+            .store
+            .pending()
+            .await?
+            .into_iter()
+            .find(|r| r.id().is_ok_and(|r_id| r_id == id))
+        {
+            Some(PegOutRequest::WithdrawByLP(event)) => event,
+            Some(_) => return Err(anyhow!("Peg-out request {} is not an LP withdrawal", id)),
+            None => return Err(anyhow!("No pending peg-out request {}", id)),
+        };
+
+        // The on-chain record is what the contract checks the payment against
+        let lp_withdraw = bridge_client.get_lp_withdraw(withdraw.withdraw_id).await?;
+        if !same_address(&lp_withdraw.receiver_addr, &withdraw.btc_address) {
+            return Err(anyhow!(
+                "Withdrawal {} pays {} on chain, not {}",
+                withdraw.withdraw_id,
+                lp_withdraw.receiver_addr,
+                withdraw.btc_address
+            ));
+        }
+        let params =
+            ClaimLPWithdrawParams::from_btc_payment(&lp_withdraw, btc_txid, vout, proof_builder)
+                .await?;
+
+        let tx_hash = bridge_client.claim_lp_withdraw(params).await?;
+        self.mark_fulfilled(id).await?;
+        Ok(tx_hash)
+    }
+
+    /// Queue a request if its payer is served
+    async fn enqueue(&self, request: PegOutRequest) -> Result<()> {
+        if let Some(payers) = &self.payers {
+            if !payers.contains(&request.payer()) {
+                return Ok(());
+            }
+        }
+
+        let _id = request.id()?;
+        if self.store.insert(request).await? {
+            #[cfg(feature = "tracing")]
+            tracing::info!(peg_out = %_id, "Queued peg-out request");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventHandler for PegOutService {
+    async fn handle_mint(&self, _event: MintEvent) -> Result<()> {
+        Ok(())
+    }
+
+    async fn handle_burn(&self, event: BurnEvent) -> Result<()> {
+        self.enqueue(PegOutRequest::Burn(event)).await
+    }
+
+    async fn handle_withdraw_by_lp(&self, event: WithdrawByLPEvent) -> Result<()> {
+        self.enqueue(PegOutRequest::WithdrawByLP(event)).await
+    }
+}
+
+/// Whether two addresses are the same; hex addresses are compared as account
+/// addresses, so `0x1` matches its zero-padded form
+#[cfg(any(feature = "bitcoind", feature = "esplora"))]
+fn same_address(a: &str, b: &str) -> bool {
+    match (parse_account_address(a), parse_account_address(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn withdraw_event(withdraw_id: u64, lp_id: u64) -> WithdrawByLPEvent {
+        WithdrawByLPEvent {
+            from_address: "0x1".to_string(),
+            withdraw_id,
+            btc_address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+            fee_rate: 10,
+            amount: Sats(500000),
+            lp_id,
+            receive_min_amount: Sats(450000),
+            timestamp: None,
+            version: Some(42),
+            transaction_hash: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queue_per_payer_and_fulfill() {
+        let path = std::env::temp_dir().join(format!("peg-out-{}.json", std::process::id()));
+        let service = PegOutService::new(Arc::new(FilePegOutStore::new(&path)))
+            .with_payer(PegOutPayer::LP(1));
+
+        service
+            .handle_withdraw_by_lp(withdraw_event(7, 1))
+            .await
+            .unwrap();
+        service
+            .handle_withdraw_by_lp(withdraw_event(8, 2))
+            .await
+            .unwrap();
+        // Replayed event is not queued twice
+        service
+            .handle_withdraw_by_lp(withdraw_event(7, 1))
+            .await
+            .unwrap();
+
+        let pending = service.pending_for(PegOutPayer::LP(1)).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id().unwrap(), "lp_withdraw:7");
+        assert!(service
+            .pending_for(PegOutPayer::LP(2))
+            .await
+            .unwrap()
+            .is_empty());
+
+        service.mark_fulfilled("lp_withdraw:7").await.unwrap();
+        assert!(service.pending().await.unwrap().is_empty());
+        assert!(service.mark_fulfilled("lp_withdraw:7").await.is_err());
+
+        // Fulfilled requests stay fulfilled across replays and restarts
+        let service = PegOutService::new(Arc::new(FilePegOutStore::new(&path)));
+        service
+            .handle_withdraw_by_lp(withdraw_event(7, 1))
+            .await
+            .unwrap();
+        assert!(service.pending().await.unwrap().is_empty());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_expected_payout_for_burn() {
+        let event = BurnEvent {
+            from_address: "0x1".to_string(),
+            btc_address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+            fee_rate: 10,
+            amount: Sats(500000),
+            operator_id: 1,
+            timestamp: None,
+            version: Some(42),
+            transaction_hash: None,
+        };
+        let payout = PegOutRequest::Burn(event.clone())
+            .expected_payout(BtcNetwork::Mainnet, ScriptType::P2WPKH)
+            .unwrap();
+        assert_eq!(payout.script_type, ScriptType::P2WPKH);
+        // 1 P2WPKH input, P2WPKH receiver and change outputs: 141 vB at 10 sat/vB
+        assert_eq!(payout.network_fee, Sats(1410));
+        assert_eq!(
+            payout.min_amount,
+            Sats(500000).saturating_sub(payout.network_fee)
+        );
+        let address = BtcAddress::new(&event.btc_address, BtcNetwork::Mainnet).unwrap();
+        assert!(payout.is_paid_by(&address, payout.min_amount));
+        assert!(!payout.is_paid_by(&address, Sats(payout.min_amount.0 - 1)));
+        let uppercase =
+            BtcAddress::new(&event.btc_address.to_uppercase(), BtcNetwork::Mainnet).unwrap();
+        assert!(payout.is_paid_by(&uppercase, payout.min_amount));
+        let other = BtcAddress::new(
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            BtcNetwork::Mainnet,
+        )
+        .unwrap();
+        assert!(!payout.is_paid_by(&other, payout.min_amount));
+
+        // The fee follows the operator wallet's input type, not the receiver's
+        let taproot_operator = PegOutRequest::Burn(event.clone())
+            .expected_payout(BtcNetwork::Mainnet, ScriptType::P2TR)
+            .unwrap();
+        assert_eq!(taproot_operator.script_type, ScriptType::P2WPKH);
+        assert_eq!(
+            taproot_operator.network_fee,
+            Sats(
+                estimate_tx_vbytes(&[ScriptType::P2TR], &[ScriptType::P2WPKH, ScriptType::P2TR])
+                    * 10
+            )
+        );
+        let to_wsh = BurnEvent {
+            btc_address: "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3"
+                .to_string(),
+            ..event.clone()
+        };
+        let payout =
+            ExpectedPayout::for_burn(&to_wsh, BtcNetwork::Mainnet, ScriptType::P2WPKH).unwrap();
+        assert_eq!(payout.script_type, ScriptType::P2WSH);
+        assert_eq!(
+            payout.network_fee,
+            Sats(
+                estimate_tx_vbytes(
+                    &[ScriptType::P2WPKH],
+                    &[ScriptType::P2WSH, ScriptType::P2WPKH]
+                ) * 10
+            )
+        );
+
+        let dust = BurnEvent {
+            amount: Sats(100),
+            ..event
+        };
+        assert!(ExpectedPayout::for_burn(&dust, BtcNetwork::Mainnet, ScriptType::P2WPKH).is_err());
+
+        let payout = PegOutRequest::WithdrawByLP(withdraw_event(7, 1))
+            .expected_payout(BtcNetwork::Mainnet, ScriptType::P2WPKH)
+            .unwrap();
+        assert_eq!(payout.min_amount, Sats(450000));
+    }
+
+    #[cfg(any(feature = "bitcoind", feature = "esplora"))]
+    #[test]
+    fn test_same_address_ignores_hex_padding() {
+        assert!(same_address(
+            "0x1",
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        ));
+        assert!(!same_address("0x1", "0x2"));
+        let btc_address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        assert!(same_address(btc_address, btc_address));
+        assert!(!same_address(btc_address, "0x1"));
+    }
+
+    #[cfg(all(feature = "esplora", feature = "test-utils"))]
+    mod claim {
+        use super::*;
+        use crate::testing::{self, MockChain};
+        use bitcoin::hashes::Hash;
+        use wiremock::MockServer;
+
+        /// Service claiming through `fullnode`, with the fixture withdrawal 7 queued
+        async fn service(
+            fullnode: &MockServer,
+            esplora: &MockServer,
+            path: &std::path::Path,
+        ) -> PegOutService {
+            let _ = std::fs::remove_file(path);
+            let service = PegOutService::new(Arc::new(FilePegOutStore::new(path))).with_claimer(
+                Arc::new(testing::bridge_client(fullnode)),
+                ProofBuilder::esplora(&esplora.uri()),
+            );
+            let withdraw = WithdrawByLPEvent {
+                btc_address: "bcrt1phcnl4zcl2fu047pv4wx6y058v8u0n02at6lthvm7pcf2wrvjm5tqatn90k"
+                    .to_string(),
+                ..withdraw_event(7, 1)
+            };
+            service.handle_withdraw_by_lp(withdraw).await.unwrap();
+            service
+        }
+
+        /// Transaction paying `amount` to `script_pubkey`, mined in a block at 800000
+        async fn mine_payment(
+            esplora: &MockServer,
+            script_pubkey: bitcoin::ScriptBuf,
+            amount: u64,
+        ) -> bitcoin::Txid {
+            let payment = bitcoin::Transaction {
+                version: bitcoin::transaction::Version::TWO,
+                lock_time: bitcoin::absolute::LockTime::ZERO,
+                input: vec![bitcoin::TxIn::default()],
+                output: vec![bitcoin::TxOut {
+                    value: bitcoin::Amount::from_sat(amount),
+                    script_pubkey,
+                }],
+            };
+            let block = testing::btc_block(bitcoin::BlockHash::all_zeros(), vec![payment.clone()]);
+            testing::mount_esplora_block(esplora, 800_000, &block).await;
+            payment.compute_txid()
+        }
+
+        #[tokio::test]
+        async fn test_claim_lp_withdraw_submits_claim_and_fulfills() {
+            let fullnode = testing::mock_fullnode().await;
+            let chain = MockChain::mount(&fullnode, 0).await;
+            let esplora = MockServer::start().await;
+            let path =
+                std::env::temp_dir().join(format!("peg-out-claimed-{}.json", std::process::id()));
+            let service = service(&fullnode, &esplora, &path).await;
+            let receiver = BtcAddress::new(
+                "bcrt1phcnl4zcl2fu047pv4wx6y058v8u0n02at6lthvm7pcf2wrvjm5tqatn90k",
+                BtcNetwork::Regtest,
+            )
+            .unwrap();
+            let txid = mine_payment(&esplora, receiver.script_pubkey(), 100_000).await;
+
+            let tx_hash = service
+                .claim_lp_withdraw("lp_withdraw:7", &txid.to_string(), 0)
+                .await
+                .unwrap();
+            assert!(chain.committed(&tx_hash).is_some());
+            assert!(service.pending().await.unwrap().is_empty());
+
+            // Claimed once: a second claim has nothing pending
+            assert!(service
+                .claim_lp_withdraw("lp_withdraw:7", &txid.to_string(), 0)
+                .await
+                .is_err());
+            assert_eq!(chain.submitted().len(), 1);
+
+            std::fs::remove_file(&path).unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_claim_lp_withdraw_rejects_payment_to_other_script() {
+            let fullnode = testing::mock_fullnode().await;
+            let chain = MockChain::mount(&fullnode, 0).await;
+            let esplora = MockServer::start().await;
+            let path =
+                std::env::temp_dir().join(format!("peg-out-claim-{}.json", std::process::id()));
+            let service = service(&fullnode, &esplora, &path).await;
+
+            // Pays the fixture withdrawal's amount, but to a P2WPKH script
+            let txid = mine_payment(
+                &esplora,
+                bitcoin::ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::from_byte_array([5; 20])),
+                100_000,
+            )
+            .await;
+
+            let err = service
+                .claim_lp_withdraw("lp_withdraw:7", &txid.to_string(), 0)
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("does not pay receiver"), "{}", err);
+            assert_eq!(service.pending().await.unwrap().len(), 1);
+            assert!(chain.submitted().is_empty());
+
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,506 @@
+//! Pipelined transaction submission
+//!
+//! [`SubmissionPipeline`] keeps up to `window` transactions in flight, signed
+//! with consecutive sequence numbers, instead of waiting for each to commit
+//! before signing the next. When one is rejected or expires, nothing new is
+//! signed until the in-flight transactions settle (a rejected transaction
+//! settles when it expires); the on-chain sequence number is then re-read and
+//! the uncommitted payloads are re-signed from there.
+//!
+//! Node errors don't abort a run: a payload that can't be signed is retried
+//! until it runs out of attempts, and if the chain ID or the account's sequence
+//! number can't be read, the payloads left fail with that error.
+//!
+//! Unlike [`TxQueue`](crate::TxQueue), the pipeline keeps its state in memory.
+//! Run one pipeline per signing account, and don't submit other transactions
+//! from that account while it runs.
+
+use crate::abort::execution_error;
+use crate::time::unix_timestamp_secs;
+use crate::types::constants::{
+    PIPELINE_EXPIRATION_MARGIN_SECS, PIPELINE_POLL_INTERVAL_MS, PIPELINE_WINDOW,
+    TX_QUEUE_MAX_ATTEMPTS,
+};
+use crate::types::Peg;
+use crate::BridgeClient;
+use anyhow::{anyhow, Result};
+use aptos_sdk::rest_client::aptos_api_types::{TransactionData, TransactionOnChainData};
+use aptos_sdk::types::transaction::TransactionPayload;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Fate of one pipelined payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipelineOutcome {
+    /// Committed successfully
+    Committed { tx_hash: String, version: u64 },
+    /// Committed with a failure, rejected before signing, or out of attempts
+    Failed {
+        tx_hash: Option<String>,
+        error: String,
+    },
+}
+
+/// Submits transactions ahead of confirmation
+pub struct SubmissionPipeline {
+    /// Client signing and submitting the transactions
+    bridge_client: Arc<BridgeClient>,
+    /// Transactions in flight at once
+    window: usize,
+    /// Transactions signed per payload before it is marked failed
+    max_attempts: u32,
+    /// Delay between checks of in-flight transactions
+    poll_interval: Duration,
+}
+
+/// Payload waiting to be signed
+struct Job {
+    index: usize,
+    payload: TransactionPayload,
+    attempts: u32,
+    last_error: Option<String>,
+}
+
+/// Signed and submitted transaction
+struct InFlight {
+    job: Job,
+    sequence_number: u64,
+    tx_hash: String,
+    expiration_timestamp_secs: u64,
+}
+
+impl SubmissionPipeline {
+    /// Create a pipeline sending through `bridge_client`
+    pub fn new(bridge_client: Arc<BridgeClient>) -> Self {
+        Self {
+            bridge_client,
+            window: PIPELINE_WINDOW,
+            max_attempts: TX_QUEUE_MAX_ATTEMPTS,
+            poll_interval: Duration::from_millis(PIPELINE_POLL_INTERVAL_MS),
+        }
+    }
+
+    /// Set how many transactions may be in flight at once (at least one)
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Set how many transactions are signed for a payload before it fails
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay between checks of in-flight transactions
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Mint every peg; outcomes are in `pegs` order
+    ///
+    /// Pegs failing validation or already minted fail without a transaction.
+    pub async fn mint(&self, pegs: &[Peg]) -> Result<Vec<PipelineOutcome>> {
+        let mut payloads = Vec::with_capacity(pegs.len());
+        for peg in pegs {
+            payloads.push(self.mint_payload(peg).await);
+        }
+        self.run(payloads).await
+    }
+
+    /// Submit every payload; outcomes are in `payloads` order
+    pub async fn submit(&self, payloads: Vec<TransactionPayload>) -> Result<Vec<PipelineOutcome>> {
+        self.run(payloads.into_iter().map(Ok).collect()).await
+    }
+
+    async fn mint_payload(&self, peg: &Peg) -> Result<TransactionPayload> {
+        #[cfg(feature = "bitcoin")]
+        peg.validate()?;
+        self.bridge_client.check_not_minted(peg, &[]).await?;
+        self.bridge_client.payloads().mint(peg, vec![])
+    }
+
+    async fn run(&self, payloads: Vec<Result<TransactionPayload>>) -> Result<Vec<PipelineOutcome>> {
+        let mut outcomes: Vec<Option<PipelineOutcome>> = vec![None; payloads.len()];
+        let mut pending = VecDeque::new();
+        for (index, payload) in payloads.into_iter().enumerate() {
+            match payload {
+                Ok(payload) => pending.push_back(Job {
+                    index,
+                    payload,
+                    attempts: 0,
+                    last_error: None,
+                }),
+                Err(e) => {
+                    outcomes[index] = Some(PipelineOutcome::Failed {
+                        tx_hash: None,
+                        error: e.to_string(),
+                    })
+                }
+            }
+        }
+
+        // Every transaction of the run is signed for the same chain
+        let start = async {
+            let chain_id = self.bridge_client.chain_id().await?;
+            let sequence_number = self.bridge_client.sequence_number().await?;
+            Ok::<_, anyhow::Error>((chain_id, sequence_number))
+        };
+        let (chain_id, mut next_sequence_number) = match start.await {
+            Ok(start) => start,
+            Err(e) => {
+                fail_unresolved(&mut outcomes, &e);
+                return collect_outcomes(outcomes);
+            }
+        };
+        let mut in_flight: Vec<InFlight> = Vec::new();
+        let mut expired: Vec<InFlight> = Vec::new();
+        // Set when a sequence number may have been left unused, which blocks
+        // every later transaction until it expires
+        let mut resync = false;
+
+        while !pending.is_empty() || !in_flight.is_empty() {
+            while !resync && in_flight.len() < self.window {
+                let Some(mut job) = pending.pop_front() else {
+                    break;
+                };
+                if job.attempts >= self.max_attempts {
+                    outcomes[job.index] = Some(PipelineOutcome::Failed {
+                        tx_hash: None,
+                        error: format!(
+                            "Gave up after {} attempts: {}",
+                            job.attempts,
+                            job.last_error.as_deref().unwrap_or("unknown error")
+                        ),
+                    });
+                    continue;
+                }
+
+                let signed_transaction = match self
+                    .bridge_client
+                    .sign_payload_on_chain(job.payload.clone(), next_sequence_number, chain_id)
+                    .await
+                {
+                    Ok(signed_transaction) => signed_transaction,
+                    Err(e) => {
+                        // Nothing used the sequence number; retry after the next poll
+                        job.attempts += 1;
+                        job.last_error = Some(e.to_string());
+                        pending.push_front(job);
+                        break;
+                    }
+                };
+                job.attempts += 1;
+                if let Err(e) = self.bridge_client.submit_signed(&signed_transaction).await {
+                    // The node may still have accepted it; it resolves like the others
+                    job.last_error = Some(e.to_string());
+                    resync = true;
+                }
+                in_flight.push(InFlight {
+                    job,
+                    sequence_number: next_sequence_number,
+                    tx_hash: signed_transaction.committed_hash().to_hex_literal(),
+                    expiration_timestamp_secs: signed_transaction.expiration_timestamp_secs(),
+                });
+                next_sequence_number += 1;
+            }
+
+            crate::time::sleep(self.poll_interval).await;
+
+            let mut still_in_flight = Vec::with_capacity(in_flight.len());
+            for flight in in_flight.drain(..) {
+                match self
+                    .bridge_client
+                    .get_transaction_by_hash(&flight.tx_hash)
+                    .await
+                {
+                    Ok(TransactionData::OnChain(txn)) => {
+                        outcomes[flight.job.index] = Some(committed_outcome(&flight.tx_hash, &txn));
+                    }
+                    _ if unix_timestamp_secs()
+                        > flight.expiration_timestamp_secs + PIPELINE_EXPIRATION_MARGIN_SECS =>
+                    {
+                        resync = true;
+                        expired.push(flight);
+                    }
+                    _ => still_in_flight.push(flight),
+                }
+            }
+            in_flight = still_in_flight;
+
+            if resync && in_flight.is_empty() {
+                next_sequence_number = match self.bridge_client.sequence_number().await {
+                    Ok(sequence_number) => sequence_number,
+                    Err(e) => {
+                        // Without it, the expired transactions can't be told
+                        // apart from committed ones or re-signed
+                        for flight in expired.drain(..) {
+                            outcomes[flight.job.index] = Some(PipelineOutcome::Failed {
+                                tx_hash: Some(flight.tx_hash),
+                                error: e.to_string(),
+                            });
+                        }
+                        fail_unresolved(&mut outcomes, &e);
+                        return collect_outcomes(outcomes);
+                    }
+                };
+                #[cfg(feature = "tracing")]
+                tracing::info!(
+                    expired = expired.len(),
+                    sequence_number = next_sequence_number,
+                    "Resynchronizing submission pipeline"
+                );
+
+                expired.sort_by_key(|flight| flight.sequence_number);
+                let mut requeued = Vec::new();
+                for flight in expired.drain(..) {
+                    if flight.sequence_number >= next_sequence_number {
+                        requeued.push(flight.job);
+                        continue;
+                    }
+                    // The sequence number was used: by this transaction after
+                    // our last check, or by another one
+                    outcomes[flight.job.index] = Some(
+                        match self
+                            .bridge_client
+                            .get_transaction_by_hash(&flight.tx_hash)
+                            .await
+                        {
+                            Ok(TransactionData::OnChain(txn)) => {
+                                committed_outcome(&flight.tx_hash, &txn)
+                            }
+                            _ => PipelineOutcome::Failed {
+                                tx_hash: Some(flight.tx_hash),
+                                error: format!(
+                                    "Sequence number {} was used by another transaction",
+                                    flight.sequence_number
+                                ),
+                            },
+                        },
+                    );
+                }
+                // Re-signed ahead of payloads not yet sent, in their original order
+                for job in requeued.into_iter().rev() {
+                    pending.push_front(job);
+                }
+                resync = false;
+            }
+        }
+
+        collect_outcomes(outcomes)
+    }
+}
+
+/// Fail every payload without an outcome with `error`
+fn fail_unresolved(outcomes: &mut [Option<PipelineOutcome>], error: &anyhow::Error) {
+    for outcome in outcomes.iter_mut().filter(|outcome| outcome.is_none()) {
+        *outcome = Some(PipelineOutcome::Failed {
+            tx_hash: None,
+            error: error.to_string(),
+        });
+    }
+}
+
+fn collect_outcomes(outcomes: Vec<Option<PipelineOutcome>>) -> Result<Vec<PipelineOutcome>> {
+    outcomes
+        .into_iter()
+        .enumerate()
+        .map(|(index, outcome)| {
+            outcome.ok_or_else(|| {
+                anyhow!(
+                    "Submission pipeline finished without an outcome for payload {}",
+                    index
+                )
+            })
+        })
+        .collect()
+}
+
+fn committed_outcome(tx_hash: &str, txn: &TransactionOnChainData) -> PipelineOutcome {
+    let status = txn.info.status();
+    if status.is_success() {
+        PipelineOutcome::Committed {
+            tx_hash: tx_hash.to_string(),
+            version: txn.version,
+        }
+    } else {
+        PipelineOutcome::Failed {
+            tx_hash: Some(tx_hash.to_string()),
+            error: execution_error("Transaction", tx_hash, status).to_string(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::config::{GasPriority, GasSettings};
+    use crate::testing::{self, MockChain};
+    use crate::types::constants::EXPIRATION_TIMESTAMP_SECS;
+    use aptos_sdk::types::transaction::Script;
+    use wiremock::matchers::{method, path, path_regex};
+    use wiremock::{Mock, ResponseTemplate};
+
+    fn pipeline(bridge_client: BridgeClient) -> SubmissionPipeline {
+        SubmissionPipeline::new(Arc::new(bridge_client))
+            .with_window(3)
+            .with_poll_interval(Duration::from_millis(10))
+    }
+
+    fn payload() -> TransactionPayload {
+        TransactionPayload::Script(Script::new(vec![], vec![], vec![]))
+    }
+
+    fn committed_version(outcome: &PipelineOutcome) -> u64 {
+        match outcome {
+            PipelineOutcome::Committed { version, .. } => *version,
+            PipelineOutcome::Failed { error, .. } => panic!("payload failed: {}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_commits_in_payload_order() {
+        let server = testing::mock_fullnode().await;
+        let chain = MockChain::mount(&server, 5).await;
+
+        let outcomes = pipeline(testing::bridge_client(&server))
+            .with_window(2)
+            .submit(vec![payload(); 4])
+            .await
+            .unwrap();
+
+        let versions: Vec<u64> = outcomes.iter().map(committed_version).collect();
+        assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+        let sequence_numbers: Vec<u64> = chain
+            .submitted()
+            .iter()
+            .map(|txn| txn.sequence_number())
+            .collect();
+        assert_eq!(sequence_numbers, [5, 6, 7, 8]);
+        assert_eq!(chain.sequence_number(), 9);
+    }
+
+    #[tokio::test]
+    async fn test_resigns_after_sequence_number_gap() {
+        let server = testing::mock_fullnode().await;
+        let chain = MockChain::mount(&server, 5).await;
+        // 6 never reaches the chain, so 7 waits behind it until both expire
+        chain.lose(6);
+
+        let pipeline = pipeline(testing::bridge_client(&server));
+        let expire = async {
+            while chain.submitted().len() < 3 {
+                crate::time::sleep(Duration::from_millis(5)).await;
+            }
+            assert_eq!(chain.sequence_number(), 6);
+            chain.expire();
+            crate::time::advance(EXPIRATION_TIMESTAMP_SECS + PIPELINE_EXPIRATION_MARGIN_SECS + 1);
+        };
+        let (outcomes, ()) = tokio::join!(pipeline.submit(vec![payload(); 3]), expire);
+
+        let versions: Vec<u64> = outcomes.unwrap().iter().map(committed_version).collect();
+        assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+        let submitted = chain.submitted();
+        let sequence_numbers: Vec<u64> =
+            submitted.iter().map(|txn| txn.sequence_number()).collect();
+        assert_eq!(sequence_numbers, [5, 6, 7, 6, 7]);
+        // The payloads committed under their re-signed transactions
+        for txn in &submitted[3..] {
+            assert!(chain
+                .committed(&txn.committed_hash().to_hex_literal())
+                .is_some());
+        }
+        assert_eq!(chain.sequence_number(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_partial_failure_keeps_other_outcomes() {
+        let server = testing::mock_fullnode().await;
+        let chain = MockChain::mount(&server, 5).await;
+        // The third payload is signed with 6, after the one rejected up front
+        chain.abort(6);
+
+        let outcomes = pipeline(testing::bridge_client(&server))
+            .run(vec![
+                Ok(payload()),
+                Err(anyhow!("Peg was already minted")),
+                Ok(payload()),
+                Ok(payload()),
+            ])
+            .await
+            .unwrap();
+
+        committed_version(&outcomes[0]);
+        assert_eq!(
+            outcomes[1],
+            PipelineOutcome::Failed {
+                tx_hash: None,
+                error: "Peg was already minted".to_string(),
+            }
+        );
+        let aborted = chain.submitted()[1].committed_hash().to_hex_literal();
+        assert!(matches!(
+            &outcomes[2],
+            PipelineOutcome::Failed { tx_hash: Some(tx_hash), .. } if *tx_hash == aborted
+        ));
+        committed_version(&outcomes[3]);
+        assert_eq!(chain.sequence_number(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_sign_errors_are_retried_per_payload() {
+        let server = testing::mock_fullnode().await;
+        let chain = MockChain::mount(&server, 5).await;
+        // The gas estimate read while signing fails twice
+        Mock::given(method("GET"))
+            .and(path("/v1/estimate_gas_price"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        testing::mount_gas_estimate(&server, serde_json::json!({"gas_estimate": 100})).await;
+        let bridge_client = testing::bridge_client(&server).with_gas_settings(GasSettings {
+            priority: Some(GasPriority::Market),
+            ..GasSettings::default()
+        });
+
+        let outcomes = pipeline(bridge_client)
+            .with_max_attempts(3)
+            .submit(vec![payload(); 2])
+            .await
+            .unwrap();
+
+        outcomes.iter().for_each(|outcome| {
+            committed_version(outcome);
+        });
+        assert_eq!(chain.submitted()[0].gas_unit_price(), 100);
+        assert_eq!(chain.sequence_number(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_sequence_number_fails_payloads() {
+        let server = testing::mock_fullnode().await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"^/v1/accounts/[^/]+$"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let outcomes = pipeline(testing::bridge_client(&server))
+            .submit(vec![payload(); 2])
+            .await
+            .unwrap();
+
+        assert_eq!(outcomes.len(), 2);
+        for outcome in outcomes {
+            assert!(matches!(
+                outcome,
+                PipelineOutcome::Failed { tx_hash: None, error }
+                    if error.contains("Failed to get sequence number")
+            ));
+        }
+    }
+}
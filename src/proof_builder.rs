@@ -0,0 +1,566 @@
+//! Bitcoin proof builder
+//!
+//! Builds `TxProof`s and ready-to-submit `Peg`s for confirmed BTC transactions by
+//! querying a Bitcoin Core node over JSON-RPC (`bitcoind` feature) or an
+//! Esplora/Electrs HTTP API (`esplora` feature).
+
+#[cfg(feature = "bitcoind")]
+use crate::bitcoind::BitcoindRpc;
+use crate::btc::script_hash_for_output;
+use crate::types::{ClaimLPWithdrawParams, LPWithdraw, Peg, Sats, TxProof};
+#[cfg(feature = "esplora")]
+use crate::utils::decode_hex;
+use anyhow::{anyhow, Result};
+use bitcoin::block::Header;
+use bitcoin::hashes::{Hash, HashEngine};
+use bitcoin::{Block, Transaction, TxMerkleNode, Txid};
+#[cfg(feature = "esplora")]
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// Esplora `/tx/:txid/status` response
+#[cfg(feature = "esplora")]
+#[derive(Debug, Deserialize)]
+struct EsploraTxStatus {
+    confirmed: bool,
+    block_height: Option<u64>,
+    block_hash: Option<String>,
+}
+
+/// Esplora `/tx/:txid/merkle-proof` response
+#[cfg(feature = "esplora")]
+#[derive(Debug, Deserialize)]
+struct EsploraMerkleProof {
+    /// Sibling hashes in display byte order, leaf to root
+    merkle: Vec<String>,
+    pos: u64,
+}
+
+/// Source of block and transaction data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProofBackend {
+    /// Bitcoin Core JSON-RPC
+    #[cfg(feature = "bitcoind")]
+    Bitcoind,
+    /// Esplora/Electrs HTTP API
+    #[cfg(feature = "esplora")]
+    Esplora,
+}
+
+/// Confirmed transaction with the data needed for its proof
+struct ConfirmedTx {
+    header: Header,
+    tx: Transaction,
+    merkle_branch: Vec<TxMerkleNode>,
+    tx_index: u64,
+    height: u64,
+}
+
+impl ConfirmedTx {
+    fn tx_proof(&self) -> TxProof {
+        TxProof::from_bitcoin(&self.header, &self.tx, &self.merkle_branch, self.tx_index)
+    }
+}
+
+/// Proof builder backed by a Bitcoin Core node or an Esplora/Electrs server
+#[derive(Debug, Clone)]
+pub struct ProofBuilder {
+    /// Data source
+    backend: ProofBackend,
+    /// Bitcoin Core RPC URL or Esplora API base URL
+    url: String,
+    /// HTTP basic auth username and password
+    basic_auth: Option<(String, String)>,
+    /// HTTP client
+    http_client: reqwest::Client,
+}
+
+impl ProofBuilder {
+    /// Create a proof builder for a Bitcoin Core RPC endpoint
+    ///
+    /// Looking up arbitrary transactions requires the node to run with `-txindex`.
+    #[cfg(feature = "bitcoind")]
+    pub fn bitcoind(rpc_url: &str) -> Self {
+        Self::with_backend(ProofBackend::Bitcoind, rpc_url)
+    }
+
+    /// Create a proof builder for an Esplora/Electrs HTTP API,
+    /// e.g. `https://blockstream.info/api`
+    #[cfg(feature = "esplora")]
+    pub fn esplora(base_url: &str) -> Self {
+        Self::with_backend(ProofBackend::Esplora, base_url.trim_end_matches('/'))
+    }
+
+    fn with_backend(backend: ProofBackend, url: &str) -> Self {
+        Self {
+            backend,
+            url: url.to_string(),
+            basic_auth: None,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// Authenticate requests with HTTP basic auth (bitcoind `rpcuser`/`rpcpassword`)
+    pub fn with_basic_auth(mut self, user: &str, password: &str) -> Self {
+        self.basic_auth = Some((user.to_string(), password.to_string()));
+        self
+    }
+
+    /// Use a preconfigured HTTP client (proxies, TLS settings, timeouts)
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Build the inclusion proof for a confirmed transaction
+    ///
+    /// `txid` is in display (explorer) byte order. Returns the proof and the
+    /// height of the block containing the transaction.
+    pub async fn build_tx_proof(&self, txid: &str) -> Result<(TxProof, u64)> {
+        let confirmed = self.fetch_confirmed_tx(txid).await?;
+        Ok((confirmed.tx_proof(), confirmed.height))
+    }
+
+    /// Build a `Peg` for output `vout` of a confirmed deposit transaction
+    ///
+    /// The value, destination script hash and script type are taken from the
+    /// output itself.
+    pub async fn build_peg(&self, txid: &str, vout: u32, aptos_recipient: &str) -> Result<Peg> {
+        let confirmed = self.fetch_confirmed_tx(txid).await?;
+        let output = confirmed
+            .tx
+            .output
+            .get(vout as usize)
+            .ok_or_else(|| anyhow!("Transaction {} has no output {}", txid, vout))?;
+        let (dest_script_hash, script_type) = script_hash_for_output(&output.script_pubkey)?;
+
+        Ok(Peg {
+            to: aptos_recipient.to_string(),
+            value: Sats(output.value.to_sat()),
+            block_num: confirmed.height,
+            inclusion_proof: confirmed.tx_proof(),
+            tx_out_ix: vout as u64,
+            dest_script_hash,
+            script_type,
+        })
+    }
+
+    /// Build `claim_lp_withdraw` parameters for the payout in output `vout` of `txid`
+    pub async fn build_claim(
+        &self,
+        withdraw_id: u64,
+        txid: &str,
+        vout: u32,
+    ) -> Result<ClaimLPWithdrawParams> {
+        let confirmed = self.fetch_confirmed_tx(txid).await?;
+        let output = confirmed
+            .tx
+            .output
+            .get(vout as usize)
+            .ok_or_else(|| anyhow!("Transaction {} has no output {}", txid, vout))?;
+
+        Ok(ClaimLPWithdrawParams {
+            withdraw_id,
+            block_num: confirmed.height,
+            tx_out_ix: vout as u64,
+            amount_sats: Sats(output.value.to_sat()),
+            inclusion_proof: confirmed.tx_proof(),
+        })
+    }
+
+    /// Height of the backend's best chain tip
+    pub async fn tip_height(&self) -> Result<u64> {
+        match self.backend {
+            #[cfg(feature = "bitcoind")]
+            ProofBackend::Bitcoind => self.bitcoind_rpc().get_block_count().await,
+            #[cfg(feature = "esplora")]
+            ProofBackend::Esplora => {
+                let height = self.esplora_text("/blocks/tip/height").await?;
+                height
+                    .trim()
+                    .parse()
+                    .map_err(|e| anyhow!("Invalid Esplora tip height '{}': {}", height, e))
+            }
+        }
+    }
+
+    /// Height of the block containing `txid`, none if unconfirmed
+    ///
+    /// `txid` is in display (explorer) byte order.
+    pub async fn tx_block_height(&self, txid: &str) -> Result<Option<u64>> {
+        let txid =
+            Txid::from_str(txid).map_err(|e| anyhow!("Invalid BTC txid '{}': {}", txid, e))?;
+
+        match self.backend {
+            #[cfg(feature = "bitcoind")]
+            ProofBackend::Bitcoind => {
+                let rpc = self.bitcoind_rpc();
+                match rpc.get_tx_block_hash(&txid).await? {
+                    Some(block_hash) => Ok(Some(rpc.get_block_height(&block_hash).await?)),
+                    None => Ok(None),
+                }
+            }
+            #[cfg(feature = "esplora")]
+            ProofBackend::Esplora => {
+                let status: EsploraTxStatus = self
+                    .esplora_get(&format!("/tx/{}/status", txid))
+                    .await?
+                    .json()
+                    .await
+                    .map_err(|e| anyhow!("Failed to parse Esplora tx status: {}", e))?;
+                Ok(status.block_height.filter(|_| status.confirmed))
+            }
+        }
+    }
+
+    /// Best-chain block at `height`, with all transactions
+    pub async fn block_at(&self, height: u64) -> Result<Block> {
+        match self.backend {
+            #[cfg(feature = "bitcoind")]
+            ProofBackend::Bitcoind => {
+                let rpc = self.bitcoind_rpc();
+                rpc.get_block(&rpc.get_block_hash(height).await?).await
+            }
+            #[cfg(feature = "esplora")]
+            ProofBackend::Esplora => {
+                let block_hash = self
+                    .esplora_text(&format!("/block-height/{}", height))
+                    .await?;
+                let bytes = self
+                    .esplora_get(&format!("/block/{}/raw", block_hash.trim()))
+                    .await?
+                    .bytes()
+                    .await
+                    .map_err(|e| anyhow!("Failed to read block {}: {}", block_hash, e))?;
+                bitcoin::consensus::deserialize(&bytes)
+                    .map_err(|e| anyhow!("Failed to decode block {}: {}", block_hash, e))
+            }
+        }
+    }
+
+    /// Fetch a confirmed transaction and check its merkle branch against the header
+    async fn fetch_confirmed_tx(&self, txid: &str) -> Result<ConfirmedTx> {
+        let txid =
+            Txid::from_str(txid).map_err(|e| anyhow!("Invalid BTC txid '{}': {}", txid, e))?;
+
+        let confirmed = match self.backend {
+            #[cfg(feature = "bitcoind")]
+            ProofBackend::Bitcoind => self.fetch_from_bitcoind(txid).await?,
+            #[cfg(feature = "esplora")]
+            ProofBackend::Esplora => self.fetch_from_esplora(txid).await?,
+        };
+
+        if confirmed.tx.compute_txid() != txid {
+            return Err(anyhow!("Fetched transaction does not match txid {}", txid));
+        }
+        let root = merkle_root_from_branch(txid, &confirmed.merkle_branch, confirmed.tx_index);
+        if root != confirmed.header.merkle_root {
+            return Err(anyhow!(
+                "Merkle branch for {} does not match block merkle root",
+                txid
+            ));
+        }
+
+        Ok(confirmed)
+    }
+
+    /// Fetch the containing block from bitcoind and compute the merkle branch
+    #[cfg(feature = "bitcoind")]
+    async fn fetch_from_bitcoind(&self, txid: Txid) -> Result<ConfirmedTx> {
+        let rpc = self.bitcoind_rpc();
+        let block_hash = rpc
+            .get_tx_block_hash(&txid)
+            .await?
+            .ok_or_else(|| anyhow!("BTC transaction {} is not confirmed", txid))?;
+        let height = rpc.get_block_height(&block_hash).await?;
+        let block = rpc.get_block(&block_hash).await?;
+
+        let txids: Vec<Txid> = block.txdata.iter().map(|tx| tx.compute_txid()).collect();
+        let tx_index = txids
+            .iter()
+            .position(|id| *id == txid)
+            .ok_or_else(|| anyhow!("BTC transaction {} not found in block {}", txid, block_hash))?;
+
+        Ok(ConfirmedTx {
+            header: block.header,
+            merkle_branch: merkle_branch(&txids, tx_index),
+            tx: block.txdata[tx_index].clone(),
+            tx_index: tx_index as u64,
+            height,
+        })
+    }
+
+    /// RPC client sharing this builder's URL, credentials and HTTP client
+    #[cfg(feature = "bitcoind")]
+    fn bitcoind_rpc(&self) -> BitcoindRpc {
+        let rpc = BitcoindRpc::new(&self.url).with_http_client(self.http_client.clone());
+        match &self.basic_auth {
+            Some((user, password)) => rpc.with_basic_auth(user, password),
+            None => rpc,
+        }
+    }
+
+    /// Fetch the transaction, header and merkle proof from Esplora
+    #[cfg(feature = "esplora")]
+    async fn fetch_from_esplora(&self, txid: Txid) -> Result<ConfirmedTx> {
+        let status: EsploraTxStatus = self
+            .esplora_get(&format!("/tx/{}/status", txid))
+            .await?
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse Esplora tx status: {}", e))?;
+        let (height, block_hash) = match (status.confirmed, status.block_height, status.block_hash)
+        {
+            (true, Some(height), Some(block_hash)) => (height, block_hash),
+            _ => return Err(anyhow!("BTC transaction {} is not confirmed", txid)),
+        };
+
+        let proof: EsploraMerkleProof = self
+            .esplora_get(&format!("/tx/{}/merkle-proof", txid))
+            .await?
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse Esplora merkle proof: {}", e))?;
+        let merkle_branch = proof
+            .merkle
+            .iter()
+            .map(|node| {
+                TxMerkleNode::from_str(node)
+                    .map_err(|e| anyhow!("Invalid merkle node '{}': {}", node, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let header_hex = self
+            .esplora_text(&format!("/block/{}/header", block_hash))
+            .await?;
+        let header: Header = bitcoin::consensus::deserialize(&decode_hex(&header_hex)?)
+            .map_err(|e| anyhow!("Failed to decode block header {}: {}", block_hash, e))?;
+        let tx_hex = self.esplora_text(&format!("/tx/{}/hex", txid)).await?;
+        let tx: Transaction = bitcoin::consensus::deserialize(&decode_hex(&tx_hex)?)
+            .map_err(|e| anyhow!("Failed to decode transaction {}: {}", txid, e))?;
+
+        Ok(ConfirmedTx {
+            header,
+            tx,
+            merkle_branch,
+            tx_index: proof.pos,
+            height,
+        })
+    }
+
+    /// GET an Esplora API path
+    #[cfg(feature = "esplora")]
+    async fn esplora_get(&self, path: &str) -> Result<reqwest::Response> {
+        let url = format!("{}{}", self.url, path);
+        let mut http_request = self.http_client.get(&url);
+        if let Some((user, password)) = &self.basic_auth {
+            http_request = http_request.basic_auth(user, Some(password));
+        }
+
+        http_request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to send Esplora request to {}: {}", url, e))?
+            .error_for_status()
+            .map_err(|e| anyhow!("Esplora request to {} failed: {}", url, e))
+    }
+
+    /// GET an Esplora API path returning plain text
+    #[cfg(feature = "esplora")]
+    async fn esplora_text(&self, path: &str) -> Result<String> {
+        self.esplora_get(path)
+            .await?
+            .text()
+            .await
+            .map_err(|e| anyhow!("Failed to read Esplora response for {}: {}", path, e))
+    }
+}
+
+impl ClaimLPWithdrawParams {
+    /// Build `claim_lp_withdraw` parameters for the LP's BTC payment of
+    /// `withdraw` in output `vout` of `txid`, proved through `backend`
+    ///
+    /// `txid` is in display (explorer) byte order; the amount and block are
+    /// read from the confirmed transaction. Fails if the output doesn't pay the
+    /// withdrawal's receiver at least its minimum amount.
+    pub async fn from_btc_payment(
+        withdraw: &LPWithdraw,
+        txid: &str,
+        vout: u32,
+        backend: &ProofBuilder,
+    ) -> Result<Self> {
+        let params = backend.build_claim(withdraw.id, txid, vout).await?;
+        params.verify_payment(withdraw)?;
+        Ok(params)
+    }
+}
+
+/// Merkle branch (sibling hashes, leaf to root) for the transaction at `index`
+#[cfg(feature = "bitcoind")]
+fn merkle_branch(txids: &[Txid], mut index: usize) -> Vec<TxMerkleNode> {
+    let mut level: Vec<TxMerkleNode> = txids
+        .iter()
+        .map(|txid| TxMerkleNode::from_raw_hash(txid.to_raw_hash()))
+        .collect();
+    let mut branch = Vec::new();
+
+    while level.len() > 1 {
+        // Odd levels pair the last node with itself
+        if level.len() % 2 == 1 {
+            level.push(level[level.len() - 1]);
+        }
+        branch.push(level[index ^ 1]);
+        level = level
+            .chunks(2)
+            .map(|pair| {
+                let mut engine = TxMerkleNode::engine();
+                engine.input(pair[0].as_byte_array());
+                engine.input(pair[1].as_byte_array());
+                TxMerkleNode::from_engine(engine)
+            })
+            .collect();
+        index /= 2;
+    }
+
+    branch
+}
+
+/// Merkle root reached by folding `branch` into the transaction at `index`
+fn merkle_root_from_branch(txid: Txid, branch: &[TxMerkleNode], mut index: u64) -> TxMerkleNode {
+    let mut node = TxMerkleNode::from_raw_hash(txid.to_raw_hash());
+    for sibling in branch {
+        let mut engine = TxMerkleNode::engine();
+        if index % 2 == 0 {
+            engine.input(node.as_byte_array());
+            engine.input(sibling.as_byte_array());
+        } else {
+            engine.input(sibling.as_byte_array());
+            engine.input(node.as_byte_array());
+        }
+        node = TxMerkleNode::from_engine(engine);
+        index /= 2;
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "bitcoind")]
+    #[test]
+    fn test_merkle_branch_reaches_root() {
+        for count in 1..=7u8 {
+            let txids: Vec<Txid> = (0..count).map(|i| Txid::from_byte_array([i; 32])).collect();
+            let root = bitcoin::merkle_tree::calculate_root(txids.iter().copied())
+                .map(|hash| TxMerkleNode::from_raw_hash(hash.to_raw_hash()))
+                .unwrap();
+
+            for (index, txid) in txids.iter().enumerate() {
+                let branch = merkle_branch(&txids, index);
+                assert_eq!(merkle_root_from_branch(*txid, &branch, index as u64), root);
+            }
+        }
+    }
+
+    #[cfg(feature = "esplora")]
+    #[test]
+    fn test_esplora_merkle_proof_is_display_order() {
+        let txids = [
+            Txid::from_byte_array([1; 32]),
+            Txid::from_byte_array([2; 32]),
+        ];
+        let root = bitcoin::merkle_tree::calculate_root(txids.iter().copied())
+            .map(|hash| TxMerkleNode::from_raw_hash(hash.to_raw_hash()))
+            .unwrap();
+
+        let response = serde_json::json!({
+            "block_height": 800000,
+            "merkle": [txids[0].to_string()],
+            "pos": 1,
+        });
+        let proof: EsploraMerkleProof = serde_json::from_value(response).unwrap();
+        let branch: Vec<TxMerkleNode> = proof
+            .merkle
+            .iter()
+            .map(|node| TxMerkleNode::from_str(node).unwrap())
+            .collect();
+
+        assert_eq!(merkle_root_from_branch(txids[1], &branch, proof.pos), root);
+    }
+
+    #[cfg(all(feature = "esplora", feature = "test-utils"))]
+    #[tokio::test]
+    async fn test_claim_from_btc_payment_checks_receiver_and_amount() {
+        use crate::testing;
+
+        let payment = Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![bitcoin::TxIn::default()],
+            output: vec![bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(100_000),
+                script_pubkey: bitcoin::ScriptBuf::new_p2wpkh(
+                    &bitcoin::WPubkeyHash::from_byte_array([5; 20]),
+                ),
+            }],
+        };
+        let coinbase = Transaction {
+            lock_time: bitcoin::absolute::LockTime::from_consensus(1),
+            ..payment.clone()
+        };
+        let block = testing::btc_block(
+            bitcoin::BlockHash::all_zeros(),
+            vec![coinbase, payment.clone()],
+        );
+        let server = wiremock::MockServer::start().await;
+        testing::mount_esplora_block(&server, 800_000, &block).await;
+        let backend = ProofBuilder::esplora(&server.uri());
+
+        let withdraw = LPWithdraw {
+            id: 7,
+            withdraw_amount: Sats(110_000),
+            receiver_addr: "bc1qq5zs2pg9q5zs2pg9q5zs2pg9q5zs2pg9wcv0gt".to_string(),
+            receiver_script_hash: format!("0x{}", "05".repeat(20)),
+            receive_min_amount: Sats(100_000),
+            fee_rate: 10,
+            timestamp: 0,
+            lp_id: 1,
+        };
+        let txid = payment.compute_txid().to_string();
+        let params = ClaimLPWithdrawParams::from_btc_payment(&withdraw, &txid, 0, &backend)
+            .await
+            .unwrap();
+        assert_eq!(params.withdraw_id, 7);
+        assert_eq!(params.block_num, 800_000);
+        assert_eq!(params.amount_sats, Sats(100_000));
+        assert_eq!(params.inclusion_proof.tx_index, 1);
+
+        let other_receiver = LPWithdraw {
+            receiver_script_hash: format!("0x{}", "06".repeat(20)),
+            ..withdraw.clone()
+        };
+        assert!(
+            ClaimLPWithdrawParams::from_btc_payment(&other_receiver, &txid, 0, &backend)
+                .await
+                .is_err()
+        );
+        let malformed_receiver = LPWithdraw {
+            receiver_script_hash: "0x0505".to_string(),
+            ..withdraw.clone()
+        };
+        let err = ClaimLPWithdrawParams::from_btc_payment(&malformed_receiver, &txid, 0, &backend)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid script hash"));
+        let underpaid = LPWithdraw {
+            receive_min_amount: Sats(100_001),
+            ..withdraw
+        };
+        assert!(
+            ClaimLPWithdrawParams::from_btc_payment(&underpaid, &txid, 0, &backend)
+                .await
+                .is_err()
+        );
+    }
+}
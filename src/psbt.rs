@@ -0,0 +1,277 @@
+//! Peg-out payout PSBTs
+//!
+//! Builds the unsigned transaction an LP broadcasts to fulfil a `withdraw_by_lp`
+//! request, as a PSBT ready for the LP's signer.
+//!
+//! Output ordering expected by `claim_lp_withdraw`:
+//! - output 0 pays the receiver; its index is the claim's `tx_out_ix`
+//! - output 1, if present, is change back to the LP
+//! - no other outputs; inputs keep the order they were added in
+
+use crate::btc::{estimate_tx_vbytes, script_hash_for_output, BtcAddress, BtcNetwork};
+use crate::types::constants::PAYOUT_DUST_LIMIT_SATS;
+use crate::types::{Sats, ScriptType, WithdrawByLPEvent};
+use anyhow::{anyhow, Result};
+use bitcoin::psbt::Psbt;
+use bitcoin::transaction::Version;
+use bitcoin::{absolute, Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness};
+
+/// Output index of the receiver payment
+pub const PAYOUT_VOUT: u32 = 0;
+
+/// Unsigned payout transaction
+#[derive(Debug, Clone)]
+pub struct PayoutPsbt {
+    /// PSBT with `witness_utxo` set on segwit inputs
+    pub psbt: Psbt,
+    /// Output index of the receiver payment
+    pub payout_vout: u32,
+    /// Amount paid to the receiver
+    pub payout_amount: Sats,
+    /// Change returned to the LP, zero if there is no change output
+    pub change_amount: Sats,
+    /// Transaction fee
+    pub fee: Sats,
+}
+
+/// Builder for the payout PSBT of a `withdraw_by_lp` request
+#[derive(Debug, Clone)]
+pub struct PayoutPsbtBuilder {
+    /// Receiver of the payout
+    receiver: BtcAddress,
+    /// Smallest amount the receiver accepts
+    receive_min_amount: Sats,
+    /// Amount paid to the receiver
+    payout_amount: Sats,
+    /// Fee rate in sat/vB
+    fee_rate: u64,
+    /// LP outputs funding the payout
+    inputs: Vec<(OutPoint, TxOut)>,
+    /// Script receiving the change, none to give any excess to fees
+    change_script: Option<ScriptBuf>,
+}
+
+impl PayoutPsbtBuilder {
+    /// Start a payout of `receive_min_amount` at the event's fee rate
+    pub fn for_withdraw_by_lp(event: &WithdrawByLPEvent, network: BtcNetwork) -> Result<Self> {
+        let receiver = BtcAddress::new(&event.btc_address, network)?;
+        Ok(Self {
+            receiver,
+            receive_min_amount: event.receive_min_amount,
+            payout_amount: event.receive_min_amount,
+            fee_rate: event.fee_rate,
+            inputs: Vec::new(),
+            change_script: None,
+        })
+    }
+
+    /// Pay the receiver `amount` instead of the minimum
+    pub fn with_payout_amount(mut self, amount: Sats) -> Self {
+        self.payout_amount = amount;
+        self
+    }
+
+    /// Spend an LP output
+    pub fn with_input(mut self, outpoint: OutPoint, txout: TxOut) -> Self {
+        self.inputs.push((outpoint, txout));
+        self
+    }
+
+    /// Return change to `script`
+    pub fn with_change_script(mut self, script: ScriptBuf) -> Self {
+        self.change_script = Some(script);
+        self
+    }
+
+    /// Build the unsigned PSBT
+    ///
+    /// Change below the dust limit is left to fees.
+    pub fn build(&self) -> Result<PayoutPsbt> {
+        if self.payout_amount < self.receive_min_amount {
+            return Err(anyhow!(
+                "Payout of {} is below the receiver minimum of {}",
+                self.payout_amount,
+                self.receive_min_amount
+            ));
+        }
+        if self.inputs.is_empty() {
+            return Err(anyhow!("Payout transaction has no inputs"));
+        }
+
+        let input_types = self
+            .inputs
+            .iter()
+            .map(|(outpoint, txout)| {
+                script_hash_for_output(&txout.script_pubkey)
+                    .map(|(_, script_type)| script_type)
+                    .map_err(|e| anyhow!("Cannot spend input {}: {}", outpoint, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let input_total = self
+            .inputs
+            .iter()
+            .map(|(_, txout)| txout.value.to_sat())
+            .sum::<u64>();
+
+        // Unknown witness versions are sized like taproot outputs
+        let receiver_type = self.receiver.script_type().unwrap_or(ScriptType::P2TR);
+        let fee_without_change =
+            estimate_tx_vbytes(&input_types, &[receiver_type]).saturating_mul(self.fee_rate);
+
+        let mut outputs = vec![TxOut {
+            value: Amount::from_sat(self.payout_amount.0),
+            script_pubkey: self.receiver.script_pubkey(),
+        }];
+        let mut change_amount = 0;
+        if let Some(change_script) = &self.change_script {
+            let change_type = script_hash_for_output(change_script)
+                .map(|(_, script_type)| script_type)
+                .map_err(|e| anyhow!("Invalid change script: {}", e))?;
+            let fee_with_change = estimate_tx_vbytes(&input_types, &[receiver_type, change_type])
+                .saturating_mul(self.fee_rate);
+            let change = input_total
+                .saturating_sub(self.payout_amount.0)
+                .saturating_sub(fee_with_change);
+            if change >= PAYOUT_DUST_LIMIT_SATS {
+                change_amount = change;
+                outputs.push(TxOut {
+                    value: Amount::from_sat(change),
+                    script_pubkey: change_script.clone(),
+                });
+            }
+        }
+
+        let required = self.payout_amount.0.saturating_add(fee_without_change);
+        if input_total < required {
+            return Err(anyhow!(
+                "Inputs total {} sats, payout needs at least {} sats",
+                input_total,
+                required
+            ));
+        }
+
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: self
+                .inputs
+                .iter()
+                .map(|(outpoint, _)| TxIn {
+                    previous_output: *outpoint,
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::new(),
+                })
+                .collect(),
+            output: outputs,
+        };
+        let mut psbt = Psbt::from_unsigned_tx(tx)
+            .map_err(|e| anyhow!("Failed to create payout PSBT: {}", e))?;
+
+        // Legacy inputs need the full previous transaction, which the signer must add
+        for (psbt_input, ((_, txout), script_type)) in psbt
+            .inputs
+            .iter_mut()
+            .zip(self.inputs.iter().zip(&input_types))
+        {
+            if *script_type != ScriptType::P2PKH {
+                psbt_input.witness_utxo = Some(txout.clone());
+            }
+        }
+
+        Ok(PayoutPsbt {
+            psbt,
+            payout_vout: PAYOUT_VOUT,
+            payout_amount: self.payout_amount,
+            change_amount: Sats(change_amount),
+            fee: Sats(input_total - self.payout_amount.0 - change_amount),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hashes::Hash;
+    use bitcoin::Txid;
+
+    fn event(receive_min_amount: u64) -> WithdrawByLPEvent {
+        WithdrawByLPEvent {
+            from_address: "0x1".to_string(),
+            withdraw_id: 7,
+            btc_address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+            fee_rate: 10,
+            amount: Sats(110_000),
+            lp_id: 1,
+            receive_min_amount: Sats(receive_min_amount),
+            timestamp: None,
+            version: None,
+            transaction_hash: None,
+        }
+    }
+
+    fn lp_input(value: u64) -> (OutPoint, TxOut) {
+        let lp: BtcAddress = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+            .parse()
+            .unwrap();
+        (
+            OutPoint::new(Txid::from_byte_array([9; 32]), 1),
+            TxOut {
+                value: Amount::from_sat(value),
+                script_pubkey: lp.script_pubkey(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_payout_with_change() {
+        let (outpoint, txout) = lp_input(200_000);
+        let change_script = txout.script_pubkey.clone();
+        let payout = PayoutPsbtBuilder::for_withdraw_by_lp(&event(100_000), BtcNetwork::Mainnet)
+            .unwrap()
+            .with_input(outpoint, txout)
+            .with_change_script(change_script.clone())
+            .build()
+            .unwrap();
+
+        let tx = &payout.psbt.unsigned_tx;
+        assert_eq!(payout.payout_vout, 0);
+        assert_eq!(tx.output.len(), 2);
+        assert_eq!(tx.output[0].value.to_sat(), 100_000);
+        assert_eq!(tx.output[1].script_pubkey, change_script);
+        // 1-in/2-out P2WPKH is 141 vbytes
+        assert_eq!(payout.fee, Sats(1_410));
+        assert_eq!(payout.change_amount, Sats(200_000 - 100_000 - 1_410));
+        assert!(payout.psbt.inputs[0].witness_utxo.is_some());
+    }
+
+    #[test]
+    fn test_payout_dust_change_goes_to_fee() {
+        let (outpoint, txout) = lp_input(101_500);
+        let change_script = txout.script_pubkey.clone();
+        let payout = PayoutPsbtBuilder::for_withdraw_by_lp(&event(100_000), BtcNetwork::Mainnet)
+            .unwrap()
+            .with_input(outpoint, txout)
+            .with_change_script(change_script)
+            .build()
+            .unwrap();
+
+        assert_eq!(payout.psbt.unsigned_tx.output.len(), 1);
+        assert_eq!(payout.change_amount, Sats(0));
+        assert_eq!(payout.fee, Sats(1_500));
+    }
+
+    #[test]
+    fn test_payout_rejects_underfunded_and_below_minimum() {
+        let (outpoint, txout) = lp_input(100_500);
+        let builder = PayoutPsbtBuilder::for_withdraw_by_lp(&event(100_000), BtcNetwork::Mainnet)
+            .unwrap()
+            .with_input(outpoint, txout);
+        assert!(builder.build().is_err());
+        assert!(builder.with_payout_amount(Sats(99_999)).build().is_err());
+
+        assert!(
+            PayoutPsbtBuilder::for_withdraw_by_lp(&event(100_000), BtcNetwork::Testnet).is_err()
+        );
+    }
+}
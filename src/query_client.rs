@@ -2,65 +2,631 @@
 //!
 //! Provides functionality to query Aptos Bridge contract configuration and status.
 
-use crate::types::{BridgeEvent, BurnEventBCS, MintEventBCS, WithdrawByLPEventBCS};
+use crate::contract::ContractSpec;
+#[cfg(feature = "events-rest")]
+use crate::events::BridgeEventDecoder;
+use crate::request_log::{self, logged, CallLog};
+#[cfg(feature = "events-rest")]
+use crate::stats::BridgeStats;
+use crate::time::{sleep, unix_timestamp_secs, Instant};
+#[cfg(feature = "events-rest")]
+use crate::types::constants::TRANSACTION_SCAN_PAGE_SIZE;
+use crate::types::{
+    constants::{TRANSACTION_POLL_INTERVAL_MS, VIEW_CONCURRENCY},
+    AccountResource, EventSchemaVersion, GasEstimate, LedgerInfo, TransactionEvent, TxKind,
+};
+#[cfg(feature = "events-rest")]
+use crate::types::{BridgeEvent, BridgeEventRecord};
+use crate::utils::strip_hex_prefix;
 use anyhow::{anyhow, Result};
 use aptos_sdk::{
     crypto::HashValue,
-    rest_client::{aptos_api_types::TransactionData, AptosBaseUrl, Client, ClientBuilder},
-    types::{account_address::AccountAddress, contract_event::ContractEvent},
+    rest_client::{
+        aptos_api_types::{self, AptosErrorCode, TransactionData, ViewRequest},
+        error::RestError,
+        AptosBaseUrl, Client, ClientBuilder,
+    },
+    types::account_address::AccountAddress,
 };
 
-use std::str::FromStr;
+#[cfg(feature = "events-rest")]
+use aptos_sdk::rest_client::aptos_api_types::TransactionOnChainData;
+#[cfg(feature = "events-rest")]
+use aptos_sdk::types::contract_event::ContractEvent;
+#[cfg(feature = "events-rest")]
+use aptos_sdk::types::transaction::Transaction;
+use futures::stream::{self, StreamExt};
+#[cfg(feature = "events-rest")]
+use futures::stream::{Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::time::Duration;
 use url::Url;
 /// Query client
+#[derive(Clone)]
 pub struct QueryClient {
     /// REST client
     rest_client: Client,
+    /// Expected bridge event schema version
+    #[cfg_attr(not(feature = "events-rest"), allow(dead_code))]
+    event_schema_version: EventSchemaVersion,
+    /// Name of the module emitting bridge events
+    #[cfg_attr(not(feature = "events-rest"), allow(dead_code))]
+    bridge_module: String,
+    /// Node URL, to rebuild the REST client when its settings change
+    node_url: Url,
+    /// Aptos API key
+    aptos_api_key: Option<String>,
+    /// Caller identifier appended to the User-Agent
+    app_id: Option<String>,
+    /// Extra headers sent with every request
+    headers: Vec<(String, String)>,
+    /// View calls in flight at once in `view_many`
+    view_concurrency: usize,
+    /// Chain id the node must report, if set
+    expected_chain_id: Option<u8>,
+    /// Proxy, TLS and timeout settings
+    #[cfg(all(feature = "http-config", not(target_arch = "wasm32")))]
+    http_config: Option<crate::http::HttpConfig>,
 }
 
 impl QueryClient {
     /// Create new query client
     pub fn new(node_url: &str, aptos_api_key: Option<&str>) -> Result<Self> {
-        let mut client_builder = ClientBuilder::new(AptosBaseUrl::Custom(
-            Url::parse(node_url).map_err(|e| anyhow!("Invalid node URL '{}': {}", node_url, e))?,
+        let node_url =
+            Url::parse(node_url).map_err(|e| anyhow!("Invalid node URL '{}': {}", node_url, e))?;
+        let mut query_client = Self {
+            rest_client: ClientBuilder::new(AptosBaseUrl::Custom(node_url.clone())).build(),
+            event_schema_version: EventSchemaVersion::default(),
+            bridge_module: ContractSpec::default().bridge_module,
+            node_url,
+            aptos_api_key: aptos_api_key.map(str::to_string),
+            app_id: None,
+            headers: Vec::new(),
+            view_concurrency: VIEW_CONCURRENCY,
+            expected_chain_id: None,
+            #[cfg(all(feature = "http-config", not(target_arch = "wasm32")))]
+            http_config: None,
+        };
+        query_client.rest_client = query_client.build_rest_client()?;
+        Ok(query_client)
+    }
+
+    /// Identify the calling application, sent as
+    /// `User-Agent: fiamma-aptos-client-sdk/<version> <app_id>`
+    pub fn with_app_id(mut self, app_id: &str) -> Result<Self> {
+        self.app_id = Some(app_id.to_string());
+        self.rest_client = self.build_rest_client()?;
+        Ok(self)
+    }
+
+    /// Add a header sent with every request to the node
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self> {
+        self.headers.push((name.to_string(), value.to_string()));
+        self.rest_client = self.build_rest_client()?;
+        Ok(self)
+    }
+
+    /// Use proxy, TLS and timeout settings for requests to the node
+    #[cfg(all(feature = "http-config", not(target_arch = "wasm32")))]
+    pub fn with_http_config(mut self, http_config: &crate::http::HttpConfig) -> Result<Self> {
+        self.http_config = Some(http_config.clone());
+        self.rest_client = self.build_rest_client()?;
+        Ok(self)
+    }
+
+    /// REST client used for node requests
+    pub(crate) fn rest_client(&self) -> &Client {
+        &self.rest_client
+    }
+
+    /// Start logging a call to `path` under the node's REST API root
+    pub(crate) fn call_log(&self, method: &'static str, path: impl Display) -> Option<CallLog> {
+        if !request_log::is_enabled() {
+            return None;
+        }
+        let base = self.node_url.as_str().trim_end_matches('/');
+        let version = if base.ends_with("/v1") { "" } else { "/v1" };
+        request_log::start(
+            method,
+            format_args!("{}{}/{}", base, version, path),
+            &[self.aptos_api_key.as_deref().unwrap_or_default()],
+        )
+    }
+
+    /// User-Agent and extra headers sent with every request
+    fn request_headers(&self) -> Vec<(String, String)> {
+        let mut headers = Vec::with_capacity(self.headers.len() + 1);
+        // Browsers set their own User-Agent
+        #[cfg(not(target_arch = "wasm32"))]
+        headers.push((
+            "user-agent".to_string(),
+            crate::utils::sdk_user_agent(self.app_id.as_deref()),
         ));
+        headers.extend(self.headers.iter().cloned());
+        headers
+    }
+
+    fn build_rest_client(&self) -> Result<Client> {
+        let headers = self.request_headers();
+        #[cfg(all(feature = "http-config", not(target_arch = "wasm32")))]
+        if let Some(http_config) = &self.http_config {
+            return http_config.build_rest_client(
+                &self.node_url,
+                self.aptos_api_key.as_deref(),
+                &headers,
+            );
+        }
 
-        if let Some(api_key) = aptos_api_key {
+        let mut client_builder = ClientBuilder::new(AptosBaseUrl::Custom(self.node_url.clone()));
+        if let Some(api_key) = &self.aptos_api_key {
             client_builder = client_builder.api_key(api_key)?;
         }
+        for (name, value) in &headers {
+            client_builder = client_builder
+                .header(name, value)
+                .map_err(|e| anyhow!("Invalid header '{}': {}", name, e))?;
+        }
+        Ok(client_builder.build())
+    }
+
+    /// Set how many view calls `view_many` keeps in flight (at least one)
+    pub fn with_view_concurrency(mut self, view_concurrency: usize) -> Self {
+        self.view_concurrency = view_concurrency.max(1);
+        self
+    }
+
+    /// Require the node to report `chain_id`, so a node of the wrong network
+    /// fails [`QueryClient::check_node_health`] and transaction signing
+    pub fn with_expected_chain_id(mut self, chain_id: u8) -> Self {
+        self.expected_chain_id = Some(chain_id);
+        self
+    }
+
+    /// Set the event schema version of the deployed bridge contract
+    ///
+    /// Other known layouts are still tried as a fallback when decoding.
+    pub fn with_event_schema_version(mut self, version: EventSchemaVersion) -> Self {
+        self.event_schema_version = version;
+        self
+    }
+
+    /// Set the name of the module emitting bridge events, for renamed deployments
+    pub fn with_bridge_module(mut self, bridge_module: &str) -> Self {
+        self.bridge_module = bridge_module.to_string();
+        self
+    }
+
+    /// Call a view function and return its return values
+    pub async fn view(&self, request: &ViewRequest) -> Result<Vec<Value>> {
+        let call_log = self.call_log("POST", "view").map(|call_log| {
+            call_log.with_request_body(&serde_json::to_string(request).unwrap_or_default())
+        });
+        let response = logged(call_log, self.rest_client.view(request, None))
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "Failed to call {} view function: {}",
+                    request.function.name.0,
+                    e
+                )
+            })?;
+        Ok(response.into_inner())
+    }
+
+    /// Call view functions concurrently and return each one's first return value,
+    /// in request order
+    ///
+    /// At most [`QueryClient::with_view_concurrency`] calls are in flight at
+    /// once. The Aptos node API has no batch view endpoint, so each request is
+    /// a separate call; one failing doesn't affect the others.
+    pub async fn view_many(&self, requests: &[ViewRequest]) -> Vec<Result<Value>> {
+        stream::iter(requests)
+            .map(|request| async move {
+                self.view(request).await?.into_iter().next().ok_or_else(|| {
+                    anyhow!("No response from {} view function", request.function.name.0)
+                })
+            })
+            .buffered(self.view_concurrency)
+            .collect()
+            .await
+    }
+
+    /// Get the item stored under `key` in the table `handle`, decoded as `T`
+    ///
+    /// `key_type` and `value_type` are Move types such as `u64` or
+    /// `0x1::string::String`, and `key` is the key's JSON encoding. Returns
+    /// `None` if the table has no item under `key`.
+    pub async fn get_table_item_typed<T: DeserializeOwned>(
+        &self,
+        handle: AccountAddress,
+        key_type: &str,
+        value_type: &str,
+        key: Value,
+    ) -> Result<Option<T>> {
+        let call_log = self
+            .call_log("POST", format_args!("tables/{}/item", handle))
+            .map(|call_log| call_log.with_request_body(&key.to_string()));
+        let response = match logged(
+            call_log,
+            self.rest_client
+                .get_table_item(handle, key_type, value_type, &key),
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err(RestError::Api(e)) if e.error.error_code == AptosErrorCode::TableItemNotFound => {
+                return Ok(None)
+            }
+            Err(e) => {
+                return Err(anyhow!(
+                    "Failed to get table item {} from Aptos node: {}",
+                    key,
+                    e
+                ))
+            }
+        };
+
+        serde_json::from_value(response.into_inner())
+            .map(Some)
+            .map_err(|e| anyhow!("Failed to parse {} table item: {}", value_type, e))
+    }
+
+    /// Get the JSON data of the `resource_type` resource under `address`,
+    /// `None` if the account doesn't hold one
+    pub async fn get_account_resource(
+        &self,
+        address: AccountAddress,
+        resource_type: &str,
+    ) -> Result<Option<Value>> {
+        let response = logged(
+            self.call_log(
+                "GET",
+                format_args!("accounts/{}/resource/{}", address, resource_type),
+            ),
+            self.rest_client
+                .get_account_resource(address, resource_type),
+        )
+        .await
+        .map_err(|e| {
+            anyhow!(
+                "Failed to get {} resource from Aptos node: {}",
+                resource_type,
+                e
+            )
+        })?;
+
+        Ok(response.into_inner().map(|resource| resource.data))
+    }
+
+    /// List the resources held by `address`, only those declared by `module`
+    /// if given
+    ///
+    /// Decode a resource's fields with [`AccountResource::decode`].
+    pub async fn list_account_resources(
+        &self,
+        address: AccountAddress,
+        module: Option<&str>,
+    ) -> Result<Vec<AccountResource>> {
+        let response = logged(
+            self.call_log("GET", format_args!("accounts/{}/resources", address)),
+            self.rest_client.get_account_resources(address),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get account resources from Aptos node: {}", e))?;
+
+        Ok(response
+            .into_inner()
+            .into_iter()
+            .filter(|resource| {
+                module.is_none_or(|module| resource.resource_type.module.0.as_str() == module)
+            })
+            .map(|resource| AccountResource {
+                resource_type: resource.resource_type.to_string(),
+                module: resource.resource_type.module.0.to_string(),
+                data: resource.data,
+            })
+            .collect())
+    }
+
+    /// Get the node's gas unit price estimates
+    ///
+    /// Tiers the node doesn't report fall back to the market price.
+    pub async fn estimate_gas_price(&self) -> Result<GasEstimate> {
+        let response = logged(
+            self.call_log("GET", "estimate_gas_price"),
+            self.rest_client.estimate_gas_price(),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get gas price estimate from Aptos node: {}", e))?;
+
+        let estimation = response.inner();
+        let market = estimation.gas_estimate;
+        Ok(GasEstimate {
+            deprioritized: estimation.deprioritized_gas_estimate.unwrap_or(market),
+            market,
+            prioritized: estimation.prioritized_gas_estimate.unwrap_or(market),
+        })
+    }
+
+    /// Get the node's chain id, ledger version and timestamp, epoch and block height
+    pub async fn get_ledger_info(&self) -> Result<LedgerInfo> {
+        let response = logged(
+            self.call_log("GET", ""),
+            self.rest_client.get_ledger_information(),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get ledger information from Aptos node: {}", e))?;
+
+        let state = response.inner();
+        Ok(LedgerInfo {
+            chain_id: state.chain_id,
+            ledger_version: state.version,
+            ledger_timestamp_usecs: state.timestamp_usecs,
+            epoch: state.epoch,
+            block_height: state.block_height,
+        })
+    }
+
+    /// Check that the node is on the expected network and has committed a
+    /// transaction within `max_staleness`
+    ///
+    /// Returns the ledger information on success, so callers can log it.
+    pub async fn check_node_health(&self, max_staleness: Duration) -> Result<LedgerInfo> {
+        let ledger_info = self.get_ledger_info().await?;
+        self.check_chain_id(ledger_info.chain_id)?;
+
+        let now_usecs = unix_timestamp_secs().saturating_mul(1_000_000);
+        let lag =
+            Duration::from_micros(now_usecs.saturating_sub(ledger_info.ledger_timestamp_usecs));
+        if lag > max_staleness {
+            return Err(anyhow!(
+                "Aptos node is stale: latest ledger version {} is {}s old (max {}s)",
+                ledger_info.ledger_version,
+                lag.as_secs(),
+                max_staleness.as_secs()
+            ));
+        }
+        Ok(ledger_info)
+    }
+
+    /// Fail if `chain_id` is not the expected chain id
+    pub(crate) fn check_chain_id(&self, chain_id: u8) -> Result<()> {
+        match self.expected_chain_id {
+            Some(expected) if expected != chain_id => Err(anyhow!(
+                "Aptos node is on chain id {}, expected chain id {}",
+                chain_id,
+                expected
+            )),
+            _ => Ok(()),
+        }
+    }
 
-        let rest_client = client_builder.build();
+    /// Get the current ledger version of the node
+    pub async fn get_ledger_version(&self) -> Result<u64> {
+        let response = logged(
+            self.call_log("GET", ""),
+            self.rest_client.get_ledger_information(),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get ledger information from Aptos node: {}", e))?;
 
-        Ok(Self { rest_client })
+        Ok(response.inner().version)
     }
 
     /// Query transaction status
     pub async fn get_transaction_by_hash(&self, tx_hash: &str) -> Result<TransactionData> {
         // Parse transaction hash
-        let tx_hash = HashValue::from_hex(tx_hash.trim_start_matches("0x"))
+        let tx_hash = HashValue::from_hex(strip_hex_prefix(tx_hash))
             .map_err(|e| anyhow!("Invalid transaction hash '{}': {}", tx_hash, e))?;
 
-        let response = self
-            .rest_client
-            .get_transaction_by_hash_bcs(tx_hash)
-            .await
-            .map_err(|e| anyhow!("Failed to get transaction from Aptos node: {}", e))?;
+        let response = logged(
+            self.call_log("GET", format_args!("transactions/by_hash/{}", tx_hash)),
+            self.rest_client.get_transaction_by_hash_bcs(tx_hash),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get transaction from Aptos node: {}", e))?;
 
         Ok(response.inner().clone())
     }
 
-    pub async fn get_tx_hash_by_version(&self, version: u64) -> Result<String> {
-        let response = self
-            .rest_client
-            .get_transaction_by_version_bcs(version)
+    /// Kind of the transaction with hash `tx_hash`
+    pub async fn get_transaction_kind(&self, tx_hash: &str) -> Result<TxKind> {
+        Ok(transaction_kind(
+            &self.get_transaction_by_hash(tx_hash).await?,
+        ))
+    }
+
+    /// Whether `tx_hash` is a committed user transaction, the only kind
+    /// carrying bridge events
+    pub async fn is_user_transaction(&self, tx_hash: &str) -> Result<bool> {
+        Ok(self.get_transaction_kind(tx_hash).await? == TxKind::User)
+    }
+
+    /// Every event emitted by a user transaction, bridge or not, with its type
+    /// and JSON data
+    ///
+    /// For debugging contracts that interact with the bridge; use
+    /// [`QueryClient::get_bridge_events_by_hash`] to decode bridge events.
+    pub async fn get_all_events_by_tx_hash(&self, tx_hash: &str) -> Result<Vec<TransactionEvent>> {
+        let hash = HashValue::from_hex(strip_hex_prefix(tx_hash))
+            .map_err(|e| anyhow!("Invalid transaction hash '{}': {}", tx_hash, e))?;
+
+        let response = logged(
+            self.call_log("GET", format_args!("transactions/by_hash/{}", hash)),
+            self.rest_client.get_transaction_by_hash(hash),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get transaction from Aptos node: {}", e))?;
+
+        match response.into_inner() {
+            aptos_api_types::Transaction::UserTransaction(txn) => Ok(txn
+                .events
+                .into_iter()
+                .enumerate()
+                .map(|(index, event)| TransactionEvent {
+                    index: index as u64,
+                    event_type: event.typ.to_string(),
+                    sequence_number: event.sequence_number.0,
+                    data: event.data,
+                })
+                .collect()),
+            _ => Err(anyhow!(
+                "Transaction {} is not a user transaction. Only user and not pending transactions are supported.",
+                tx_hash
+            )),
+        }
+    }
+
+    /// Wait until a transaction is committed on chain
+    ///
+    /// Returns the committed transaction, which may have failed; check its status.
+    pub async fn wait_for_transaction(
+        &self,
+        tx_hash: &str,
+        timeout: Duration,
+    ) -> Result<TransactionData> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let result = self.get_transaction_by_hash(tx_hash).await;
+            if let Ok(TransactionData::OnChain(_)) = &result {
+                return result;
+            }
+            if Instant::now() >= deadline {
+                return match result {
+                    Err(e) => Err(e),
+                    Ok(_) => Err(anyhow!(
+                        "Transaction {} was not committed within {:?}",
+                        tx_hash,
+                        timeout
+                    )),
+                };
+            }
+            sleep(Duration::from_millis(TRANSACTION_POLL_INTERVAL_MS)).await;
+        }
+    }
+
+    /// Wait until the node's ledger has reached `version`
+    ///
+    /// For reading, possibly through another node behind a load balancer,
+    /// what a committed transaction wrote. Returns the node's ledger version.
+    pub async fn wait_for_version(&self, version: u64, timeout: Duration) -> Result<u64> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let result = self.get_ledger_version().await;
+            if let Ok(ledger_version) = result {
+                if ledger_version >= version {
+                    return Ok(ledger_version);
+                }
+            }
+            if Instant::now() >= deadline {
+                return match result {
+                    Err(e) => Err(e),
+                    Ok(ledger_version) => Err(anyhow!(
+                        "Aptos node did not reach version {} within {:?} (at version {})",
+                        version,
+                        timeout,
+                        ledger_version
+                    )),
+                };
+            }
+            sleep(Duration::from_millis(TRANSACTION_POLL_INTERVAL_MS)).await;
+        }
+    }
+
+    /// Versions of the transactions committed between two Unix timestamps
+    /// (seconds, both inclusive), or `None` if there are none
+    ///
+    /// Binary-searches blocks by height, so it costs about 30 requests on a
+    /// long-running network. Fails if the range starts before the node's
+    /// oldest unpruned block.
+    pub async fn get_version_range_for_time(
+        &self,
+        start_secs: u64,
+        end_secs: u64,
+    ) -> Result<Option<RangeInclusive<u64>>> {
+        if start_secs > end_secs {
+            return Err(anyhow!(
+                "Invalid time range: start {} is after end {}",
+                start_secs,
+                end_secs
+            ));
+        }
+        let first = self
+            .first_version_at_or_after(start_secs.saturating_mul(1_000_000))
+            .await?;
+        let after_end = self
+            .first_version_at_or_after(end_secs.saturating_add(1).saturating_mul(1_000_000))
+            .await?;
+        Ok((after_end > first).then(|| first..=after_end - 1))
+    }
+
+    /// First version in a block with timestamp at or after `timestamp_usecs`,
+    /// or one past the ledger version if there is none yet
+    async fn first_version_at_or_after(&self, timestamp_usecs: u64) -> Result<u64> {
+        let response = logged(
+            self.call_log("GET", ""),
+            self.rest_client.get_ledger_information(),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get ledger information from Aptos node: {}", e))?;
+        let state = response.inner();
+        if state.timestamp_usecs < timestamp_usecs {
+            return Ok(state.version + 1);
+        }
+
+        // Smallest height in [low, high] whose block is not before the timestamp
+        let (mut low, mut high) = (state.oldest_block_height, state.block_height);
+        let mut first_version = None;
+        while low <= high {
+            let height = low + (high - low) / 2;
+            let block = logged(
+                self.call_log("GET", format_args!("blocks/by_height/{}", height)),
+                self.rest_client.get_block_by_height(height, false),
+            )
             .await
-            .map_err(|e| {
-                anyhow!(
-                    "Failed to get aptos transaction by version {}: {}",
-                    version,
-                    e
-                )
-            })?;
+            .map_err(|e| anyhow!("Failed to get block at height {}: {}", height, e))?
+            .into_inner();
+            if block.block_timestamp.0 >= timestamp_usecs {
+                first_version = Some(block.first_version.0);
+                if height == 0 {
+                    break;
+                }
+                high = height - 1;
+            } else {
+                low = height + 1;
+            }
+        }
+
+        match first_version {
+            Some(version) if low > state.oldest_block_height || state.oldest_block_height == 0 => {
+                Ok(version)
+            }
+            Some(_) => Err(anyhow!(
+                "Timestamp {}s predates the node's oldest block {}; use an archive node",
+                timestamp_usecs / 1_000_000,
+                state.oldest_block_height
+            )),
+            // The ledger timestamp is at or after it, so the newest block is
+            None => Ok(state.version + 1),
+        }
+    }
+
+    pub async fn get_tx_hash_by_version(&self, version: u64) -> Result<String> {
+        let response = logged(
+            self.call_log("GET", format_args!("transactions/by_version/{}", version)),
+            self.rest_client.get_transaction_by_version_bcs(version),
+        )
+        .await
+        .map_err(|e| {
+            anyhow!(
+                "Failed to get aptos transaction by version {}: {}",
+                version,
+                e
+            )
+        })?;
 
         match response.into_inner() {
             TransactionData::OnChain(txn) => Ok(txn.info.transaction_hash().to_hex_literal()),
@@ -72,29 +638,35 @@ impl QueryClient {
         }
     }
 
+    /// Decoder for bridge events of `bridge_contract_address`, with this
+    /// client's bridge module and event schema version
+    ///
+    /// For backfills that fetch transactions themselves; reuse it across events.
+    #[cfg(feature = "events-rest")]
+    pub fn bridge_event_decoder(
+        &self,
+        bridge_contract_address: &str,
+    ) -> Result<BridgeEventDecoder> {
+        BridgeEventDecoder::new(
+            bridge_contract_address,
+            &self.bridge_module,
+            self.event_schema_version,
+        )
+    }
+
     /// Get bridge events from user transaction hash
+    #[cfg(feature = "events-rest")]
     pub async fn get_bridge_events_by_hash(
         &self,
         tx_hash: &str,
         bridge_contract_address: &str,
     ) -> Result<Vec<BridgeEvent>> {
-        // Get transaction details
-        let transaction = self.get_transaction_by_hash(tx_hash).await?;
-
-        // Only process user transactions
-        let events = match transaction {
-            TransactionData::OnChain(txn) => txn.events,
-            _ => {
-                return Err(anyhow!(
-                    "Transaction {} is not a user transaction. Only user and not pending transactions are supported.",
-                    tx_hash
-                ));
-            }
-        };
+        let txn = self.get_on_chain_transaction(tx_hash).await?;
+        let decoder = self.bridge_event_decoder(bridge_contract_address)?;
         let mut bridge_events = Vec::new();
         // Parse each event
-        for event in &events {
-            if let Some(bridge_event) = self.parse_bridge_event(event, bridge_contract_address)? {
+        for event in &txn.events {
+            if let Some(bridge_event) = decoder.decode(event)? {
                 bridge_events.push(bridge_event);
             }
         }
@@ -102,68 +674,579 @@ impl QueryClient {
         Ok(bridge_events)
     }
 
-    /// Parse a single event to check if it's a bridge event using BCS directly
-    fn parse_bridge_event(
+    /// Get the bridge events of a transaction with their event index, type,
+    /// sequence number and the transaction's version, hash and block timestamp
+    #[cfg(feature = "events-rest")]
+    pub async fn get_bridge_events_detailed(
         &self,
-        event: &ContractEvent,
+        tx_hash: &str,
         bridge_contract_address: &str,
-    ) -> Result<Option<BridgeEvent>> {
-        let event_type_tag = event.type_tag();
-        let event_type_str = event_type_tag.to_canonical_string();
-
-        // Parse and normalize contract addresses using Aptos SDK
-        let expected_addr = AccountAddress::from_str(bridge_contract_address)
-            .map_err(|e| anyhow!("Invalid bridge contract address: {}", e))?;
-
-        // Extract contract address from event type
-        let event_addr_str = if let Some(pos) = event_type_str.find("::") {
-            &event_type_str[..pos]
-        } else {
-            return Ok(None);
-        };
+    ) -> Result<Vec<BridgeEventRecord>> {
+        let txn = self.get_on_chain_transaction(tx_hash).await?;
+        let decoder = self.bridge_event_decoder(bridge_contract_address)?;
+        let transaction_hash = txn.info.transaction_hash().to_hex_literal();
 
-        let event_addr = AccountAddress::from_str(event_addr_str)
-            .map_err(|e| anyhow!("Invalid event contract address: {}", e))?;
+        let mut records = Vec::new();
+        let mut timestamp = None;
+        for (event_index, event) in txn.events.iter().enumerate() {
+            let Some(mut bridge_event) = decoder.decode(event)? else {
+                continue;
+            };
+            let timestamp = match timestamp {
+                Some(timestamp) => timestamp,
+                None => *timestamp.insert(self.block_timestamp_secs(txn.version).await?),
+            };
+            set_transaction_metadata(
+                &mut bridge_event,
+                txn.version,
+                transaction_hash.clone(),
+                timestamp,
+            );
+            records.push(BridgeEventRecord {
+                event: bridge_event,
+                event_index: event_index as u64,
+                event_type: event.type_tag().to_canonical_string(),
+                sequence_number: match event {
+                    ContractEvent::V1(event) => Some(event.sequence_number()),
+                    ContractEvent::V2(_) => None,
+                },
+                version: txn.version,
+                transaction_hash: transaction_hash.clone(),
+                timestamp,
+            });
+        }
 
-        // Compare normalized addresses
-        if event_addr != expected_addr {
-            return Ok(None);
+        Ok(records)
+    }
+
+    /// Committed transaction by hash, with its events
+    #[cfg(feature = "events-rest")]
+    async fn get_on_chain_transaction(&self, tx_hash: &str) -> Result<TransactionOnChainData> {
+        match self.get_transaction_by_hash(tx_hash).await? {
+            TransactionData::OnChain(txn) => Ok(txn),
+            _ => Err(anyhow!(
+                "Transaction {} is not a user transaction. Only user and not pending transactions are supported.",
+                tx_hash
+            )),
         }
+    }
 
-        let event_data = event.event_data();
+    /// Timestamp of the block containing `version`, in seconds
+    #[cfg(feature = "events-rest")]
+    async fn block_timestamp_secs(&self, version: u64) -> Result<u64> {
+        let block = logged(
+            self.call_log("GET", format_args!("blocks/by_version/{}", version)),
+            self.rest_client.get_block_by_version(version, false),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get block at version {}: {}", version, e))?;
+        Ok(block.inner().block_timestamp.0 / 1_000_000)
+    }
 
-        // Parse BCS event data directly based on event type
-        let bridge_event = if event_type_str.ends_with("::bridge::Mint") {
-            let mint_bcs: MintEventBCS = bcs::from_bytes(event_data).map_err(|e| {
-                anyhow!(
-                    "Failed to deserialize mint event data: {} (type: {})",
-                    e,
-                    event_type_str
-                )
-            })?;
-            BridgeEvent::Mint(mint_bcs.into())
-        } else if event_type_str.ends_with("::bridge::Burn") {
-            let burn_bcs: BurnEventBCS = bcs::from_bytes(event_data).map_err(|e| {
-                anyhow!(
-                    "Failed to deserialize burn event data: {} (type: {})",
-                    e,
-                    event_type_str
-                )
-            })?;
-            BridgeEvent::Burn(burn_bcs.into())
-        } else if event_type_str.ends_with("::bridge::WithdrawByLP") {
-            let withdraw_bcs: WithdrawByLPEventBCS = bcs::from_bytes(event_data).map_err(|e| {
-                anyhow!(
-                    "Failed to deserialize withdraw event data: {} (type: {})",
-                    e,
-                    event_type_str
+    /// Scan every transaction in `versions` for bridge events
+    ///
+    /// Events get the version, hash and block timestamp of their transaction.
+    /// This reads the whole range from the fullnode, one page at a time, and
+    /// holds every event in memory; use [`QueryClient::stream_bridge_events`]
+    /// for large ranges, or aggregate indexer events with
+    /// [`BridgeStats::from_events`] instead.
+    #[cfg(feature = "events-rest")]
+    pub async fn scan_bridge_events(
+        &self,
+        versions: RangeInclusive<u64>,
+        bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEvent>> {
+        self.stream_bridge_events(
+            versions,
+            bridge_contract_address,
+            TRANSACTION_SCAN_PAGE_SIZE,
+        )
+        .try_collect()
+        .await
+    }
+
+    /// Bridge events committed between two Unix timestamps (seconds, both inclusive)
+    ///
+    /// Translates the times to versions with
+    /// [`QueryClient::get_version_range_for_time`], then scans them like
+    /// [`QueryClient::scan_bridge_events`].
+    #[cfg(feature = "events-rest")]
+    pub async fn get_bridge_events_in_time_range(
+        &self,
+        start_secs: u64,
+        end_secs: u64,
+        bridge_contract_address: &str,
+    ) -> Result<Vec<BridgeEvent>> {
+        match self
+            .get_version_range_for_time(start_secs, end_secs)
+            .await?
+        {
+            Some(versions) => {
+                self.scan_bridge_events(versions, bridge_contract_address)
+                    .await
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Stream the bridge events of every transaction in `versions`
+    ///
+    /// Transactions are fetched `chunk_size` at a time (the node may return
+    /// fewer), and the next chunk is only fetched once the events of the
+    /// previous one have been consumed, so memory stays bounded by one chunk
+    /// however long the range. Events are decorated as in
+    /// [`QueryClient::scan_bridge_events`]; the stream ends after the first error.
+    #[cfg(feature = "events-rest")]
+    pub fn stream_bridge_events<'a>(
+        &'a self,
+        versions: RangeInclusive<u64>,
+        bridge_contract_address: &'a str,
+        chunk_size: u16,
+    ) -> impl Stream<Item = Result<BridgeEvent>> + 'a {
+        let (start, end) = (*versions.start(), *versions.end());
+        let chunk_size = chunk_size.max(1);
+        let cursor = ScanCursor {
+            next: start,
+            block_timestamp_usecs: None,
+            decoder: None,
+        };
+        stream::try_unfold(cursor, move |mut cursor| async move {
+            if cursor.next > end {
+                return Ok(None);
+            }
+            let events = self
+                .scan_chunk(&mut cursor, end, bridge_contract_address, chunk_size)
+                .await?;
+            Ok::<_, anyhow::Error>(events.map(|events| (events, cursor)))
+        })
+        .map_ok(|events| stream::iter(events.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Decode the bridge events of the next chunk of transactions, or `None`
+    /// if the node has no more
+    #[cfg(feature = "events-rest")]
+    async fn scan_chunk(
+        &self,
+        cursor: &mut ScanCursor,
+        end: u64,
+        bridge_contract_address: &str,
+        chunk_size: u16,
+    ) -> Result<Option<Vec<BridgeEvent>>> {
+        let next = cursor.next;
+        let decoder = match cursor.decoder.take() {
+            Some(decoder) => decoder,
+            None => self.bridge_event_decoder(bridge_contract_address)?,
+        };
+        let decoder = cursor.decoder.insert(decoder);
+        // Transactions before the first block metadata transaction in the range
+        // belong to the block containing its start
+        let mut block_timestamp_usecs = match cursor.block_timestamp_usecs {
+            Some(timestamp) => timestamp,
+            None => {
+                let block = logged(
+                    self.call_log("GET", format_args!("blocks/by_version/{}", next)),
+                    self.rest_client.get_block_by_version(next, false),
                 )
-            })?;
-            BridgeEvent::WithdrawByLP(withdraw_bcs.into())
-        } else {
-            return Ok(None);
+                .await
+                .map_err(|e| anyhow!("Failed to get block at version {}: {}", next, e))?;
+                block.inner().block_timestamp.0
+            }
         };
 
-        Ok(Some(bridge_event))
+        let limit = (end - next + 1).min(chunk_size as u64) as u16;
+        let response = logged(
+            self.call_log(
+                "GET",
+                format_args!("transactions?start={}&limit={}", next, limit),
+            ),
+            self.rest_client
+                .get_transactions_bcs(Some(next), Some(limit)),
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to get transactions from version {}: {}", next, e))?;
+        let transactions = response.into_inner();
+        if transactions.is_empty() {
+            return Ok(None);
+        }
+
+        let mut bridge_events = Vec::new();
+        for txn in &transactions {
+            match &txn.transaction {
+                Transaction::BlockMetadata(metadata) => {
+                    block_timestamp_usecs = metadata.timestamp_usecs()
+                }
+                Transaction::BlockMetadataExt(metadata) => {
+                    block_timestamp_usecs = metadata.timestamp_usecs()
+                }
+                _ => {}
+            }
+            for event in &txn.events {
+                if let Some(mut bridge_event) = decoder.decode(event)? {
+                    set_transaction_metadata(
+                        &mut bridge_event,
+                        txn.version,
+                        txn.info.transaction_hash().to_hex_literal(),
+                        block_timestamp_usecs / 1_000_000,
+                    );
+                    bridge_events.push(bridge_event);
+                }
+            }
+        }
+        cursor.next += transactions.len() as u64;
+        cursor.block_timestamp_usecs = Some(block_timestamp_usecs);
+        Ok(Some(bridge_events))
+    }
+
+    /// Daily, per-operator and per-LP bridge activity over `versions`
+    ///
+    /// See [`QueryClient::scan_bridge_events`] for the cost of the scan.
+    #[cfg(feature = "events-rest")]
+    pub async fn get_bridge_stats(
+        &self,
+        versions: RangeInclusive<u64>,
+        bridge_contract_address: &str,
+    ) -> Result<BridgeStats> {
+        let events = self
+            .scan_bridge_events(versions, bridge_contract_address)
+            .await?;
+        Ok(BridgeStats::from_events(&events))
+    }
+}
+
+fn transaction_kind(transaction: &TransactionData) -> TxKind {
+    use aptos_sdk::types::transaction::Transaction as ChainTransaction;
+
+    match transaction {
+        TransactionData::OnChain(txn) => match &txn.transaction {
+            ChainTransaction::UserTransaction(_) => TxKind::User,
+            ChainTransaction::GenesisTransaction(_) => TxKind::Genesis,
+            ChainTransaction::BlockMetadata(_) | ChainTransaction::BlockMetadataExt(_) => {
+                TxKind::BlockMetadata
+            }
+            ChainTransaction::StateCheckpoint(_) => TxKind::StateCheckpoint,
+            ChainTransaction::ValidatorTransaction(_) => TxKind::Validator,
+            _ => TxKind::Other,
+        },
+        TransactionData::Pending(_) => TxKind::Pending,
+    }
+}
+
+/// Progress of [`QueryClient::stream_bridge_events`]
+#[cfg(feature = "events-rest")]
+struct ScanCursor {
+    /// First version not scanned yet
+    next: u64,
+    /// Timestamp of the block containing `next - 1`, once the scan has started
+    block_timestamp_usecs: Option<u64>,
+    decoder: Option<BridgeEventDecoder>,
+}
+
+/// Fill in the transaction fields BCS events don't carry
+///
+/// A timestamp already in the event (burn schema V2) is kept.
+#[cfg(feature = "events-rest")]
+fn set_transaction_metadata(
+    event: &mut BridgeEvent,
+    version: u64,
+    transaction_hash: String,
+    block_timestamp: u64,
+) {
+    let (event_version, event_hash, event_timestamp) = match event {
+        BridgeEvent::Mint(e) => (&mut e.version, &mut e.transaction_hash, &mut e.timestamp),
+        BridgeEvent::Burn(e) => (&mut e.version, &mut e.transaction_hash, &mut e.timestamp),
+        BridgeEvent::WithdrawByLP(e) => (&mut e.version, &mut e.transaction_hash, &mut e.timestamp),
+    };
+    *event_version = Some(version);
+    *event_hash = Some(transaction_hash);
+    event_timestamp.get_or_insert(block_timestamp);
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use crate::testing::{self, BRIDGE_ADDRESS};
+    use crate::{GasEstimate, GasPriority, QueryClient};
+    use aptos_sdk::move_types::identifier::Identifier;
+    use aptos_sdk::rest_client::aptos_api_types::IdentifierWrapper;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_view_many_keeps_request_order() {
+        let server = testing::mock_fullnode().await;
+        let client = testing::bridge_client(&server);
+
+        let mut missing = client.withdraw_status_view_request(1).unwrap();
+        missing.function.name = IdentifierWrapper(Identifier::new("missing").unwrap());
+        let requests = vec![
+            client.lp_withdraw_view_request(1).unwrap(),
+            missing,
+            client.withdraw_status_view_request(1).unwrap(),
+        ];
+        let query_client = QueryClient::new(&testing::fullnode_url(&server), None)
+            .unwrap()
+            .with_view_concurrency(2);
+        let results = query_client.view_many(&requests).await;
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0]
+            .as_ref()
+            .unwrap()
+            .get("withdraw_amount")
+            .is_some());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+        assert_eq!(
+            client.get_bridge_config().await.unwrap().min_confirmations,
+            6
+        );
+    }
+
+    #[tokio::test]
+    async fn test_estimate_gas_price() {
+        let server = testing::mock_fullnode().await;
+        testing::mount_gas_estimate(
+            &server,
+            serde_json::json!({
+                "gas_estimate": 100,
+                "deprioritized_gas_estimate": 80,
+                "prioritized_gas_estimate": 150,
+            }),
+        )
+        .await;
+        let query_client = QueryClient::new(&testing::fullnode_url(&server), None).unwrap();
+        assert_eq!(
+            query_client.estimate_gas_price().await.unwrap(),
+            GasEstimate {
+                deprioritized: 80,
+                market: 100,
+                prioritized: 150,
+            }
+        );
+
+        // Tiers the node leaves out fall back to the market price
+        let server = testing::mock_fullnode().await;
+        testing::mount_gas_estimate(&server, serde_json::json!({"gas_estimate": 120})).await;
+        let query_client = QueryClient::new(&testing::fullnode_url(&server), None).unwrap();
+        let estimate = query_client.estimate_gas_price().await.unwrap();
+        assert_eq!(
+            (
+                estimate.deprioritized,
+                estimate.market,
+                estimate.prioritized
+            ),
+            (120, 120, 120)
+        );
+        assert_eq!(estimate.price(GasPriority::Prioritized), 120);
+
+        let server = testing::mock_fullnode().await;
+        let query_client = QueryClient::new(&testing::fullnode_url(&server), None).unwrap();
+        assert!(query_client.estimate_gas_price().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_node_health() {
+        let server = testing::mock_fullnode().await;
+        let node_url = testing::fullnode_url(&server);
+        let query_client = QueryClient::new(&node_url, None)
+            .unwrap()
+            .with_expected_chain_id(testing::CHAIN_ID);
+
+        let ledger_info = query_client.get_ledger_info().await.unwrap();
+        assert_eq!(ledger_info.chain_id, testing::CHAIN_ID);
+        assert_eq!(ledger_info.ledger_version, testing::LEDGER_VERSION);
+        assert_eq!(ledger_info.epoch, 9876);
+
+        // The fixture ledger timestamp is in 2024
+        let max_staleness = Duration::from_secs(100 * 365 * 24 * 3600);
+        assert!(query_client.check_node_health(max_staleness).await.is_ok());
+        assert!(query_client
+            .check_node_health(Duration::from_secs(60))
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("stale"));
+
+        assert_eq!(
+            query_client
+                .wait_for_version(testing::LEDGER_VERSION, Duration::ZERO)
+                .await
+                .unwrap(),
+            testing::LEDGER_VERSION
+        );
+        assert!(query_client
+            .wait_for_version(testing::LEDGER_VERSION + 1, Duration::ZERO)
+            .await
+            .is_err());
+
+        let mainnet_client = QueryClient::new(&node_url, None)
+            .unwrap()
+            .with_expected_chain_id(1);
+        assert!(mainnet_client
+            .check_node_health(max_staleness)
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("expected chain id 1"));
+    }
+
+    /// One block per second up to the fixture ledger, two transactions each
+    struct Blocks;
+
+    const TIP_HEIGHT: u64 = 3_210_987;
+    const TIP_TIMESTAMP_SECS: u64 = 1_714_564_800;
+
+    fn block_first_version(height: u64) -> u64 {
+        testing::LEDGER_VERSION - 1 - (TIP_HEIGHT - height) * 2
+    }
+
+    impl wiremock::Respond for Blocks {
+        fn respond(&self, request: &wiremock::Request) -> wiremock::ResponseTemplate {
+            let height: u64 = request
+                .url
+                .path()
+                .rsplit('/')
+                .next()
+                .and_then(|height| height.parse().ok())
+                .unwrap();
+            let first_version = block_first_version(height);
+            testing::fullnode_response(
+                &serde_json::json!({
+                    "block_height": height.to_string(),
+                    "block_hash": format!("0x{:064x}", height),
+                    "block_timestamp": ((TIP_TIMESTAMP_SECS - (TIP_HEIGHT - height)) * 1_000_000).to_string(),
+                    "first_version": first_version.to_string(),
+                    "last_version": (first_version + 1).to_string(),
+                    "transactions": null,
+                })
+                .to_string(),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_version_range_for_time() {
+        let server = testing::mock_fullnode().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path_regex(
+                r"^/v1/blocks/by_height/\d+$",
+            ))
+            .respond_with(Blocks)
+            .mount(&server)
+            .await;
+        let query_client = QueryClient::new(&testing::fullnode_url(&server), None).unwrap();
+
+        let range = query_client
+            .get_version_range_for_time(TIP_TIMESTAMP_SECS - 10, TIP_TIMESTAMP_SECS - 5)
+            .await
+            .unwrap();
+        assert_eq!(
+            range,
+            Some(block_first_version(TIP_HEIGHT - 10)..=block_first_version(TIP_HEIGHT - 5) + 1)
+        );
+        assert_eq!(
+            query_client
+                .get_version_range_for_time(TIP_TIMESTAMP_SECS + 1, TIP_TIMESTAMP_SECS + 60)
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(query_client
+            .get_version_range_for_time(TIP_TIMESTAMP_SECS, TIP_TIMESTAMP_SECS - 1)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_table_items() {
+        let server = testing::mock_fullnode().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path_regex(
+                r"^/v1/accounts/[^/]+/resource/.*::bridge::BridgeStore$",
+            ))
+            .respond_with(testing::fullnode_response(
+                &serde_json::json!({
+                    "type": format!("{}::bridge::BridgeStore", BRIDGE_ADDRESS),
+                    "data": {
+                        "pegs": { "handle": "0xaa" },
+                        "lp_withdraws": { "handle": "0xbb" },
+                    },
+                })
+                .to_string(),
+            ))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path(format!(
+                "/v1/tables/{}/item",
+                aptos_sdk::types::account_address::AccountAddress::from_hex_literal("0xaa")
+                    .unwrap()
+            )))
+            .respond_with(testing::fullnode_response(
+                r#"{"to":"0x5","value":"110000","block_num":"812345"}"#,
+            ))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path_regex(r"^/v1/tables/[^/]+/item$"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                    "message": "Table Item not found",
+                    "error_code": "table_item_not_found",
+                    "vm_error_code": null,
+                })),
+            )
+            .mount(&server)
+            .await;
+        let client = testing::bridge_client(&server);
+
+        let peg_record = client.get_peg_record(&[1; 32], 0).await.unwrap().unwrap();
+        assert_eq!(peg_record.value, crate::Sats(110_000));
+        assert_eq!(peg_record.block_num, 812_345);
+        assert!(client.get_withdraw_record(7).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_account_resources() {
+        let server = testing::mock_fullnode().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path_regex(
+                r"^/v1/accounts/[^/]+/resources$",
+            ))
+            .respond_with(testing::fullnode_response(
+                &serde_json::json!([
+                    {
+                        "type": "0x1::account::Account",
+                        "data": { "sequence_number": "3" },
+                    },
+                    {
+                        "type": format!("{}::bridge::BridgeStore", BRIDGE_ADDRESS),
+                        "data": { "next_withdraw_id": "8" },
+                    },
+                ])
+                .to_string(),
+            ))
+            .mount(&server)
+            .await;
+        let query_client = QueryClient::new(&testing::fullnode_url(&server), None).unwrap();
+        let address =
+            aptos_sdk::types::account_address::AccountAddress::from_hex_literal(BRIDGE_ADDRESS)
+                .unwrap();
+
+        assert_eq!(
+            query_client
+                .list_account_resources(address, None)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        let resources = query_client
+            .list_account_resources(address, Some("bridge"))
+            .await
+            .unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].module, "bridge");
+        let data: serde_json::Map<String, serde_json::Value> = resources[0].decode().unwrap();
+        assert_eq!(data["next_withdraw_id"], "8");
     }
 }
@@ -0,0 +1,247 @@
+//! Proof-of-reserve reconciliation
+//!
+//! Compares three views of the peg: net issuance from indexed events, the
+//! on-chain total supply, and the BTC actually held in custody. Event totals
+//! come from an [`EventStore`](crate::EventStore) (`postgres` feature) via
+//! [`EventTotals::from`], or from any slice of events, e.g. those returned by
+//! `EventMonitor::replay` (which also re-dispatches them to its handler).
+
+use crate::types::{BridgeEvent, Sats};
+use crate::BridgeClient;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Net issuance from bridge events
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventTotals {
+    /// Sum of `MintEvent` amounts
+    pub minted: Sats,
+    /// Sum of `BurnEvent` amounts
+    pub burned: Sats,
+    /// Sum of `WithdrawByLPEvent` amounts
+    pub withdrawn_by_lp: Sats,
+}
+
+impl EventTotals {
+    /// Totals over `events`
+    pub fn from_events(events: &[BridgeEvent]) -> Self {
+        let mut totals = Self::default();
+        for event in events {
+            let (total, amount) = match event {
+                BridgeEvent::Mint(event) => (&mut totals.minted, event.amount),
+                BridgeEvent::Burn(event) => (&mut totals.burned, event.amount),
+                BridgeEvent::WithdrawByLP(event) => (&mut totals.withdrawn_by_lp, event.amount),
+            };
+            *total = Sats(total.0.saturating_add(amount.0));
+        }
+        totals
+    }
+
+    /// Supply implied by the events, or `None` if more left the peg than entered it
+    pub fn expected_supply(&self) -> Option<Sats> {
+        self.minted
+            .checked_sub(self.burned)?
+            .checked_sub(self.withdrawn_by_lp)
+    }
+}
+
+#[cfg(all(feature = "postgres", not(target_arch = "wasm32")))]
+impl From<crate::EventVolume> for EventTotals {
+    fn from(volume: crate::EventVolume) -> Self {
+        Self {
+            minted: volume.mint_amount,
+            burned: volume.burn_amount,
+            withdrawn_by_lp: volume.withdraw_by_lp_amount,
+        }
+    }
+}
+
+/// BTC output held by the bridge custodians
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustodyUtxo {
+    /// Transaction ID, display byte order
+    pub txid: String,
+    /// Output index
+    pub vout: u32,
+    /// Output value
+    pub value: Sats,
+}
+
+/// Problem found by [`ReserveReport::new`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Discrepancy {
+    /// More was burned or withdrawn than was ever minted
+    BurnsExceedMints { totals: EventTotals },
+    /// Event totals and the on-chain supply disagree
+    SupplyMismatch { expected: Sats, on_chain: Sats },
+    /// Custody holds less BTC than the on-chain supply
+    Undercollateralized { supply: Sats, custody: Sats },
+    /// The same UTXO was listed more than once
+    DuplicateUtxo { txid: String, vout: u32 },
+}
+
+/// Reconciliation result for auditors
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReserveReport {
+    /// Event totals the report was built from
+    pub totals: EventTotals,
+    /// Supply implied by `totals`, if non-negative
+    pub expected_supply: Option<Sats>,
+    /// Total supply reported by the peg contract
+    pub on_chain_supply: Sats,
+    /// Sum of distinct custody UTXO values
+    pub custody_balance: Sats,
+    /// Everything that didn't reconcile, empty when balanced
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl ReserveReport {
+    /// Reconcile event totals, on-chain supply and custody UTXOs
+    ///
+    /// Duplicate UTXOs are reported and counted once.
+    pub fn new(totals: EventTotals, on_chain_supply: Sats, custody: &[CustodyUtxo]) -> Self {
+        let mut discrepancies = Vec::new();
+
+        let expected_supply = totals.expected_supply();
+        match expected_supply {
+            None => discrepancies.push(Discrepancy::BurnsExceedMints { totals }),
+            Some(expected) if expected != on_chain_supply => {
+                discrepancies.push(Discrepancy::SupplyMismatch {
+                    expected,
+                    on_chain: on_chain_supply,
+                })
+            }
+            Some(_) => {}
+        }
+
+        let mut seen = HashSet::new();
+        let mut custody_balance = Sats::ZERO;
+        for utxo in custody {
+            if !seen.insert((utxo.txid.as_str(), utxo.vout)) {
+                discrepancies.push(Discrepancy::DuplicateUtxo {
+                    txid: utxo.txid.clone(),
+                    vout: utxo.vout,
+                });
+                continue;
+            }
+            custody_balance = Sats(custody_balance.0.saturating_add(utxo.value.0));
+        }
+        if custody_balance < on_chain_supply {
+            discrepancies.push(Discrepancy::Undercollateralized {
+                supply: on_chain_supply,
+                custody: custody_balance,
+            });
+        }
+
+        Self {
+            totals,
+            expected_supply,
+            on_chain_supply,
+            custody_balance,
+            discrepancies,
+        }
+    }
+
+    /// Whether everything reconciled
+    pub fn is_balanced(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// Fetch the on-chain total supply and reconcile it against `totals` and `custody`
+pub async fn reconcile(
+    client: &BridgeClient,
+    totals: EventTotals,
+    custody: &[CustodyUtxo],
+) -> Result<ReserveReport> {
+    let on_chain_supply = client.get_total_peg_supply().await?;
+    Ok(ReserveReport::new(totals, on_chain_supply, custody))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BurnEvent, MintEvent};
+
+    fn utxo(txid: &str, vout: u32, value: u64) -> CustodyUtxo {
+        CustodyUtxo {
+            txid: txid.to_string(),
+            vout,
+            value: Sats(value),
+        }
+    }
+
+    fn mint(amount: u64) -> BridgeEvent {
+        BridgeEvent::Mint(MintEvent {
+            to_address: "0x1".to_string(),
+            amount: Sats(amount),
+            btc_tx_id: "aa".to_string(),
+            btc_block_num: 1,
+            timestamp: None,
+            version: None,
+            transaction_hash: None,
+        })
+    }
+
+    fn burn(amount: u64) -> BridgeEvent {
+        BridgeEvent::Burn(BurnEvent {
+            from_address: "0x1".to_string(),
+            btc_address: "bc1q".to_string(),
+            fee_rate: 1,
+            amount: Sats(amount),
+            operator_id: 0,
+            timestamp: None,
+            version: None,
+            transaction_hash: None,
+        })
+    }
+
+    #[test]
+    fn test_event_totals_from_events() {
+        let events = vec![mint(700), mint(300), burn(400)];
+        let totals = EventTotals::from_events(&events);
+        assert_eq!(totals.minted, Sats(1000));
+        assert_eq!(totals.burned, Sats(400));
+        assert_eq!(totals.expected_supply(), Some(Sats(600)));
+    }
+
+    #[test]
+    fn test_balanced_report() {
+        let totals = EventTotals {
+            minted: Sats(1000),
+            burned: Sats(300),
+            withdrawn_by_lp: Sats(100),
+        };
+        let report =
+            ReserveReport::new(totals, Sats(600), &[utxo("aa", 0, 400), utxo("bb", 1, 250)]);
+        assert!(report.is_balanced());
+        assert_eq!(report.custody_balance, Sats(650));
+    }
+
+    #[test]
+    fn test_report_discrepancies() {
+        let totals = EventTotals {
+            minted: Sats(100),
+            burned: Sats(200),
+            withdrawn_by_lp: Sats::ZERO,
+        };
+        let report =
+            ReserveReport::new(totals, Sats(500), &[utxo("aa", 0, 400), utxo("aa", 0, 400)]);
+        assert_eq!(
+            report.discrepancies,
+            vec![
+                Discrepancy::BurnsExceedMints { totals },
+                Discrepancy::DuplicateUtxo {
+                    txid: "aa".to_string(),
+                    vout: 0
+                },
+                Discrepancy::Undercollateralized {
+                    supply: Sats(500),
+                    custody: Sats(400)
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,309 @@
+//! BTC reorg detection
+//!
+//! Remembers the hashes of the most recent BTC blocks and re-reads them on every
+//! poll. When blocks that were part of the best chain disappear, a
+//! [`ReorgDetected`] notification is sent to the event handler so that work built
+//! on the orphaned blocks (peg-in proofs in particular) can be redone.
+
+#[cfg(feature = "bitcoind")]
+use crate::bitcoind::BitcoindRpc;
+use crate::btc::txid_to_display_hex;
+use crate::events::EventHandler;
+use crate::types::constants::{REORG_MIN_DEPTH, REORG_POLL_INTERVAL_SECS, REORG_TRACKED_BLOCKS};
+use crate::types::{OrphanedBlock, ReorgDetected};
+use crate::BridgeClient;
+use anyhow::Result;
+#[cfg(feature = "bitcoind")]
+use bitcoin::hashes::Hash;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Chain whose best blocks are watched
+enum ChainSource {
+    /// On-chain BTC light client
+    LightClient(Arc<BridgeClient>),
+    /// Bitcoin Core node
+    #[cfg(feature = "bitcoind")]
+    Bitcoind(BitcoindRpc),
+}
+
+impl ChainSource {
+    async fn tip_height(&self) -> Result<u64> {
+        match self {
+            ChainSource::LightClient(bridge_client) => {
+                bridge_client.get_latest_block_height().await
+            }
+            #[cfg(feature = "bitcoind")]
+            ChainSource::Bitcoind(bitcoind) => bitcoind.get_block_count().await,
+        }
+    }
+
+    /// Block hash at `height`, internal byte order
+    async fn block_hash(&self, height: u64) -> Result<Vec<u8>> {
+        match self {
+            ChainSource::LightClient(bridge_client) => bridge_client.get_block_hash(height).await,
+            #[cfg(feature = "bitcoind")]
+            ChainSource::Bitcoind(bitcoind) => Ok(bitcoind
+                .get_block_hash(height)
+                .await?
+                .to_byte_array()
+                .to_vec()),
+        }
+    }
+}
+
+/// BTC reorg detector
+pub struct ReorgDetector {
+    /// Chain being watched
+    source: ChainSource,
+    /// Receiver of `ReorgDetected` notifications
+    handler: Option<Arc<dyn EventHandler>>,
+    /// Recent best-chain blocks as `(height, hash)`, lowest first
+    tracked: VecDeque<(u64, Vec<u8>)>,
+    /// Number of recent blocks remembered
+    tracked_blocks: usize,
+    /// Shallowest reorg that is reported
+    min_depth: u64,
+    /// Delay between polls in `run`
+    poll_interval: Duration,
+}
+
+impl ReorgDetector {
+    /// Watch the blocks accepted by the on-chain light client
+    pub fn light_client(bridge_client: Arc<BridgeClient>) -> Self {
+        Self::new(ChainSource::LightClient(bridge_client))
+    }
+
+    /// Watch the best chain of a Bitcoin Core node
+    #[cfg(feature = "bitcoind")]
+    pub fn bitcoind(bitcoind: BitcoindRpc) -> Self {
+        Self::new(ChainSource::Bitcoind(bitcoind))
+    }
+
+    fn new(source: ChainSource) -> Self {
+        Self {
+            source,
+            handler: None,
+            tracked: VecDeque::new(),
+            tracked_blocks: REORG_TRACKED_BLOCKS,
+            min_depth: REORG_MIN_DEPTH,
+            poll_interval: Duration::from_secs(REORG_POLL_INTERVAL_SECS),
+        }
+    }
+
+    /// Send `ReorgDetected` notifications to `handler`
+    pub fn with_handler(mut self, handler: Arc<dyn EventHandler>) -> Self {
+        self.handler = Some(handler);
+        self
+    }
+
+    /// Set the number of recent blocks remembered, which bounds the detectable depth
+    pub fn with_tracked_blocks(mut self, tracked_blocks: usize) -> Self {
+        self.tracked_blocks = tracked_blocks.max(1);
+        self
+    }
+
+    /// Only report reorgs dropping at least `min_depth` blocks
+    pub fn with_min_depth(mut self, min_depth: u64) -> Self {
+        self.min_depth = min_depth.max(1);
+        self
+    }
+
+    /// Set the delay between polls in `run`
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Poll forever, sleeping `poll_interval` between polls
+    pub async fn run(&mut self) -> Result<()> {
+        loop {
+            if let Err(_error) = self.poll().await {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %_error, "Reorg detection poll failed");
+            }
+            crate::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Compare remembered blocks with the current chain and record new blocks
+    ///
+    /// Returns the reorg found, if it is at least `min_depth` blocks deep. The
+    /// first poll only records blocks.
+    pub async fn poll(&mut self) -> Result<Option<ReorgDetected>> {
+        let tip = self.source.tip_height().await?;
+
+        // Walk down from the highest remembered block until the chains agree
+        let mut orphaned_blocks = Vec::new();
+        while let Some((height, hash)) = self.tracked.back() {
+            if *height <= tip && self.source.block_hash(*height).await? == *hash {
+                break;
+            }
+            orphaned_blocks.push(OrphanedBlock {
+                height: *height,
+                hash: txid_to_display_hex(hash),
+            });
+            self.tracked.pop_back();
+        }
+
+        let reorg = orphaned_blocks.last().map(|lowest| ReorgDetected {
+            fork_height: lowest.height.saturating_sub(1),
+            depth: orphaned_blocks.len() as u64,
+            orphaned_blocks: orphaned_blocks.clone(),
+            new_tip_height: tip,
+        });
+
+        self.record_new_blocks(tip).await?;
+
+        let Some(reorg) = reorg.filter(|reorg| reorg.depth >= self.min_depth) else {
+            return Ok(None);
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            fork_height = reorg.fork_height,
+            depth = reorg.depth,
+            new_tip_height = reorg.new_tip_height,
+            "BTC reorg detected"
+        );
+        if let Some(handler) = &self.handler {
+            handler.handle_reorg(reorg.clone()).await?;
+        }
+        Ok(Some(reorg))
+    }
+
+    /// Remember the blocks above the highest tracked one, up to `tip`
+    async fn record_new_blocks(&mut self, tip: u64) -> Result<()> {
+        let window_start = (tip + 1).saturating_sub(self.tracked_blocks as u64);
+        // A gap between remembered and new blocks would hide reorgs inside it
+        if self
+            .tracked
+            .back()
+            .is_some_and(|(height, _)| height + 1 < window_start)
+        {
+            self.tracked.clear();
+        }
+        let start = self
+            .tracked
+            .back()
+            .map_or(window_start, |(height, _)| height + 1);
+
+        for height in start..=tip {
+            let hash = self.source.block_hash(height).await?;
+            self.tracked.push_back((height, hash));
+        }
+        while self.tracked.len() > self.tracked_blocks {
+            self.tracked.pop_front();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "bitcoind", feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::testing::{self, MockBitcoind};
+    use crate::types::{BurnEvent, MintEvent, WithdrawByLPEvent};
+    use async_trait::async_trait;
+    use bitcoin::block::Header;
+    use std::sync::Mutex;
+    use wiremock::MockServer;
+
+    /// Records the reorgs it is notified of
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<ReorgDetected>>);
+
+    #[async_trait]
+    impl EventHandler for Recorder {
+        async fn handle_mint(&self, _event: MintEvent) -> Result<()> {
+            Ok(())
+        }
+
+        async fn handle_burn(&self, _event: BurnEvent) -> Result<()> {
+            Ok(())
+        }
+
+        async fn handle_withdraw_by_lp(&self, _event: WithdrawByLPEvent) -> Result<()> {
+            Ok(())
+        }
+
+        async fn handle_reorg(&self, event: ReorgDetected) -> Result<()> {
+            self.0.lock().unwrap().push(event);
+            Ok(())
+        }
+    }
+
+    fn orphaned(chain: &[Header], heights: &[u64]) -> Vec<OrphanedBlock> {
+        heights
+            .iter()
+            .map(|height| OrphanedBlock {
+                height: *height,
+                hash: chain[*height as usize].block_hash().to_string(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_poll_ignores_extension_and_reports_replaced_blocks() {
+        let chain = testing::btc_headers(bitcoin::BlockHash::all_zeros(), 12, 0);
+        let server = MockServer::start().await;
+        let bitcoind = MockBitcoind::mount(&server, chain[..10].to_vec()).await;
+        let recorder = Arc::new(Recorder::default());
+        let mut detector = ReorgDetector::bitcoind(BitcoindRpc::new(&server.uri()))
+            .with_handler(recorder.clone())
+            .with_tracked_blocks(5);
+
+        // First poll records blocks; new blocks on top are no reorg
+        assert_eq!(detector.poll().await.unwrap(), None);
+        bitcoind.set_chain(chain.clone());
+        assert_eq!(detector.poll().await.unwrap(), None);
+
+        // Blocks 10 and 11 are replaced by a longer branch from 9
+        let mut reorged = chain[..10].to_vec();
+        reorged.extend(testing::btc_headers(chain[9].block_hash(), 3, 1));
+        bitcoind.set_chain(reorged);
+        let expected = ReorgDetected {
+            fork_height: 9,
+            depth: 2,
+            orphaned_blocks: orphaned(&chain, &[11, 10]),
+            new_tip_height: 12,
+        };
+        assert_eq!(detector.poll().await.unwrap(), Some(expected.clone()));
+        assert_eq!(*recorder.0.lock().unwrap(), [expected]);
+
+        // The new branch is now the remembered one
+        assert_eq!(detector.poll().await.unwrap(), None);
+        assert_eq!(recorder.0.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_poll_reports_rollback_to_shorter_chain() {
+        let chain = testing::btc_headers(bitcoin::BlockHash::all_zeros(), 12, 0);
+        let server = MockServer::start().await;
+        let bitcoind = MockBitcoind::mount(&server, chain.clone()).await;
+        let mut detector = ReorgDetector::bitcoind(BitcoindRpc::new(&server.uri()));
+        assert_eq!(detector.poll().await.unwrap(), None);
+
+        // The tip falls back to 9 before the new branch arrives
+        bitcoind.set_chain(chain[..10].to_vec());
+        assert_eq!(
+            detector.poll().await.unwrap(),
+            Some(ReorgDetected {
+                fork_height: 9,
+                depth: 2,
+                orphaned_blocks: orphaned(&chain, &[11, 10]),
+                new_tip_height: 9,
+            })
+        );
+
+        // Shallower than min_depth: recorded, not reported
+        let mut detector =
+            ReorgDetector::bitcoind(BitcoindRpc::new(&server.uri())).with_min_depth(2);
+        assert_eq!(detector.poll().await.unwrap(), None);
+        let mut reorged = chain[..9].to_vec();
+        reorged.extend(testing::btc_headers(chain[8].block_hash(), 2, 1));
+        bitcoind.set_chain(reorged);
+        assert_eq!(detector.poll().await.unwrap(), None);
+    }
+}
@@ -0,0 +1,33 @@
+//! Transaction signers
+//!
+//! `BridgeClient` signs through a [`TransactionSigner`], so keys can live
+//! outside the process. [`LocalAccount`] signs in memory; with the `vault`
+//! feature, [`VaultSigner`](crate::vault::VaultSigner) signs with a HashiCorp
+//! Vault transit key.
+
+use anyhow::Result;
+use aptos_sdk::types::account_address::AccountAddress;
+use aptos_sdk::types::transaction::{RawTransaction, SignedTransaction};
+use aptos_sdk::types::LocalAccount;
+use async_trait::async_trait;
+
+/// Signs transactions for one account
+#[async_trait]
+pub trait TransactionSigner: Send + Sync {
+    /// Address the transactions are sent from
+    fn address(&self) -> AccountAddress;
+
+    /// Sign `raw_transaction`, whose sender is [`TransactionSigner::address`]
+    async fn sign_transaction(&self, raw_transaction: RawTransaction) -> Result<SignedTransaction>;
+}
+
+#[async_trait]
+impl TransactionSigner for LocalAccount {
+    fn address(&self) -> AccountAddress {
+        LocalAccount::address(self)
+    }
+
+    async fn sign_transaction(&self, raw_transaction: RawTransaction) -> Result<SignedTransaction> {
+        Ok(LocalAccount::sign_transaction(self, raw_transaction))
+    }
+}
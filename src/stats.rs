@@ -0,0 +1,176 @@
+//! Bridge volume statistics
+//!
+//! [`BridgeStats::from_events`] aggregates any slice of events, such as those
+//! from `EventMonitor::replay`; [`QueryClient::get_bridge_stats`](crate::QueryClient::get_bridge_stats)
+//! scans a version range on the fullnode first (`events-rest` feature).
+
+use crate::types::{BridgeEvent, Sats};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Seconds per UTC day
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Event counts and amounts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Volume {
+    /// Number of mints
+    pub mint_count: u64,
+    /// Total minted
+    pub mint_amount: Sats,
+    /// Number of burns
+    pub burn_count: u64,
+    /// Total burned
+    pub burn_amount: Sats,
+    /// Number of LP withdrawals
+    pub withdraw_by_lp_count: u64,
+    /// Total withdrawn through LPs
+    pub withdraw_by_lp_amount: Sats,
+}
+
+impl Volume {
+    fn add(&mut self, event: &BridgeEvent) {
+        let (count, amount, value) = match event {
+            BridgeEvent::Mint(e) => (&mut self.mint_count, &mut self.mint_amount, e.amount),
+            BridgeEvent::Burn(e) => (&mut self.burn_count, &mut self.burn_amount, e.amount),
+            BridgeEvent::WithdrawByLP(e) => (
+                &mut self.withdraw_by_lp_count,
+                &mut self.withdraw_by_lp_amount,
+                e.amount,
+            ),
+        };
+        *count += 1;
+        *amount = Sats(amount.0.saturating_add(value.0));
+    }
+}
+
+/// Activity on one UTC day
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyStats {
+    /// Start of the day, seconds since the Unix epoch
+    pub day_start: u64,
+    /// Counts and amounts
+    pub volume: Volume,
+    /// Distinct addresses that minted, burned or withdrew
+    pub unique_users: u64,
+}
+
+/// Aggregated bridge activity
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeStats {
+    /// Counts and amounts over all events
+    pub total: Volume,
+    /// Distinct addresses over all events
+    pub unique_users: u64,
+    /// Per-day activity, oldest first; events without a timestamp are only in `total`
+    pub daily: Vec<DailyStats>,
+    /// Burns by operator ID
+    pub per_operator: BTreeMap<u64, Volume>,
+    /// LP withdrawals by LP ID
+    pub per_lp: BTreeMap<u64, Volume>,
+}
+
+impl BridgeStats {
+    /// Aggregate `events`
+    pub fn from_events(events: &[BridgeEvent]) -> Self {
+        let mut stats = Self::default();
+        let mut users = HashSet::new();
+        let mut days: BTreeMap<u64, (Volume, HashSet<&str>)> = BTreeMap::new();
+
+        for event in events {
+            let (user, timestamp) = match event {
+                BridgeEvent::Mint(e) => (e.to_address.as_str(), e.timestamp),
+                BridgeEvent::Burn(e) => {
+                    stats
+                        .per_operator
+                        .entry(e.operator_id)
+                        .or_default()
+                        .add(event);
+                    (e.from_address.as_str(), e.timestamp)
+                }
+                BridgeEvent::WithdrawByLP(e) => {
+                    stats.per_lp.entry(e.lp_id).or_default().add(event);
+                    (e.from_address.as_str(), e.timestamp)
+                }
+            };
+            stats.total.add(event);
+            users.insert(user);
+            if let Some(timestamp) = timestamp {
+                let (volume, day_users) = days
+                    .entry(timestamp - timestamp % SECS_PER_DAY)
+                    .or_default();
+                volume.add(event);
+                day_users.insert(user);
+            }
+        }
+
+        stats.unique_users = users.len() as u64;
+        stats.daily = days
+            .into_iter()
+            .map(|(day_start, (volume, day_users))| DailyStats {
+                day_start,
+                volume,
+                unique_users: day_users.len() as u64,
+            })
+            .collect();
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BurnEvent, MintEvent};
+
+    fn mint(to_address: &str, amount: u64, timestamp: Option<u64>) -> BridgeEvent {
+        BridgeEvent::Mint(MintEvent {
+            to_address: to_address.to_string(),
+            amount: Sats(amount),
+            btc_tx_id: "aa".to_string(),
+            btc_block_num: 1,
+            timestamp,
+            version: None,
+            transaction_hash: None,
+        })
+    }
+
+    fn burn(from_address: &str, amount: u64, operator_id: u64, timestamp: u64) -> BridgeEvent {
+        BridgeEvent::Burn(BurnEvent {
+            from_address: from_address.to_string(),
+            btc_address: "bc1q".to_string(),
+            fee_rate: 1,
+            amount: Sats(amount),
+            operator_id,
+            timestamp: Some(timestamp),
+            version: None,
+            transaction_hash: None,
+        })
+    }
+
+    #[test]
+    fn test_bridge_stats_from_events() {
+        let day = 1_700_006_400; // 2023-11-15T00:00:00Z
+        let events = vec![
+            mint("0x1", 1_000, Some(day + 10)),
+            mint("0x2", 2_000, Some(day + 20)),
+            burn("0x1", 500, 7, day + SECS_PER_DAY + 5),
+            mint("0x3", 4_000, None),
+        ];
+        let stats = BridgeStats::from_events(&events);
+
+        assert_eq!(stats.total.mint_count, 3);
+        assert_eq!(stats.total.mint_amount, Sats(7_000));
+        assert_eq!(stats.total.burn_amount, Sats(500));
+        assert_eq!(stats.unique_users, 3);
+
+        assert_eq!(stats.daily.len(), 2);
+        assert_eq!(stats.daily[0].day_start, day);
+        assert_eq!(stats.daily[0].volume.mint_amount, Sats(3_000));
+        assert_eq!(stats.daily[0].unique_users, 2);
+        assert_eq!(stats.daily[1].day_start, day + SECS_PER_DAY);
+        assert_eq!(stats.daily[1].volume.burn_count, 1);
+
+        assert_eq!(stats.per_operator[&7].burn_amount, Sats(500));
+        assert!(stats.per_lp.is_empty());
+    }
+}
@@ -0,0 +1,209 @@
+//! HTTP status server
+//!
+//! Serves `/healthz`, `/metrics` and `/status` for relayers and other
+//! long-running services built on the SDK, so they can be probed and scraped
+//! in Kubernetes. Enabled by the `status-server` feature.
+//!
+//! Lags are read from the SDK's Prometheus gauges, which the `EventMonitor` and
+//! `HeaderSyncService` keep current; the last submitted transaction is recorded
+//! by every `BridgeClient` in the process.
+
+use crate::time::Instant;
+use anyhow::{anyhow, Result};
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use prometheus::{Encoder, Registry, TextEncoder};
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+/// Most recent transaction submitted by a `BridgeClient` in this process
+static LAST_SUBMISSION: Mutex<Option<Submission>> = Mutex::new(None);
+
+/// Record a submitted transaction for `/status`
+pub(crate) fn record_submission(function: &str, tx_hash: &str) {
+    if let Ok(mut last) = LAST_SUBMISSION.lock() {
+        *last = Some(Submission {
+            function: function.to_string(),
+            tx_hash: tx_hash.to_string(),
+            unix_timestamp: crate::time::unix_timestamp_secs(),
+        });
+    }
+}
+
+/// Submitted transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Submission {
+    /// Entry function name
+    pub function: String,
+    /// Transaction hash
+    pub tx_hash: String,
+    /// Submission time, seconds since the Unix epoch
+    pub unix_timestamp: u64,
+}
+
+/// `/status` response body
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusReport {
+    /// Whether every lag is within its configured limit
+    pub healthy: bool,
+    /// Seconds since the server was created
+    pub uptime_secs: u64,
+    /// Ledger versions between the node tip and the event monitor cursor
+    pub indexer_lag_versions: u64,
+    /// BTC blocks the light client is behind the header source
+    pub light_client_lag_blocks: u64,
+    /// Most recent transaction submitted in this process
+    pub last_submission: Option<Submission>,
+}
+
+/// Status server configuration and shared handler state
+struct StatusState {
+    registry: Registry,
+    max_indexer_lag: Option<u64>,
+    max_light_client_lag: Option<u64>,
+    started: Instant,
+}
+
+impl StatusState {
+    fn report(&self) -> StatusReport {
+        let indexer_lag_versions = crate::metrics::INDEXER_LAG.get().max(0) as u64;
+        let light_client_lag_blocks = crate::metrics::LIGHT_CLIENT_LAG.get().max(0) as u64;
+        let healthy = self
+            .max_indexer_lag
+            .is_none_or(|max| indexer_lag_versions <= max)
+            && self
+                .max_light_client_lag
+                .is_none_or(|max| light_client_lag_blocks <= max);
+
+        StatusReport {
+            healthy,
+            uptime_secs: self.started.elapsed().as_secs(),
+            indexer_lag_versions,
+            light_client_lag_blocks,
+            last_submission: LAST_SUBMISSION.lock().ok().and_then(|last| last.clone()),
+        }
+    }
+}
+
+/// Embedded HTTP server exposing health, metrics and status
+pub struct StatusServer {
+    state: StatusState,
+}
+
+impl StatusServer {
+    /// Status server exposing the SDK metrics from a fresh registry
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+        crate::metrics::register(&registry)?;
+        Ok(Self::with_registry(registry))
+    }
+
+    /// Status server exposing `registry`, which should already hold the SDK
+    /// metrics (see [`crate::metrics::register`]) alongside the service's own
+    pub fn with_registry(registry: Registry) -> Self {
+        Self {
+            state: StatusState {
+                registry,
+                max_indexer_lag: None,
+                max_light_client_lag: None,
+                started: Instant::now(),
+            },
+        }
+    }
+
+    /// Report unhealthy while the event monitor is more than `versions` behind
+    pub fn with_max_indexer_lag(mut self, versions: u64) -> Self {
+        self.state.max_indexer_lag = Some(versions);
+        self
+    }
+
+    /// Report unhealthy while the light client is more than `blocks` behind
+    pub fn with_max_light_client_lag(mut self, blocks: u64) -> Self {
+        self.state.max_light_client_lag = Some(blocks);
+        self
+    }
+
+    /// Current status, as served on `/status`
+    pub fn report(&self) -> StatusReport {
+        self.state.report()
+    }
+
+    /// Router serving the three endpoints, for mounting in an existing server
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/healthz", get(healthz))
+            .route("/metrics", get(metrics))
+            .route("/status", get(status))
+            .with_state(Arc::new(self.state))
+    }
+
+    /// Serve on `addr` until the server fails
+    pub async fn serve(self, addr: SocketAddr) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| anyhow!("Failed to bind status server to {}: {}", addr, e))?;
+        #[cfg(feature = "tracing")]
+        tracing::info!(%addr, "Status server listening");
+        axum::serve(listener, self.router())
+            .await
+            .map_err(|e| anyhow!("Status server failed: {}", e))
+    }
+}
+
+/// `200 ok`, or `503` with the lags while a limit is exceeded
+async fn healthz(State(state): State<Arc<StatusState>>) -> Response {
+    let report = state.report();
+    if report.healthy {
+        (StatusCode::OK, "ok").into_response()
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, Json(report)).into_response()
+    }
+}
+
+async fn metrics(State(state): State<Arc<StatusState>>) -> Response {
+    let encoder = TextEncoder::new();
+    let mut body = Vec::new();
+    match encoder.encode(&state.registry.gather(), &mut body) {
+        Ok(()) => ([(header::CONTENT_TYPE, encoder.format_type())], body).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+async fn status(State(state): State<Arc<StatusState>>) -> Json<StatusReport> {
+    Json(state.report())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_health_limits() {
+        let server = StatusServer::with_registry(Registry::new());
+        assert!(server.report().healthy);
+
+        crate::metrics::LIGHT_CLIENT_LAG.set(7);
+        let server = server.with_max_light_client_lag(6);
+        let report = server.report();
+        assert!(!report.healthy);
+        assert_eq!(report.light_client_lag_blocks, 7);
+
+        crate::metrics::LIGHT_CLIENT_LAG.set(0);
+        assert!(server.report().healthy);
+    }
+
+    #[test]
+    fn test_record_submission() {
+        record_submission("mint", "0xabc");
+        let last = StatusServer::with_registry(Registry::new())
+            .report()
+            .last_submission
+            .unwrap();
+        assert_eq!(last.function, "mint");
+        assert_eq!(last.tx_hash, "0xabc");
+    }
+}
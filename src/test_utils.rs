@@ -0,0 +1,345 @@
+//! Property-testing support
+//!
+//! `proptest` strategies and `Arbitrary` impls for the SDK's data types, so
+//! downstream crates can property-test storage and serialization layers against
+//! realistic values. Enabled by the `test-utils` feature.
+
+use crate::btc::{BtcAddress, BtcNetwork};
+use crate::types::{
+    BridgeEvent, BurnEvent, MintEvent, Peg, Sats, ScriptType, TxProof, WithdrawByLPEvent,
+};
+use aptos_sdk::types::account_address::AccountAddress;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// Upper bound for generated amounts (total BTC supply in sats)
+const MAX_SATS: u64 = 21_000_000 * 100_000_000;
+
+/// Aptos account address in the SDK's `0x`-prefixed string form
+pub fn arb_account_address() -> impl Strategy<Value = String> {
+    any::<[u8; 32]>().prop_map(|bytes| {
+        AccountAddress::from_bytes(bytes)
+            .map(|addr| addr.to_hex_literal())
+            .unwrap_or_else(|_| format!("0x{}", hex::encode(bytes)))
+    })
+}
+
+/// Aptos transaction hash (`0x` followed by 64 hex digits)
+pub fn arb_transaction_hash() -> impl Strategy<Value = String> {
+    any::<[u8; 32]>().prop_map(|bytes| format!("0x{}", hex::encode(bytes)))
+}
+
+/// Bitcoin network
+pub fn arb_btc_network() -> impl Strategy<Value = BtcNetwork> {
+    prop_oneof![
+        Just(BtcNetwork::Mainnet),
+        Just(BtcNetwork::Testnet),
+        Just(BtcNetwork::Regtest),
+    ]
+}
+
+/// Valid Bitcoin address of any supported type on any network
+pub fn arb_btc_address() -> impl Strategy<Value = BtcAddress> {
+    prop_oneof![
+        (arb_btc_network(), any::<[u8; 20]>())
+            .prop_map(|(network, hash)| segwit_address(network, 0, &hash)),
+        (arb_btc_network(), any::<[u8; 32]>())
+            .prop_map(|(network, hash)| segwit_address(network, 0, &hash)),
+        (arb_btc_network(), any::<[u8; 32]>())
+            .prop_map(|(network, key)| segwit_address(network, 1, &key)),
+        (any::<bool>(), any::<bool>(), any::<[u8; 20]>()).prop_map(|(mainnet, script, hash)| {
+            let version = match (mainnet, script) {
+                (true, false) => 0x00,
+                (true, true) => 0x05,
+                (false, false) => 0x6f,
+                (false, true) => 0xc4,
+            };
+            let mut data = vec![version];
+            data.extend_from_slice(&hash);
+            bs58::encode(data)
+                .with_check()
+                .into_string()
+                .parse()
+                .expect("generated base58 address is valid")
+        }),
+    ]
+}
+
+/// Amount between zero and the total BTC supply
+pub fn arb_sats() -> impl Strategy<Value = Sats> {
+    (0..=MAX_SATS).prop_map(Sats)
+}
+
+/// Optional event metadata: (timestamp, version, transaction hash)
+fn arb_event_metadata() -> impl Strategy<Value = (Option<u64>, Option<u64>, Option<String>)> {
+    (
+        proptest::option::of(0..4_102_444_800u64),
+        proptest::option::of(any::<u64>()),
+        proptest::option::of(arb_transaction_hash()),
+    )
+}
+
+fn segwit_address(network: BtcNetwork, version: u8, program: &[u8]) -> BtcAddress {
+    let hrp = bech32::Hrp::parse(network.bech32_hrp()).expect("static hrp is valid");
+    let version = if version == 0 {
+        bech32::segwit::VERSION_0
+    } else {
+        bech32::segwit::VERSION_1
+    };
+    bech32::segwit::encode(hrp, version, program)
+        .expect("generated witness program is valid")
+        .parse()
+        .expect("generated segwit address is valid")
+}
+
+impl Arbitrary for Sats {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        arb_sats().boxed()
+    }
+}
+
+impl Arbitrary for BtcAddress {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        arb_btc_address().boxed()
+    }
+}
+
+impl Arbitrary for ScriptType {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            Just(ScriptType::P2SH),
+            Just(ScriptType::P2WSH),
+            Just(ScriptType::P2TR),
+            Just(ScriptType::P2PKH),
+            Just(ScriptType::P2WPKH),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for TxProof {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            vec(any::<u8>(), 80),
+            vec(any::<u8>(), 32),
+            0..10_000u64,
+            vec(vec(any::<u8>(), 32), 0..16),
+            vec(any::<u8>(), 60..1_000),
+        )
+            .prop_map(
+                |(block_header, tx_id, tx_index, merkle_proof, raw_tx)| TxProof {
+                    block_header,
+                    tx_id,
+                    tx_index,
+                    merkle_proof,
+                    raw_tx,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for Peg {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            arb_account_address(),
+            arb_sats(),
+            0..10_000_000u64,
+            any::<TxProof>(),
+            0..100u64,
+            prop_oneof![vec(any::<u8>(), 20), vec(any::<u8>(), 32)],
+            any::<ScriptType>(),
+        )
+            .prop_map(
+                |(
+                    to,
+                    value,
+                    block_num,
+                    inclusion_proof,
+                    tx_out_ix,
+                    dest_script_hash,
+                    script_type,
+                )| {
+                    Peg {
+                        to,
+                        value,
+                        block_num,
+                        inclusion_proof,
+                        tx_out_ix,
+                        dest_script_hash,
+                        script_type,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for MintEvent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            arb_account_address(),
+            arb_sats(),
+            any::<[u8; 32]>(),
+            0..10_000_000u64,
+            arb_event_metadata(),
+        )
+            .prop_map(
+                |(
+                    to_address,
+                    amount,
+                    tx_id,
+                    btc_block_num,
+                    (timestamp, version, transaction_hash),
+                )| {
+                    MintEvent {
+                        to_address,
+                        amount,
+                        btc_tx_id: hex::encode(tx_id),
+                        btc_block_num,
+                        timestamp,
+                        version,
+                        transaction_hash,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for BurnEvent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            arb_account_address(),
+            arb_btc_address(),
+            1..1_000u64,
+            arb_sats(),
+            0..100u64,
+            arb_event_metadata(),
+        )
+            .prop_map(
+                |(
+                    from_address,
+                    btc_address,
+                    fee_rate,
+                    amount,
+                    operator_id,
+                    (timestamp, version, transaction_hash),
+                )| BurnEvent {
+                    from_address,
+                    btc_address: btc_address.into(),
+                    fee_rate,
+                    amount,
+                    operator_id,
+                    timestamp,
+                    version,
+                    transaction_hash,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for WithdrawByLPEvent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            arb_account_address(),
+            any::<u64>(),
+            arb_btc_address(),
+            1..1_000u64,
+            arb_sats(),
+            0..100u64,
+            arb_sats(),
+            arb_event_metadata(),
+        )
+            .prop_map(
+                |(
+                    from_address,
+                    withdraw_id,
+                    btc_address,
+                    fee_rate,
+                    amount,
+                    lp_id,
+                    receive_min_amount,
+                    (timestamp, version, transaction_hash),
+                )| WithdrawByLPEvent {
+                    from_address,
+                    withdraw_id,
+                    btc_address: btc_address.into(),
+                    fee_rate,
+                    amount,
+                    lp_id,
+                    receive_min_amount,
+                    timestamp,
+                    version,
+                    transaction_hash,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for BridgeEvent {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            any::<MintEvent>().prop_map(BridgeEvent::Mint),
+            any::<BurnEvent>().prop_map(BridgeEvent::Burn),
+            any::<WithdrawByLPEvent>().prop_map(BridgeEvent::WithdrawByLP),
+        ]
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_generated_btc_addresses_parse(address in arb_btc_address()) {
+            let reparsed: BtcAddress = address.as_str().parse().unwrap();
+            prop_assert_eq!(reparsed, address);
+        }
+
+        #[test]
+        fn test_peg_bcs_round_trip(peg in any::<Peg>()) {
+            let args = peg.to_bcs_args().unwrap();
+            let decoded = Peg::from_bcs_args(&args).unwrap();
+            prop_assert_eq!(decoded.to_bcs_args().unwrap(), args);
+        }
+
+        #[test]
+        fn test_bridge_event_json_round_trip(event in any::<BridgeEvent>()) {
+            let json = serde_json::to_string(&event).unwrap();
+            let decoded: BridgeEvent = serde_json::from_str(&json).unwrap();
+            prop_assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+        }
+    }
+}
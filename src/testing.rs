@@ -0,0 +1,1248 @@
+//! HTTP fixtures for integration tests
+//!
+//! Canned fullnode and GraphQL responses for a testnet-shaped deployment, and
+//! helpers that serve them from a local `wiremock` server, so integration tests
+//! don't depend on a live node or indexer. Enabled by the `test-utils` feature.
+//!
+//! The fullnode mock answers `GET /v1`, the JSON transaction-by-hash endpoint for
+//! [`MINT_TX_HASH`] and [`BURN_TX_HASH`], and the bridge view functions; the
+//! GraphQL mock answers every `POST` with one event of each kind. [`MockChain`]
+//! adds the signing account and a simulated mempool, for tests that submit
+//! transactions, and [`MockBitcoind`] serves a header chain over Bitcoin Core RPC.
+
+use serde_json::Value;
+use wiremock::matchers::{method, path, path_regex};
+use wiremock::{Match, Mock, MockServer, Request, ResponseTemplate};
+
+/// Raw fixture documents
+pub mod fixtures {
+    /// `GET /v1` ledger information
+    pub const FULLNODE_INDEX: &str = include_str!("../fixtures/fullnode_index.json");
+    /// Committed `bridge::mint` transaction with its `Mint` event
+    pub const MINT_TX: &str = include_str!("../fixtures/fullnode_mint_tx.json");
+    /// Committed `bridge::burn` transaction with its `Burn` event
+    pub const BURN_TX: &str = include_str!("../fixtures/fullnode_burn_tx.json");
+    /// GraphQL response with one mint, burn and withdraw-by-LP event
+    pub const GRAPHQL_BRIDGE_EVENTS: &str = include_str!("../fixtures/graphql_bridge_events.json");
+    /// `btc_mirror::get_latest_block_height` view response
+    pub const VIEW_LATEST_BLOCK_HEIGHT: &str =
+        include_str!("../fixtures/view_get_latest_block_height.json");
+    /// `bridge::get_bridge_config` view response
+    pub const VIEW_BRIDGE_CONFIG: &str = include_str!("../fixtures/view_get_bridge_config.json");
+    /// `bridge::min_confirmations` view response
+    pub const VIEW_MIN_CONFIRMATIONS: &str =
+        include_str!("../fixtures/view_min_confirmations.json");
+    /// `bridge::is_peg_minted` view response
+    pub const VIEW_IS_PEG_MINTED: &str = include_str!("../fixtures/view_is_peg_minted.json");
+    /// `bridge::get_lp_withdraw` view response
+    pub const VIEW_LP_WITHDRAW: &str = include_str!("../fixtures/view_get_lp_withdraw.json");
+    /// `bridge::get_withdraw_status` view response
+    pub const VIEW_WITHDRAW_STATUS: &str =
+        include_str!("../fixtures/view_get_withdraw_status.json");
+    /// `lp_manager::get_lp_status` view response
+    pub const VIEW_LP_STATUS: &str = include_str!("../fixtures/view_get_lp_status.json");
+    /// `lp_manager::get_lp_info` view response
+    pub const VIEW_LP_INFO: &str = include_str!("../fixtures/view_get_lp_info.json");
+    /// `lp_manager::get_lp_id_by_address` view response
+    pub const VIEW_LP_ID_BY_ADDRESS: &str =
+        include_str!("../fixtures/view_get_lp_id_by_address.json");
+    /// `btc_peg::balance_of` view response
+    pub const VIEW_BALANCE_OF: &str = include_str!("../fixtures/view_balance_of.json");
+    /// `coin::balance<AptosCoin>` view response (10 APT)
+    pub const VIEW_COIN_BALANCE: &str = include_str!("../fixtures/view_coin_balance.json");
+    /// `btc_peg::total_supply` view response
+    pub const VIEW_TOTAL_SUPPLY: &str = include_str!("../fixtures/view_total_supply.json");
+}
+
+/// Bridge contract address used by the fixtures
+pub const BRIDGE_ADDRESS: &str =
+    "0xeed4b8e27b6bd68e902e0e20633814d0d6d1a1c096763507fcaf058854a5b9b4";
+/// BTC light client address used by the fixtures
+pub const LIGHT_CLIENT_ADDRESS: &str =
+    "0x749e2800973809a39eb72ed6e38f154151cef1213b2e72e031ad86875bbc051a";
+/// Hash of the mint transaction fixture
+pub const MINT_TX_HASH: &str = "0x6d696e7400000000000000000000000000000000000000000000000000000001";
+/// Hash of the burn transaction fixture
+pub const BURN_TX_HASH: &str = "0x6275726e00000000000000000000000000000000000000000000000000000002";
+/// Chain id reported by the fullnode mock
+pub const CHAIN_ID: u8 = 2;
+/// Ledger version reported by the fullnode mock
+pub const LEDGER_VERSION: u64 = 6_543_210;
+
+/// View functions served by [`mock_fullnode`], as `(function, response)`
+const VIEW_FIXTURES: &[(&str, &str)] = &[
+    (
+        "get_latest_block_height",
+        fixtures::VIEW_LATEST_BLOCK_HEIGHT,
+    ),
+    ("get_bridge_config", fixtures::VIEW_BRIDGE_CONFIG),
+    ("min_confirmations", fixtures::VIEW_MIN_CONFIRMATIONS),
+    ("is_peg_minted", fixtures::VIEW_IS_PEG_MINTED),
+    ("get_lp_withdraw", fixtures::VIEW_LP_WITHDRAW),
+    ("get_withdraw_status", fixtures::VIEW_WITHDRAW_STATUS),
+    ("get_lp_status", fixtures::VIEW_LP_STATUS),
+    ("get_lp_info", fixtures::VIEW_LP_INFO),
+    ("get_lp_id_by_address", fixtures::VIEW_LP_ID_BY_ADDRESS),
+    ("balance_of", fixtures::VIEW_BALANCE_OF),
+    ("total_supply", fixtures::VIEW_TOTAL_SUPPLY),
+    ("balance", fixtures::VIEW_COIN_BALANCE),
+];
+
+/// Start a fullnode mock pre-loaded with the fixtures
+pub async fn mock_fullnode() -> MockServer {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/v1/?$"))
+        .respond_with(fullnode_response(fixtures::FULLNODE_INDEX))
+        .mount(&server)
+        .await;
+    for (tx_hash, body) in [
+        (MINT_TX_HASH, fixtures::MINT_TX),
+        (BURN_TX_HASH, fixtures::BURN_TX),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/transactions/by_hash/{}", tx_hash)))
+            .respond_with(fullnode_response(body))
+            .mount(&server)
+            .await;
+    }
+    for (function, body) in VIEW_FIXTURES {
+        Mock::given(method("POST"))
+            .and(path("/v1/view"))
+            .and(ViewFunction(function.to_string()))
+            .respond_with(fullnode_response(body))
+            .mount(&server)
+            .await;
+    }
+
+    server
+}
+
+/// Serve `response` for the view function `function` (matched by name), taking
+/// precedence over the fixtures
+pub async fn mount_view(server: &MockServer, function: &str, response: Value) {
+    Mock::given(method("POST"))
+        .and(path("/v1/view"))
+        .and(ViewFunction(function.to_string()))
+        .respond_with(fullnode_response(&response.to_string()))
+        .with_priority(1)
+        .mount(server)
+        .await;
+}
+
+/// Serve `response` for calls of the view function `function` with `arguments`,
+/// taking precedence over the fixtures
+pub async fn mount_view_with_args(
+    server: &MockServer,
+    function: &str,
+    arguments: Value,
+    response: Value,
+) {
+    Mock::given(method("POST"))
+        .and(path("/v1/view"))
+        .and(ViewFunction(function.to_string()))
+        .and(wiremock::matchers::body_partial_json(
+            serde_json::json!({ "arguments": arguments }),
+        ))
+        .respond_with(fullnode_response(&response.to_string()))
+        .with_priority(1)
+        .mount(server)
+        .await;
+}
+
+/// Fail the view function `function` with HTTP `status`, taking precedence over
+/// the fixtures
+pub async fn mount_view_error(server: &MockServer, function: &str, status: u16) {
+    Mock::given(method("POST"))
+        .and(path("/v1/view"))
+        .and(ViewFunction(function.to_string()))
+        .respond_with(
+            ResponseTemplate::new(status).set_body_json(serde_json::json!({
+                "message": "Internal error",
+                "error_code": "internal_error",
+                "vm_error_code": null,
+            })),
+        )
+        .with_priority(1)
+        .mount(server)
+        .await;
+}
+
+/// Serve `estimate` from the gas price endpoint, taking precedence over
+/// estimates mounted later
+pub async fn mount_gas_estimate(server: &MockServer, estimate: Value) {
+    Mock::given(method("GET"))
+        .and(path("/v1/estimate_gas_price"))
+        .respond_with(fullnode_response(&estimate.to_string()))
+        .mount(server)
+        .await;
+}
+
+/// Signing account and mempool simulated behind a fullnode mock
+///
+/// Serves the account's sequence number, accepts submitted transactions and
+/// commits them in sequence number order, leaving the ones after a gap
+/// pending, and serves each by hash (BCS) as pending or committed with the
+/// events set by [`emit`](MockChain::emit). Responses
+/// to submissions carry only the hash and header fields of the transaction.
+#[derive(Clone, Default)]
+pub struct MockChain(std::sync::Arc<std::sync::Mutex<MockChainState>>);
+
+#[derive(Default)]
+struct MockChainState {
+    sequence_number: u64,
+    next_version: u64,
+    /// Every accepted submission, in arrival order
+    submitted: Vec<aptos_sdk::types::transaction::SignedTransaction>,
+    /// Accepted transactions waiting for their sequence number, by it
+    mempool: std::collections::BTreeMap<u64, aptos_sdk::types::transaction::SignedTransaction>,
+    /// Committed transactions by hash
+    committed: std::collections::HashMap<String, (u64, bool)>,
+    /// Sequence numbers whose next submission is rejected
+    reject: std::collections::HashSet<u64>,
+    /// Sequence numbers whose transaction commits with a Move abort
+    abort: std::collections::HashSet<u64>,
+    /// Sequence numbers whose next accepted transaction is lost
+    lose: std::collections::HashSet<u64>,
+    /// Events emitted by the transaction using a sequence number
+    events: std::collections::HashMap<u64, Vec<aptos_sdk::types::contract_event::ContractEvent>>,
+    /// Leave accepted transactions in the mempool
+    hold: bool,
+}
+
+impl MockChain {
+    /// Serve the account, submission and transaction-by-hash endpoints from
+    /// `server`, starting at `sequence_number`
+    pub async fn mount(server: &MockServer, sequence_number: u64) -> Self {
+        let chain = Self::default();
+        {
+            let mut state = chain.lock();
+            state.sequence_number = sequence_number;
+            state.next_version = LEDGER_VERSION + 1;
+        }
+        Mock::given(method("GET"))
+            .and(path_regex(r"^/v1/accounts/[^/]+$"))
+            .respond_with(MockChainEndpoint::Account(chain.clone()))
+            .mount(server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/transactions"))
+            .respond_with(MockChainEndpoint::Submit(chain.clone()))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"^/v1/transactions/by_hash/[^/]+$"))
+            .respond_with(MockChainEndpoint::ByHash(chain.clone()))
+            .mount(server)
+            .await;
+        chain
+    }
+
+    /// Reject the next submission using `sequence_number`
+    pub fn reject(&self, sequence_number: u64) {
+        self.lock().reject.insert(sequence_number);
+    }
+
+    /// Commit the transaction using `sequence_number` with a Move abort
+    pub fn abort(&self, sequence_number: u64) {
+        self.lock().abort.insert(sequence_number);
+    }
+
+    /// Emit `events` from the transaction using `sequence_number`
+    pub fn emit(
+        &self,
+        sequence_number: u64,
+        events: Vec<aptos_sdk::types::contract_event::ContractEvent>,
+    ) {
+        self.lock().events.insert(sequence_number, events);
+    }
+
+    /// Accept the next submission using `sequence_number` but never commit it,
+    /// as if the mempool evicted it, leaving a gap before later ones
+    pub fn lose(&self, sequence_number: u64) {
+        self.lock().lose.insert(sequence_number);
+    }
+
+    /// Keep accepted transactions pending until released
+    pub fn hold(&self) {
+        self.lock().hold = true;
+    }
+
+    /// Commit the held transactions that are in sequence
+    pub fn release(&self) {
+        let mut state = self.lock();
+        state.hold = false;
+        state.commit_in_sequence();
+    }
+
+    /// Drop the pending transactions, as the mempool does once they expire
+    pub fn expire(&self) {
+        self.lock().mempool.clear();
+    }
+
+    /// Use up the account's next sequence number, as a transaction submitted
+    /// elsewhere would
+    pub fn commit_external(&self) {
+        let mut state = self.lock();
+        state.sequence_number += 1;
+        state.next_version += 1;
+        state.commit_in_sequence();
+    }
+
+    /// Next sequence number of the account
+    pub fn sequence_number(&self) -> u64 {
+        self.lock().sequence_number
+    }
+
+    /// Every accepted submission, in arrival order
+    pub fn submitted(&self) -> Vec<aptos_sdk::types::transaction::SignedTransaction> {
+        self.lock().submitted.clone()
+    }
+
+    /// Version and success of the committed transaction with `tx_hash`
+    pub fn committed(&self, tx_hash: &str) -> Option<(u64, bool)> {
+        self.lock().committed.get(tx_hash).copied()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockChainState> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl MockChainState {
+    fn commit_in_sequence(&mut self) {
+        if self.hold {
+            return;
+        }
+        while let Some(signed_transaction) = self.mempool.remove(&self.sequence_number) {
+            let success = !self.abort.contains(&self.sequence_number);
+            self.committed.insert(
+                signed_transaction.committed_hash().to_hex_literal(),
+                (self.next_version, success),
+            );
+            self.sequence_number += 1;
+            self.next_version += 1;
+        }
+        // Transactions for used sequence numbers can never commit
+        let sequence_number = self.sequence_number;
+        self.mempool.retain(|n, _| *n >= sequence_number);
+    }
+
+    fn find(&self, tx_hash: &str) -> Option<&aptos_sdk::types::transaction::SignedTransaction> {
+        self.submitted
+            .iter()
+            .rev()
+            .find(|txn| txn.committed_hash().to_hex_literal() == tx_hash)
+    }
+}
+
+enum MockChainEndpoint {
+    Account(MockChain),
+    Submit(MockChain),
+    ByHash(MockChain),
+}
+
+impl wiremock::Respond for MockChainEndpoint {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        use aptos_sdk::crypto::HashValue;
+        use aptos_sdk::move_types::vm_status::AbortLocation;
+        use aptos_sdk::rest_client::aptos_api_types::{TransactionData, TransactionOnChainData};
+        use aptos_sdk::types::transaction::{
+            ExecutionStatus, SignedTransaction, Transaction, TransactionInfo,
+        };
+        use aptos_sdk::types::write_set::WriteSet;
+
+        match self {
+            MockChainEndpoint::Account(chain) => {
+                // AccountResource: authentication key, sequence number, GUID
+                // counter, two event handles and two empty capability offers
+                let mut account = vec![32];
+                account.extend([0u8; 32]);
+                account.extend(chain.sequence_number().to_le_bytes());
+                account.extend(0u64.to_le_bytes());
+                for _ in 0..2 {
+                    account.extend(0u64.to_le_bytes());
+                    account.extend(0u64.to_le_bytes());
+                    account.extend([0u8; 32]);
+                }
+                account.extend([0, 0]);
+                bcs_response(account)
+            }
+            MockChainEndpoint::Submit(chain) => {
+                let signed_transaction: SignedTransaction = match bcs::from_bytes(&request.body) {
+                    Ok(signed_transaction) => signed_transaction,
+                    Err(e) => {
+                        return api_error(400, &format!("Invalid BCS: {}", e), "invalid_input")
+                    }
+                };
+                let mut state = chain.lock();
+                let sequence_number = signed_transaction.sequence_number();
+                if state.reject.remove(&sequence_number) || sequence_number < state.sequence_number
+                {
+                    return api_error(
+                        400,
+                        "Invalid transaction: Type: Validation Code: SEQUENCE_NUMBER_TOO_OLD",
+                        "vm_error",
+                    );
+                }
+                state.submitted.push(signed_transaction.clone());
+                if !state.lose.remove(&sequence_number) {
+                    state
+                        .mempool
+                        .insert(sequence_number, signed_transaction.clone());
+                }
+                state.commit_in_sequence();
+                let body = serde_json::json!({
+                    "hash": signed_transaction.committed_hash().to_hex_literal(),
+                    "sender": signed_transaction.sender().to_hex_literal(),
+                    "sequence_number": sequence_number.to_string(),
+                    "max_gas_amount": signed_transaction.max_gas_amount().to_string(),
+                    "gas_unit_price": signed_transaction.gas_unit_price().to_string(),
+                    "expiration_timestamp_secs":
+                        signed_transaction.expiration_timestamp_secs().to_string(),
+                    "payload": {
+                        "type": "entry_function_payload",
+                        "function": "0x1::aptos_account::transfer",
+                        "type_arguments": [],
+                        "arguments": [],
+                    },
+                });
+                fullnode_response(&body.to_string()).set_status_code(202)
+            }
+            MockChainEndpoint::ByHash(chain) => {
+                let tx_hash = request.url.path().rsplit('/').next().unwrap_or_default();
+                let state = chain.lock();
+                let Some(signed_transaction) = state.find(tx_hash).cloned() else {
+                    return api_error(
+                        404,
+                        &format!("Transaction not found by Transaction hash({})", tx_hash),
+                        "transaction_not_found",
+                    );
+                };
+                let data = match state.committed.get(tx_hash) {
+                    Some((version, success)) => {
+                        let status = if *success {
+                            ExecutionStatus::Success
+                        } else {
+                            ExecutionStatus::MoveAbort {
+                                location: AbortLocation::Script,
+                                code: 1,
+                                info: None,
+                            }
+                        };
+                        TransactionData::OnChain(TransactionOnChainData {
+                            version: *version,
+                            info: TransactionInfo::new(
+                                signed_transaction.committed_hash(),
+                                HashValue::zero(),
+                                HashValue::zero(),
+                                None,
+                                10,
+                                status,
+                                None,
+                            ),
+                            events: state
+                                .events
+                                .get(&signed_transaction.sequence_number())
+                                .cloned()
+                                .unwrap_or_default(),
+                            transaction: Transaction::UserTransaction(signed_transaction),
+                            accumulator_root_hash: HashValue::zero(),
+                            changes: WriteSet::default(),
+                        })
+                    }
+                    None => TransactionData::Pending(Box::new(signed_transaction)),
+                };
+                bcs_response(bcs::to_bytes(&data).expect("BCS-serializable transaction"))
+            }
+        }
+    }
+}
+
+/// BCS response with the ledger headers the Aptos REST client requires
+fn bcs_response(body: Vec<u8>) -> ResponseTemplate {
+    fullnode_response("").set_body_raw(body, "application/x-bcs")
+}
+
+/// Fullnode API error response
+fn api_error(status: u16, message: &str, error_code: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(serde_json::json!({
+        "message": message,
+        "error_code": error_code,
+        "vm_error_code": null,
+    }))
+}
+
+/// Peg of a 50000 sat P2WPKH deposit to `0x1` that passes
+/// [`Peg::validate`](crate::types::Peg::validate)
+#[cfg(feature = "bitcoin")]
+pub fn deposit_peg() -> crate::types::Peg {
+    use bitcoin::hashes::Hash;
+
+    let script_hash = [5u8; 20];
+    let tx = bitcoin::Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: bitcoin::absolute::LockTime::ZERO,
+        input: vec![bitcoin::TxIn::default()],
+        output: vec![bitcoin::TxOut {
+            value: bitcoin::Amount::from_sat(50_000),
+            script_pubkey: bitcoin::ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::from_byte_array(
+                script_hash,
+            )),
+        }],
+    };
+    let genesis = bitcoin::blockdata::constants::genesis_block(bitcoin::Network::Bitcoin);
+    crate::types::Peg::new(
+        "0x1".to_string(),
+        crate::types::Sats(50_000),
+        100,
+        crate::types::TxProof::from_bitcoin(&genesis.header, &tx, &[], 0),
+        0,
+        script_hash.to_vec(),
+    )
+    .with_script_type(crate::types::ScriptType::P2WPKH)
+}
+
+/// Block on `prev_blockhash` holding `txdata`, with a matching merkle root
+#[cfg(feature = "bitcoin")]
+pub fn btc_block(
+    prev_blockhash: bitcoin::BlockHash,
+    txdata: Vec<bitcoin::Transaction>,
+) -> bitcoin::Block {
+    use bitcoin::hashes::Hash;
+
+    let mut block = bitcoin::Block {
+        header: bitcoin::block::Header {
+            version: bitcoin::block::Version::TWO,
+            prev_blockhash,
+            merkle_root: bitcoin::TxMerkleNode::all_zeros(),
+            time: 0,
+            bits: bitcoin::CompactTarget::from_consensus(0x207fffff),
+            nonce: 0,
+        },
+        txdata,
+    };
+    if let Some(merkle_root) = block.compute_merkle_root() {
+        block.header.merkle_root = merkle_root;
+    }
+    block
+}
+
+/// Serve `block` at `height` from an Esplora mock: its hash by height, raw
+/// block and header, and each transaction's status, hex and merkle proof
+#[cfg(feature = "esplora")]
+pub async fn mount_esplora_block(server: &MockServer, height: u64, block: &bitcoin::Block) {
+    use bitcoin::consensus::encode::{serialize, serialize_hex};
+    use bitcoin::hashes::{sha256d, Hash};
+
+    let block_hash = block.block_hash();
+    Mock::given(method("GET"))
+        .and(path(format!("/block-height/{}", height)))
+        .respond_with(ResponseTemplate::new(200).set_body_string(block_hash.to_string()))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/block/{}/raw", block_hash)))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(serialize(block)))
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/block/{}/header", block_hash)))
+        .respond_with(ResponseTemplate::new(200).set_body_string(serialize_hex(&block.header)))
+        .mount(server)
+        .await;
+
+    let txids: Vec<sha256d::Hash> = block
+        .txdata
+        .iter()
+        .map(|tx| tx.compute_txid().to_raw_hash())
+        .collect();
+    for (pos, tx) in block.txdata.iter().enumerate() {
+        // Sibling hashes, leaf to root, odd levels pairing the last node with itself
+        let mut merkle = Vec::new();
+        let (mut level, mut index) = (txids.clone(), pos);
+        while level.len() > 1 {
+            if level.len() % 2 == 1 {
+                level.push(level[level.len() - 1]);
+            }
+            merkle.push(bitcoin::TxMerkleNode::from_raw_hash(level[index ^ 1]).to_string());
+            level = level
+                .chunks(2)
+                .map(|pair| {
+                    let mut bytes = pair[0].to_byte_array().to_vec();
+                    bytes.extend_from_slice(&pair[1].to_byte_array());
+                    sha256d::Hash::hash(&bytes)
+                })
+                .collect();
+            index /= 2;
+        }
+
+        let txid = tx.compute_txid();
+        for (suffix, body) in [
+            (
+                "status",
+                serde_json::json!({
+                    "confirmed": true,
+                    "block_height": height,
+                    "block_hash": block_hash.to_string(),
+                })
+                .to_string(),
+            ),
+            (
+                "merkle-proof",
+                serde_json::json!({
+                    "block_height": height,
+                    "merkle": merkle,
+                    "pos": pos,
+                })
+                .to_string(),
+            ),
+            ("hex", serialize_hex(tx)),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(format!("/tx/{}/{}", txid, suffix)))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(server)
+                .await;
+        }
+    }
+}
+
+/// Serve `height` as an Esplora mock's chain tip, taking precedence over tips
+/// mounted with a larger `priority`
+#[cfg(feature = "esplora")]
+pub async fn mount_esplora_tip(server: &MockServer, height: u64, priority: u8) {
+    Mock::given(method("GET"))
+        .and(path("/blocks/tip/height"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(height.to_string()))
+        .with_priority(priority)
+        .mount(server)
+        .await;
+}
+
+/// `count` headers chained onto `prev_blockhash`
+///
+/// Chains built from the same parent with different `nonce`s fork there.
+#[cfg(feature = "bitcoin")]
+pub fn btc_headers(
+    prev_blockhash: bitcoin::BlockHash,
+    count: u32,
+    nonce: u32,
+) -> Vec<bitcoin::block::Header> {
+    let mut headers: Vec<bitcoin::block::Header> = Vec::new();
+    for time in 0..count {
+        let header = bitcoin::block::Header {
+            version: bitcoin::block::Version::TWO,
+            prev_blockhash: headers
+                .last()
+                .map_or(prev_blockhash, |header| header.block_hash()),
+            merkle_root: bitcoin::hashes::Hash::all_zeros(),
+            time,
+            bits: bitcoin::CompactTarget::from_consensus(0x207fffff),
+            nonce,
+        };
+        headers.push(header);
+    }
+    headers
+}
+
+/// Bitcoin Core RPC mock serving a chain of headers from height 0
+///
+/// Answers `getblockcount`, `getblockhash` and `getblockheader`. Replace the
+/// chain with [`set_chain`](MockBitcoind::set_chain) to simulate a reorg.
+#[cfg(feature = "bitcoind")]
+#[derive(Clone, Default)]
+pub struct MockBitcoind(std::sync::Arc<std::sync::Mutex<Vec<bitcoin::block::Header>>>);
+
+#[cfg(feature = "bitcoind")]
+impl MockBitcoind {
+    /// Serve `chain` from every `POST` to `server`
+    pub async fn mount(server: &MockServer, chain: Vec<bitcoin::block::Header>) -> Self {
+        let bitcoind = Self::default();
+        bitcoind.set_chain(chain);
+        Mock::given(method("POST"))
+            .respond_with(bitcoind.clone())
+            .mount(server)
+            .await;
+        bitcoind
+    }
+
+    /// Replace the best chain
+    pub fn set_chain(&self, chain: Vec<bitcoin::block::Header>) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = chain;
+    }
+
+    fn chain(&self) -> Vec<bitcoin::block::Header> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[cfg(feature = "bitcoind")]
+impl wiremock::Respond for MockBitcoind {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let request: Value = serde_json::from_slice(&request.body).unwrap_or_default();
+        let params = &request["params"];
+        let chain = self.chain();
+        let result = match request["method"].as_str().unwrap_or_default() {
+            "getblockcount" => Ok(serde_json::json!(chain.len().saturating_sub(1))),
+            "getblockhash" => params[0]
+                .as_u64()
+                .and_then(|height| chain.get(height as usize))
+                .map(|header| serde_json::json!(header.block_hash().to_string()))
+                .ok_or((-8, "Block height out of range")),
+            "getblockheader" => match chain.iter().position(|header| {
+                params[0].as_str() == Some(header.block_hash().to_string().as_str())
+            }) {
+                Some(height) if params[1] == Value::Bool(true) => {
+                    Ok(serde_json::json!({ "height": height }))
+                }
+                Some(height) => Ok(serde_json::json!(
+                    bitcoin::consensus::encode::serialize_hex(&chain[height])
+                )),
+                None => Err((-5, "Block not found")),
+            },
+            _ => Err((-32601, "Method not found")),
+        };
+        // Bitcoin Core reports errors with a 500 and a JSON body
+        match result {
+            Ok(result) => ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "result": result,
+                "error": null,
+                "id": request["id"],
+            })),
+            Err((code, message)) => ResponseTemplate::new(500).set_body_json(serde_json::json!({
+                "result": null,
+                "error": { "code": code, "message": message },
+                "id": request["id"],
+            })),
+        }
+    }
+}
+
+/// Start a GraphQL mock answering every query with the events fixture
+pub async fn mock_graphql() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(fixtures::GRAPHQL_BRIDGE_EVENTS, "application/json"),
+        )
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Fullnode REST URL of a mock server, for `BridgeClient::new` and `QueryClient::new`
+pub fn fullnode_url(server: &MockServer) -> String {
+    format!("{}/v1", server.uri())
+}
+
+/// Client for the [`BRIDGE_ADDRESS`] deployment behind a fullnode mock, without
+/// retries, so a failed request shows up on the first attempt
+pub fn bridge_client(server: &MockServer) -> crate::BridgeClient {
+    crate::BridgeClient::new(
+        &fullnode_url(server),
+        None,
+        "0x1111111111111111111111111111111111111111111111111111111111111111",
+        BRIDGE_ADDRESS,
+        Some(LIGHT_CLIENT_ADDRESS),
+    )
+    .unwrap()
+    .with_retry_policy(crate::RetryPolicy::none())
+}
+
+/// JSON response with the ledger headers the Aptos REST client requires
+pub fn fullnode_response(body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .set_body_raw(body.to_string(), "application/json")
+        .insert_header("X-Aptos-Chain-Id", CHAIN_ID.to_string())
+        .insert_header("X-Aptos-Ledger-Version", LEDGER_VERSION.to_string())
+        .insert_header("X-Aptos-Ledger-Oldest-Version", "0")
+        .insert_header("X-Aptos-Ledger-TimestampUsec", "1714564800000000")
+        .insert_header("X-Aptos-Epoch", "9876")
+        .insert_header("X-Aptos-Block-Height", "3210987")
+        .insert_header("X-Aptos-Oldest-Block-Height", "0")
+}
+
+/// Matches view requests for a function name, whatever its address and module
+struct ViewFunction(String);
+
+impl Match for ViewFunction {
+    fn matches(&self, request: &Request) -> bool {
+        serde_json::from_slice::<Value>(&request.body)
+            .ok()
+            .and_then(|body| body["function"].as_str().map(str::to_string))
+            .is_some_and(|function| function.ends_with(&format!("::{}", self.0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{InsufficientBridgeBalance, InsufficientGasFunds, LPStatus, Sats};
+    use crate::QueryClient;
+    #[cfg(feature = "events-graphql")]
+    use crate::{
+        events::{EventHandler, EventMonitor},
+        types::{BurnEvent, MintEvent, WithdrawByLPEvent},
+    };
+    #[cfg(feature = "events-graphql")]
+    use anyhow::Result;
+    #[cfg(feature = "events-graphql")]
+    use async_trait::async_trait;
+
+    #[cfg(feature = "events-graphql")]
+    struct NoopHandler;
+
+    #[cfg(feature = "events-graphql")]
+    #[async_trait]
+    impl EventHandler for NoopHandler {
+        async fn handle_mint(&self, _event: MintEvent) -> Result<()> {
+            Ok(())
+        }
+        async fn handle_burn(&self, _event: BurnEvent) -> Result<()> {
+            Ok(())
+        }
+        async fn handle_withdraw_by_lp(&self, _event: WithdrawByLPEvent) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_fullnode_serves_fixtures() {
+        let server = mock_fullnode().await;
+        let client = bridge_client(&server);
+
+        assert_eq!(client.get_latest_block_height().await.unwrap(), 850_000);
+        assert_eq!(client.get_min_confirmations().await.unwrap(), 6);
+        assert_eq!(client.get_lp_status(1).await.unwrap(), LPStatus::ACTIVE);
+        assert_eq!(client.get_lp_withdraw(7).await.unwrap().lp_id, 1);
+        assert_eq!(client.get_lp_info(1).await.unwrap().lp_fee, 1000);
+        let lp = client.find_lp_by_address("0x2").await.unwrap().unwrap();
+        assert_eq!(lp.status, LPStatus::ACTIVE);
+        mount_view(
+            &server,
+            "get_lp_id_by_address",
+            serde_json::json!([{"vec": []}]),
+        )
+        .await;
+        assert!(client.find_lp_by_address("0x3").await.unwrap().is_none());
+
+        mount_view(
+            &server,
+            "get_latest_block_height",
+            serde_json::json!(["850001"]),
+        )
+        .await;
+        assert_eq!(client.get_latest_block_height().await.unwrap(), 850_001);
+        assert_eq!(client.get_gas_balance().await.unwrap(), 1_000_000_000);
+
+        let query_client = QueryClient::new(&fullnode_url(&server), None).unwrap();
+        assert_eq!(
+            query_client.get_ledger_version().await.unwrap(),
+            LEDGER_VERSION
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preflight_checks_reject_underfunded_sender() {
+        let server = mock_fullnode().await;
+        let client = bridge_client(&server).with_preflight_checks(true);
+        let btc_address: crate::btc::BtcAddress = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+            .parse()
+            .unwrap();
+
+        // The fixture balance is 500000 sats
+        let err = client
+            .burn(btc_address.clone(), 10, Sats(600_000), 1)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InsufficientBridgeBalance>()
+                .unwrap()
+                .required,
+            Sats(600_000)
+        );
+
+        mount_view(&server, "balance", serde_json::json!(["1000"])).await;
+        let err = client
+            .burn(btc_address.clone(), 10, Sats(100_000), 1)
+            .await
+            .unwrap_err();
+        let err = err.downcast_ref::<InsufficientGasFunds>().unwrap();
+        assert_eq!(err.balance, 1000);
+        assert_eq!(err.required, 200_000_000);
+
+        // The required fee uses the price the transaction would be signed with
+        mount_gas_estimate(
+            &server,
+            serde_json::json!({
+                "gas_estimate": 100,
+                "deprioritized_gas_estimate": 100,
+                "prioritized_gas_estimate": 150,
+            }),
+        )
+        .await;
+        let client = client.with_gas_settings(crate::config::GasSettings {
+            priority: Some(crate::config::GasPriority::Prioritized),
+            ..Default::default()
+        });
+        let err = client
+            .burn(btc_address.clone(), 10, Sats(100_000), 1)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InsufficientGasFunds>().unwrap().required,
+            300_000_000
+        );
+
+        // Off by default: the burn goes on to signing, which has no account to read
+        let client = bridge_client(&server);
+        let err = client
+            .burn(btc_address, 10, Sats(600_000), 1)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<InsufficientBridgeBalance>().is_none());
+    }
+
+    #[cfg(feature = "events-graphql")]
+    #[tokio::test]
+    async fn test_mock_graphql_serves_events() {
+        let graphql = mock_graphql().await;
+        let fullnode = mock_fullnode().await;
+        let monitor = EventMonitor::new(
+            &graphql.uri(),
+            "key",
+            &fullnode_url(&fullnode),
+            None,
+            Box::new(NoopHandler),
+            0,
+        )
+        .unwrap();
+
+        let events = monitor.process().await.unwrap();
+        assert_eq!(events.len(), 3);
+        let report = monitor.process_report().await.unwrap();
+        assert_eq!((report.fetched, report.handled, report.skipped), (3, 3, 0));
+        assert_eq!(report.new_cursor, 6_543_300);
+        assert_eq!(report.max_seen_version, Some(6_543_300));
+        let health = monitor.health();
+        assert_eq!(health.consecutive_failures, 0);
+        assert!(health.is_ready(std::time::Duration::from_secs(60)));
+
+        let unreachable = EventMonitor::new(
+            "http://127.0.0.1:1",
+            "key",
+            &fullnode_url(&fullnode),
+            None,
+            Box::new(NoopHandler),
+            0,
+        )
+        .unwrap();
+        assert!(unreachable.process().await.is_err());
+        let health = unreachable.health();
+        assert_eq!(health.consecutive_failures, 1);
+        assert_eq!(
+            health.last_error.map(|error| error.kind),
+            Some(crate::PollErrorKind::Indexer)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gas_priority_resolves_against_estimate() {
+        use crate::{GasPriority, GasSettings, TxOptions};
+        use aptos_sdk::types::transaction::{Script, TransactionPayload};
+
+        let server = mock_fullnode().await;
+        let chain = MockChain::mount(&server, 0).await;
+        mount_gas_estimate(
+            &server,
+            serde_json::json!({
+                "gas_estimate": 100,
+                "deprioritized_gas_estimate": 80,
+                "prioritized_gas_estimate": 150,
+            }),
+        )
+        .await;
+        let client = bridge_client(&server).with_gas_settings(GasSettings {
+            priority: Some(GasPriority::Deprioritized),
+            ..GasSettings::default()
+        });
+        let payload = || TransactionPayload::Script(Script::new(vec![], vec![], vec![]));
+
+        for options in [
+            TxOptions::new(),
+            TxOptions::new().with_gas_priority(GasPriority::Prioritized),
+            TxOptions::new()
+                .with_gas_priority(GasPriority::Prioritized)
+                .with_gas_unit_price(200),
+        ] {
+            client
+                .submit_with_options(payload(), options)
+                .await
+                .unwrap();
+        }
+
+        let prices: Vec<u64> = chain
+            .submitted()
+            .iter()
+            .map(|txn| txn.gas_unit_price())
+            .collect();
+        assert_eq!(prices, [80, 150, 200]);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_submission_latency_records_accepted_submissions_only() {
+        use crate::TxOptions;
+        use aptos_sdk::move_types::identifier::Identifier;
+        use aptos_sdk::move_types::language_storage::ModuleId;
+        use aptos_sdk::types::account_address::AccountAddress;
+        use aptos_sdk::types::transaction::{EntryFunction, TransactionPayload};
+
+        let server = mock_fullnode().await;
+        let chain = MockChain::mount(&server, 0).await;
+        let client = bridge_client(&server);
+        // Function names unique to this test, as the histogram is process-wide
+        let payload = |function: &str| {
+            TransactionPayload::EntryFunction(EntryFunction::new(
+                ModuleId::new(AccountAddress::ONE, Identifier::new("latency").unwrap()),
+                Identifier::new(function).unwrap(),
+                vec![],
+                vec![],
+            ))
+        };
+        let samples = |function: &str| {
+            crate::metrics::SUBMISSION_LATENCY
+                .with_label_values(&[function])
+                .get_sample_count()
+        };
+
+        chain.reject(0);
+        assert!(client
+            .submit_with_options(payload("latency_rejected"), TxOptions::new())
+            .await
+            .is_err());
+        assert_eq!(samples("latency_rejected"), 0);
+
+        client
+            .submit_with_options(payload("latency_accepted"), TxOptions::new())
+            .await
+            .unwrap();
+        assert_eq!(samples("latency_accepted"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sign_only_submits_nothing_until_asked() {
+        use crate::TxOptions;
+        use aptos_sdk::types::transaction::{Script, SignedTransaction, TransactionPayload};
+
+        let server = mock_fullnode().await;
+        let chain = MockChain::mount(&server, 4).await;
+        let client = bridge_client(&server);
+
+        let (signed_transaction, expected_hash) = client
+            .sign_only(
+                TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+                TxOptions::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(signed_transaction.sequence_number(), 4);
+        assert_eq!(
+            signed_transaction.committed_hash().to_hex_literal(),
+            expected_hash
+        );
+        assert!(chain.submitted().is_empty());
+
+        // The persisted bytes submit under the recorded hash
+        let bytes = bcs::to_bytes(&signed_transaction).unwrap();
+        let restored: SignedTransaction = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            client.submit_signed(&restored).await.unwrap(),
+            expected_hash
+        );
+        assert!(chain.committed(&expected_hash).is_some());
+        assert_eq!(chain.sequence_number(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_resubmit_with_bump_replaces_expired_transaction() {
+        use crate::types::constants::EXPIRATION_TIMESTAMP_SECS;
+        use crate::TxOptions;
+        use aptos_sdk::types::transaction::{Script, TransactionPayload};
+
+        let server = mock_fullnode().await;
+        let chain = MockChain::mount(&server, 4).await;
+        let client = bridge_client(&server);
+        let (original, original_hash) = client
+            .sign_only(
+                TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+                TxOptions::new().with_gas_unit_price(100),
+            )
+            .await
+            .unwrap();
+        chain.hold();
+        client.submit_signed(&original).await.unwrap();
+
+        let err = client.resubmit_with_bump(&original, 1.5).await.unwrap_err();
+        assert!(err.to_string().contains("has not expired"));
+
+        crate::time::advance(EXPIRATION_TIMESTAMP_SECS + 1);
+        chain.expire();
+        chain.release();
+        assert!(client.resubmit_with_bump(&original, 0.5).await.is_err());
+        let tx_hash = client.resubmit_with_bump(&original, 1.5).await.unwrap();
+
+        assert_ne!(tx_hash, original_hash);
+        let replacement = chain.submitted().pop().unwrap();
+        assert_eq!(replacement.committed_hash().to_hex_literal(), tx_hash);
+        assert_eq!(replacement.sequence_number(), 4);
+        assert_eq!(replacement.gas_unit_price(), 150);
+        assert_eq!(replacement.max_gas_amount(), original.max_gas_amount());
+        assert!(replacement.expiration_timestamp_secs() > original.expiration_timestamp_secs());
+        assert!(chain.committed(&tx_hash).is_some());
+        assert!(chain.committed(&original_hash).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_explicit_sequence_numbers_bypass_account_lookup() {
+        use aptos_sdk::types::transaction::{Script, TransactionPayload};
+
+        let payload = || TransactionPayload::Script(Script::new(vec![], vec![], vec![]));
+        let server = mock_fullnode().await;
+        let chain = MockChain::mount(&server, 3).await;
+        let client = bridge_client(&server);
+
+        // Submitted ahead of the account, it waits for the gap to fill
+        let ahead = client
+            .submit_with_sequence_number(payload(), 4)
+            .await
+            .unwrap();
+        assert!(chain.committed(&ahead).is_none());
+        let next = client
+            .submit_with_sequence_number(payload(), 3)
+            .await
+            .unwrap();
+        assert!(chain.committed(&next).is_some());
+        assert!(chain.committed(&ahead).is_some());
+        assert_eq!(chain.sequence_number(), 5);
+
+        let sequence_numbers: Vec<u64> = chain
+            .submitted()
+            .iter()
+            .map(|txn| txn.sequence_number())
+            .collect();
+        assert_eq!(sequence_numbers, [4, 3]);
+        assert!(client
+            .submit_with_sequence_number(payload(), 3)
+            .await
+            .is_err());
+
+        let account_lookups = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|request| request.url.path().starts_with("/v1/accounts/"))
+            .count();
+        assert_eq!(account_lookups, 0);
+    }
+
+    #[tokio::test]
+    async fn test_quote_withdraw_by_lp_uses_lp_script_type() {
+        use crate::lp_select::WithdrawQuote;
+        use crate::types::ScriptType;
+
+        let server = mock_fullnode().await;
+        let client = bridge_client(&server);
+        let amount = Sats(100_000);
+
+        let quote = client.quote_withdraw_by_lp(amount, 1, 10).await.unwrap();
+        let lp = client.get_lp_info(1).await.unwrap();
+        assert_eq!(
+            quote,
+            WithdrawQuote::new(amount, &lp, 10, ScriptType::P2WPKH).unwrap()
+        );
+
+        mount_view(
+            &server,
+            "get_lp_info",
+            serde_json::json!([{
+                "lp_id": "1",
+                "bitcoin_addr": "bcrt1pqypqxpq9qcrsszg2pvxq6rs0zqg3yyc5z5tpwxqergd3c8g7rusq58nmtx",
+                "lp_addr": "0x2",
+                "lp_fee": "1000",
+                "status": {"__variant__": "ACTIVE"},
+            }]),
+        )
+        .await;
+        let lp = client.get_lp_info(1).await.unwrap();
+        let quote = client.quote_withdraw_by_lp(amount, 1, 10).await.unwrap();
+        assert_eq!(
+            quote,
+            WithdrawQuote::new(amount, &lp, 10, ScriptType::P2TR).unwrap()
+        );
+        assert_ne!(
+            quote,
+            WithdrawQuote::new(amount, &lp, 10, ScriptType::P2WPKH).unwrap()
+        );
+
+        // Checked against the client's Bitcoin network
+        let mainnet_client = client.with_btc_network(crate::btc::BtcNetwork::Mainnet);
+        assert!(mainnet_client
+            .quote_withdraw_by_lp(amount, 1, 10)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_select_lp_skips_unreadable_lps() {
+        use crate::lp_select::LPConstraints;
+        use wiremock::matchers::body_partial_json;
+
+        let server = mock_fullnode().await;
+        let client = bridge_client(&server);
+        mount_view(&server, "get_lp_ids", serde_json::json!([["1", "2", "3"]])).await;
+        let lp_info = |lp_id: &str, response: ResponseTemplate| {
+            Mock::given(method("POST"))
+                .and(path("/v1/view"))
+                .and(ViewFunction("get_lp_info".to_string()))
+                .and(body_partial_json(
+                    serde_json::json!({ "arguments": [lp_id] }),
+                ))
+                .respond_with(response)
+                .with_priority(1)
+        };
+        lp_info("2", api_error(500, "Internal error", "internal_error"))
+            .mount(&server)
+            .await;
+        lp_info(
+            "3",
+            fullnode_response(
+                &serde_json::json!([{
+                    "lp_id": "3",
+                    "bitcoin_addr": "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080",
+                    "lp_addr": "0x3",
+                    "lp_fee": "not a number",
+                    "status": {"__variant__": "ACTIVE"},
+                }])
+                .to_string(),
+            ),
+        )
+        .mount(&server)
+        .await;
+
+        let amount = Sats(100_000);
+        let lp = client
+            .select_lp(amount, &LPConstraints::new())
+            .await
+            .unwrap();
+        assert_eq!(lp.lp_id, 1);
+        assert!(client
+            .select_lp(amount, &LPConstraints::new().excluding(1))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,444 @@
+//! Threshold and MPC signing
+//!
+//! A [`ThresholdSigner`] drives an external signing service through
+//! "request signature → poll until quorum signs → assemble authenticator", so
+//! TSS or multi-party services can sign bridge submissions. Services implement
+//! [`SigningService`]; with the `http-signer` feature, [`HttpSigningService`]
+//! speaks a minimal JSON protocol:
+//!
+//! - `POST {base}/sign` with `{"message": "<hex>"}` returns `{"request_id": "..."}`
+//! - `GET {base}/sign/{request_id}` returns one of
+//!   `{"status": "pending", "signed": 1, "threshold": 2}`,
+//!   `{"status": "complete", "signature": "<hex>"}` (aggregated TSS signature),
+//!   `{"status": "complete", "signatures": [{"index": 0, "signature": "<hex>"}]}`
+//!   (MultiEd25519 shares) or `{"status": "failed", "error": "..."}`
+//!
+//! Transactions expire [`EXPIRATION_TIMESTAMP_SECS`](crate::types::constants::EXPIRATION_TIMESTAMP_SECS)
+//! after they are built, so the quorum has to sign within that window.
+
+use crate::signer::TransactionSigner;
+use crate::time::{sleep, Instant};
+use crate::types::constants::{THRESHOLD_SIGNING_POLL_INTERVAL_MS, THRESHOLD_SIGNING_TIMEOUT_SECS};
+use anyhow::{anyhow, Result};
+use aptos_sdk::crypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use aptos_sdk::crypto::multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature};
+use aptos_sdk::crypto::traits::{signing_message, Signature};
+use aptos_sdk::types::account_address::AccountAddress;
+use aptos_sdk::types::transaction::authenticator::AuthenticationKey;
+use aptos_sdk::types::transaction::{RawTransaction, SignedTransaction};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Key the signing group signs for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupKey {
+    /// Single key whose signature the group produces jointly (TSS)
+    Ed25519(Ed25519PublicKey),
+    /// k-of-n key set, one signature share per member
+    MultiEd25519(MultiEd25519PublicKey),
+}
+
+impl GroupKey {
+    /// Account address derived from the key
+    pub fn account_address(&self) -> AccountAddress {
+        match self {
+            GroupKey::Ed25519(public_key) => AuthenticationKey::ed25519(public_key),
+            GroupKey::MultiEd25519(public_key) => AuthenticationKey::multi_ed25519(public_key),
+        }
+        .account_address()
+    }
+}
+
+/// Signature produced once the quorum has signed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThresholdSignature {
+    /// Aggregated signature for a [`GroupKey::Ed25519`]
+    Ed25519(Ed25519Signature),
+    /// `(member index, signature)` shares for a [`GroupKey::MultiEd25519`]
+    MultiEd25519(Vec<(u8, Ed25519Signature)>),
+}
+
+/// Progress of a signing request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigningStatus {
+    /// Waiting for more signers
+    Pending {
+        /// Shares collected so far, if the service reports them
+        signed: Option<usize>,
+        /// Shares needed, if the service reports them
+        threshold: Option<usize>,
+    },
+    /// Quorum reached
+    Complete(ThresholdSignature),
+    /// Rejected or aborted by the service
+    Failed(String),
+}
+
+/// External service collecting signatures from a signing group
+#[async_trait]
+pub trait SigningService: Send + Sync {
+    /// Ask the group to sign `message`; returns the service's request ID
+    async fn request_signature(&self, message: &[u8]) -> Result<String>;
+
+    /// Current status of a request
+    async fn signature_status(&self, request_id: &str) -> Result<SigningStatus>;
+}
+
+/// [`TransactionSigner`] that waits for a [`SigningService`] quorum
+pub struct ThresholdSigner<S> {
+    service: S,
+    group_key: GroupKey,
+    account_address: AccountAddress,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl<S: SigningService> ThresholdSigner<S> {
+    /// Sign for the account of `group_key` through `service`
+    pub fn new(service: S, group_key: GroupKey) -> Self {
+        Self {
+            service,
+            account_address: group_key.account_address(),
+            group_key,
+            poll_interval: Duration::from_millis(THRESHOLD_SIGNING_POLL_INTERVAL_MS),
+            timeout: Duration::from_secs(THRESHOLD_SIGNING_TIMEOUT_SECS),
+        }
+    }
+
+    /// Sign for `address` instead of the one derived from the group key, for
+    /// accounts whose authentication key was rotated to it
+    pub fn with_account_address(mut self, address: AccountAddress) -> Self {
+        self.account_address = address;
+        self
+    }
+
+    /// Set how often the service is polled
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Set how long to wait for the quorum
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Request a signature over `message` and wait for the quorum
+    pub async fn collect_signature(&self, message: &[u8]) -> Result<ThresholdSignature> {
+        let request_id = self.service.request_signature(message).await?;
+        #[cfg(feature = "tracing")]
+        tracing::info!(%request_id, "Requested threshold signature");
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            match self.service.signature_status(&request_id).await? {
+                SigningStatus::Complete(signature) => return Ok(signature),
+                SigningStatus::Failed(error) => {
+                    return Err(anyhow!("Signing request {} failed: {}", request_id, error))
+                }
+                SigningStatus::Pending {
+                    signed: _signed,
+                    threshold: _threshold,
+                } => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(%request_id, signed = ?_signed, threshold = ?_threshold, "Waiting for signers");
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "Signing request {} did not reach quorum within {:?}",
+                    request_id,
+                    self.timeout
+                ));
+            }
+            sleep(self.poll_interval).await;
+        }
+    }
+}
+
+#[async_trait]
+impl<S: SigningService> TransactionSigner for ThresholdSigner<S> {
+    fn address(&self) -> AccountAddress {
+        self.account_address
+    }
+
+    async fn sign_transaction(&self, raw_transaction: RawTransaction) -> Result<SignedTransaction> {
+        let message = signing_message(&raw_transaction)
+            .map_err(|e| anyhow!("Failed to build transaction signing message: {}", e))?;
+        let signature = self.collect_signature(&message).await?;
+        assemble(raw_transaction, &self.group_key, signature, &message)
+    }
+}
+
+/// Build the signed transaction, checking the signature against the group key
+fn assemble(
+    raw_transaction: RawTransaction,
+    group_key: &GroupKey,
+    signature: ThresholdSignature,
+    message: &[u8],
+) -> Result<SignedTransaction> {
+    match (group_key, signature) {
+        (GroupKey::Ed25519(public_key), ThresholdSignature::Ed25519(signature)) => {
+            signature
+                .verify_arbitrary_msg(message, public_key)
+                .map_err(|e| anyhow!("Threshold signature does not match the group key: {}", e))?;
+            Ok(SignedTransaction::new(
+                raw_transaction,
+                public_key.clone(),
+                signature,
+            ))
+        }
+        (GroupKey::MultiEd25519(public_key), ThresholdSignature::MultiEd25519(shares)) => {
+            let signature = MultiEd25519Signature::new(
+                shares
+                    .into_iter()
+                    .map(|(index, share)| (share, index))
+                    .collect(),
+            )
+            .map_err(|e| anyhow!("Invalid MultiEd25519 signature shares: {}", e))?;
+            signature
+                .verify_arbitrary_msg(message, public_key)
+                .map_err(|e| anyhow!("Threshold signature does not match the group key: {}", e))?;
+            Ok(SignedTransaction::new_multisig(
+                raw_transaction,
+                public_key.clone(),
+                signature,
+            ))
+        }
+        (GroupKey::Ed25519(_), ThresholdSignature::MultiEd25519(_)) => Err(anyhow!(
+            "Signing service returned signature shares for a single-key group"
+        )),
+        (GroupKey::MultiEd25519(_), ThresholdSignature::Ed25519(_)) => Err(anyhow!(
+            "Signing service returned a single signature for a MultiEd25519 group"
+        )),
+    }
+}
+
+/// [`SigningService`] speaking the JSON protocol in the module docs
+#[cfg(feature = "http-signer")]
+#[derive(Debug, Clone)]
+pub struct HttpSigningService {
+    base_url: String,
+    http_client: reqwest::Client,
+    bearer_token: Option<String>,
+}
+
+#[cfg(feature = "http-signer")]
+impl HttpSigningService {
+    /// Service rooted at `base_url`
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http_client: reqwest::Client::new(),
+            bearer_token: None,
+        }
+    }
+
+    /// Send `Authorization: Bearer <token>` with every request
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.bearer_token = Some(token.to_string());
+        self
+    }
+
+    /// Use a preconfigured HTTP client (proxies, TLS settings, timeouts)
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<serde_json::Value> {
+        let request = match &self.bearer_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach signing service {}: {}", self.base_url, e))?
+            .error_for_status()
+            .map_err(|e| anyhow!("Signing service request failed: {}", e))?;
+        response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse signing service response: {}", e))
+    }
+}
+
+#[cfg(feature = "http-signer")]
+#[async_trait]
+impl SigningService for HttpSigningService {
+    async fn request_signature(&self, message: &[u8]) -> Result<String> {
+        let response = self
+            .send(
+                self.http_client
+                    .post(format!("{}/sign", self.base_url))
+                    .json(&serde_json::json!({ "message": hex::encode(message) })),
+            )
+            .await?;
+        response["request_id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Signing service response has no request_id"))
+    }
+
+    async fn signature_status(&self, request_id: &str) -> Result<SigningStatus> {
+        let response = self
+            .send(
+                self.http_client
+                    .get(format!("{}/sign/{}", self.base_url, request_id)),
+            )
+            .await?;
+        parse_signing_status(&response)
+    }
+}
+
+/// Decode a `GET /sign/{request_id}` response
+#[cfg(feature = "http-signer")]
+fn parse_signing_status(response: &serde_json::Value) -> Result<SigningStatus> {
+    let signature = |value: &serde_json::Value| -> Result<Ed25519Signature> {
+        let bytes = crate::utils::decode_hex(
+            value
+                .as_str()
+                .ok_or_else(|| anyhow!("Signature is not a hex string"))?,
+        )?;
+        Ed25519Signature::try_from(bytes.as_slice())
+            .map_err(|e| anyhow!("Invalid Ed25519 signature: {}", e))
+    };
+    let count = |field: &str| response[field].as_u64().map(|count| count as usize);
+
+    match response["status"].as_str() {
+        Some("pending") => Ok(SigningStatus::Pending {
+            signed: count("signed"),
+            threshold: count("threshold"),
+        }),
+        Some("complete") => match response["signatures"].as_array() {
+            Some(shares) => Ok(SigningStatus::Complete(ThresholdSignature::MultiEd25519(
+                shares
+                    .iter()
+                    .map(|share| {
+                        let index = share["index"]
+                            .as_u64()
+                            .and_then(|index| u8::try_from(index).ok())
+                            .ok_or_else(|| anyhow!("Invalid signature share index"))?;
+                        Ok((index, signature(&share["signature"])?))
+                    })
+                    .collect::<Result<_>>()?,
+            ))),
+            None => Ok(SigningStatus::Complete(ThresholdSignature::Ed25519(
+                signature(&response["signature"])?,
+            ))),
+        },
+        Some("failed") => Ok(SigningStatus::Failed(
+            response["error"]
+                .as_str()
+                .unwrap_or("unknown error")
+                .to_string(),
+        )),
+        _ => Err(anyhow!(
+            "Unknown signing status in response: {}",
+            response["status"]
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_sdk::crypto::ed25519::Ed25519PrivateKey;
+    use aptos_sdk::crypto::SigningKey;
+    use aptos_sdk::types::chain_id::ChainId;
+    use aptos_sdk::types::transaction::{Script, TransactionPayload};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Service that signs `raw_transaction` after `polls` pending responses
+    struct FakeService {
+        key: Ed25519PrivateKey,
+        raw_transaction: RawTransaction,
+        polls: usize,
+        seen: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SigningService for FakeService {
+        async fn request_signature(&self, message: &[u8]) -> Result<String> {
+            assert_eq!(message, signing_message(&self.raw_transaction)?);
+            Ok("req-1".to_string())
+        }
+
+        async fn signature_status(&self, request_id: &str) -> Result<SigningStatus> {
+            assert_eq!(request_id, "req-1");
+            if self.seen.fetch_add(1, Ordering::SeqCst) < self.polls {
+                return Ok(SigningStatus::Pending {
+                    signed: Some(1),
+                    threshold: Some(2),
+                });
+            }
+            Ok(SigningStatus::Complete(ThresholdSignature::Ed25519(
+                self.key.sign(&self.raw_transaction)?,
+            )))
+        }
+    }
+
+    fn signer(polls: usize) -> (ThresholdSigner<FakeService>, RawTransaction) {
+        let key = Ed25519PrivateKey::try_from([7u8; 32].as_slice()).unwrap();
+        let group_key = GroupKey::Ed25519(Ed25519PublicKey::from(&key));
+        let raw_transaction = RawTransaction::new(
+            group_key.account_address(),
+            0,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            1_000,
+            100,
+            u64::MAX,
+            ChainId::test(),
+        );
+        let service = FakeService {
+            key,
+            raw_transaction: raw_transaction.clone(),
+            polls,
+            seen: AtomicUsize::new(0),
+        };
+        let signer =
+            ThresholdSigner::new(service, group_key).with_poll_interval(Duration::from_millis(1));
+        (signer, raw_transaction)
+    }
+
+    #[tokio::test]
+    async fn test_sign_transaction_polls_until_quorum() {
+        let (signer, raw_transaction) = signer(2);
+        let signed = signer.sign_transaction(raw_transaction).await.unwrap();
+        signed.verify_signature().unwrap();
+        assert_eq!(signed.sender(), signer.address());
+        assert_eq!(signer.service.seen.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_sign_transaction_times_out() {
+        let (signer, raw_transaction) = signer(usize::MAX);
+        let signer = signer.with_timeout(Duration::from_millis(20));
+        assert!(signer.sign_transaction(raw_transaction).await.is_err());
+    }
+
+    #[cfg(feature = "http-signer")]
+    #[test]
+    fn test_parse_signing_status() {
+        let pending =
+            parse_signing_status(&serde_json::json!({"status": "pending", "signed": 1})).unwrap();
+        assert_eq!(
+            pending,
+            SigningStatus::Pending {
+                signed: Some(1),
+                threshold: None
+            }
+        );
+        let shares = parse_signing_status(&serde_json::json!({
+            "status": "complete",
+            "signatures": [{"index": 2, "signature": "11".repeat(64)}],
+        }));
+        assert!(matches!(
+            shares,
+            Ok(SigningStatus::Complete(ThresholdSignature::MultiEd25519(ref s))) if s[0].0 == 2
+        ));
+        assert!(parse_signing_status(&serde_json::json!({"status": "weird"})).is_err());
+    }
+}
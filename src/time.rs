@@ -0,0 +1,55 @@
+//! Timers that work on native targets and `wasm32-unknown-unknown`
+//!
+//! Tokio's timers and `std::time::{Instant, SystemTime}` are unavailable in the
+//! browser, so WASM builds use `gloo-timers` and `web-time` instead.
+
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::{Instant, SystemTime, UNIX_EPOCH};
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Wait for `duration`
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+/// Wait for `duration`
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await
+}
+
+/// Seconds since the Unix epoch
+pub(crate) fn unix_timestamp_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+        + clock_offset_secs()
+}
+
+#[cfg(not(test))]
+fn clock_offset_secs() -> u64 {
+    0
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Seconds [`advance`] moved this thread's clock forward
+    static CLOCK_OFFSET_SECS: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+}
+
+#[cfg(test)]
+fn clock_offset_secs() -> u64 {
+    CLOCK_OFFSET_SECS.with(std::cell::Cell::get)
+}
+
+/// Move [`unix_timestamp_secs`] forward by `secs` on the calling thread, to
+/// expire transactions without waiting
+#[cfg(test)]
+pub(crate) fn advance(secs: u64) {
+    CLOCK_OFFSET_SECS.with(|offset| offset.set(offset.get() + secs));
+}
@@ -0,0 +1,748 @@
+//! Persistent outbound transaction queue
+//!
+//! [`TxQueue`] stores mint and burn intents durably and drains them one at a
+//! time. Each signed transaction is saved before it is submitted, so after a
+//! crash the queue knows exactly which transaction may be on chain: it looks it
+//! up by hash, resubmits it unchanged while it can still commit, and only signs
+//! a replacement once the original has expired. Completed and failed items keep
+//! their final transaction hash.
+
+use crate::abort::execution_error;
+use crate::btc::BtcAddress;
+use crate::json_file::{read_json, write_json_atomic};
+use crate::types::constants::{TX_QUEUE_MAX_ATTEMPTS, TX_QUEUE_POLL_INTERVAL_SECS};
+use crate::types::{AlreadyMinted, Peg, Sats};
+use crate::BridgeClient;
+use anyhow::{anyhow, Result};
+use aptos_sdk::rest_client::aptos_api_types::TransactionData;
+use aptos_sdk::types::transaction::{SignedTransaction, TransactionPayload};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Transaction to be sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TxIntent {
+    /// `bridge::mint`
+    Mint { peg: Peg },
+    /// `bridge::burn`
+    Burn {
+        btc_address: BtcAddress,
+        fee_rate: u64,
+        amount: Sats,
+        operator_id: u64,
+    },
+}
+
+impl TxIntent {
+    /// Payload to sign, after the same validation `BridgeClient` does
+    ///
+    /// Makes no network calls, so an error means the intent can never be sent.
+    fn payload(&self, client: &BridgeClient) -> Result<TransactionPayload> {
+        match self {
+            TxIntent::Mint { peg } => {
+                #[cfg(feature = "bitcoin")]
+                peg.validate()?;
+                client.payloads().mint(peg, vec![])
+            }
+            TxIntent::Burn {
+                btc_address,
+                fee_rate,
+                amount,
+                operator_id,
+            } => {
+                client.check_btc_network(btc_address)?;
+                client
+                    .payloads()
+                    .burn(btc_address, *fee_rate, *amount, *operator_id, vec![])
+            }
+        }
+    }
+
+    /// Fail with [`AlreadyMinted`] if a mint's output already has a peg record
+    async fn check_not_minted(&self, client: &BridgeClient) -> Result<()> {
+        match self {
+            TxIntent::Mint { peg } => client.check_not_minted(peg, &[]).await,
+            TxIntent::Burn { .. } => Ok(()),
+        }
+    }
+}
+
+/// Progress of a queued transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum TxQueueState {
+    /// Not yet signed, or the previous attempt expired unsubmitted
+    Pending,
+    /// Signed and possibly submitted; saved before submission
+    Submitted {
+        tx_hash: String,
+        /// BCS-encoded signed transaction, hex, resubmitted after a crash
+        signed_transaction: String,
+        /// The transaction cannot commit after this time
+        expiration_timestamp_secs: u64,
+    },
+    /// Committed successfully
+    Completed { tx_hash: String, version: u64 },
+    /// Committed with a failure, or out of attempts
+    Failed {
+        tx_hash: Option<String>,
+        error: String,
+    },
+}
+
+impl TxQueueState {
+    /// Whether the item needs no more work
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            TxQueueState::Completed { .. } | TxQueueState::Failed { .. }
+        )
+    }
+}
+
+/// Queued intent and its progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxQueueItem {
+    /// Queue-assigned ID, increasing in enqueue order
+    pub id: u64,
+    /// What to send
+    pub intent: TxIntent,
+    /// Current progress
+    pub state: TxQueueState,
+    /// Transactions signed so far
+    pub attempts: u32,
+    /// Error of the last failed attempt
+    pub last_error: Option<String>,
+}
+
+/// Durable storage for queue items
+#[async_trait]
+pub trait TxQueueStore: Send + Sync {
+    /// Add an intent as a new pending item and return its ID
+    async fn push(&self, intent: TxIntent) -> Result<u64>;
+
+    /// All items, in ID order
+    async fn items(&self) -> Result<Vec<TxQueueItem>>;
+
+    /// Replace the stored item with the same ID
+    async fn update(&self, item: &TxQueueItem) -> Result<()>;
+}
+
+/// Queue contents as persisted by `FileTxQueueStore`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TxQueueFile {
+    next_id: u64,
+    items: BTreeMap<u64, TxQueueItem>,
+}
+
+/// Store keeping the queue in a single JSON file
+pub struct FileTxQueueStore {
+    /// Queue file
+    path: PathBuf,
+    /// Serializes read-modify-write cycles
+    lock: Mutex<()>,
+}
+
+impl FileTxQueueStore {
+    /// Create a store backed by `path`, which is created on first write
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    async fn read(&self) -> Result<TxQueueFile> {
+        Ok(read_json(&self.path, "transaction queue")
+            .await?
+            .unwrap_or_default())
+    }
+
+    async fn write(&self, queue: &TxQueueFile) -> Result<()> {
+        write_json_atomic(&self.path, "transaction queue", queue).await
+    }
+}
+
+#[async_trait]
+impl TxQueueStore for FileTxQueueStore {
+    async fn push(&self, intent: TxIntent) -> Result<u64> {
+        let _guard = self.lock.lock().await;
+        let mut queue = self.read().await?;
+        let id = queue.next_id;
+        queue.next_id += 1;
+        queue.items.insert(
+            id,
+            TxQueueItem {
+                id,
+                intent,
+                state: TxQueueState::Pending,
+                attempts: 0,
+                last_error: None,
+            },
+        );
+        self.write(&queue).await?;
+        Ok(id)
+    }
+
+    async fn items(&self) -> Result<Vec<TxQueueItem>> {
+        let _guard = self.lock.lock().await;
+        Ok(self.read().await?.items.into_values().collect())
+    }
+
+    async fn update(&self, item: &TxQueueItem) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let mut queue = self.read().await?;
+        match queue.items.get_mut(&item.id) {
+            Some(stored) => *stored = item.clone(),
+            None => return Err(anyhow!("Transaction queue has no item {}", item.id)),
+        }
+        self.write(&queue).await
+    }
+}
+
+/// Outbound transaction queue
+///
+/// Items are sent strictly one after another, each waiting for its transaction
+/// to resolve, so the account's sequence number read at signing time is always
+/// the next one. Run a single queue per signing account.
+pub struct TxQueue {
+    /// Client signing and submitting the transactions
+    bridge_client: Arc<BridgeClient>,
+    /// Durable queue contents
+    store: Arc<dyn TxQueueStore>,
+    /// Transactions signed per item before it is marked failed
+    max_attempts: u32,
+    /// Delay between drains in `run`
+    poll_interval: Duration,
+}
+
+impl TxQueue {
+    /// Create a queue sending through `bridge_client`
+    pub fn new(bridge_client: Arc<BridgeClient>, store: Arc<dyn TxQueueStore>) -> Self {
+        Self {
+            bridge_client,
+            store,
+            max_attempts: TX_QUEUE_MAX_ATTEMPTS,
+            poll_interval: Duration::from_secs(TX_QUEUE_POLL_INTERVAL_SECS),
+        }
+    }
+
+    /// Set how many transactions are signed for an item before it fails
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay between drains in `run`
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Durably enqueue an intent and return its ID
+    pub async fn enqueue(&self, intent: TxIntent) -> Result<u64> {
+        self.store.push(intent).await
+    }
+
+    /// All items, in ID order
+    pub async fn items(&self) -> Result<Vec<TxQueueItem>> {
+        self.store.items().await
+    }
+
+    /// Process every unfinished item once; returns how many reached a final state
+    ///
+    /// Items left over from a crash are recovered first, as they come first in
+    /// ID order. An item still in flight when its wait runs out stops the drain,
+    /// keeping later items from signing with a sequence number it may still use.
+    pub async fn drain(&self) -> Result<usize> {
+        let mut finished = 0;
+        for mut item in self.store.items().await? {
+            if item.state.is_final() {
+                continue;
+            }
+            if !self.process(&mut item).await? {
+                break;
+            }
+            finished += 1;
+        }
+        Ok(finished)
+    }
+
+    /// Drain forever, sleeping `poll_interval` between rounds
+    pub async fn run(&self) -> Result<()> {
+        loop {
+            match self.drain().await {
+                Ok(_finished) =>
+                {
+                    #[cfg(feature = "tracing")]
+                    if _finished > 0 {
+                        tracing::info!(finished = _finished, "Drained transaction queue");
+                    }
+                }
+                Err(_error) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(error = %_error, "Transaction queue drain failed");
+                }
+            }
+            crate::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Advance one item until it is final; returns false if it is still in flight
+    async fn process(&self, item: &mut TxQueueItem) -> Result<bool> {
+        loop {
+            let next = match &item.state {
+                TxQueueState::Pending => self.sign_and_submit(item).await?,
+                TxQueueState::Submitted {
+                    tx_hash,
+                    signed_transaction,
+                    expiration_timestamp_secs,
+                } => {
+                    match self
+                        .resolve(tx_hash, signed_transaction, *expiration_timestamp_secs)
+                        .await?
+                    {
+                        Some(next) => next,
+                        None => return Ok(false),
+                    }
+                }
+                TxQueueState::Completed { .. } | TxQueueState::Failed { .. } => return Ok(true),
+            };
+
+            #[cfg(feature = "tracing")]
+            tracing::info!(item = item.id, state = ?next, "Transaction queue item progressed");
+            item.state = next;
+            self.store.update(item).await?;
+        }
+    }
+
+    /// Sign a new transaction for the item, save it, then submit it
+    async fn sign_and_submit(&self, item: &mut TxQueueItem) -> Result<TxQueueState> {
+        if item.attempts >= self.max_attempts {
+            return Ok(TxQueueState::Failed {
+                tx_hash: None,
+                error: format!(
+                    "Gave up after {} attempts: {}",
+                    item.attempts,
+                    item.last_error.as_deref().unwrap_or("unknown error")
+                ),
+            });
+        }
+
+        let payload = match item.intent.payload(&self.bridge_client) {
+            Ok(payload) => payload,
+            Err(e) => {
+                return Ok(TxQueueState::Failed {
+                    tx_hash: None,
+                    error: e.to_string(),
+                })
+            }
+        };
+        // Other errors are the node's, so the item stays pending for the next drain
+        if let Err(e) = item.intent.check_not_minted(&self.bridge_client).await {
+            if e.downcast_ref::<AlreadyMinted>().is_some() {
+                return Ok(TxQueueState::Failed {
+                    tx_hash: None,
+                    error: e.to_string(),
+                });
+            }
+            return Err(e);
+        }
+        let signed_transaction = self.bridge_client.sign_payload(payload).await?;
+        let signed_bytes = bcs::to_bytes(&signed_transaction)
+            .map_err(|e| anyhow!("Failed to serialize signed transaction: {}", e))?;
+        let submitted = TxQueueState::Submitted {
+            tx_hash: signed_transaction.committed_hash().to_hex_literal(),
+            signed_transaction: hex::encode(signed_bytes),
+            expiration_timestamp_secs: signed_transaction.expiration_timestamp_secs(),
+        };
+
+        // Save before submitting, so a crash in between is recoverable
+        item.attempts += 1;
+        item.state = submitted.clone();
+        self.store.update(item).await?;
+
+        if let Err(e) = self.bridge_client.submit_signed(&signed_transaction).await {
+            // The node may still have accepted it; `resolve` finds out
+            item.last_error = Some(e.to_string());
+        }
+        Ok(submitted)
+    }
+
+    /// Final state of a submitted transaction, none if it may still commit
+    async fn resolve(
+        &self,
+        tx_hash: &str,
+        signed_transaction: &str,
+        expiration_timestamp_secs: u64,
+    ) -> Result<Option<TxQueueState>> {
+        // Resubmit in case a crash or error kept it from reaching the node
+        if self
+            .bridge_client
+            .get_transaction_by_hash(tx_hash)
+            .await
+            .is_err()
+            && crate::time::unix_timestamp_secs() < expiration_timestamp_secs
+        {
+            let _ = self
+                .bridge_client
+                .submit_signed(&decode_signed_transaction(signed_transaction)?)
+                .await;
+        }
+
+        // Wait until just past expiration; after that it can never commit
+        let timeout = Duration::from_secs(
+            expiration_timestamp_secs.saturating_sub(crate::time::unix_timestamp_secs()) + 1,
+        );
+        match self
+            .bridge_client
+            .wait_for_transaction(tx_hash, timeout)
+            .await
+        {
+            Ok(TransactionData::OnChain(txn)) => {
+                let status = txn.info.status();
+                Ok(Some(if status.is_success() {
+                    TxQueueState::Completed {
+                        tx_hash: tx_hash.to_string(),
+                        version: txn.version,
+                    }
+                } else {
+                    TxQueueState::Failed {
+                        tx_hash: Some(tx_hash.to_string()),
+                        error: execution_error("Transaction", tx_hash, status).to_string(),
+                    }
+                }))
+            }
+            Ok(TransactionData::Pending(_)) => Ok(None),
+            Err(_) if crate::time::unix_timestamp_secs() > expiration_timestamp_secs => {
+                self.resolve_expired(tx_hash, signed_transaction).await
+            }
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// State of a transaction not seen on chain by its expiration
+    ///
+    /// A lookup error alone doesn't prove it never committed, so the account's
+    /// sequence number decides: if it hasn't moved past the transaction's, the
+    /// transaction can no longer commit and the item is re-signed.
+    async fn resolve_expired(
+        &self,
+        tx_hash: &str,
+        signed_transaction: &str,
+    ) -> Result<Option<TxQueueState>> {
+        let sequence_number = decode_signed_transaction(signed_transaction)?.sequence_number();
+        if self.bridge_client.sequence_number().await? <= sequence_number {
+            return Ok(Some(TxQueueState::Pending));
+        }
+        match self.bridge_client.get_transaction_by_hash(tx_hash).await {
+            // Committed after all; the next `resolve` records it
+            Ok(TransactionData::OnChain(_)) => Ok(None),
+            _ => Ok(Some(TxQueueState::Failed {
+                tx_hash: Some(tx_hash.to_string()),
+                error: format!(
+                    "Sequence number {} was used by another transaction",
+                    sequence_number
+                ),
+            })),
+        }
+    }
+}
+
+fn decode_signed_transaction(signed_transaction: &str) -> Result<SignedTransaction> {
+    let bytes = hex::decode(signed_transaction)
+        .map_err(|e| anyhow!("Invalid signed transaction hex: {}", e))?;
+    bcs::from_bytes(&bytes).map_err(|e| anyhow!("Invalid signed transaction: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btc::BtcNetwork;
+
+    fn burn_intent() -> TxIntent {
+        TxIntent::Burn {
+            btc_address: BtcAddress::new(
+                "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+                BtcNetwork::Mainnet,
+            )
+            .unwrap(),
+            fee_rate: 5,
+            amount: Sats(10_000),
+            operator_id: 0,
+        }
+    }
+
+    #[cfg(all(feature = "test-utils", feature = "bitcoin"))]
+    #[tokio::test]
+    async fn test_mint_check_errors_retry_unless_already_minted() {
+        use crate::testing;
+
+        let queue = |server: &wiremock::MockServer, store: Arc<FileTxQueueStore>| {
+            TxQueue::new(Arc::new(testing::bridge_client(server)), store)
+        };
+        let path = std::env::temp_dir().join(format!("tx-queue-mint-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = Arc::new(FileTxQueueStore::new(&path));
+        store
+            .push(TxIntent::Mint {
+                peg: testing::deposit_peg(),
+            })
+            .await
+            .unwrap();
+
+        // A node error leaves the item pending for the next drain
+        let unavailable = testing::mock_fullnode().await;
+        testing::mount_view_error(&unavailable, "is_peg_minted", 500).await;
+        assert!(queue(&unavailable, store.clone()).drain().await.is_err());
+        let item = store.items().await.unwrap().remove(0);
+        assert_eq!(item.state, TxQueueState::Pending);
+        assert_eq!(item.attempts, 0);
+
+        let minted = testing::mock_fullnode().await;
+        testing::mount_view(&minted, "is_peg_minted", serde_json::json!([true])).await;
+        assert_eq!(queue(&minted, store.clone()).drain().await.unwrap(), 1);
+        let item = store.items().await.unwrap().remove(0);
+        assert!(matches!(
+            item.state,
+            TxQueueState::Failed { tx_hash: None, ref error } if error.contains("already minted")
+        ));
+        assert_eq!(item.attempts, 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "test-utils")]
+    mod process {
+        use super::*;
+        use crate::testing::{self, MockChain};
+        use crate::types::constants::EXPIRATION_TIMESTAMP_SECS;
+
+        /// Store over a fresh file, removed when the test ends
+        struct TempStore {
+            path: PathBuf,
+            store: Arc<FileTxQueueStore>,
+        }
+
+        impl TempStore {
+            fn new(name: &str) -> Self {
+                let path = std::env::temp_dir().join(format!(
+                    "tx-queue-{}-{}.json",
+                    name,
+                    std::process::id()
+                ));
+                let _ = std::fs::remove_file(&path);
+                let store = Arc::new(FileTxQueueStore::new(&path));
+                Self { path, store }
+            }
+
+            /// Another store over the same file, as after a restart
+            fn reopen(&self) -> Arc<FileTxQueueStore> {
+                Arc::new(FileTxQueueStore::new(&self.path))
+            }
+        }
+
+        impl Drop for TempStore {
+            fn drop(&mut self) {
+                let _ = std::fs::remove_file(&self.path);
+            }
+        }
+
+        /// Sign the first item and save it as submitted without submitting it,
+        /// as a crash right after saving leaves it
+        async fn sign_without_submitting(
+            bridge_client: &BridgeClient,
+            store: &FileTxQueueStore,
+        ) -> TxQueueItem {
+            let mut item = store.items().await.unwrap().remove(0);
+            let signed_transaction = bridge_client
+                .sign_payload(item.intent.payload(bridge_client).unwrap())
+                .await
+                .unwrap();
+            item.attempts = 1;
+            item.state = TxQueueState::Submitted {
+                tx_hash: signed_transaction.committed_hash().to_hex_literal(),
+                signed_transaction: hex::encode(bcs::to_bytes(&signed_transaction).unwrap()),
+                expiration_timestamp_secs: signed_transaction.expiration_timestamp_secs(),
+            };
+            store.update(&item).await.unwrap();
+            item
+        }
+
+        fn tx_hash(state: &TxQueueState) -> &str {
+            match state {
+                TxQueueState::Submitted { tx_hash, .. }
+                | TxQueueState::Completed { tx_hash, .. } => tx_hash,
+                TxQueueState::Failed {
+                    tx_hash: Some(tx_hash),
+                    ..
+                } => tx_hash,
+                state => panic!("no transaction hash in {:?}", state),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_drain_completes_items_in_order() {
+            let server = testing::mock_fullnode().await;
+            let chain = MockChain::mount(&server, 3).await;
+            chain.abort(4);
+            let temp = TempStore::new("order");
+            let queue = TxQueue::new(
+                Arc::new(testing::bridge_client(&server)),
+                temp.store.clone(),
+            );
+            for _ in 0..3 {
+                queue.enqueue(burn_intent()).await.unwrap();
+            }
+
+            assert_eq!(queue.drain().await.unwrap(), 3);
+            let items = queue.items().await.unwrap();
+            let sequence_numbers: Vec<u64> = chain
+                .submitted()
+                .iter()
+                .map(|txn| txn.sequence_number())
+                .collect();
+            assert_eq!(sequence_numbers, [3, 4, 5]);
+            for item in &items {
+                assert_eq!(item.attempts, 1);
+                let committed = chain.committed(tx_hash(&item.state));
+                match &item.state {
+                    TxQueueState::Completed { version, .. } => {
+                        assert_eq!(committed, Some((*version, true)))
+                    }
+                    TxQueueState::Failed { .. } => assert_eq!(committed.map(|c| c.1), Some(false)),
+                    state => panic!("item {} left {:?}", item.id, state),
+                }
+            }
+            assert!(matches!(items[0].state, TxQueueState::Completed { .. }));
+            assert!(matches!(items[1].state, TxQueueState::Failed { .. }));
+            assert!(matches!(items[2].state, TxQueueState::Completed { .. }));
+
+            // Final items are not sent again
+            assert_eq!(queue.drain().await.unwrap(), 0);
+            assert_eq!(chain.submitted().len(), 3);
+        }
+
+        #[tokio::test]
+        async fn test_restart_resubmits_saved_transaction() {
+            let server = testing::mock_fullnode().await;
+            let chain = MockChain::mount(&server, 0).await;
+            let bridge_client = Arc::new(testing::bridge_client(&server));
+            let temp = TempStore::new("restart");
+            temp.store.push(burn_intent()).await.unwrap();
+            let saved = sign_without_submitting(&bridge_client, &temp.store).await;
+
+            let queue = TxQueue::new(bridge_client, temp.reopen());
+            assert_eq!(queue.drain().await.unwrap(), 1);
+
+            // The saved transaction went out unchanged instead of a new one
+            let item = queue.items().await.unwrap().remove(0);
+            assert!(matches!(item.state, TxQueueState::Completed { .. }));
+            assert_eq!(tx_hash(&item.state), tx_hash(&saved.state));
+            assert_eq!(item.attempts, 1);
+            assert_eq!(chain.submitted().len(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_expired_transaction_is_signed_again() {
+            let server = testing::mock_fullnode().await;
+            let chain = MockChain::mount(&server, 0).await;
+            let bridge_client = Arc::new(testing::bridge_client(&server));
+            let temp = TempStore::new("expired");
+            temp.store.push(burn_intent()).await.unwrap();
+            let saved = sign_without_submitting(&bridge_client, &temp.store).await;
+
+            // Expired before reaching the node, with the sequence number unused
+            crate::time::advance(EXPIRATION_TIMESTAMP_SECS + 1);
+            let queue = TxQueue::new(bridge_client, temp.store.clone());
+            assert_eq!(queue.drain().await.unwrap(), 1);
+
+            let item = queue.items().await.unwrap().remove(0);
+            assert!(matches!(item.state, TxQueueState::Completed { .. }));
+            assert_ne!(tx_hash(&item.state), tx_hash(&saved.state));
+            assert_eq!(item.attempts, 2);
+            let submitted = chain.submitted();
+            assert_eq!(submitted.len(), 1);
+            assert_eq!(submitted[0].sequence_number(), 0);
+        }
+
+        #[tokio::test]
+        async fn test_expired_transaction_fails_if_sequence_number_was_used() {
+            let server = testing::mock_fullnode().await;
+            let chain = MockChain::mount(&server, 0).await;
+            let bridge_client = Arc::new(testing::bridge_client(&server));
+            let temp = TempStore::new("sequence-used");
+            temp.store.push(burn_intent()).await.unwrap();
+            let saved = sign_without_submitting(&bridge_client, &temp.store).await;
+
+            // Another transaction took sequence number 0 before this one expired
+            chain.commit_external();
+            crate::time::advance(EXPIRATION_TIMESTAMP_SECS + 1);
+            let queue = TxQueue::new(bridge_client, temp.store.clone());
+            assert_eq!(queue.drain().await.unwrap(), 1);
+
+            let item = queue.items().await.unwrap().remove(0);
+            assert!(matches!(
+                item.state,
+                TxQueueState::Failed { ref error, .. } if error.contains("Sequence number 0")
+            ));
+            assert_eq!(tx_hash(&item.state), tx_hash(&saved.state));
+            assert!(chain.submitted().is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_rejected_submission_is_resubmitted() {
+            let server = testing::mock_fullnode().await;
+            let chain = MockChain::mount(&server, 0).await;
+            let temp = TempStore::new("rejected");
+            let queue = TxQueue::new(
+                Arc::new(testing::bridge_client(&server)),
+                temp.store.clone(),
+            );
+            queue.enqueue(burn_intent()).await.unwrap();
+            chain.reject(0);
+
+            // The same transaction is sent again while it can still commit
+            assert_eq!(queue.drain().await.unwrap(), 1);
+            let item = queue.items().await.unwrap().remove(0);
+            assert!(matches!(item.state, TxQueueState::Completed { .. }));
+            assert_eq!(item.attempts, 1);
+            assert!(item.last_error.is_some());
+            assert_eq!(chain.submitted().len(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_item_out_of_attempts_fails() {
+            let server = testing::mock_fullnode().await;
+            let chain = MockChain::mount(&server, 0).await;
+            let temp = TempStore::new("attempts");
+            let queue = TxQueue::new(
+                Arc::new(testing::bridge_client(&server)),
+                temp.store.clone(),
+            )
+            .with_max_attempts(1);
+            queue.enqueue(burn_intent()).await.unwrap();
+            let mut item = queue.items().await.unwrap().remove(0);
+            item.attempts = 1;
+            item.last_error = Some("Sequence number too old".to_string());
+            temp.store.update(&item).await.unwrap();
+
+            assert_eq!(queue.drain().await.unwrap(), 1);
+            let item = queue.items().await.unwrap().remove(0);
+            assert_eq!(
+                item.state,
+                TxQueueState::Failed {
+                    tx_hash: None,
+                    error: "Gave up after 1 attempts: Sequence number too old".to_string(),
+                }
+            );
+            assert!(chain.submitted().is_empty());
+        }
+    }
+}
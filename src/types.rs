@@ -2,18 +2,146 @@
 //!
 //! This module defines all data types required for interacting with Aptos Bridge contracts.
 
+use crate::btc::{decode_txid_hex, txid_to_display_hex, BtcAddress, TxidByteOrder};
+use crate::config::GasPriority;
+use crate::utils::parse_account_address;
 use anyhow::{anyhow, Result};
 use aptos_sdk::types::account_address::AccountAddress;
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
+/// Satoshi amount
+///
+/// Serializes exactly like the underlying `u64` (JSON and BCS), displays as a BTC
+/// decimal, and parses from `"0.005 BTC"`, `"500000 sats"` or a bare satoshi count.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct Sats(pub u64);
+
+impl Sats {
+    pub const ZERO: Sats = Sats(0);
+    /// Satoshis per bitcoin
+    pub const ONE_BTC: Sats = Sats(100_000_000);
+
+    /// Amount in satoshis
+    pub fn to_sat(self) -> u64 {
+        self.0
+    }
+
+    pub fn checked_add(self, rhs: Sats) -> Option<Sats> {
+        self.0.checked_add(rhs.0).map(Sats)
+    }
+
+    pub fn checked_sub(self, rhs: Sats) -> Option<Sats> {
+        self.0.checked_sub(rhs.0).map(Sats)
+    }
+
+    pub fn checked_mul(self, rhs: u64) -> Option<Sats> {
+        self.0.checked_mul(rhs).map(Sats)
+    }
+
+    pub fn saturating_sub(self, rhs: Sats) -> Sats {
+        Sats(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Parse a BTC decimal string such as `"0.005"` (at most 8 fractional digits)
+    pub fn from_btc_str(btc: &str) -> Result<Self> {
+        let (whole, frac) = btc.split_once('.').unwrap_or((btc, ""));
+        if whole.is_empty() && frac.is_empty() {
+            return Err(anyhow!("Invalid BTC amount '{}'", btc));
+        }
+        if frac.len() > 8 {
+            return Err(anyhow!(
+                "Invalid BTC amount '{}': more than 8 decimal places",
+                btc
+            ));
+        }
+        if !whole
+            .chars()
+            .chain(frac.chars())
+            .all(|c| c.is_ascii_digit())
+        {
+            return Err(anyhow!("Invalid BTC amount '{}'", btc));
+        }
+
+        let whole: u64 = if whole.is_empty() {
+            0
+        } else {
+            whole
+                .parse()
+                .map_err(|e| anyhow!("Invalid BTC amount '{}': {}", btc, e))?
+        };
+        let frac: u64 = if frac.is_empty() {
+            0
+        } else {
+            format!("{:0<8}", frac)
+                .parse()
+                .map_err(|e| anyhow!("Invalid BTC amount '{}': {}", btc, e))?
+        };
+
+        whole
+            .checked_mul(Self::ONE_BTC.0)
+            .and_then(|sats| sats.checked_add(frac))
+            .map(Sats)
+            .ok_or_else(|| anyhow!("BTC amount '{}' overflows u64 satoshis", btc))
+    }
+}
+
+impl From<u64> for Sats {
+    fn from(sats: u64) -> Self {
+        Sats(sats)
+    }
+}
+
+impl From<Sats> for u64 {
+    fn from(sats: Sats) -> Self {
+        sats.0
+    }
+}
+
+impl std::fmt::Display for Sats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{:08} BTC",
+            self.0 / Self::ONE_BTC.0,
+            self.0 % Self::ONE_BTC.0
+        )
+    }
+}
+
+impl FromStr for Sats {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let lower = s.to_ascii_lowercase();
+
+        if let Some(btc) = lower.strip_suffix("btc") {
+            return Sats::from_btc_str(btc.trim());
+        }
+
+        let sats = ["sats", "sat"]
+            .iter()
+            .find_map(|suffix| lower.strip_suffix(suffix))
+            .unwrap_or(&lower)
+            .trim();
+        sats.parse::<u64>()
+            .map(Sats)
+            .map_err(|e| anyhow!("Invalid satoshi amount '{}': {}", s, e))
+    }
+}
+
 /// Bitcoin transaction proof
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TxProof {
     /// Block header data
     pub block_header: Vec<u8>,
-    /// Transaction ID
+    /// Transaction ID in internal byte order (see [`TxidByteOrder`])
     pub tx_id: Vec<u8>,
     /// Transaction index in block
     pub tx_index: u64,
@@ -23,12 +151,239 @@ pub struct TxProof {
     pub raw_tx: Vec<u8>,
 }
 
+#[cfg(feature = "bitcoin")]
+impl TxProof {
+    /// Build a proof from rust-bitcoin types
+    ///
+    /// The header and transaction are consensus-serialized; `tx_id` and the merkle
+    /// branch are kept in internal (little-endian) byte order. The raw transaction is
+    /// serialized without witness data so that its double-SHA256 equals `tx_id`.
+    pub fn from_bitcoin(
+        block_header: &bitcoin::block::Header,
+        tx: &bitcoin::Transaction,
+        merkle_branch: &[bitcoin::TxMerkleNode],
+        tx_index: u64,
+    ) -> Self {
+        use bitcoin::consensus::encode::serialize;
+        use bitcoin::hashes::Hash;
+
+        let mut stripped_tx = tx.clone();
+        for input in &mut stripped_tx.input {
+            input.witness.clear();
+        }
+
+        Self {
+            block_header: serialize(block_header),
+            tx_id: tx.compute_txid().to_byte_array().to_vec(),
+            tx_index,
+            merkle_proof: merkle_branch
+                .iter()
+                .map(|node| node.to_byte_array().to_vec())
+                .collect(),
+            raw_tx: serialize(&stripped_tx),
+        }
+    }
+}
+
+/// Inconsistency between a `Peg` and the transaction in its inclusion proof
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PegValidationError {
+    /// Inconsistent `Peg` field (e.g. `dest_script_hash`)
+    pub field: &'static str,
+    /// What does not match
+    pub reason: String,
+}
+
+impl std::fmt::Display for PegValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Inconsistent peg field {}: {}", self.field, self.reason)
+    }
+}
+
+impl std::error::Error for PegValidationError {}
+
+/// `mint` refused because the BTC output already has a peg record on chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlreadyMinted {
+    /// BTC transaction ID, internal byte order
+    pub tx_id: Vec<u8>,
+    /// Output index
+    pub tx_out_ix: u64,
+}
+
+impl std::fmt::Display for AlreadyMinted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "BTC output {}:{} was already minted",
+            txid_to_display_hex(&self.tx_id),
+            self.tx_out_ix
+        )
+    }
+}
+
+impl std::error::Error for AlreadyMinted {}
+
+/// Transaction refused because the sender can't pay its maximum gas fee
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsufficientGasFunds {
+    /// Sender address
+    pub address: String,
+    /// APT balance in octas
+    pub balance: u64,
+    /// `max_gas_amount * gas_unit_price` in octas
+    pub required: u64,
+}
+
+impl std::fmt::Display for InsufficientGasFunds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Account {} holds {} octas, but the transaction may use up to {} octas of gas",
+            self.address, self.balance, self.required
+        )
+    }
+}
+
+impl std::error::Error for InsufficientGasFunds {}
+
+/// `burn` refused because the sender holds less pegged BTC than the amount
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsufficientBridgeBalance {
+    /// Sender address
+    pub address: String,
+    /// Pegged BTC balance
+    pub balance: Sats,
+    /// Amount to burn
+    pub required: Sats,
+}
+
+impl std::fmt::Display for InsufficientBridgeBalance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Account {} holds {} of pegged BTC, less than the {} to burn",
+            self.address, self.balance, self.required
+        )
+    }
+}
+
+impl std::error::Error for InsufficientBridgeBalance {}
+
+#[cfg(feature = "bitcoin")]
+impl Peg {
+    /// Check the proof's raw transaction against the peg before submitting `mint`
+    ///
+    /// Verifies that `raw_tx` parses, hashes to `tx_id`, and that output
+    /// `tx_out_ix` pays at least `value` to `dest_script_hash` with `script_type`.
+    pub fn validate(&self) -> std::result::Result<(), PegValidationError> {
+        use bitcoin::hashes::Hash;
+
+        let error = |field, reason: String| PegValidationError { field, reason };
+        let proof = &self.inclusion_proof;
+
+        let tx: bitcoin::Transaction =
+            bitcoin::consensus::deserialize(&proof.raw_tx).map_err(|e| {
+                error(
+                    "inclusion_proof.raw_tx",
+                    format!("not a transaction: {}", e),
+                )
+            })?;
+        let txid = tx.compute_txid().to_byte_array();
+        if proof.tx_id != txid {
+            return Err(error(
+                "inclusion_proof.tx_id",
+                format!(
+                    "{} does not match raw transaction {}",
+                    txid_to_display_hex(&proof.tx_id),
+                    txid_to_display_hex(&txid)
+                ),
+            ));
+        }
+
+        let output = usize::try_from(self.tx_out_ix)
+            .ok()
+            .and_then(|ix| tx.output.get(ix))
+            .ok_or_else(|| {
+                error(
+                    "tx_out_ix",
+                    format!(
+                        "output {} out of range, transaction has {} outputs",
+                        self.tx_out_ix,
+                        tx.output.len()
+                    ),
+                )
+            })?;
+        let (script_hash, script_type) = crate::btc::script_hash_for_output(&output.script_pubkey)
+            .map_err(|e| error("script_type", e.to_string()))?;
+        if script_type != self.script_type {
+            return Err(error(
+                "script_type",
+                format!(
+                    "declared {:?}, output {} is {:?}",
+                    self.script_type, self.tx_out_ix, script_type
+                ),
+            ));
+        }
+        if script_hash != self.dest_script_hash {
+            return Err(error(
+                "dest_script_hash",
+                format!(
+                    "declared {}, output {} pays {}",
+                    hex::encode(&self.dest_script_hash),
+                    self.tx_out_ix,
+                    hex::encode(&script_hash)
+                ),
+            ));
+        }
+        let paid = Sats(output.value.to_sat());
+        if paid < self.value {
+            return Err(error(
+                "value",
+                format!(
+                    "declared {}, output {} pays {}",
+                    self.value, self.tx_out_ix, paid
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 /// Bitcoin script type
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Discriminants match the `u8` script type codes expected by the Move contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
 pub enum ScriptType {
-    P2SH = 0,  // Pay to Script Hash
-    P2WSH = 1, // Pay to Witness Script Hash
-    P2TR = 2,  // Pay to Taproot
+    P2SH = 0,   // Pay to Script Hash
+    P2WSH = 1,  // Pay to Witness Script Hash
+    P2TR = 2,   // Pay to Taproot
+    P2PKH = 3,  // Pay to Public Key Hash
+    P2WPKH = 4, // Pay to Witness Public Key Hash
+}
+
+impl ScriptType {
+    /// On-chain script type code
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for ScriptType {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(ScriptType::P2SH),
+            1 => Ok(ScriptType::P2WSH),
+            2 => Ok(ScriptType::P2TR),
+            3 => Ok(ScriptType::P2PKH),
+            4 => Ok(ScriptType::P2WPKH),
+            _ => Err(anyhow!("Invalid script type code: {}", value)),
+        }
+    }
 }
 
 /// Peg structure for mint operations
@@ -36,8 +391,8 @@ pub enum ScriptType {
 pub struct Peg {
     /// Recipient address
     pub to: String,
-    /// BTC amount (satoshi)
-    pub value: u64,
+    /// BTC amount
+    pub value: Sats,
     /// Block height
     pub block_num: u64,
     /// Inclusion proof
@@ -53,7 +408,7 @@ pub struct Peg {
 impl Peg {
     pub fn new(
         to: String,
-        value: u64,
+        value: Sats,
         block_num: u64,
         inclusion_proof: TxProof,
         tx_out_ix: u64,
@@ -70,18 +425,24 @@ impl Peg {
         }
     }
 
+    /// Set the script type of the deposit output
+    pub fn with_script_type(mut self, script_type: ScriptType) -> Self {
+        self.script_type = script_type;
+        self
+    }
+
     /// Serialize peg data to BCS format for contract calls
     pub fn serialize_to_args(&self) -> Result<Vec<Vec<u8>>> {
+        self.to_bcs_args()
+    }
+
+    /// Serialize peg data to BCS `mint` arguments, in contract parameter order
+    pub fn to_bcs_args(&self) -> Result<Vec<Vec<u8>>> {
         // Convert address string to AccountAddress
-        let to_address = aptos_sdk::types::account_address::AccountAddress::from_str(&self.to)
-            .map_err(|e| anyhow!("Invalid address format '{}': {}", self.to, e))?;
-
-        // Convert script type to u8
-        let script_type_u8 = match self.script_type {
-            ScriptType::P2SH => 0u8,
-            ScriptType::P2WSH => 1u8,
-            ScriptType::P2TR => 2u8,
-        };
+        let to_address = parse_account_address(&self.to)?;
+
+        // Convert script type to its on-chain code
+        let script_type_u8 = self.script_type.as_u8();
 
         // Serialize each parameter according to contract requirements
         let args = vec![
@@ -110,16 +471,40 @@ impl Peg {
 
         Ok(args)
     }
+
+    /// Decode BCS `mint` arguments produced by [`Peg::to_bcs_args`]
+    pub fn from_bcs_args(args: &[Vec<u8>]) -> Result<Self> {
+        check_arg_count("mint", args, 11)?;
+        let to: AccountAddress = decode_arg(args, 0, "to address")?;
+        let script_type: u8 = decode_arg(args, 5, "script_type")?;
+
+        Ok(Self {
+            to: to.to_hex_literal(),
+            value: decode_arg(args, 1, "value")?,
+            block_num: decode_arg(args, 2, "block_num")?,
+            inclusion_proof: TxProof {
+                block_header: decode_arg(args, 6, "block_header")?,
+                tx_id: decode_arg(args, 7, "tx_id")?,
+                tx_index: decode_arg(args, 3, "tx_index")?,
+                merkle_proof: decode_arg(args, 8, "tx_merkle_proof")?,
+                raw_tx: decode_arg(args, 9, "raw_tx")?,
+            },
+            tx_out_ix: decode_arg(args, 4, "tx_out_ix")?,
+            dest_script_hash: decode_arg(args, 10, "dest_script_hash")?,
+            script_type: ScriptType::try_from(script_type)?,
+        })
+    }
 }
 
 /// Mint event data
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MintEvent {
     /// Recipient address
     pub to_address: String,
     /// Minted amount
-    pub amount: u64,
-    /// BTC transaction ID
+    pub amount: Sats,
+    /// BTC transaction ID, hex in internal byte order (see [`MintEvent::format_btc_tx_id`])
     pub btc_tx_id: String,
     /// BTC block height
     pub btc_block_num: u64,
@@ -133,6 +518,7 @@ pub struct MintEvent {
 
 /// Burn event data
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct BurnEvent {
     /// Sender address
     pub from_address: String,
@@ -141,7 +527,7 @@ pub struct BurnEvent {
     /// Fee rate
     pub fee_rate: u64,
     /// Burned amount
-    pub amount: u64,
+    pub amount: Sats,
     /// Operator ID
     pub operator_id: u64,
     /// Timestamp
@@ -154,6 +540,7 @@ pub struct BurnEvent {
 
 /// WithdrawByLP event data
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct WithdrawByLPEvent {
     /// Sender address
     pub from_address: String,
@@ -164,11 +551,11 @@ pub struct WithdrawByLPEvent {
     /// Fee rate
     pub fee_rate: u64,
     /// Withdrawn amount
-    pub amount: u64,
+    pub amount: Sats,
     /// LP ID
     pub lp_id: u64,
     /// Minimum receive amount
-    pub receive_min_amount: u64,
+    pub receive_min_amount: Sats,
     /// Timestamp
     pub timestamp: Option<u64>,
     /// Version
@@ -247,177 +634,446 @@ pub(crate) struct WithdrawByLPEventRaw {
     pub transaction_hash: Option<String>,
 }
 
-impl From<MintEventRaw> for MintEvent {
-    fn from(raw: MintEventRaw) -> Self {
-        Self {
+/// Error converting a raw event or view response field into its typed value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldParseError {
+    /// Record being converted (e.g. `Mint`)
+    pub record: &'static str,
+    /// Field that failed to parse
+    pub field: &'static str,
+    /// Offending raw value
+    pub value: String,
+}
+
+impl std::fmt::Display for FieldParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid {} field '{}' in {} record",
+            self.field, self.value, self.record
+        )
+    }
+}
+
+impl std::error::Error for FieldParseError {}
+
+/// Parse a numeric string field of a raw record
+fn parse_field<T: FromStr>(
+    record: &'static str,
+    field: &'static str,
+    value: &str,
+) -> std::result::Result<T, FieldParseError> {
+    value.parse().map_err(|_| FieldParseError {
+        record,
+        field,
+        value: value.to_string(),
+    })
+}
+
+/// Parse an optional numeric string field of a raw record
+fn parse_optional_field<T: FromStr>(
+    record: &'static str,
+    field: &'static str,
+    value: Option<&str>,
+) -> std::result::Result<Option<T>, FieldParseError> {
+    value
+        .map(|value| parse_field(record, field, value))
+        .transpose()
+}
+
+/// Parse the optional ISO 8601 `timestamp` field of a raw record
+fn parse_optional_timestamp(
+    record: &'static str,
+    value: Option<&str>,
+) -> std::result::Result<Option<u64>, FieldParseError> {
+    value
+        .map(|value| {
+            parse_timestamp(value).ok_or_else(|| FieldParseError {
+                record,
+                field: "timestamp",
+                value: value.to_string(),
+            })
+        })
+        .transpose()
+}
+
+impl TryFrom<MintEventRaw> for MintEvent {
+    type Error = FieldParseError;
+
+    fn try_from(raw: MintEventRaw) -> std::result::Result<Self, Self::Error> {
+        const RECORD: &str = "Mint";
+        Ok(Self {
+            amount: Sats(parse_field(RECORD, "amount", &raw.amount)?),
+            btc_block_num: parse_field(RECORD, "btc_block_num", &raw.btc_block_num)?,
+            timestamp: parse_optional_timestamp(RECORD, raw.timestamp.as_deref())?,
+            version: parse_optional_field(RECORD, "version", raw.version.as_deref())?,
             to_address: raw.to_address,
-            amount: raw.amount.parse().unwrap_or(0),
             btc_tx_id: raw.btc_tx_id,
-            btc_block_num: raw.btc_block_num.parse().unwrap_or(0),
-            timestamp: raw.timestamp.and_then(|t| parse_timestamp(&t)),
-            version: raw.version.and_then(|v| v.parse().ok()),
             transaction_hash: raw.transaction_hash,
-        }
+        })
     }
 }
 
-impl From<BurnEventRaw> for BurnEvent {
-    fn from(raw: BurnEventRaw) -> Self {
-        Self {
+impl TryFrom<BurnEventRaw> for BurnEvent {
+    type Error = FieldParseError;
+
+    fn try_from(raw: BurnEventRaw) -> std::result::Result<Self, Self::Error> {
+        const RECORD: &str = "Burn";
+        Ok(Self {
+            fee_rate: parse_field(RECORD, "fee_rate", &raw.fee_rate)?,
+            amount: Sats(parse_field(RECORD, "amount", &raw.amount)?),
+            operator_id: parse_field(RECORD, "operator_id", &raw.operator_id)?,
+            timestamp: parse_optional_timestamp(RECORD, raw.timestamp.as_deref())?,
+            version: parse_optional_field(RECORD, "version", raw.version.as_deref())?,
             from_address: raw.from_address,
             btc_address: raw.btc_address,
-            fee_rate: raw.fee_rate.parse().unwrap_or(0),
-            amount: raw.amount.parse().unwrap_or(0),
-            operator_id: raw.operator_id.parse().unwrap_or(0),
-            timestamp: raw.timestamp.and_then(|t| parse_timestamp(&t)),
-            version: raw.version.and_then(|v| v.parse().ok()),
             transaction_hash: raw.transaction_hash,
-        }
+        })
     }
 }
 
-impl From<WithdrawByLPEventRaw> for WithdrawByLPEvent {
-    fn from(raw: WithdrawByLPEventRaw) -> Self {
-        Self {
+impl TryFrom<WithdrawByLPEventRaw> for WithdrawByLPEvent {
+    type Error = FieldParseError;
+
+    fn try_from(raw: WithdrawByLPEventRaw) -> std::result::Result<Self, Self::Error> {
+        const RECORD: &str = "WithdrawByLP";
+        Ok(Self {
+            withdraw_id: parse_field(RECORD, "withdraw_id", &raw.withdraw_id)?,
+            fee_rate: parse_field(RECORD, "fee_rate", &raw.fee_rate)?,
+            amount: Sats(parse_field(RECORD, "amount", &raw.amount)?),
+            lp_id: parse_field(RECORD, "lp_id", &raw.lp_id)?,
+            receive_min_amount: Sats(parse_field(
+                RECORD,
+                "receive_min_amount",
+                &raw.receive_min_amount,
+            )?),
+            timestamp: parse_optional_timestamp(RECORD, raw.timestamp.as_deref())?,
+            version: parse_optional_field(RECORD, "version", raw.version.as_deref())?,
             from_address: raw.from_address,
-            withdraw_id: raw.withdraw_id.parse().unwrap_or(0),
             btc_address: raw.btc_address,
-            fee_rate: raw.fee_rate.parse().unwrap_or(0),
-            amount: raw.amount.parse().unwrap_or(0),
-            lp_id: raw.lp_id.parse().unwrap_or(0),
-            receive_min_amount: raw.receive_min_amount.parse().unwrap_or(0),
-            timestamp: raw.timestamp.and_then(|t| parse_timestamp(&t)),
-            version: raw.version.and_then(|v| v.parse().ok()),
             transaction_hash: raw.transaction_hash,
-        }
+        })
     }
 }
 
-/// BCS-compatible Mint event structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct MintEventBCS {
-    pub to_address: [u8; 32], // AccountAddress as fixed-size array
-    pub amount: u64,
-    pub btc_tx_id: Vec<u8>,
-    pub btc_block_num: u64,
+/// Bridge contract event schema version
+///
+/// Contract upgrades may append fields to events. Decoding tries the configured
+/// version first and falls back to the other known layouts, so events emitted
+/// before and after an upgrade can both be read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EventSchemaVersion {
+    /// Original event layouts
+    #[default]
+    V1,
+    /// `Burn` carries the on-chain burn timestamp
+    V2,
 }
 
-/// BCS-compatible Burn event structure  
+/// Bridge event enum
+///
+/// The serde representation is part of the public API: variants are externally
+/// tagged as `Mint`, `Burn` and `WithdrawByLP`, event fields keep their Rust names,
+/// amounts are plain satoshi integers and absent optional fields are `null`.
+/// Golden files under `tests/golden` pin this format.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct BurnEventBCS {
-    pub from_address: [u8; 32], // AccountAddress as fixed-size array
-    pub btc_address: String,
-    pub fee_rate: u64,
-    pub amount: u64,
-    pub operator_id: u64,
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum BridgeEvent {
+    /// Mint event
+    Mint(MintEvent),
+    /// Burn event
+    Burn(BurnEvent),
+    /// WithdrawByLP event
+    WithdrawByLP(WithdrawByLPEvent),
+}
+
+impl BridgeEvent {
+    /// Kind of the event
+    pub fn kind(&self) -> BridgeEventKind {
+        match self {
+            BridgeEvent::Mint(_) => BridgeEventKind::Mint,
+            BridgeEvent::Burn(_) => BridgeEventKind::Burn,
+            BridgeEvent::WithdrawByLP(_) => BridgeEventKind::WithdrawByLP,
+        }
+    }
+
+    /// Aptos version of the emitting transaction, when known
+    pub fn version(&self) -> Option<u64> {
+        match self {
+            BridgeEvent::Mint(e) => e.version,
+            BridgeEvent::Burn(e) => e.version,
+            BridgeEvent::WithdrawByLP(e) => e.version,
+        }
+    }
+}
+
+/// Kind of a [`BridgeEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BridgeEventKind {
+    Mint,
+    Burn,
+    WithdrawByLP,
 }
 
-/// BCS-compatible WithdrawByLP event structure
+impl std::fmt::Display for BridgeEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BridgeEvent::Mint(event) => event.fmt(f),
+            BridgeEvent::Burn(event) => event.fmt(f),
+            BridgeEvent::WithdrawByLP(event) => event.fmt(f),
+        }
+    }
+}
+
+/// Bridge event with its position in the emitting transaction
+///
+/// `(version, event_index)` identifies an event uniquely, so reconcilers can
+/// key on it instead of the decoded payload.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct WithdrawByLPEventBCS {
-    pub from_address: [u8; 32], // AccountAddress as fixed-size array
-    pub withdraw_id: u64,
-    pub btc_address: String, // BCS will handle variable-length encoding
-    pub fee_rate: u64,
-    pub amount: u64,
-    pub lp_id: u64,
-    pub receive_min_amount: u64,
+pub struct BridgeEventRecord {
+    /// Decoded event, with its transaction fields filled in
+    pub event: BridgeEvent,
+    /// Index of the event among all events of the transaction
+    pub event_index: u64,
+    /// Canonical Move type of the event, e.g. `0x...::bridge::Mint`
+    pub event_type: String,
+    /// Sequence number in its event handle; `None` for module events
+    pub sequence_number: Option<u64>,
+    /// Transaction version
+    pub version: u64,
+    /// Transaction hash
+    pub transaction_hash: String,
+    /// Block timestamp, seconds since the Unix epoch
+    pub timestamp: u64,
 }
 
-impl From<MintEventBCS> for MintEvent {
-    fn from(bcs: MintEventBCS) -> Self {
-        // Convert AccountAddress bytes to hex string with fallback
-        let to_address = AccountAddress::from_bytes(&bcs.to_address)
-            .map(|addr| addr.to_hex_literal())
-            .unwrap_or_else(|_| format!("0x{}", hex::encode(&bcs.to_address)));
+/// Fullnode ledger state, from [`QueryClient::get_ledger_info`](crate::QueryClient::get_ledger_info)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerInfo {
+    /// Chain id of the node's network (1 on mainnet, 2 on testnet)
+    pub chain_id: u8,
+    /// Latest committed version
+    pub ledger_version: u64,
+    /// Timestamp of the latest committed version, microseconds since the Unix epoch
+    pub ledger_timestamp_usecs: u64,
+    /// Current epoch
+    pub epoch: u64,
+    /// Latest block height
+    pub block_height: u64,
+}
 
-        // Convert btc_tx_id bytes to hex string
-        let btc_tx_id = hex::encode(&bcs.btc_tx_id);
+/// Move resource held by an account, from
+/// [`QueryClient::list_account_resources`](crate::QueryClient::list_account_resources)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountResource {
+    /// Resource type, `address::module::Struct<..>`
+    pub resource_type: String,
+    /// Module declaring the resource
+    pub module: String,
+    /// Resource fields (Move `u64` and larger values are JSON strings)
+    pub data: serde_json::Value,
+}
 
-        MintEvent {
-            to_address,
-            amount: bcs.amount,
-            btc_tx_id,
-            btc_block_num: bcs.btc_block_num,
-            timestamp: None, // Not available in BCS events
-            version: None,
-            transaction_hash: None,
-        }
+impl AccountResource {
+    /// Decode the resource fields as `T`
+    pub fn decode<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_value(self.data.clone())
+            .map_err(|e| anyhow!("Failed to decode {} resource: {}", self.resource_type, e))
     }
 }
 
-impl From<BurnEventBCS> for BurnEvent {
-    fn from(bcs: BurnEventBCS) -> Self {
-        // Convert AccountAddress bytes to hex string with fallback
-        let from_address = AccountAddress::from_bytes(&bcs.from_address)
-            .map(|addr| addr.to_hex_literal())
-            .unwrap_or_else(|_| format!("0x{}", hex::encode(&bcs.from_address)));
+/// Gas unit prices in octas, from [`QueryClient::estimate_gas_price`](crate::QueryClient::estimate_gas_price)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasEstimate {
+    /// Price for transactions that can wait
+    pub deprioritized: u64,
+    /// Current market price
+    pub market: u64,
+    /// Price for inclusion ahead of the market
+    pub prioritized: u64,
+}
 
-        BurnEvent {
-            from_address,
-            btc_address: bcs.btc_address,
-            fee_rate: bcs.fee_rate,
-            amount: bcs.amount,
-            operator_id: bcs.operator_id,
-            timestamp: None, // Not available in BCS events
-            version: None,
-            transaction_hash: None,
+impl GasEstimate {
+    /// Price of the `priority` tier
+    pub fn price(&self, priority: GasPriority) -> u64 {
+        match priority {
+            GasPriority::Deprioritized => self.deprioritized,
+            GasPriority::Market => self.market,
+            GasPriority::Prioritized => self.prioritized,
         }
     }
 }
 
-impl From<WithdrawByLPEventBCS> for WithdrawByLPEvent {
-    fn from(bcs: WithdrawByLPEventBCS) -> Self {
-        // Convert AccountAddress bytes to hex string with fallback
-        let from_address = AccountAddress::from_bytes(&bcs.from_address)
-            .map(|addr| addr.to_hex_literal())
-            .unwrap_or_else(|_| format!("0x{}", hex::encode(&bcs.from_address)));
+/// Kind of a transaction, from [`QueryClient::get_transaction_kind`](crate::QueryClient::get_transaction_kind)
+///
+/// Only user transactions emit bridge events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TxKind {
+    /// Signed transaction submitted by an account
+    User,
+    /// Genesis transaction
+    Genesis,
+    /// Block prologue, with the block timestamp
+    BlockMetadata,
+    /// State checkpoint at the end of a block
+    StateCheckpoint,
+    /// Validator transaction (DKG, JWK updates)
+    Validator,
+    /// Another system transaction kind
+    Other,
+    /// Submitted but not committed yet
+    Pending,
+}
+
+/// Any event emitted by a transaction, as decoded by the fullnode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionEvent {
+    /// Index of the event among all events of the transaction
+    pub index: u64,
+    /// Move type of the event, e.g. `0x1::coin::CoinDeposit`
+    pub event_type: String,
+    /// Sequence number in its event handle; `0` for module events
+    pub sequence_number: u64,
+    /// Event fields as JSON
+    pub data: serde_json::Value,
+}
 
-        WithdrawByLPEvent {
-            from_address,
-            withdraw_id: bcs.withdraw_id,
-            btc_address: bcs.btc_address,
-            fee_rate: bcs.fee_rate,
-            amount: bcs.amount,
-            lp_id: bcs.lp_id,
-            receive_min_amount: bcs.receive_min_amount,
-            timestamp: None, // Not available in BCS events
-            version: None,
-            transaction_hash: None,
-        }
+/// Block dropped from the BTC best chain by a reorganization
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrphanedBlock {
+    /// Block height on the old chain
+    pub height: u64,
+    /// Block hash, display byte order
+    pub hash: String,
+}
+
+/// BTC chain reorganization reported by a [`ReorgDetector`](crate::ReorgDetector)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReorgDetected {
+    /// Highest height shared by the old and new chains
+    pub fork_height: u64,
+    /// Number of blocks dropped from the old chain
+    pub depth: u64,
+    /// Dropped blocks, highest first
+    pub orphaned_blocks: Vec<OrphanedBlock>,
+    /// Height of the new chain tip
+    pub new_tip_height: u64,
+}
+
+impl ReorgDetected {
+    /// Whether the block with `block_hash` (internal byte order) was dropped
+    pub fn orphans(&self, block_hash: &[u8]) -> bool {
+        let hash = txid_to_display_hex(block_hash);
+        self.orphaned_blocks.iter().any(|block| block.hash == hash)
     }
 }
 
-/// Bridge event enum
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum BridgeEvent {
-    /// Mint event
-    Mint(MintEvent),
-    /// Burn event
-    Burn(BurnEvent),
-    /// WithdrawByLP event
-    WithdrawByLP(WithdrawByLPEvent),
+impl std::fmt::Display for ReorgDetected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "BTC reorg of {} blocks above height {}, new tip {}",
+            self.depth, self.fork_height, self.new_tip_height
+        )
+    }
+}
+
+impl TxProof {
+    /// Transaction ID as shown by explorers
+    pub fn display_txid(&self) -> String {
+        txid_to_display_hex(&self.tx_id)
+    }
+}
+
+impl MintEvent {
+    /// BTC transaction ID bytes in internal byte order
+    pub fn btc_tx_id_bytes(&self) -> Result<Vec<u8>> {
+        decode_txid_hex(&self.btc_tx_id)
+    }
+
+    /// BTC transaction ID as hex in the given byte order
+    pub fn format_btc_tx_id(&self, order: TxidByteOrder) -> Result<String> {
+        let txid = self.btc_tx_id_bytes()?;
+        Ok(match order {
+            TxidByteOrder::Internal => hex::encode(txid),
+            TxidByteOrder::Display => txid_to_display_hex(&txid),
+        })
+    }
+}
+
+impl std::fmt::Display for MintEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Mint {} to {} (btc_tx {} @ block {})",
+            self.amount, self.to_address, self.btc_tx_id, self.btc_block_num
+        )?;
+        fmt_event_origin(f, self.version, self.transaction_hash.as_deref())
+    }
+}
+
+impl std::fmt::Display for BurnEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Burn {} from {} to {} (fee_rate {}, operator {})",
+            self.amount, self.from_address, self.btc_address, self.fee_rate, self.operator_id
+        )?;
+        fmt_event_origin(f, self.version, self.transaction_hash.as_deref())
+    }
+}
+
+impl std::fmt::Display for WithdrawByLPEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "WithdrawByLP #{} {} from {} to {} (lp {}, min {}, fee_rate {})",
+            self.withdraw_id,
+            self.amount,
+            self.from_address,
+            self.btc_address,
+            self.lp_id,
+            self.receive_min_amount,
+            self.fee_rate
+        )?;
+        fmt_event_origin(f, self.version, self.transaction_hash.as_deref())
+    }
+}
+
+/// Append the Aptos version and transaction hash of an event, when known
+fn fmt_event_origin(
+    f: &mut std::fmt::Formatter<'_>,
+    version: Option<u64>,
+    transaction_hash: Option<&str>,
+) -> std::fmt::Result {
+    if let Some(version) = version {
+        write!(f, " version {}", version)?;
+    }
+    if let Some(transaction_hash) = transaction_hash {
+        write!(f, " tx {}", transaction_hash)?;
+    }
+    Ok(())
 }
 
 /// Parse mint event using serde_json
 pub fn parse_mint_event(data: &serde_json::Value) -> Result<MintEvent> {
     let raw_event: MintEventRaw = serde_json::from_value(data.clone())
         .map_err(|e| anyhow!("Failed to parse mint event data: {}", e))?;
-    Ok(raw_event.into())
+    Ok(raw_event.try_into()?)
 }
 
 /// Parse burn event using serde_json
 pub fn parse_burn_event(data: &serde_json::Value) -> Result<BurnEvent> {
     let raw_event: BurnEventRaw = serde_json::from_value(data.clone())
         .map_err(|e| anyhow!("Failed to parse burn event data: {}", e))?;
-    Ok(raw_event.into())
+    Ok(raw_event.try_into()?)
 }
 
 /// Parse WithdrawByLP event using serde_json
 pub fn parse_withdraw_by_lp_event(data: &serde_json::Value) -> Result<WithdrawByLPEvent> {
     let raw_event: WithdrawByLPEventRaw = serde_json::from_value(data.clone())
         .map_err(|e| anyhow!("Failed to parse withdraw by LP event data: {}", e))?;
-    Ok(raw_event.into())
+    Ok(raw_event.try_into()?)
 }
 
 /// LP Status enumeration (matches Move contract LPStatus enum)
@@ -463,14 +1119,53 @@ pub struct LPInfo {
     pub status: LPStatus,
 }
 
+/// LP information raw structure (Move contract returns string values)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LPInfoRaw {
+    pub lp_id: String,
+    pub bitcoin_addr: String,
+    pub lp_addr: String,
+    pub lp_fee: String,
+    pub status: LPStatus,
+}
+
+impl TryFrom<LPInfoRaw> for LPInfo {
+    type Error = FieldParseError;
+
+    fn try_from(raw: LPInfoRaw) -> std::result::Result<Self, Self::Error> {
+        const RECORD: &str = "LPInfo";
+        Ok(Self {
+            lp_id: parse_field(RECORD, "lp_id", &raw.lp_id)?,
+            bitcoin_addr: raw.bitcoin_addr,
+            lp_addr: raw.lp_addr,
+            lp_fee: parse_field(RECORD, "lp_fee", &raw.lp_fee)?,
+            status: raw.status,
+        })
+    }
+}
+
+impl LPInfo {
+    /// Fee the LP charges on `amount`, rounded down (`lp_fee` is in basis points)
+    pub fn fee_for(&self, amount: Sats) -> Sats {
+        Sats((u128::from(amount.0) * u128::from(self.lp_fee) / 10_000) as u64)
+    }
+
+    /// Parse LP data from view function response
+    pub fn from_view_response(result: &serde_json::Value) -> Result<Self> {
+        let raw = serde_json::from_value::<LPInfoRaw>(result.clone())
+            .map_err(|e| anyhow!("Failed to parse get_lp_info response: {}", e))?;
+        Ok(raw.try_into()?)
+    }
+}
+
 /// LP withdraw information structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LPWithdraw {
     pub id: u64,
-    pub withdraw_amount: u64,
+    pub withdraw_amount: Sats,
     pub receiver_addr: String,
     pub receiver_script_hash: String,
-    pub receive_min_amount: u64,
+    pub receive_min_amount: Sats,
     pub fee_rate: u64,
     pub timestamp: u64,
     pub lp_id: u64,
@@ -489,18 +1184,29 @@ pub struct LPWithdrawRaw {
     pub lp_id: String,
 }
 
-impl From<LPWithdrawRaw> for LPWithdraw {
-    fn from(raw: LPWithdrawRaw) -> Self {
-        Self {
-            id: raw.id.parse().unwrap_or(0),
-            withdraw_amount: raw.withdraw_amount.parse().unwrap_or(0),
+impl TryFrom<LPWithdrawRaw> for LPWithdraw {
+    type Error = FieldParseError;
+
+    fn try_from(raw: LPWithdrawRaw) -> std::result::Result<Self, Self::Error> {
+        const RECORD: &str = "LPWithdraw";
+        Ok(Self {
+            id: parse_field(RECORD, "id", &raw.id)?,
+            withdraw_amount: Sats(parse_field(
+                RECORD,
+                "withdraw_amount",
+                &raw.withdraw_amount,
+            )?),
+            receive_min_amount: Sats(parse_field(
+                RECORD,
+                "receive_min_amount",
+                &raw.receive_min_amount,
+            )?),
+            fee_rate: parse_field(RECORD, "fee_rate", &raw.fee_rate)?,
+            timestamp: parse_field(RECORD, "timestamp", &raw.timestamp)?,
+            lp_id: parse_field(RECORD, "lp_id", &raw.lp_id)?,
             receiver_addr: raw.receiver_addr,
             receiver_script_hash: raw.receiver_script_hash,
-            receive_min_amount: raw.receive_min_amount.parse().unwrap_or(0),
-            fee_rate: raw.fee_rate.parse().unwrap_or(0),
-            timestamp: raw.timestamp.parse().unwrap_or(0),
-            lp_id: raw.lp_id.parse().unwrap_or(0),
-        }
+        })
     }
 }
 
@@ -510,7 +1216,149 @@ impl LPWithdraw {
         // Parse as LPWithdrawRaw since Move contract returns string values for numbers
         let raw = serde_json::from_value::<LPWithdrawRaw>(result.clone())
             .map_err(|e| anyhow!("Failed to parse get_lp_withdraw response: {}", e))?;
-        Ok(raw.into())
+        Ok(raw.try_into()?)
+    }
+}
+
+/// Peg record of a minted BTC output, stored in the bridge's peg table
+///
+/// From [`BridgeClient::get_peg_record`](crate::BridgeClient::get_peg_record).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PegRecord {
+    /// Recipient address
+    pub to: String,
+    /// Minted BTC amount
+    pub value: Sats,
+    /// Height of the block containing the BTC transaction
+    pub block_num: u64,
+}
+
+/// Peg record as stored on chain (Move `u64` values are JSON strings)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PegRecordRaw {
+    pub to: String,
+    pub value: String,
+    pub block_num: String,
+}
+
+impl TryFrom<PegRecordRaw> for PegRecord {
+    type Error = FieldParseError;
+
+    fn try_from(raw: PegRecordRaw) -> std::result::Result<Self, Self::Error> {
+        const RECORD: &str = "PegRecord";
+        Ok(Self {
+            value: Sats(parse_field(RECORD, "value", &raw.value)?),
+            block_num: parse_field(RECORD, "block_num", &raw.block_num)?,
+            to: raw.to,
+        })
+    }
+}
+
+/// Withdraw status enumeration (matches Move contract WithdrawStatus enum)
+/// 0 = PENDING, 1 = CLAIMED, 2 = EXPIRED
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[repr(u8)]
+#[serde(tag = "__variant__")]
+pub enum WithdrawStatus {
+    PENDING = 0,
+    CLAIMED = 1,
+    EXPIRED = 2,
+}
+
+impl WithdrawStatus {
+    /// Parse withdraw status data from view function response
+    pub fn from_view_response(result: &serde_json::Value) -> Result<Self> {
+        let status = serde_json::from_value::<WithdrawStatus>(result.clone())
+            .map_err(|e| anyhow!("Failed to parse withdraw status response: {}", e))?;
+        Ok(status)
+    }
+}
+
+/// Operator information structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorInfo {
+    pub operator_id: u64,
+    pub operator_addr: String,
+    pub btc_addr: String,
+    pub is_active: bool,
+}
+
+/// Operator raw information structure (Move contract returns string values)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorInfoRaw {
+    pub operator_id: String,
+    pub operator_addr: String,
+    pub btc_addr: String,
+    pub is_active: bool,
+}
+
+impl TryFrom<OperatorInfoRaw> for OperatorInfo {
+    type Error = FieldParseError;
+
+    fn try_from(raw: OperatorInfoRaw) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            operator_id: parse_field("OperatorInfo", "operator_id", &raw.operator_id)?,
+            operator_addr: raw.operator_addr,
+            btc_addr: raw.btc_addr,
+            is_active: raw.is_active,
+        })
+    }
+}
+
+impl OperatorInfo {
+    /// Parse operator data from view function response
+    pub fn from_view_response(result: &serde_json::Value) -> Result<Self> {
+        let raw = serde_json::from_value::<OperatorInfoRaw>(result.clone())
+            .map_err(|e| anyhow!("Failed to parse operator info response: {}", e))?;
+        Ok(raw.try_into()?)
+    }
+}
+
+/// Global bridge configuration structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeConfig {
+    pub min_confirmations: u64,
+    pub min_burn_amount: Sats,
+    pub max_fee_rate: u64,
+    pub btc_light_client: String,
+    pub paused: bool,
+}
+
+/// Global bridge configuration raw structure (Move contract returns string values)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeConfigRaw {
+    pub min_confirmations: String,
+    pub min_burn_amount: String,
+    pub max_fee_rate: String,
+    pub btc_light_client: String,
+    pub paused: bool,
+}
+
+impl TryFrom<BridgeConfigRaw> for BridgeConfig {
+    type Error = FieldParseError;
+
+    fn try_from(raw: BridgeConfigRaw) -> std::result::Result<Self, Self::Error> {
+        const RECORD: &str = "BridgeConfig";
+        Ok(Self {
+            min_confirmations: parse_field(RECORD, "min_confirmations", &raw.min_confirmations)?,
+            min_burn_amount: Sats(parse_field(
+                RECORD,
+                "min_burn_amount",
+                &raw.min_burn_amount,
+            )?),
+            max_fee_rate: parse_field(RECORD, "max_fee_rate", &raw.max_fee_rate)?,
+            btc_light_client: raw.btc_light_client,
+            paused: raw.paused,
+        })
+    }
+}
+
+impl BridgeConfig {
+    /// Parse bridge configuration from view function response
+    pub fn from_view_response(result: &serde_json::Value) -> Result<Self> {
+        let raw = serde_json::from_value::<BridgeConfigRaw>(result.clone())
+            .map_err(|e| anyhow!("Failed to parse bridge config response: {}", e))?;
+        Ok(raw.try_into()?)
     }
 }
 
@@ -518,17 +1366,22 @@ impl LPWithdraw {
 #[derive(Debug, Clone)]
 pub struct WithdrawByLPParams {
     pub withdraw_id: u64,
-    pub btc_address: String,
+    pub btc_address: BtcAddress,
     pub receiver_script_hash: Vec<u8>,
-    pub receive_min_amount: u64,
+    pub receive_min_amount: Sats,
     pub lp_id: u64,
-    pub amount: u64,
+    pub amount: Sats,
     pub fee_rate: u64,
 }
 
 impl WithdrawByLPParams {
     /// Serialize request parameters to BCS format for contract calls
     pub fn serialize_to_args(&self) -> Result<Vec<Vec<u8>>> {
+        self.to_bcs_args()
+    }
+
+    /// Serialize request parameters to BCS arguments, in contract parameter order
+    pub fn to_bcs_args(&self) -> Result<Vec<Vec<u8>>> {
         let args = vec![
             bcs::to_bytes(&self.withdraw_id)
                 .map_err(|e| anyhow!("Failed to serialize withdraw_id: {}", e))?,
@@ -546,6 +1399,20 @@ impl WithdrawByLPParams {
         ];
         Ok(args)
     }
+
+    /// Decode BCS arguments produced by [`WithdrawByLPParams::to_bcs_args`]
+    pub fn from_bcs_args(args: &[Vec<u8>]) -> Result<Self> {
+        check_arg_count("withdraw_by_lp", args, 7)?;
+        Ok(Self {
+            withdraw_id: decode_arg(args, 0, "withdraw_id")?,
+            btc_address: decode_arg(args, 1, "btc_address")?,
+            receiver_script_hash: decode_arg(args, 2, "receiver_script_hash")?,
+            receive_min_amount: decode_arg(args, 3, "receive_min_amount")?,
+            lp_id: decode_arg(args, 4, "lp_id")?,
+            amount: decode_arg(args, 5, "amount")?,
+            fee_rate: decode_arg(args, 6, "fee_rate")?,
+        })
+    }
 }
 
 /// Request parameters for claim_lp_withdraw function
@@ -554,13 +1421,50 @@ pub struct ClaimLPWithdrawParams {
     pub withdraw_id: u64,
     pub block_num: u64,
     pub tx_out_ix: u64,
-    pub amount_sats: u64,
+    pub amount_sats: Sats,
     pub inclusion_proof: TxProof,
 }
 
 impl ClaimLPWithdrawParams {
+    /// Check that the proved output pays `withdraw`'s receiver script at least
+    /// its minimum amount, as `claim_lp_withdraw` will
+    #[cfg(feature = "bitcoin")]
+    pub fn verify_payment(&self, withdraw: &LPWithdraw) -> Result<()> {
+        let tx: bitcoin::Transaction =
+            bitcoin::consensus::deserialize(&self.inclusion_proof.raw_tx)
+                .map_err(|e| anyhow!("Failed to decode payment transaction: {}", e))?;
+        let output = usize::try_from(self.tx_out_ix)
+            .ok()
+            .and_then(|ix| tx.output.get(ix))
+            .ok_or_else(|| anyhow!("Payment transaction has no output {}", self.tx_out_ix))?;
+        let (script_hash, _) = crate::btc::script_hash_for_output(&output.script_pubkey)?;
+        if script_hash != crate::utils::parse_script_hash(&withdraw.receiver_script_hash)? {
+            return Err(anyhow!(
+                "Output {} does not pay receiver {} of withdrawal {}",
+                self.tx_out_ix,
+                withdraw.receiver_addr,
+                withdraw.id
+            ));
+        }
+        let amount = Sats(output.value.to_sat());
+        if amount < withdraw.receive_min_amount {
+            return Err(anyhow!(
+                "Payout {} is below the minimum {} for withdrawal {}",
+                amount,
+                withdraw.receive_min_amount,
+                withdraw.id
+            ));
+        }
+        Ok(())
+    }
+
     /// Serialize request parameters to BCS format for contract calls
     pub fn serialize_to_args(&self) -> Result<Vec<Vec<u8>>> {
+        self.to_bcs_args()
+    }
+
+    /// Serialize request parameters to BCS arguments, in contract parameter order
+    pub fn to_bcs_args(&self) -> Result<Vec<Vec<u8>>> {
         let args = vec![
             bcs::to_bytes(&self.withdraw_id)
                 .map_err(|e| anyhow!("Failed to serialize withdraw_id: {}", e))?,
@@ -583,13 +1487,31 @@ impl ClaimLPWithdrawParams {
         ];
         Ok(args)
     }
+
+    /// Decode BCS arguments produced by [`ClaimLPWithdrawParams::to_bcs_args`]
+    pub fn from_bcs_args(args: &[Vec<u8>]) -> Result<Self> {
+        check_arg_count("claim_lp_withdraw", args, 9)?;
+        Ok(Self {
+            withdraw_id: decode_arg(args, 0, "withdraw_id")?,
+            block_num: decode_arg(args, 1, "block_num")?,
+            tx_out_ix: decode_arg(args, 2, "tx_out_ix")?,
+            amount_sats: decode_arg(args, 3, "amount_sats")?,
+            inclusion_proof: TxProof {
+                block_header: decode_arg(args, 4, "block_header")?,
+                tx_id: decode_arg(args, 5, "tx_id")?,
+                tx_index: decode_arg(args, 6, "tx_index")?,
+                merkle_proof: decode_arg(args, 7, "tx_merkle_proof")?,
+                raw_tx: decode_arg(args, 8, "raw_tx")?,
+            },
+        })
+    }
 }
 
 /// Request parameters for register_lp function
 #[derive(Debug, Clone)]
 pub struct RegisterLPParams {
     pub lp_id: u64,
-    pub bitcoin_addr: String,
+    pub bitcoin_addr: BtcAddress,
     pub lp_addr: String,
     pub lp_fee: u64,
 }
@@ -597,9 +1519,14 @@ pub struct RegisterLPParams {
 impl RegisterLPParams {
     /// Serialize request parameters to BCS format for contract calls
     pub fn serialize_to_args(&self) -> Result<Vec<Vec<u8>>> {
+        self.to_bcs_args()
+    }
+
+    /// Serialize request parameters to BCS arguments, in contract parameter order
+    pub fn to_bcs_args(&self) -> Result<Vec<Vec<u8>>> {
         // Convert lp_addr string to AccountAddress
-        let lp_addr = aptos_sdk::types::account_address::AccountAddress::from_str(&self.lp_addr)
-            .map_err(|e| anyhow!("Invalid LP address format '{}': {}", self.lp_addr, e))?;
+        let lp_addr = parse_account_address(&self.lp_addr)
+            .map_err(|e| anyhow!("Invalid LP address: {}", e))?;
 
         let args = vec![
             bcs::to_bytes(&self.lp_id).map_err(|e| anyhow!("Failed to serialize lp_id: {}", e))?,
@@ -611,19 +1538,683 @@ impl RegisterLPParams {
         ];
         Ok(args)
     }
+
+    /// Decode BCS arguments produced by [`RegisterLPParams::to_bcs_args`]
+    pub fn from_bcs_args(args: &[Vec<u8>]) -> Result<Self> {
+        check_arg_count("register_lp", args, 4)?;
+        let lp_addr: AccountAddress = decode_arg(args, 2, "lp_addr")?;
+        Ok(Self {
+            lp_id: decode_arg(args, 0, "lp_id")?,
+            bitcoin_addr: decode_arg(args, 1, "bitcoin_addr")?,
+            lp_addr: lp_addr.to_hex_literal(),
+            lp_fee: decode_arg(args, 3, "lp_fee")?,
+        })
+    }
+}
+
+/// Check the number of BCS arguments of an entry function call
+fn check_arg_count(function: &str, args: &[Vec<u8>], expected: usize) -> Result<()> {
+    if args.len() != expected {
+        return Err(anyhow!(
+            "Invalid {} arguments: expected {}, got {}",
+            function,
+            expected,
+            args.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Decode a single BCS argument
+fn decode_arg<T: serde::de::DeserializeOwned>(
+    args: &[Vec<u8>],
+    index: usize,
+    name: &str,
+) -> Result<T> {
+    bcs::from_bytes(&args[index]).map_err(|e| anyhow!("Failed to deserialize {}: {}", name, e))
 }
 
 /// Constants module
 pub mod constants {
     pub const EXPIRATION_TIMESTAMP_SECS: u64 = 60;
+    pub const GRAPHQL_REQUEST_TIMEOUT_SECS: u64 = 30;
+    pub const REQUEST_LOG_MAX_BODY_LEN: usize = 2048;
+    pub const SDK_USER_AGENT: &str = concat!("fiamma-aptos-client-sdk/", env!("CARGO_PKG_VERSION"));
+    pub const REST_REQUEST_TIMEOUT_SECS: u64 = 10;
+    pub const HTTP_CONNECT_TIMEOUT_SECS: u64 = 10;
+    pub const HTTP_TCP_KEEPALIVE_SECS: u64 = 30;
+    pub const FEE_ORACLE_REQUEST_TIMEOUT_SECS: u64 = 10;
+    pub const TRANSACTION_POLL_INTERVAL_MS: u64 = 1000;
+    pub const TRANSACTION_COMMIT_TIMEOUT_SECS: u64 = 60;
+    pub const TRANSACTION_SCAN_PAGE_SIZE: u16 = 100;
+    pub const PEG_IN_POLL_INTERVAL_SECS: u64 = 60;
+    pub const CONFIRMATION_POLL_INTERVAL_SECS: u64 = 60;
+    pub const HEADER_SYNC_BATCH_SIZE: usize = 50;
+    pub const HEADER_SYNC_MAX_RETRIES: u32 = 3;
+    pub const HEADER_SYNC_MAX_REORG_DEPTH: u64 = 100;
+    pub const HEADER_SYNC_POLL_INTERVAL_SECS: u64 = 60;
+    pub const REORG_TRACKED_BLOCKS: usize = 100;
+    pub const REORG_MIN_DEPTH: u64 = 1;
+    pub const REORG_POLL_INTERVAL_SECS: u64 = 60;
+    pub const PAYOUT_DUST_LIMIT_SATS: u64 = 546;
+    pub const TX_QUEUE_MAX_ATTEMPTS: u32 = 3;
+    pub const TX_QUEUE_POLL_INTERVAL_SECS: u64 = 10;
+    pub const PIPELINE_WINDOW: usize = 8;
+    pub const VIEW_CONCURRENCY: usize = 8;
+    pub const PIPELINE_POLL_INTERVAL_MS: u64 = 1000;
+    pub const PIPELINE_EXPIRATION_MARGIN_SECS: u64 = 5;
+    pub const VAULT_REQUEST_TIMEOUT_SECS: u64 = 10;
+    pub const VAULT_MIN_RENEWAL_INTERVAL_SECS: u64 = 5;
+    pub const THRESHOLD_SIGNING_POLL_INTERVAL_MS: u64 = 1000;
+    pub const THRESHOLD_SIGNING_TIMEOUT_SECS: u64 = 45;
+    pub const OPERATOR_WATCH_POLL_INTERVAL_SECS: u64 = 10;
+    pub const PAYOUT_WATCH_POLL_INTERVAL_SECS: u64 = 60;
+    pub const PAYOUT_WATCH_TIMEOUT_BLOCKS: u64 = 144;
+    pub const PAYOUT_WATCH_CONFIRMATIONS: u64 = 6;
+    pub const DEFAULT_MAX_GAS_AMOUNT: u64 = 2_000_000;
+    pub const DEFAULT_GAS_UNIT_PRICE: u64 = 100;
+    pub const APT_COIN_TYPE: &str = "0x1::aptos_coin::AptosCoin";
+    pub const BRIDGE_STORE_RESOURCE: &str = "BridgeStore";
+    pub const PEG_TABLE_FIELD: &str = "pegs";
+    pub const PEG_KEY_STRUCT: &str = "PegKey";
+    pub const PEG_RECORD_STRUCT: &str = "PegRecord";
+    pub const WITHDRAW_TABLE_FIELD: &str = "lp_withdraws";
+    pub const LP_WITHDRAW_STRUCT: &str = "LPWithdraw";
 }
 
 /// Parse ISO 8601 timestamp string to Unix timestamp (u64)
-/// Assumes timestamp without timezone info is in UTC
+///
+/// Accepts RFC 3339 timestamps with an offset (`Z`, `+00:00`, ...) and indexer
+/// timestamps without timezone info, which are assumed to be UTC. Fractional
+/// seconds are accepted and truncated. Plain integers are taken as seconds
+/// since the Unix epoch.
 fn parse_timestamp(timestamp_str: &str) -> Option<u64> {
-    // First try to parse as NaiveDateTime (no timezone), then treat as UTC
-    if let Ok(naive_dt) = NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%dT%H:%M:%S") {
-        return Some(naive_dt.and_utc().timestamp() as u64);
+    if let Ok(secs) = timestamp_str.parse() {
+        return Some(secs);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(timestamp_str) {
+        return u64::try_from(dt.timestamp()).ok();
+    }
+    // No timezone: treat as UTC, with optional fractional seconds
+    if let Ok(naive_dt) = NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%dT%H:%M:%S%.f") {
+        return u64::try_from(naive_dt.and_utc().timestamp()).ok();
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sats_display_and_parse() {
+        assert_eq!(Sats(500000).to_string(), "0.00500000 BTC");
+        assert_eq!(
+            Sats(2_100_000_000_000_000).to_string(),
+            "21000000.00000000 BTC"
+        );
+
+        assert_eq!("0.005 BTC".parse::<Sats>().unwrap(), Sats(500000));
+        assert_eq!("1btc".parse::<Sats>().unwrap(), Sats::ONE_BTC);
+        assert_eq!(".5 BTC".parse::<Sats>().unwrap(), Sats(50_000_000));
+        assert_eq!("500000 sats".parse::<Sats>().unwrap(), Sats(500000));
+        assert_eq!("1 sat".parse::<Sats>().unwrap(), Sats(1));
+        assert_eq!("42".parse::<Sats>().unwrap(), Sats(42));
+
+        assert!("0.000000001 BTC".parse::<Sats>().is_err());
+        assert!("-1 sats".parse::<Sats>().is_err());
+        assert!("abc".parse::<Sats>().is_err());
+        assert!(" BTC".parse::<Sats>().is_err());
+    }
+
+    #[test]
+    fn test_sats_serialization() {
+        assert_eq!(serde_json::to_string(&Sats(7)).unwrap(), "7");
+        assert_eq!(
+            bcs::to_bytes(&Sats(7)).unwrap(),
+            bcs::to_bytes(&7u64).unwrap()
+        );
+        assert_eq!(Sats(1).checked_sub(Sats(2)), None);
+        assert_eq!(Sats(u64::MAX).checked_add(Sats(1)), None);
+    }
+
+    fn sample_events() -> Vec<(&'static str, BridgeEvent)> {
+        vec![
+            (
+                include_str!("../tests/golden/mint_event.json"),
+                BridgeEvent::Mint(MintEvent {
+                    to_address: "0x1".to_string(),
+                    amount: Sats(500000),
+                    btc_tx_id: "29".to_string(),
+                    btc_block_num: 800000,
+                    timestamp: Some(1714564800),
+                    version: Some(42),
+                    transaction_hash: Some("0xabc".to_string()),
+                }),
+            ),
+            (
+                include_str!("../tests/golden/burn_event.json"),
+                BridgeEvent::Burn(BurnEvent {
+                    from_address: "0x2".to_string(),
+                    btc_address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+                    fee_rate: 5,
+                    amount: Sats(250000),
+                    operator_id: 1,
+                    timestamp: None,
+                    version: None,
+                    transaction_hash: None,
+                }),
+            ),
+            (
+                include_str!("../tests/golden/withdraw_by_lp_event.json"),
+                BridgeEvent::WithdrawByLP(WithdrawByLPEvent {
+                    from_address: "0x3".to_string(),
+                    withdraw_id: 7,
+                    btc_address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+                    fee_rate: 10,
+                    amount: Sats(500000),
+                    lp_id: 1,
+                    receive_min_amount: Sats(450000),
+                    timestamp: Some(1714564800),
+                    version: Some(43),
+                    transaction_hash: None,
+                }),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_bridge_event_golden_json() {
+        for (golden, event) in sample_events() {
+            let expected: serde_json::Value = serde_json::from_str(golden).unwrap();
+            assert_eq!(serde_json::to_value(&event).unwrap(), expected);
+
+            let decoded: BridgeEvent = serde_json::from_str(golden).unwrap();
+            assert_eq!(serde_json::to_value(&decoded).unwrap(), expected);
+        }
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_bridge_event_schema_matches_golden_json() {
+        let schema = serde_json::to_value(schemars::schema_for!(BridgeEvent)).unwrap();
+        let definitions = &schema["definitions"];
+        assert_eq!(
+            definitions["MintEvent"]["properties"]["amount"]["type"],
+            "integer"
+        );
+
+        for (golden, event) in sample_events() {
+            let (variant, definition) = match event {
+                BridgeEvent::Mint(_) => ("Mint", "MintEvent"),
+                BridgeEvent::Burn(_) => ("Burn", "BurnEvent"),
+                BridgeEvent::WithdrawByLP(_) => ("WithdrawByLP", "WithdrawByLPEvent"),
+            };
+            let golden: serde_json::Value = serde_json::from_str(golden).unwrap();
+            let properties = definitions[definition]["properties"].as_object().unwrap();
+            for field in golden[variant].as_object().unwrap().keys() {
+                assert!(
+                    properties.contains_key(field),
+                    "{} missing {}",
+                    definition,
+                    field
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_bridge_event_display() {
+        let lines: Vec<String> = sample_events()
+            .into_iter()
+            .map(|(_, event)| event.to_string())
+            .collect();
+        assert_eq!(
+            lines[0],
+            "Mint 0.00500000 BTC to 0x1 (btc_tx 29 @ block 800000) version 42 tx 0xabc"
+        );
+        assert_eq!(
+            lines[1],
+            "Burn 0.00250000 BTC from 0x2 to bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 (fee_rate 5, operator 1)"
+        );
+        assert_eq!(
+            lines[2],
+            "WithdrawByLP #7 0.00500000 BTC from 0x3 to bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 (lp 1, min 0.00450000 BTC, fee_rate 10) version 43"
+        );
+    }
+
+    #[test]
+    fn test_raw_event_conversion_rejects_bad_numbers() {
+        let raw = serde_json::json!({
+            "to_address": "0x1",
+            "amount": "not-a-number",
+            "btc_tx_id": "29",
+            "btc_block_num": "800000",
+            "version": "42",
+        });
+        let err = parse_mint_event(&raw).unwrap_err();
+        let parse_error = err.downcast_ref::<FieldParseError>().unwrap();
+        assert_eq!(parse_error.field, "amount");
+        assert_eq!(parse_error.value, "not-a-number");
+
+        let raw = serde_json::json!({
+            "to_address": "0x1",
+            "amount": "500000",
+            "btc_tx_id": "29",
+            "btc_block_num": "800000",
+        });
+        let event = parse_mint_event(&raw).unwrap();
+        assert_eq!(event.amount, Sats(500000));
+        assert_eq!(event.version, None);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let expected = Some(1714564800);
+        assert_eq!(parse_timestamp("2024-05-01T12:00:00"), expected);
+        assert_eq!(parse_timestamp("2024-05-01T12:00:00.123456"), expected);
+        assert_eq!(parse_timestamp("2024-05-01T12:00:00Z"), expected);
+        assert_eq!(parse_timestamp("2024-05-01T12:00:00+00:00"), expected);
+        assert_eq!(parse_timestamp("2024-05-01T12:00:00.5+00:00"), expected);
+        assert_eq!(parse_timestamp("2024-05-01T14:00:00+02:00"), expected);
+        assert_eq!(parse_timestamp("1714564800"), expected);
+        assert_eq!(parse_timestamp("not a timestamp"), None);
+        assert_eq!(parse_timestamp("1969-12-31T23:59:59Z"), None);
+
+        assert_eq!(parse_optional_timestamp("Mint", None), Ok(None));
+        assert_eq!(
+            parse_optional_timestamp("Mint", Some("2024-05-01T12:00:00")),
+            Ok(expected)
+        );
+        let err = parse_optional_timestamp("Burn", Some("yesterday")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid timestamp field 'yesterday' in Burn record"
+        );
+    }
+
+    #[test]
+    fn test_view_response_parsing() {
+        let status =
+            WithdrawStatus::from_view_response(&serde_json::json!({"__variant__": "CLAIMED"}))
+                .unwrap();
+        assert_eq!(status, WithdrawStatus::CLAIMED);
+        assert!(
+            WithdrawStatus::from_view_response(&serde_json::json!({"__variant__": "UNKNOWN"}))
+                .is_err()
+        );
+
+        let operator = OperatorInfo::from_view_response(&serde_json::json!({
+            "operator_id": "3",
+            "operator_addr": "0x1",
+            "btc_addr": "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            "is_active": true,
+        }))
+        .unwrap();
+        assert_eq!(operator.operator_id, 3);
+        assert!(operator.is_active);
+
+        let lp = LPInfo::from_view_response(&serde_json::json!({
+            "lp_id": "1",
+            "bitcoin_addr": "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            "lp_addr": "0x2",
+            "lp_fee": "1000",
+            "status": {"__variant__": "ACTIVE"},
+        }))
+        .unwrap();
+        assert_eq!(lp.lp_fee, 1000);
+        assert_eq!(lp.status, LPStatus::ACTIVE);
+
+        let config = BridgeConfig::from_view_response(&serde_json::json!({
+            "min_confirmations": "6",
+            "min_burn_amount": "10000",
+            "max_fee_rate": "100",
+            "btc_light_client": "0x2",
+            "paused": false,
+        }))
+        .unwrap();
+        assert_eq!(config.min_confirmations, 6);
+        assert_eq!(config.min_burn_amount, Sats(10000));
+
+        let err = BridgeConfig::from_view_response(&serde_json::json!({
+            "min_confirmations": "six",
+            "min_burn_amount": "10000",
+            "max_fee_rate": "100",
+            "btc_light_client": "0x2",
+            "paused": false,
+        }))
+        .unwrap_err();
+        assert!(err.downcast_ref::<FieldParseError>().is_some());
+    }
+
+    fn sample_arg_structs() -> (
+        Peg,
+        WithdrawByLPParams,
+        ClaimLPWithdrawParams,
+        RegisterLPParams,
+    ) {
+        let inclusion_proof = TxProof {
+            block_header: vec![0x99; 4],
+            tx_id: vec![0x29; 4],
+            tx_index: 3,
+            merkle_proof: vec![vec![0x01; 2], vec![0x02; 2]],
+            raw_tx: vec![0xab, 0xcd],
+        };
+        let btc_address: BtcAddress = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+            .parse()
+            .unwrap();
+
+        let peg = Peg {
+            to: "0x1".to_string(),
+            value: Sats(500000),
+            block_num: 800000,
+            inclusion_proof: inclusion_proof.clone(),
+            tx_out_ix: 1,
+            dest_script_hash: vec![0x75; 20],
+            script_type: ScriptType::P2WSH,
+        };
+        let withdraw = WithdrawByLPParams {
+            withdraw_id: 7,
+            btc_address: btc_address.clone(),
+            receiver_script_hash: vec![0x75; 20],
+            receive_min_amount: Sats(450000),
+            lp_id: 1,
+            amount: Sats(500000),
+            fee_rate: 10,
+        };
+        let claim = ClaimLPWithdrawParams {
+            withdraw_id: 7,
+            block_num: 800001,
+            tx_out_ix: 0,
+            amount_sats: Sats(460000),
+            inclusion_proof,
+        };
+        let register = RegisterLPParams {
+            lp_id: 1,
+            bitcoin_addr: btc_address,
+            lp_addr: "0x2".to_string(),
+            lp_fee: 1000,
+        };
+        (peg, withdraw, claim, register)
+    }
+
+    fn golden_args(function: &str) -> Vec<Vec<u8>> {
+        let golden: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/golden/bcs_args.json")).unwrap();
+        golden[function]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|arg| hex::decode(arg.as_str().unwrap()).unwrap())
+            .collect()
+    }
+
+    /// `golden_args` spelled out from the BCS spec (little-endian integers,
+    /// ULEB128 length prefixes, unprefixed 32-byte addresses), without the
+    /// `bcs` crate, so the golden file doesn't only echo the code under test
+    fn spec_args(function: &str) -> Vec<Vec<u8>> {
+        fn bytes(value: &[u8]) -> Vec<u8> {
+            // Every length here is below 128, so its ULEB128 prefix is one byte
+            assert!(value.len() < 128);
+            let mut encoded = vec![value.len() as u8];
+            encoded.extend_from_slice(value);
+            encoded
+        }
+        fn address(last_byte: u8) -> Vec<u8> {
+            let mut encoded = vec![0u8; 32];
+            encoded[31] = last_byte;
+            encoded
+        }
+        let u64_le = |value: u64| value.to_le_bytes().to_vec();
+        let btc_address = bytes(b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+        let merkle_proof = [vec![2], bytes(&[0x01; 2]), bytes(&[0x02; 2])].concat();
+
+        match function {
+            "mint" => vec![
+                address(0x01),
+                u64_le(500000),
+                u64_le(800000),
+                u64_le(3),
+                u64_le(1),
+                vec![ScriptType::P2WSH as u8],
+                bytes(&[0x99; 4]),
+                bytes(&[0x29; 4]),
+                merkle_proof,
+                bytes(&[0xab, 0xcd]),
+                bytes(&[0x75; 20]),
+            ],
+            "withdraw_by_lp" => vec![
+                u64_le(7),
+                btc_address,
+                bytes(&[0x75; 20]),
+                u64_le(450000),
+                u64_le(1),
+                u64_le(500000),
+                u64_le(10),
+            ],
+            "claim_lp_withdraw" => vec![
+                u64_le(7),
+                u64_le(800001),
+                u64_le(0),
+                u64_le(460000),
+                bytes(&[0x99; 4]),
+                bytes(&[0x29; 4]),
+                u64_le(3),
+                merkle_proof,
+                bytes(&[0xab, 0xcd]),
+            ],
+            "register_lp" => vec![u64_le(1), btc_address, address(0x02), u64_le(1000)],
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_bcs_args_golden_vectors_follow_spec() {
+        for function in ["mint", "withdraw_by_lp", "claim_lp_withdraw", "register_lp"] {
+            assert_eq!(golden_args(function), spec_args(function), "{}", function);
+        }
+    }
+
+    #[test]
+    fn test_bcs_args_golden_vectors() {
+        let (peg, withdraw, claim, register) = sample_arg_structs();
+
+        assert_eq!(peg.to_bcs_args().unwrap(), golden_args("mint"));
+        assert_eq!(
+            withdraw.to_bcs_args().unwrap(),
+            golden_args("withdraw_by_lp")
+        );
+        assert_eq!(
+            claim.to_bcs_args().unwrap(),
+            golden_args("claim_lp_withdraw")
+        );
+        assert_eq!(register.to_bcs_args().unwrap(), golden_args("register_lp"));
+    }
+
+    #[test]
+    fn test_bcs_args_round_trip() {
+        let (peg, withdraw, claim, register) = sample_arg_structs();
+
+        let decoded = Peg::from_bcs_args(&peg.to_bcs_args().unwrap()).unwrap();
+        assert_eq!(decoded.to, peg.to);
+        assert_eq!(decoded.script_type, peg.script_type);
+        assert_eq!(decoded.to_bcs_args().unwrap(), peg.to_bcs_args().unwrap());
+
+        let decoded = WithdrawByLPParams::from_bcs_args(&withdraw.to_bcs_args().unwrap()).unwrap();
+        assert_eq!(decoded.btc_address, withdraw.btc_address);
+        assert_eq!(
+            decoded.to_bcs_args().unwrap(),
+            withdraw.to_bcs_args().unwrap()
+        );
+
+        let decoded = ClaimLPWithdrawParams::from_bcs_args(&claim.to_bcs_args().unwrap()).unwrap();
+        assert_eq!(decoded.to_bcs_args().unwrap(), claim.to_bcs_args().unwrap());
+
+        let decoded = RegisterLPParams::from_bcs_args(&register.to_bcs_args().unwrap()).unwrap();
+        assert_eq!(decoded.lp_addr, register.lp_addr);
+        assert_eq!(
+            decoded.to_bcs_args().unwrap(),
+            register.to_bcs_args().unwrap()
+        );
+
+        assert!(Peg::from_bcs_args(&withdraw.to_bcs_args().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_script_type_codes() {
+        let all = [
+            ScriptType::P2SH,
+            ScriptType::P2WSH,
+            ScriptType::P2TR,
+            ScriptType::P2PKH,
+            ScriptType::P2WPKH,
+        ];
+        for script_type in all {
+            assert_eq!(
+                ScriptType::try_from(script_type.as_u8()).unwrap(),
+                script_type
+            );
+        }
+        assert_eq!(ScriptType::P2WSH.as_u8(), 1);
+        assert!(ScriptType::try_from(5).is_err());
+    }
+
+    #[test]
+    fn test_mint_event_btc_tx_id_byte_order() {
+        let mut event = MintEvent {
+            to_address: "0x1".to_string(),
+            amount: Sats(1),
+            btc_tx_id: format!("0x{}{}", "00".repeat(31), "ff"),
+            btc_block_num: 1,
+            timestamp: None,
+            version: None,
+            transaction_hash: None,
+        };
+        assert_eq!(
+            event.format_btc_tx_id(TxidByteOrder::Internal).unwrap(),
+            format!("{}ff", "00".repeat(31))
+        );
+        assert_eq!(
+            event.format_btc_tx_id(TxidByteOrder::Display).unwrap(),
+            format!("ff{}", "00".repeat(31))
+        );
+
+        event.btc_tx_id = "29".to_string();
+        assert!(event.format_btc_tx_id(TxidByteOrder::Display).is_err());
+    }
+
+    #[test]
+    fn test_reorg_detected_orphans() {
+        let orphaned_hash = [3u8; 32];
+        let reorg = ReorgDetected {
+            fork_height: 10,
+            depth: 1,
+            orphaned_blocks: vec![OrphanedBlock {
+                height: 11,
+                hash: txid_to_display_hex(&orphaned_hash),
+            }],
+            new_tip_height: 12,
+        };
+        assert!(reorg.orphans(&orphaned_hash));
+        assert!(!reorg.orphans(&[4u8; 32]));
+        assert_eq!(
+            reorg.to_string(),
+            "BTC reorg of 1 blocks above height 10, new tip 12"
+        );
+    }
+
+    #[test]
+    fn test_peg_serializes_script_type() {
+        let peg = Peg::new(
+            "0x1".to_string(),
+            Sats(500000),
+            0,
+            TxProof {
+                block_header: vec![],
+                tx_id: vec![],
+                tx_index: 0,
+                merkle_proof: vec![],
+                raw_tx: vec![],
+            },
+            0,
+            vec![],
+        )
+        .with_script_type(ScriptType::P2WPKH);
+
+        let args = peg.to_bcs_args().unwrap();
+        assert_eq!(args[5], vec![ScriptType::P2WPKH.as_u8()]);
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[test]
+    fn test_peg_validate() {
+        use bitcoin::hashes::Hash;
+
+        let script_hash = [5u8; 20];
+        let tx = bitcoin::Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![bitcoin::TxIn::default()],
+            output: vec![bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(50_000),
+                script_pubkey: bitcoin::ScriptBuf::new_p2wpkh(
+                    &bitcoin::WPubkeyHash::from_byte_array(script_hash),
+                ),
+            }],
+        };
+        let genesis = bitcoin::blockdata::constants::genesis_block(bitcoin::Network::Bitcoin);
+        let peg = Peg::new(
+            "0x1".to_string(),
+            Sats(50_000),
+            100,
+            TxProof::from_bitcoin(&genesis.header, &tx, &[], 0),
+            0,
+            script_hash.to_vec(),
+        )
+        .with_script_type(ScriptType::P2WPKH);
+        assert_eq!(peg.validate(), Ok(()));
+
+        let field = |peg: Peg| peg.validate().unwrap_err().field;
+
+        let mut bad = peg.clone();
+        bad.inclusion_proof.raw_tx.truncate(10);
+        assert_eq!(field(bad), "inclusion_proof.raw_tx");
+
+        let mut bad = peg.clone();
+        bad.inclusion_proof.tx_id = vec![0; 32];
+        assert_eq!(field(bad), "inclusion_proof.tx_id");
+
+        let mut bad = peg.clone();
+        bad.tx_out_ix = 1;
+        assert_eq!(field(bad), "tx_out_ix");
+
+        assert_eq!(
+            field(peg.clone().with_script_type(ScriptType::P2SH)),
+            "script_type"
+        );
+
+        let mut bad = peg.clone();
+        bad.dest_script_hash = vec![6; 20];
+        assert_eq!(field(bad), "dest_script_hash");
+
+        let mut bad = peg;
+        bad.value = Sats(50_001);
+        assert_eq!(field(bad), "value");
+    }
+
+    #[cfg(feature = "bitcoin")]
+    #[test]
+    fn test_tx_proof_from_bitcoin() {
+        let genesis = bitcoin::blockdata::constants::genesis_block(bitcoin::Network::Bitcoin);
+        let proof = TxProof::from_bitcoin(&genesis.header, &genesis.txdata[0], &[], 0);
+
+        assert_eq!(proof.block_header.len(), 80);
+        let mut display_txid = proof.tx_id.clone();
+        display_txid.reverse();
+        assert_eq!(
+            hex::encode(display_txid),
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b"
+        );
+        assert!(proof.merkle_proof.is_empty());
+    }
+}
@@ -4,15 +4,72 @@
 
 use std::str::FromStr;
 
+use crate::types::constants::SDK_USER_AGENT;
 use anyhow::{anyhow, Result};
 use aptos_sdk::types::account_address::AccountAddress;
 
-/// Convert hex string to AccountAddress
+/// User-Agent sent by the SDK, with the caller's `app_id` appended if given
+pub fn sdk_user_agent(app_id: Option<&str>) -> String {
+    match app_id {
+        Some(app_id) => format!("{} {}", SDK_USER_AGENT, app_id),
+        None => SDK_USER_AGENT.to_string(),
+    }
+}
+
+/// Strip an optional `0x`/`0X` prefix from a hex string
+pub fn strip_hex_prefix(hex_str: &str) -> &str {
+    hex_str
+        .strip_prefix("0x")
+        .or_else(|| hex_str.strip_prefix("0X"))
+        .unwrap_or(hex_str)
+}
+
+/// Decode a hex string, with or without `0x` prefix
+pub fn decode_hex(hex_str: &str) -> Result<Vec<u8>> {
+    hex::decode(strip_hex_prefix(hex_str))
+        .map_err(|e| anyhow!("Invalid hex string '{}': {}", hex_str, e))
+}
+
+/// Decode a hex string of exactly `expected_len` bytes, with or without `0x` prefix
+pub fn decode_hex_exact(hex_str: &str, expected_len: usize) -> Result<Vec<u8>> {
+    let bytes = decode_hex(hex_str)?;
+    if bytes.len() != expected_len {
+        return Err(anyhow!(
+            "Invalid hex string '{}': expected {} bytes, got {}",
+            hex_str,
+            expected_len,
+            bytes.len()
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Convert hex string (with or without `0x` prefix, short form allowed) to AccountAddress
 pub fn parse_account_address(addr_str: &str) -> Result<AccountAddress> {
-    AccountAddress::from_str(addr_str)
+    let digits = strip_hex_prefix(addr_str);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(anyhow!(
+            "Invalid address format '{}': expected hex digits",
+            addr_str
+        ));
+    }
+    AccountAddress::from_str(&format!("0x{}", digits))
         .map_err(|e| anyhow!("Invalid address format '{}': {}", addr_str, e))
 }
 
+/// Parse a script hash (20-byte hash or 32-byte script hash/output key) from hex
+pub fn parse_script_hash(hash_str: &str) -> Result<Vec<u8>> {
+    let bytes = decode_hex(hash_str)?;
+    if bytes.len() != 20 && bytes.len() != 32 {
+        return Err(anyhow!(
+            "Invalid script hash '{}': expected 20 or 32 bytes, got {}",
+            hash_str,
+            bytes.len()
+        ));
+    }
+    Ok(bytes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -27,4 +84,34 @@ mod tests {
         let result = parse_account_address(invalid_addr);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_sdk_user_agent() {
+        assert!(sdk_user_agent(None).starts_with("fiamma-aptos-client-sdk/"));
+        assert_eq!(
+            sdk_user_agent(Some("relayer/1.2")),
+            format!("{} relayer/1.2", SDK_USER_AGENT)
+        );
+    }
+
+    #[test]
+    fn test_hex_prefix_is_optional() {
+        assert_eq!(
+            parse_account_address("1").unwrap(),
+            parse_account_address("0x1").unwrap()
+        );
+        assert_eq!(
+            parse_account_address("0X0a").unwrap(),
+            parse_account_address("a").unwrap()
+        );
+        assert!(parse_account_address("0x").is_err());
+
+        assert_eq!(decode_hex("0xabcd").unwrap(), decode_hex("abcd").unwrap());
+        assert!(decode_hex("0xabc").is_err());
+        assert!(decode_hex_exact("abcd", 3).is_err());
+
+        assert_eq!(parse_script_hash(&"75".repeat(20)).unwrap(), vec![0x75; 20]);
+        assert!(parse_script_hash(&format!("0x{}", "75".repeat(32))).is_ok());
+        assert!(parse_script_hash("7575").is_err());
+    }
 }
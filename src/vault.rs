@@ -0,0 +1,627 @@
+//! HashiCorp Vault transit signer
+//!
+//! Signs bridge transactions with an `ed25519` key in Vault's transit secrets
+//! engine, so the private key never leaves Vault. Enabled by the `vault`
+//! feature.
+//!
+//! Renewable tokens are renewed once half their TTL has passed, before each
+//! signature; use [`VaultSigner::spawn_token_renewal`] to keep an idle signer's
+//! token alive as well. A signature request refused with 403 renews the token
+//! and is sent once more, in case Vault cut the lease short.
+
+use crate::signer::TransactionSigner;
+use crate::time::{sleep, Instant};
+use crate::types::constants::{VAULT_MIN_RENEWAL_INTERVAL_SECS, VAULT_REQUEST_TIMEOUT_SECS};
+use anyhow::{anyhow, Result};
+use aptos_sdk::crypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use aptos_sdk::crypto::traits::{signing_message, Signature};
+use aptos_sdk::types::account_address::AccountAddress;
+use aptos_sdk::types::transaction::authenticator::AuthenticationKey;
+use aptos_sdk::types::transaction::{RawTransaction, SignedTransaction};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use secrecy::{ExposeSecret, SecretString};
+use serde_json::{json, Value};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Default mount path of the transit engine
+pub const DEFAULT_TRANSIT_MOUNT: &str = "transit";
+
+/// Connection settings for a [`VaultSigner`]
+pub struct VaultConfig {
+    address: String,
+    token: SecretString,
+    key_name: String,
+    mount: String,
+    namespace: Option<String>,
+    account_address: Option<AccountAddress>,
+    http_client: Option<reqwest::Client>,
+}
+
+impl VaultConfig {
+    /// Sign with transit key `key_name` on the Vault server at `address`
+    pub fn new(address: &str, token: impl Into<SecretString>, key_name: &str) -> Self {
+        Self {
+            address: address.trim_end_matches('/').to_string(),
+            token: token.into(),
+            key_name: key_name.to_string(),
+            mount: DEFAULT_TRANSIT_MOUNT.to_string(),
+            namespace: None,
+            account_address: None,
+            http_client: None,
+        }
+    }
+
+    /// Set the transit engine mount path
+    pub fn with_mount(mut self, mount: &str) -> Self {
+        self.mount = mount.trim_matches('/').to_string();
+        self
+    }
+
+    /// Send requests in a Vault Enterprise namespace
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.to_string());
+        self
+    }
+
+    /// Sign for `address` instead of the one derived from the key, for accounts
+    /// whose authentication key was rotated to the Vault key
+    pub fn with_account_address(mut self, address: AccountAddress) -> Self {
+        self.account_address = Some(address);
+        self
+    }
+
+    /// Use a preconfigured HTTP client (proxies, TLS settings, timeouts)
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+}
+
+/// Token lifetime as last reported by Vault
+struct TokenLease {
+    /// `None` for tokens that don't expire or can't be renewed
+    ttl: Option<Duration>,
+    renewed_at: Instant,
+}
+
+/// Authenticated connection to a Vault server
+struct VaultClient {
+    address: String,
+    token: SecretString,
+    namespace: Option<String>,
+    http_client: reqwest::Client,
+}
+
+impl VaultClient {
+    /// Latest version of a transit key and its public key
+    async fn read_public_key(
+        &self,
+        mount: &str,
+        key_name: &str,
+    ) -> Result<(Ed25519PublicKey, u64)> {
+        let response = self
+            .request(
+                reqwest::Method::GET,
+                &format!("{}/keys/{}", mount, key_name),
+                None,
+            )
+            .await?;
+        let data = &response["data"];
+        if data["type"].as_str() != Some("ed25519") {
+            return Err(anyhow!(
+                "Vault transit key '{}' is {}, expected ed25519",
+                key_name,
+                data["type"]
+            ));
+        }
+        let version = data["latest_version"]
+            .as_u64()
+            .ok_or_else(|| anyhow!("Vault key response has no latest_version"))?;
+        let public_key = data["keys"][version.to_string()]["public_key"]
+            .as_str()
+            .ok_or_else(|| {
+                anyhow!(
+                    "Vault key response has no public key for version {}",
+                    version
+                )
+            })?;
+        let public_key = BASE64
+            .decode(public_key)
+            .map_err(|e| anyhow!("Invalid Vault public key encoding: {}", e))?;
+        let public_key = Ed25519PublicKey::try_from(public_key.as_slice())
+            .map_err(|e| anyhow!("Invalid Vault public key: {}", e))?;
+        Ok((public_key, version))
+    }
+
+    async fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value> {
+        let url = format!("{}/v1/{}", self.address, path);
+        let mut request = self
+            .http_client
+            .request(method, &url)
+            .header("X-Vault-Token", self.token.expose_secret());
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to send Vault request to {}: {}", url, e))?;
+        let status = response.status();
+        // Error bodies aren't always JSON, e.g. from a proxy in front of Vault
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let errors = match serde_json::from_str::<Value>(&body) {
+                Ok(body) => body["errors"].to_string(),
+                Err(_) => body,
+            };
+            return Err(VaultRequestError {
+                url,
+                status,
+                errors,
+            }
+            .into());
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse Vault response from {}: {}", url, e))
+    }
+}
+
+/// Vault answered a request with an error status
+#[derive(Debug)]
+struct VaultRequestError {
+    url: String,
+    status: reqwest::StatusCode,
+    errors: String,
+}
+
+impl std::fmt::Display for VaultRequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Vault request to {} failed with {}: {}",
+            self.url, self.status, self.errors
+        )
+    }
+}
+
+impl std::error::Error for VaultRequestError {}
+
+/// [`TransactionSigner`] backed by a Vault transit key
+pub struct VaultSigner {
+    client: VaultClient,
+    key_name: String,
+    mount: String,
+    public_key: Ed25519PublicKey,
+    key_version: u64,
+    account_address: AccountAddress,
+    lease: Mutex<TokenLease>,
+}
+
+impl VaultSigner {
+    /// Look up the token and the transit key's latest public key
+    pub async fn connect(config: VaultConfig) -> Result<Self> {
+        let http_client = match config.http_client {
+            Some(http_client) => http_client,
+            None => reqwest::Client::builder()
+                .timeout(Duration::from_secs(VAULT_REQUEST_TIMEOUT_SECS))
+                .build()
+                .map_err(|e| anyhow!("Failed to build Vault HTTP client: {}", e))?,
+        };
+        let client = VaultClient {
+            address: config.address,
+            token: config.token,
+            namespace: config.namespace,
+            http_client,
+        };
+
+        let (public_key, key_version) = client
+            .read_public_key(&config.mount, &config.key_name)
+            .await?;
+        let token = client
+            .request(reqwest::Method::GET, "auth/token/lookup-self", None)
+            .await?;
+        let ttl = token["data"]["ttl"].as_u64().unwrap_or(0);
+        let renewable = token["data"]["renewable"].as_bool().unwrap_or(false);
+
+        Ok(Self {
+            client,
+            key_name: config.key_name,
+            mount: config.mount,
+            account_address: config
+                .account_address
+                .unwrap_or_else(|| AuthenticationKey::ed25519(&public_key).account_address()),
+            public_key,
+            key_version,
+            lease: Mutex::new(TokenLease {
+                ttl: (renewable && ttl > 0).then(|| Duration::from_secs(ttl)),
+                renewed_at: Instant::now(),
+            }),
+        })
+    }
+
+    /// Public key of the transit key version used for signing
+    pub fn public_key(&self) -> &Ed25519PublicKey {
+        &self.public_key
+    }
+
+    /// Sign `message` with the transit key
+    ///
+    /// The signature is checked against [`VaultSigner::public_key`] before it
+    /// is returned, so a rotated key is caught here rather than on chain. A
+    /// request refused with 403 is retried once after renewing the token.
+    pub async fn sign(&self, message: &[u8]) -> Result<Ed25519Signature> {
+        self.renew_token_if_due().await;
+        let response = match self.request_signature(message).await {
+            Err(e)
+                if e.downcast_ref::<VaultRequestError>()
+                    .is_some_and(|e| e.status == reqwest::StatusCode::FORBIDDEN) =>
+            {
+                self.renew_token().await?;
+                self.request_signature(message).await?
+            }
+            response => response?,
+        };
+        let signature = response["data"]["signature"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Vault sign response has no signature"))?;
+        let signature = parse_vault_signature(signature)?;
+        signature
+            .verify_arbitrary_msg(message, &self.public_key)
+            .map_err(|e| anyhow!("Vault signature does not match the transit key: {}", e))?;
+        Ok(signature)
+    }
+
+    async fn request_signature(&self, message: &[u8]) -> Result<Value> {
+        self.client
+            .request(
+                reqwest::Method::POST,
+                &format!("{}/sign/{}", self.mount, self.key_name),
+                Some(json!({
+                    "input": BASE64.encode(message),
+                    "key_version": self.key_version,
+                })),
+            )
+            .await
+    }
+
+    /// Check `signature` over `message` with Vault's verify endpoint
+    pub async fn verify(&self, message: &[u8], signature: &Ed25519Signature) -> Result<bool> {
+        self.renew_token_if_due().await;
+        let response = self
+            .client
+            .request(
+                reqwest::Method::POST,
+                &format!("{}/verify/{}", self.mount, self.key_name),
+                Some(json!({
+                    "input": BASE64.encode(message),
+                    "signature": format!(
+                        "vault:v{}:{}",
+                        self.key_version,
+                        BASE64.encode(signature.to_bytes())
+                    ),
+                })),
+            )
+            .await?;
+        response["data"]["valid"]
+            .as_bool()
+            .ok_or_else(|| anyhow!("Vault verify response has no result"))
+    }
+
+    /// Renew the token now
+    pub async fn renew_token(&self) -> Result<()> {
+        let mut lease = self.lease.lock().await;
+        self.renew_locked(&mut lease).await
+    }
+
+    /// Renew the token in the background while the signer is alive
+    pub fn spawn_token_renewal(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let signer: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                let interval = match signer.upgrade() {
+                    Some(signer) => match signer.lease.lock().await.ttl {
+                        Some(ttl) => {
+                            (ttl / 2).max(Duration::from_secs(VAULT_MIN_RENEWAL_INTERVAL_SECS))
+                        }
+                        None => return,
+                    },
+                    None => return,
+                };
+                sleep(interval).await;
+                match signer.upgrade() {
+                    Some(signer) => signer.renew_token_if_due().await,
+                    None => return,
+                }
+            }
+        })
+    }
+
+    /// Renew once half the TTL has passed; failures are logged, since the
+    /// token may still be valid
+    async fn renew_token_if_due(&self) {
+        let mut lease = self.lease.lock().await;
+        let due = lease
+            .ttl
+            .is_some_and(|ttl| lease.renewed_at.elapsed() >= ttl / 2);
+        if due {
+            if let Err(_e) = self.renew_locked(&mut lease).await {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %_e, "Failed to renew Vault token");
+            }
+        }
+    }
+
+    async fn renew_locked(&self, lease: &mut TokenLease) -> Result<()> {
+        let response = self
+            .client
+            .request(
+                reqwest::Method::POST,
+                "auth/token/renew-self",
+                Some(json!({})),
+            )
+            .await?;
+        let lease_duration = response["auth"]["lease_duration"].as_u64().unwrap_or(0);
+        lease.ttl = (lease_duration > 0).then(|| Duration::from_secs(lease_duration));
+        lease.renewed_at = Instant::now();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(lease_duration, "Renewed Vault token");
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for VaultSigner {
+    fn address(&self) -> AccountAddress {
+        self.account_address
+    }
+
+    async fn sign_transaction(&self, raw_transaction: RawTransaction) -> Result<SignedTransaction> {
+        let message = signing_message(&raw_transaction)
+            .map_err(|e| anyhow!("Failed to build transaction signing message: {}", e))?;
+        let signature = self.sign(&message).await?;
+        Ok(SignedTransaction::new(
+            raw_transaction,
+            self.public_key.clone(),
+            signature,
+        ))
+    }
+}
+
+/// Decode a `vault:v<version>:<base64>` signature
+fn parse_vault_signature(signature: &str) -> Result<Ed25519Signature> {
+    let encoded = signature
+        .strip_prefix("vault:v")
+        .and_then(|rest| rest.split_once(':'))
+        .map(|(_, encoded)| encoded)
+        .ok_or_else(|| anyhow!("Invalid Vault signature format '{}'", signature))?;
+    let bytes = BASE64
+        .decode(encoded)
+        .map_err(|e| anyhow!("Invalid Vault signature encoding: {}", e))?;
+    Ed25519Signature::try_from(bytes.as_slice())
+        .map_err(|e| anyhow!("Invalid Ed25519 signature from Vault: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vault_signature() {
+        let bytes = [7u8; 64];
+        let signature =
+            parse_vault_signature(&format!("vault:v3:{}", BASE64.encode(bytes))).unwrap();
+        assert_eq!(signature.to_bytes(), bytes);
+
+        assert!(parse_vault_signature(&BASE64.encode(bytes)).is_err());
+        assert!(parse_vault_signature(&format!("vault:v1:{}", BASE64.encode([7u8; 10]))).is_err());
+    }
+
+    #[cfg(feature = "test-utils")]
+    mod sign {
+        use super::*;
+        use aptos_sdk::crypto::ed25519::Ed25519PrivateKey;
+        use aptos_sdk::crypto::traits::SigningKey;
+        use aptos_sdk::types::chain_id::ChainId;
+        use aptos_sdk::types::transaction::{Script, TransactionPayload};
+        use wiremock::matchers::{body_partial_json, header, method, path};
+        use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+        const TOKEN: &str = "s.test-token";
+
+        fn transit_key(seed: u8) -> Ed25519PrivateKey {
+            Ed25519PrivateKey::try_from([seed; 32].as_slice()).unwrap()
+        }
+
+        /// Transit sign endpoint signing the request input with its key
+        struct TransitSign(Ed25519PrivateKey);
+
+        impl wiremock::Respond for TransitSign {
+            fn respond(&self, request: &Request) -> ResponseTemplate {
+                let body: Value = serde_json::from_slice(&request.body).unwrap();
+                let input = BASE64.decode(body["input"].as_str().unwrap()).unwrap();
+                let signature = self.0.sign_arbitrary_message(&input);
+                ResponseTemplate::new(200).set_body_json(json!({
+                    "data": {
+                        "signature": format!("vault:v2:{}", BASE64.encode(signature.to_bytes())),
+                    },
+                }))
+            }
+        }
+
+        /// Vault mock holding transit key version 2 from `seed`, whose token
+        /// lookup reports `ttl` seconds
+        async fn mock_vault(seed: u8, ttl: u64) -> MockServer {
+            let server = MockServer::start().await;
+            let public_key = Ed25519PublicKey::from(&transit_key(seed));
+            Mock::given(method("GET"))
+                .and(path("/v1/transit/keys/bridge"))
+                .and(header("X-Vault-Token", TOKEN))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "data": {
+                        "type": "ed25519",
+                        "latest_version": 2,
+                        "keys": {
+                            "1": { "public_key": BASE64.encode([0u8; 32]) },
+                            "2": { "public_key": BASE64.encode(public_key.to_bytes()) },
+                        },
+                    },
+                })))
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/v1/auth/token/lookup-self"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "data": { "ttl": ttl, "renewable": ttl > 0 },
+                })))
+                .mount(&server)
+                .await;
+            server
+        }
+
+        async fn mount_sign(server: &MockServer, key: Ed25519PrivateKey) {
+            Mock::given(method("POST"))
+                .and(path("/v1/transit/sign/bridge"))
+                .and(header("X-Vault-Token", TOKEN))
+                .and(body_partial_json(json!({ "key_version": 2 })))
+                .respond_with(TransitSign(key))
+                .mount(server)
+                .await;
+        }
+
+        async fn mount_renew(server: &MockServer, status: u16, expected: u64) {
+            Mock::given(method("POST"))
+                .and(path("/v1/auth/token/renew-self"))
+                .respond_with(ResponseTemplate::new(status).set_body_json(json!({
+                    "auth": { "lease_duration": 3600 },
+                    "errors": [],
+                })))
+                .expect(expected)
+                .mount(server)
+                .await;
+        }
+
+        fn forbidden() -> ResponseTemplate {
+            ResponseTemplate::new(403).set_body_json(json!({ "errors": ["permission denied"] }))
+        }
+
+        async fn connect(server: &MockServer) -> VaultSigner {
+            VaultSigner::connect(VaultConfig::new(&server.uri(), TOKEN, "bridge"))
+                .await
+                .unwrap()
+        }
+
+        fn raw_transaction(sender: AccountAddress) -> RawTransaction {
+            RawTransaction::new(
+                sender,
+                0,
+                TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+                1_000,
+                100,
+                u64::MAX,
+                ChainId::test(),
+            )
+        }
+
+        #[tokio::test]
+        async fn test_sign_transaction_with_transit_key() {
+            let server = mock_vault(7, 0).await;
+            mount_sign(&server, transit_key(7)).await;
+            mount_renew(&server, 200, 0).await;
+
+            let signer = connect(&server).await;
+            let public_key = Ed25519PublicKey::from(&transit_key(7));
+            assert_eq!(signer.public_key(), &public_key);
+            assert_eq!(
+                signer.address(),
+                AuthenticationKey::ed25519(&public_key).account_address()
+            );
+
+            let signed = signer
+                .sign_transaction(raw_transaction(signer.address()))
+                .await
+                .unwrap();
+            signed.verify_signature().unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_sign_rejects_signature_from_other_key() {
+            let server = mock_vault(7, 0).await;
+            mount_sign(&server, transit_key(8)).await;
+
+            let signer = connect(&server).await;
+            let err = signer.sign(b"message").await.unwrap_err();
+            assert!(err.to_string().contains("does not match"));
+        }
+
+        #[tokio::test]
+        async fn test_sign_renews_token_after_forbidden() {
+            let server = mock_vault(7, 0).await;
+            // As a proxy in front of Vault answers, without a JSON body
+            Mock::given(method("POST"))
+                .and(path("/v1/transit/sign/bridge"))
+                .respond_with(ResponseTemplate::new(403).set_body_string("Forbidden"))
+                .up_to_n_times(1)
+                .with_priority(1)
+                .mount(&server)
+                .await;
+            mount_sign(&server, transit_key(7)).await;
+            mount_renew(&server, 200, 1).await;
+
+            let signer = connect(&server).await;
+            let signature = signer.sign(b"message").await.unwrap();
+            signature
+                .verify_arbitrary_msg(b"message", signer.public_key())
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_sign_fails_when_renewal_is_forbidden() {
+            let server = mock_vault(7, 0).await;
+            Mock::given(method("POST"))
+                .and(path("/v1/transit/sign/bridge"))
+                .respond_with(forbidden())
+                .expect(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("POST"))
+                .and(path("/v1/auth/token/renew-self"))
+                .respond_with(forbidden())
+                .expect(1)
+                .mount(&server)
+                .await;
+
+            let signer = connect(&server).await;
+            let err = signer.sign(b"message").await.unwrap_err();
+            assert!(err.to_string().contains("403"));
+            assert!(err.to_string().contains("permission denied"));
+        }
+
+        #[tokio::test]
+        async fn test_sign_renews_token_past_half_ttl() {
+            let server = mock_vault(7, 1).await;
+            mount_sign(&server, transit_key(7)).await;
+            mount_renew(&server, 200, 1).await;
+
+            let signer = connect(&server).await;
+            sleep(Duration::from_millis(600)).await;
+            signer.sign(b"message").await.unwrap();
+            // Renewed for an hour, so not again
+            signer.sign(b"message").await.unwrap();
+        }
+    }
+}
@@ -0,0 +1,743 @@
+//! LP withdraw lifecycle tracking
+//!
+//! [`WithdrawTracker`] follows each LP withdraw from its `WithdrawByLP` event
+//! to its end: the LP reports the BTC payout and its claim transaction, and
+//! [`WithdrawTracker::refresh`] reads the `get_withdraw_status` view of every
+//! open withdraw to see it claimed or expired. Plug the tracker into an
+//! `EventMonitor` as its event handler; every phase change is passed to the
+//! registered [`WithdrawObserver`]s.
+//!
+//! A [`WithdrawSla`] sets how long a withdraw may wait for its BTC payout and
+//! its claim; [`WithdrawTracker::check_sla`] raises a [`WithdrawAlert`] once
+//! per breach, and expiries are alerted as [`WithdrawTracker::refresh`] sees
+//! them. With the `webhook` feature, [`WebhookAlertSink`] posts alerts as JSON.
+
+use crate::api::BridgeApi;
+use crate::events::EventHandler;
+use crate::time::unix_timestamp_secs;
+use crate::types::{BurnEvent, MintEvent, WithdrawByLPEvent, WithdrawStatus};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Phase of an LP withdraw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WithdrawPhase {
+    /// Requested on chain, not paid yet
+    Requested,
+    /// The LP sent the BTC payout
+    BtcSent,
+    /// The LP claimed the withdraw on chain
+    Claimed,
+    /// The withdraw expired unclaimed
+    Expired,
+}
+
+impl WithdrawPhase {
+    /// Whether the withdraw can no longer change
+    pub fn is_final(self) -> bool {
+        matches!(self, WithdrawPhase::Claimed | WithdrawPhase::Expired)
+    }
+}
+
+/// Withdraw followed by a [`WithdrawTracker`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedWithdraw {
+    /// Event that requested the withdraw
+    pub request: WithdrawByLPEvent,
+    /// Current phase
+    pub phase: WithdrawPhase,
+    /// BTC payout transaction, once reported
+    pub btc_txid: Option<String>,
+    /// `claim_lp_withdraw` transaction, once reported
+    pub claim_tx_hash: Option<String>,
+    /// Request time from the event, or when the tracker first saw it,
+    /// seconds since the Unix epoch
+    pub requested_at: u64,
+    /// Alerts already raised
+    pub alerts: Vec<WithdrawAlertKind>,
+    /// Time of the last phase change, seconds since the Unix epoch
+    pub updated_at: u64,
+}
+
+/// Phase change of one withdraw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WithdrawTransition {
+    /// Withdraw ID
+    pub withdraw_id: u64,
+    /// Previous phase, `None` when the withdraw was first seen
+    pub from: Option<WithdrawPhase>,
+    /// New phase
+    pub to: WithdrawPhase,
+}
+
+/// Reason a withdraw needs attention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WithdrawAlertKind {
+    /// No BTC payout reported within [`WithdrawSla::btc_sent_within`]
+    BtcNotSent,
+    /// No claim observed within [`WithdrawSla::claimed_within`]
+    ClaimOverdue,
+    /// The withdraw expired unclaimed
+    Expired,
+}
+
+/// Withdraw that breached its SLA or expired
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithdrawAlert {
+    /// Reason for the alert
+    pub kind: WithdrawAlertKind,
+    /// Seconds since the withdraw was requested
+    pub age_secs: u64,
+    /// The withdraw, as of the alert
+    pub withdraw: TrackedWithdraw,
+}
+
+/// Time limits for LP withdraws, counted from the request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WithdrawSla {
+    /// Limit for the LP to report the BTC payout
+    pub btc_sent_within: Option<Duration>,
+    /// Limit for the claim to be observed on chain
+    pub claimed_within: Option<Duration>,
+}
+
+impl WithdrawSla {
+    /// No limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Alert when the BTC payout isn't reported within `limit`
+    pub fn with_btc_sent_within(mut self, limit: Duration) -> Self {
+        self.btc_sent_within = Some(limit);
+        self
+    }
+
+    /// Alert when the claim isn't observed within `limit`, e.g. 24 hours
+    pub fn with_claimed_within(mut self, limit: Duration) -> Self {
+        self.claimed_within = Some(limit);
+        self
+    }
+
+    /// Breaches of `withdraw` at `now` not alerted yet
+    fn breaches(&self, withdraw: &TrackedWithdraw, now: u64) -> Vec<WithdrawAlertKind> {
+        let age = now.saturating_sub(withdraw.requested_at);
+        let overdue = |limit: Option<Duration>| limit.is_some_and(|limit| age > limit.as_secs());
+        let mut breaches = Vec::new();
+        if withdraw.phase == WithdrawPhase::Requested && overdue(self.btc_sent_within) {
+            breaches.push(WithdrawAlertKind::BtcNotSent);
+        }
+        if !withdraw.phase.is_final() && overdue(self.claimed_within) {
+            breaches.push(WithdrawAlertKind::ClaimOverdue);
+        }
+        // Retries an expiry alert that failed in `refresh`
+        if withdraw.phase == WithdrawPhase::Expired {
+            breaches.push(WithdrawAlertKind::Expired);
+        }
+        breaches.retain(|kind| !withdraw.alerts.contains(kind));
+        breaches
+    }
+}
+
+/// Receives withdraw phase changes and alerts
+#[async_trait]
+pub trait WithdrawObserver: Send + Sync {
+    /// Called after `withdraw` moved as described by `transition`
+    async fn on_transition(
+        &self,
+        withdraw: &TrackedWithdraw,
+        transition: &WithdrawTransition,
+    ) -> Result<()>;
+
+    /// Called once per SLA breach or expiry
+    async fn on_alert(&self, _alert: &WithdrawAlert) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Observer posting alerts as JSON to a webhook
+#[cfg(feature = "webhook")]
+pub struct WebhookAlertSink {
+    url: String,
+    http_client: reqwest::Client,
+    bearer_token: Option<String>,
+}
+
+#[cfg(feature = "webhook")]
+impl WebhookAlertSink {
+    /// Post alerts to `url`
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            http_client: reqwest::Client::new(),
+            bearer_token: None,
+        }
+    }
+
+    /// Send `Authorization: Bearer <token>` with every alert
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.bearer_token = Some(token.to_string());
+        self
+    }
+
+    /// Use a preconfigured HTTP client (proxies, TLS settings, timeouts)
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+}
+
+#[cfg(feature = "webhook")]
+#[async_trait]
+impl WithdrawObserver for WebhookAlertSink {
+    async fn on_transition(
+        &self,
+        _withdraw: &TrackedWithdraw,
+        _transition: &WithdrawTransition,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_alert(&self, alert: &WithdrawAlert) -> Result<()> {
+        let request = self.http_client.post(&self.url).json(alert);
+        let request = match &self.bearer_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach alert webhook {}: {}", self.url, e))?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Alert webhook {} returned {}",
+                self.url,
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Tracks LP withdraws through their lifecycle
+pub struct WithdrawTracker {
+    /// Client reading withdraw statuses
+    bridge_client: Arc<dyn BridgeApi>,
+    /// Tracked withdraws by ID
+    withdraws: Mutex<BTreeMap<u64, TrackedWithdraw>>,
+    /// Notified of every transition, in registration order
+    observers: Vec<Arc<dyn WithdrawObserver>>,
+    /// Only track withdraws served by this LP
+    lp_id: Option<u64>,
+    /// Limits checked by `check_sla`
+    sla: WithdrawSla,
+}
+
+impl WithdrawTracker {
+    /// Create a tracker reading withdraw statuses through `bridge_client`
+    pub fn new(bridge_client: Arc<dyn BridgeApi>) -> Self {
+        Self {
+            bridge_client,
+            withdraws: Mutex::new(BTreeMap::new()),
+            observers: Vec::new(),
+            lp_id: None,
+            sla: WithdrawSla::default(),
+        }
+    }
+
+    /// Notify `observer` of every transition
+    pub fn with_observer(mut self, observer: Arc<dyn WithdrawObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// Ignore withdraws served by other LPs
+    pub fn with_lp_id(mut self, lp_id: u64) -> Self {
+        self.lp_id = Some(lp_id);
+        self
+    }
+
+    /// Alert on withdraws exceeding `sla`
+    pub fn with_sla(mut self, sla: WithdrawSla) -> Self {
+        self.sla = sla;
+        self
+    }
+
+    /// Start tracking a requested withdraw; already tracked ones are ignored
+    pub async fn track(&self, request: WithdrawByLPEvent) -> Result<()> {
+        if self.lp_id.is_some_and(|lp_id| lp_id != request.lp_id) {
+            return Ok(());
+        }
+        let withdraw_id = request.withdraw_id;
+        let tracked = {
+            let mut withdraws = self.lock();
+            if withdraws.contains_key(&withdraw_id) {
+                return Ok(());
+            }
+            let now = unix_timestamp_secs();
+            let tracked = TrackedWithdraw {
+                requested_at: request.timestamp.unwrap_or(now),
+                request,
+                phase: WithdrawPhase::Requested,
+                btc_txid: None,
+                claim_tx_hash: None,
+                alerts: Vec::new(),
+                updated_at: now,
+            };
+            withdraws.insert(withdraw_id, tracked.clone());
+            tracked
+        };
+        self.notify(
+            &tracked,
+            &WithdrawTransition {
+                withdraw_id,
+                from: None,
+                to: WithdrawPhase::Requested,
+            },
+        )
+        .await
+    }
+
+    /// Record the BTC payout of a requested withdraw
+    pub async fn mark_btc_sent(&self, withdraw_id: u64, btc_txid: &str) -> Result<()> {
+        let change = self.update(withdraw_id, |withdraw| {
+            withdraw.btc_txid = Some(btc_txid.to_string());
+            (withdraw.phase == WithdrawPhase::Requested).then_some(WithdrawPhase::BtcSent)
+        })?;
+        self.notify_change(change).await
+    }
+
+    /// Record the `claim_lp_withdraw` transaction of a withdraw
+    ///
+    /// The withdraw becomes claimed once [`WithdrawTracker::refresh`] sees
+    /// the claim on chain.
+    pub async fn record_claim(&self, withdraw_id: u64, tx_hash: &str) -> Result<()> {
+        self.update(withdraw_id, |withdraw| {
+            withdraw.claim_tx_hash = Some(tx_hash.to_string());
+            None
+        })?;
+        Ok(())
+    }
+
+    /// Read the on-chain status of every open withdraw, returning the transitions
+    ///
+    /// Expired withdraws are alerted. Withdraws whose status can't be read are
+    /// left unchanged and retried on the next refresh; those and observer
+    /// failures don't stop the refresh, and the first such error is returned
+    /// after the others have been updated.
+    pub async fn refresh(&self) -> Result<Vec<WithdrawTransition>> {
+        let open: Vec<u64> = self
+            .lock()
+            .values()
+            .filter(|withdraw| !withdraw.phase.is_final())
+            .map(|withdraw| withdraw.request.withdraw_id)
+            .collect();
+
+        let mut transitions = Vec::new();
+        let mut first_error = None;
+        for withdraw_id in open {
+            let phase = match self.bridge_client.get_withdraw_status(withdraw_id).await {
+                Ok(WithdrawStatus::PENDING) => continue,
+                Ok(WithdrawStatus::CLAIMED) => WithdrawPhase::Claimed,
+                Ok(WithdrawStatus::EXPIRED) => WithdrawPhase::Expired,
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                    continue;
+                }
+            };
+            let change = self.update(withdraw_id, |_| Some(phase))?;
+            let Some((withdraw, transition)) = change else {
+                continue;
+            };
+            transitions.push(transition);
+            if let Err(e) = self.notify(&withdraw, &transition).await {
+                first_error.get_or_insert(e);
+            }
+            if phase == WithdrawPhase::Expired {
+                let alert = WithdrawAlert {
+                    kind: WithdrawAlertKind::Expired,
+                    age_secs: unix_timestamp_secs().saturating_sub(withdraw.requested_at),
+                    withdraw,
+                };
+                match self.raise(&alert).await {
+                    Ok(()) => self.mark_alerted(&alert),
+                    Err(e) => {
+                        first_error.get_or_insert(e);
+                    }
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(transitions),
+        }
+    }
+
+    /// Alert every open withdraw exceeding the SLA, once per breach
+    ///
+    /// Expiries whose alert failed in [`WithdrawTracker::refresh`] are alerted
+    /// again. A breach counts as alerted once every observer accepts it.
+    pub async fn check_sla(&self) -> Result<Vec<WithdrawAlert>> {
+        let now = unix_timestamp_secs();
+        let alerts: Vec<WithdrawAlert> = self
+            .lock()
+            .values()
+            .flat_map(|withdraw| {
+                self.sla
+                    .breaches(withdraw, now)
+                    .into_iter()
+                    .map(move |kind| WithdrawAlert {
+                        kind,
+                        age_secs: now.saturating_sub(withdraw.requested_at),
+                        withdraw: withdraw.clone(),
+                    })
+            })
+            .collect();
+        for alert in &alerts {
+            self.raise(alert).await?;
+            self.mark_alerted(alert);
+        }
+        Ok(alerts)
+    }
+
+    /// Refresh statuses and check the SLA every `interval`, logging failures
+    pub async fn run(&self, interval: Duration) -> Result<()> {
+        loop {
+            if let Err(_error) = self.refresh().await {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %_error, "LP withdraw status refresh failed");
+            }
+            if let Err(_error) = self.check_sla().await {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %_error, "LP withdraw SLA check failed");
+            }
+            crate::time::sleep(interval).await;
+        }
+    }
+
+    /// Current state of a withdraw
+    pub fn get(&self, withdraw_id: u64) -> Option<TrackedWithdraw> {
+        self.lock().get(&withdraw_id).cloned()
+    }
+
+    /// Every tracked withdraw, by ID
+    pub fn withdraws(&self) -> Vec<TrackedWithdraw> {
+        self.lock().values().cloned().collect()
+    }
+
+    /// Apply `f` to a tracked withdraw and move it to the phase it returns
+    fn update(
+        &self,
+        withdraw_id: u64,
+        f: impl FnOnce(&mut TrackedWithdraw) -> Option<WithdrawPhase>,
+    ) -> Result<Option<(TrackedWithdraw, WithdrawTransition)>> {
+        let mut withdraws = self.lock();
+        let withdraw = withdraws
+            .get_mut(&withdraw_id)
+            .ok_or_else(|| anyhow!("Withdraw {} is not tracked", withdraw_id))?;
+        let from = withdraw.phase;
+        match f(withdraw) {
+            Some(to) if to != from => {
+                withdraw.phase = to;
+                withdraw.updated_at = unix_timestamp_secs();
+                Ok(Some((
+                    withdraw.clone(),
+                    WithdrawTransition {
+                        withdraw_id,
+                        from: Some(from),
+                        to,
+                    },
+                )))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn notify_change(
+        &self,
+        change: Option<(TrackedWithdraw, WithdrawTransition)>,
+    ) -> Result<()> {
+        match change {
+            Some((withdraw, transition)) => self.notify(&withdraw, &transition).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn notify(
+        &self,
+        withdraw: &TrackedWithdraw,
+        transition: &WithdrawTransition,
+    ) -> Result<()> {
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            withdraw_id = transition.withdraw_id,
+            from = ?transition.from,
+            to = ?transition.to,
+            "LP withdraw changed phase"
+        );
+        for observer in &self.observers {
+            observer.on_transition(withdraw, transition).await?;
+        }
+        Ok(())
+    }
+
+    async fn raise(&self, alert: &WithdrawAlert) -> Result<()> {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            withdraw_id = alert.withdraw.request.withdraw_id,
+            kind = ?alert.kind,
+            age_secs = alert.age_secs,
+            "LP withdraw needs attention"
+        );
+        for observer in &self.observers {
+            observer.on_alert(alert).await?;
+        }
+        Ok(())
+    }
+
+    /// Record that `alert` was raised so it isn't raised again
+    fn mark_alerted(&self, alert: &WithdrawAlert) {
+        if let Some(withdraw) = self.lock().get_mut(&alert.withdraw.request.withdraw_id) {
+            if !withdraw.alerts.contains(&alert.kind) {
+                withdraw.alerts.push(alert.kind);
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<u64, TrackedWithdraw>> {
+        self.withdraws.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl EventHandler for WithdrawTracker {
+    async fn handle_mint(&self, _event: MintEvent) -> Result<()> {
+        Ok(())
+    }
+
+    async fn handle_burn(&self, _event: BurnEvent) -> Result<()> {
+        Ok(())
+    }
+
+    async fn handle_withdraw_by_lp(&self, event: WithdrawByLPEvent) -> Result<()> {
+        self.track(event).await
+    }
+}
+
+#[cfg(all(test, feature = "test-utils"))]
+mod tests {
+    use super::*;
+    use crate::mock::MockBridgeClient;
+    use crate::types::Sats;
+
+    /// Records every transition and alert
+    #[derive(Default)]
+    struct Recorder(
+        Mutex<Vec<WithdrawTransition>>,
+        Mutex<Vec<WithdrawAlertKind>>,
+    );
+
+    #[async_trait]
+    impl WithdrawObserver for Recorder {
+        async fn on_transition(
+            &self,
+            _withdraw: &TrackedWithdraw,
+            transition: &WithdrawTransition,
+        ) -> Result<()> {
+            self.0.lock().unwrap().push(*transition);
+            Ok(())
+        }
+
+        async fn on_alert(&self, alert: &WithdrawAlert) -> Result<()> {
+            self.1.lock().unwrap().push(alert.kind);
+            Ok(())
+        }
+    }
+
+    fn request(withdraw_id: u64, lp_id: u64) -> WithdrawByLPEvent {
+        WithdrawByLPEvent {
+            from_address: "0x1".to_string(),
+            withdraw_id,
+            btc_address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+            fee_rate: 10,
+            amount: Sats(500000),
+            lp_id,
+            receive_min_amount: Sats(450000),
+            timestamp: None,
+            version: Some(42),
+            transaction_hash: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_lifecycle() {
+        let mock = Arc::new(MockBridgeClient::new());
+        let recorder = Arc::new(Recorder::default());
+        let tracker = WithdrawTracker::new(mock.clone())
+            .with_lp_id(1)
+            .with_observer(recorder.clone());
+
+        tracker.track(request(7, 1)).await.unwrap();
+        tracker.track(request(8, 1)).await.unwrap();
+        tracker.track(request(9, 2)).await.unwrap();
+        assert_eq!(tracker.withdraws().len(), 2);
+
+        tracker
+            .mark_btc_sent(7, "ab".repeat(32).as_str())
+            .await
+            .unwrap();
+        tracker.record_claim(7, "0x99").await.unwrap();
+        assert!(tracker.mark_btc_sent(9, "cd").await.is_err());
+
+        mock.set_withdraw_status(7, WithdrawStatus::CLAIMED);
+        mock.set_withdraw_status(8, WithdrawStatus::EXPIRED);
+        let transitions = tracker.refresh().await.unwrap();
+        assert_eq!(transitions.len(), 2);
+        assert!(tracker.refresh().await.unwrap().is_empty());
+
+        let claimed = tracker.get(7).unwrap();
+        assert_eq!(claimed.phase, WithdrawPhase::Claimed);
+        assert_eq!(claimed.claim_tx_hash.as_deref(), Some("0x99"));
+        let phases: Vec<_> = recorder
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|transition| transition.withdraw_id == 7)
+            .map(|transition| transition.to)
+            .collect();
+        assert_eq!(
+            phases,
+            [
+                WithdrawPhase::Requested,
+                WithdrawPhase::BtcSent,
+                WithdrawPhase::Claimed
+            ]
+        );
+        assert_eq!(tracker.get(8).unwrap().phase, WithdrawPhase::Expired);
+    }
+
+    #[tokio::test]
+    async fn test_sla_alerts_once_per_breach() {
+        let mock = Arc::new(MockBridgeClient::new());
+        let recorder = Arc::new(Recorder::default());
+        let tracker = WithdrawTracker::new(mock.clone())
+            .with_sla(
+                WithdrawSla::new()
+                    .with_btc_sent_within(Duration::from_secs(1800))
+                    .with_claimed_within(Duration::from_secs(6 * 3600)),
+            )
+            .with_observer(recorder.clone());
+
+        let now = unix_timestamp_secs();
+        let mut stale = request(7, 1);
+        stale.timestamp = Some(now - 8 * 3600);
+        let mut unpaid = request(8, 1);
+        unpaid.timestamp = Some(now - 3600);
+        tracker.track(stale).await.unwrap();
+        tracker.track(unpaid).await.unwrap();
+        tracker.track(request(9, 1)).await.unwrap();
+
+        let alerts = tracker.check_sla().await.unwrap();
+        let kinds: Vec<_> = alerts
+            .iter()
+            .map(|alert| (alert.withdraw.request.withdraw_id, alert.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (7, WithdrawAlertKind::BtcNotSent),
+                (7, WithdrawAlertKind::ClaimOverdue),
+                (8, WithdrawAlertKind::BtcNotSent),
+            ]
+        );
+        assert!(alerts[1].age_secs >= 8 * 3600);
+        assert!(tracker.check_sla().await.unwrap().is_empty());
+
+        mock.set_withdraw_status(9, WithdrawStatus::EXPIRED);
+        tracker.refresh().await.unwrap();
+        assert_eq!(
+            recorder.1.lock().unwrap().last(),
+            Some(&WithdrawAlertKind::Expired)
+        );
+        assert_eq!(recorder.1.lock().unwrap().len(), 4);
+    }
+
+    /// Fails the first `.0` alerts
+    struct FlakyAlerts(std::sync::atomic::AtomicUsize);
+
+    #[async_trait]
+    impl WithdrawObserver for FlakyAlerts {
+        async fn on_transition(
+            &self,
+            _withdraw: &TrackedWithdraw,
+            _transition: &WithdrawTransition,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn on_alert(&self, _alert: &WithdrawAlert) -> Result<()> {
+            use std::sync::atomic::Ordering;
+            match self
+                .0
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            {
+                Ok(_) => Err(anyhow!("alert sink unavailable")),
+                Err(_) => Ok(()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_alerts_are_raised_again() {
+        let mock = Arc::new(MockBridgeClient::new());
+        let tracker = WithdrawTracker::new(mock.clone())
+            .with_sla(WithdrawSla::new().with_btc_sent_within(Duration::from_secs(1800)))
+            .with_observer(Arc::new(FlakyAlerts(1.into())));
+
+        let mut stale = request(7, 1);
+        stale.timestamp = Some(unix_timestamp_secs() - 3600);
+        tracker.track(stale).await.unwrap();
+        tracker.track(request(8, 1)).await.unwrap();
+
+        assert!(tracker.check_sla().await.is_err());
+        assert!(tracker.get(7).unwrap().alerts.is_empty());
+        let alerts = tracker.check_sla().await.unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(
+            tracker.get(7).unwrap().alerts,
+            [WithdrawAlertKind::BtcNotSent]
+        );
+        assert!(tracker.check_sla().await.unwrap().is_empty());
+
+        // A failed expiry alert is retried by the SLA check, and doesn't keep
+        // the other withdraws from being updated
+        let tracker =
+            WithdrawTracker::new(mock.clone()).with_observer(Arc::new(FlakyAlerts(1.into())));
+        tracker.track(request(8, 1)).await.unwrap();
+        tracker.track(request(10, 1)).await.unwrap();
+        mock.set_withdraw_status(8, WithdrawStatus::EXPIRED);
+        mock.set_withdraw_status(10, WithdrawStatus::EXPIRED);
+        assert!(tracker.refresh().await.is_err());
+        let expired = tracker.get(8).unwrap();
+        assert_eq!(expired.phase, WithdrawPhase::Expired);
+        assert!(expired.alerts.is_empty());
+        let expired = tracker.get(10).unwrap();
+        assert_eq!(expired.phase, WithdrawPhase::Expired);
+        assert_eq!(expired.alerts, [WithdrawAlertKind::Expired]);
+
+        let alerts = tracker.check_sla().await.unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, WithdrawAlertKind::Expired);
+        assert_eq!(alerts[0].withdraw.request.withdraw_id, 8);
+        assert!(tracker.check_sla().await.unwrap().is_empty());
+        assert!(tracker.refresh().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+//! Mint → burn → claim round trip against a local Aptos node and regtest bitcoind
+//!
+//! Run with `BRIDGE_MOVE_PACKAGE=<path> cargo test --features e2e --test e2e -- --ignored`;
+//! `aptos` and `bitcoind` must be on `PATH` (or set `APTOS_CLI` / `BITCOIND`).
+#![cfg(feature = "e2e")]
+
+use aptos_client_sdk::e2e::{E2eConfig, E2eHarness};
+use aptos_client_sdk::psbt::PAYOUT_VOUT;
+use aptos_client_sdk::{
+    script_hash_for_address, BridgeEvent, BtcAddress, BtcNetwork, PayoutPsbtBuilder,
+    RegisterLPParams, Sats, ScriptType, WithdrawByLPParams,
+};
+use std::sync::Arc;
+
+const DEPOSIT: Sats = Sats(1_000_000);
+const BURN_AMOUNT: Sats = Sats(100_000);
+const WITHDRAW_AMOUNT: Sats = Sats(200_000);
+const RECEIVE_MIN_AMOUNT: Sats = Sats(150_000);
+const FEE_RATE: u64 = 2;
+const LP_ID: u64 = 1;
+const WITHDRAW_ID: u64 = 1;
+
+#[tokio::test]
+#[ignore = "starts an Aptos localnet and regtest bitcoind"]
+async fn test_mint_burn_claim_round_trip() {
+    let harness = E2eHarness::start(E2eConfig::from_env().unwrap())
+        .await
+        .unwrap();
+    let client = Arc::new(harness.bridge_client().unwrap());
+    let recipient = harness.deployer_address().to_string();
+
+    // Peg in: deposit on regtest, confirm and relay the headers, then mint
+    let deposit_address = harness.new_address().await.unwrap();
+    let (deposit_txid, deposit_vout) = harness
+        .send_to_address(&deposit_address, DEPOSIT)
+        .await
+        .unwrap();
+    let min_confirmations = client.get_min_confirmations().await.unwrap();
+    harness.mine(min_confirmations.max(1)).await.unwrap();
+    harness.sync_headers(client.clone()).await.unwrap();
+
+    let peg = harness
+        .proof_builder()
+        .build_peg(&deposit_txid.to_string(), deposit_vout, &recipient)
+        .await
+        .unwrap();
+    let tx_id = peg.inclusion_proof.tx_id.clone();
+    let tx_hash = client.mint(peg).await.unwrap();
+    harness.wait_for_success(&client, &tx_hash).await.unwrap();
+    assert!(client
+        .is_peg_minted(&tx_id, deposit_vout as u64)
+        .await
+        .unwrap());
+    assert_eq!(
+        client.get_btc_peg_balance(&recipient).await.unwrap(),
+        DEPOSIT
+    );
+
+    // Peg out through the operator
+    let burn_address =
+        BtcAddress::new(&harness.new_address().await.unwrap(), BtcNetwork::Regtest).unwrap();
+    let tx_hash = client
+        .burn(burn_address, FEE_RATE, BURN_AMOUNT, 0)
+        .await
+        .unwrap();
+    harness.wait_for_success(&client, &tx_hash).await.unwrap();
+    let events = client
+        .get_bridge_events_by_hash(&tx_hash, &client.bridge_contract_address().to_hex_literal())
+        .await
+        .unwrap();
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, BridgeEvent::Burn(burn) if burn.amount == BURN_AMOUNT)));
+
+    // Peg out through an LP: register, request, pay on regtest, claim
+    let lp_btc_address =
+        BtcAddress::new(&harness.new_address().await.unwrap(), BtcNetwork::Regtest).unwrap();
+    let tx_hash = client
+        .register_lp(RegisterLPParams {
+            lp_id: LP_ID,
+            bitcoin_addr: lp_btc_address,
+            lp_addr: recipient.clone(),
+            lp_fee: 0,
+        })
+        .await
+        .unwrap();
+    harness.wait_for_success(&client, &tx_hash).await.unwrap();
+
+    let receiver_address = harness.new_address().await.unwrap();
+    let receiver = BtcAddress::new(&receiver_address, BtcNetwork::Regtest).unwrap();
+    let receiver_script_hash = script_hash_for_address(&receiver, ScriptType::P2WPKH).unwrap();
+    let tx_hash = client
+        .withdraw_by_lp(WithdrawByLPParams {
+            withdraw_id: WITHDRAW_ID,
+            btc_address: receiver,
+            receiver_script_hash,
+            receive_min_amount: RECEIVE_MIN_AMOUNT,
+            lp_id: LP_ID,
+            amount: WITHDRAW_AMOUNT,
+            fee_rate: FEE_RATE,
+        })
+        .await
+        .unwrap();
+    harness.wait_for_success(&client, &tx_hash).await.unwrap();
+    let withdraw_event = client
+        .get_bridge_events_by_hash(&tx_hash, &client.bridge_contract_address().to_hex_literal())
+        .await
+        .unwrap()
+        .into_iter()
+        .find_map(|event| match event {
+            BridgeEvent::WithdrawByLP(event) => Some(event),
+            _ => None,
+        })
+        .expect("withdraw_by_lp emits a WithdrawByLP event");
+
+    let funding_address = harness.new_address().await.unwrap();
+    let (funding_txid, funding_vout) = harness
+        .send_to_address(&funding_address, DEPOSIT)
+        .await
+        .unwrap();
+    let (outpoint, txout) = harness.utxo(&funding_txid, funding_vout).await.unwrap();
+    let change_script = txout.script_pubkey.clone();
+    let payout = PayoutPsbtBuilder::for_withdraw_by_lp(&withdraw_event, BtcNetwork::Regtest)
+        .unwrap()
+        .with_input(outpoint, txout)
+        .with_change_script(change_script)
+        .build()
+        .unwrap();
+    let payout_txid = harness
+        .sign_and_broadcast(&payout.psbt.unsigned_tx)
+        .await
+        .unwrap();
+    harness.mine(min_confirmations.max(1)).await.unwrap();
+    harness.sync_headers(client.clone()).await.unwrap();
+
+    let claim = harness
+        .proof_builder()
+        .build_claim(WITHDRAW_ID, &payout_txid.to_string(), PAYOUT_VOUT)
+        .await
+        .unwrap();
+    let tx_hash = client.claim_lp_withdraw(claim).await.unwrap();
+    harness.wait_for_success(&client, &tx_hash).await.unwrap();
+}